@@ -0,0 +1,147 @@
+//! Encode or decode many small payloads in one run, read as JSON Lines
+//! from stdin & written as JSON Lines to stdout
+//!
+//! Each line of input is either a JSON string, or an object with the
+//! string to encode or decode as `data` & optionally an `id` of any
+//! type, which is given back with the result. Every line gets a line of
+//! output, even if it can't be parsed, so one bad line doesn't stop the
+//! rest. Input is read & output written a line at a time, so a batch
+//! never has to fit in memory
+
+use std::io::{BufRead, Write};
+
+use color_eyre::Result;
+use serde_json::{json, Value};
+
+use crate::report;
+
+/// What happened in a batch
+pub struct Summary {
+    /// How many lines there were, leaving out blank ones
+    pub lines: usize,
+    /// How many of them failed
+    pub failed: usize,
+}
+
+/// Run `process` on the data of every line of `input`, writing its
+/// result or the error for each line to `output`
+///
+/// `process` gives back an object for the line, like
+/// `{"encoded": "..."}`, which `ok`, the 1-based `line` number, & the
+/// `id` from the input if it had one are added to
+pub fn run<R, W, F>(mut input: R, mut output: W, mut process: F) -> Result<Summary>
+where
+    R: BufRead,
+    W: Write,
+    F: FnMut(&str) -> Result<Value>,
+{
+    let mut summary = Summary {
+        lines: 0,
+        failed: 0,
+    };
+    let mut buf = Vec::new();
+
+    for line_number in 1.. {
+        buf.clear();
+        if input.read_until(b'\n', &mut buf)? == 0 {
+            break;
+        }
+        // Blank lines, like one at the end of the input, aren't items
+        if buf.trim_ascii().is_empty() {
+            continue;
+        }
+        summary.lines += 1;
+
+        let (id, data) = parse_line(&buf);
+        let result = match data {
+            Ok(data) => process(&data).map_err(|e| report::error_json(&e)),
+            Err(message) => Err(json!({"ok": false, "error": "InvalidLine", "message": message})),
+        };
+        let mut object = match result {
+            Ok(mut object) => {
+                object["ok"] = true.into();
+                object
+            }
+            Err(error) => {
+                summary.failed += 1;
+                error
+            }
+        };
+        object["line"] = line_number.into();
+        if let Some(id) = id {
+            object["id"] = id;
+        }
+
+        writeln!(output, "{object}")?;
+        output.flush()?;
+    }
+
+    Ok(summary)
+}
+
+/// Get the `id` & `data` of a line of input, keeping the `id` even
+/// if there's no `data`
+fn parse_line(line: &[u8]) -> (Option<Value>, Result<String, String>) {
+    match serde_json::from_slice(line) {
+        Ok(Value::String(data)) => (None, Ok(data)),
+        Ok(Value::Object(mut object)) => {
+            let data = match object.remove("data") {
+                Some(Value::String(data)) => Ok(data),
+                _ => Err("Object has no `data` string".into()),
+            };
+            (object.remove("id"), data)
+        }
+        Ok(_) => (
+            None,
+            Err("Line isn't a string or an object with `data`".into()),
+        ),
+        Err(e) => (None, Err(format!("Line isn't valid JSON: {e}"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use color_eyre::eyre::eyre;
+
+    use super::*;
+
+    #[test]
+    fn every_line_gets_output() {
+        let input = b"\"a\"\n\n  \n{\"id\": 7}\n\"fail\"\r\n\"b\"";
+        let mut output = Vec::new();
+        let summary = run(&input[..], &mut output, |data| match data {
+            "fail" => Err(eyre!("failed")),
+            _ => Ok(json!({ "data": data })),
+        })
+        .unwrap();
+
+        assert_eq!((summary.lines, summary.failed), (4, 2));
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            concat!(
+                r#"{"data":"a","line":1,"ok":true}"#,
+                "\n",
+                r#"{"error":"InvalidLine","id":7,"line":4,"message":"Object has no `data` string","ok":false}"#,
+                "\n",
+                r#"{"error":"Other","line":5,"message":"failed","ok":false}"#,
+                "\n",
+                r#"{"data":"b","line":6,"ok":true}"#,
+                "\n",
+            )
+        );
+    }
+
+    #[test]
+    fn invalid_utf8_line() {
+        let mut output = Vec::new();
+        let summary = run(&b"\"\xff\"\n\"ok\"\n"[..], &mut output, |data| {
+            Ok(json!({ "data": data }))
+        })
+        .unwrap();
+
+        assert_eq!(summary.failed, 1);
+        assert!(String::from_utf8(output)
+            .unwrap()
+            .ends_with("{\"data\":\"ok\",\"line\":2,\"ok\":true}\n"));
+    }
+}
@@ -0,0 +1,328 @@
+use std::path::PathBuf;
+
+use baze64::{
+    alphabet::{self, AnyAlphabet},
+    DecodeCompat, TextEncoding,
+};
+use clap::{
+    builder::{PossibleValuesParser, TypedValueParser},
+    Parser, Subcommand,
+};
+use clap_complete::Shell;
+
+#[derive(Debug, Parser)]
+#[clap(name = "baze64", author, version, about, long_about = None)]
+pub struct Args {
+    #[clap(subcommand)]
+    pub cmd: Command,
+    /// Output results & errors as JSON
+    #[clap(long, global = true)]
+    pub json: bool,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Encode something into Base64
+    Encode {
+        /// Encode a UTF-8 string
+        string: Option<String>,
+        /// Encode a file
+        #[clap(short, long)]
+        file: Option<PathBuf>,
+        /// The output file for the encoded base64
+        #[clap(short, long)]
+        output: Option<PathBuf>,
+        /// Keep encoding the file every time it changes, until interrupted
+        #[clap(short, long, requires = "file", conflicts_with = "string")]
+        watch: bool,
+        /// Memory map the file, which is done for files of 256 MB or
+        /// more unless `--no-mmap` is given
+        #[clap(long, requires = "file", conflicts_with = "watch")]
+        mmap: bool,
+        /// Read the file a chunk at a time rather than memory mapping it
+        #[clap(long, conflicts_with = "mmap")]
+        no_mmap: bool,
+        /// The base64 alphabet to encode using
+        #[clap(
+            short,
+            long,
+            default_value = "standard",
+            ignore_case = true,
+            value_parser = alphabet_parser()
+        )]
+        alphabet: AnyAlphabet,
+        /// Return the encoded base64 without padding
+        #[clap(long)]
+        no_padding: bool,
+        /// Treat the input string as a hex string
+        #[clap(short = 'H', long)]
+        hex: bool,
+        /// Output a `data:` URI, which always uses the standard alphabet
+        #[clap(long, conflicts_with = "no_padding")]
+        data_uri: bool,
+        /// The media type of the data for `--data-uri`
+        #[clap(long, requires = "data_uri")]
+        mime: Option<String>,
+        /// Wrap the output at this many columns
+        #[clap(
+            long,
+            value_name = "N",
+            value_parser = clap::value_parser!(u16).range(1..),
+            conflicts_with = "data_uri"
+        )]
+        wrap: Option<u16>,
+        /// Encode the string as `utf8`, `utf16le` or `utf16be` text
+        /// before encoding it into base64
+        #[clap(
+            long,
+            value_name = "ENCODING",
+            requires = "string",
+            conflicts_with = "hex",
+            value_parser = text_encoding_parser()
+        )]
+        text_encoding: Option<TextEncoding>,
+        /// Append a CRC-32 of the data before encoding it, for checking
+        /// with `decode --verify-crc`
+        #[clap(long, conflicts_with = "watch")]
+        crc: bool,
+        /// Print the SHA-256 of the data being encoded to stderr, or
+        /// add it to the `--json` output
+        #[clap(long, conflicts_with_all = ["watch", "batch_jsonl"])]
+        sha256: bool,
+        /// Encode every line of JSON Lines read from stdin, each a string
+        /// or an object with a `data` string & an `id`, writing a JSON
+        /// object with the base64 for each
+        #[clap(long, conflicts_with_all = ["string", "file", "output"])]
+        batch_jsonl: bool,
+        /// Don't end the encoded base64 with a newline
+        #[clap(long, conflicts_with_all = ["watch", "batch_jsonl"])]
+        no_newline: bool,
+    },
+    /// Decode a Base64 string
+    Decode {
+        /// The Base64 string to decode, read from stdin if not given
+        base64: Option<String>,
+        /// The output file for the exact decoded data, or `-` for stdout,
+        /// the same as `--raw`
+        #[clap(short, long, conflicts_with_all = ["verify", "hex", "bytes", "dump"])]
+        output: Option<PathBuf>,
+        /// The base64 alphabet the input was encoded in
+        #[clap(
+            short,
+            long,
+            default_value = "standard",
+            ignore_case = true,
+            value_parser = alphabet_parser()
+        )]
+        alphabet: AnyAlphabet,
+        /// Output the decoded data as lowercase hex, like `deadbeef`
+        #[clap(short = 'H', long)]
+        hex: bool,
+        /// Output the decoded data in byte form
+        #[clap(short, long)]
+        bytes: bool,
+        /// Output a hex dump of the decoded data, with offsets & the
+        /// bytes as ASCII
+        #[clap(long, conflicts_with_all = ["hex", "bytes"])]
+        dump: bool,
+        /// Write the exact decoded bytes to stdout, without a newline or
+        /// replacing anything that isn't UTF-8
+        #[clap(long, conflicts_with_all = ["output", "hex", "bytes", "dump"])]
+        raw: bool,
+        /// Don't end the decoded text with a newline
+        #[clap(long, conflicts_with_all = ["output", "raw", "hex", "bytes", "dump"])]
+        no_newline: bool,
+        /// Only check the input decodes, the same as it would without
+        /// this, without outputting anything
+        #[clap(long, conflicts_with_all = ["hex", "bytes", "dump", "raw", "no_newline"])]
+        verify: bool,
+        /// Don't print an error message if `--verify` fails
+        #[clap(short, long, requires = "verify")]
+        quiet: bool,
+        /// Fail without decoding if the decoded data would be more
+        /// than this many bytes
+        #[clap(long, value_name = "BYTES")]
+        max_size: Option<usize>,
+        /// Decode the way another language does, to see what it made of
+        /// the input: `python` for `base64.b64decode` or `java-mime` for
+        /// Java's `Base64.getMimeDecoder()`
+        #[clap(
+            long,
+            value_name = "MODE",
+            conflicts_with = "verify",
+            value_parser = compat_parser()
+        )]
+        compat: Option<DecodeCompat>,
+        /// Check the CRC-32 appended by `encode --crc` & strip it off,
+        /// failing if it doesn't match
+        #[clap(long)]
+        verify_crc: bool,
+        /// Print the SHA-256 of the decoded data to stderr, or add it to
+        /// the `--json` output
+        #[clap(long, conflicts_with_all = ["verify", "batch_jsonl"])]
+        sha256: bool,
+        /// Read the decoded data as `utf8`, `utf16le` or `utf16be` text,
+        /// failing if it isn't valid, & output it as UTF-8
+        #[clap(
+            long,
+            value_name = "ENCODING",
+            conflicts_with_all = ["hex", "bytes", "dump", "verify"],
+            value_parser = text_encoding_parser()
+        )]
+        text_encoding: Option<TextEncoding>,
+        /// Decode every line of JSON Lines read from stdin, each a string
+        /// or an object with a `data` string & an `id`, writing a JSON
+        /// object with the decoded data for each
+        #[clap(
+            long,
+            conflicts_with_all = [
+                "base64",
+                "output",
+                "verify",
+                "hex",
+                "bytes",
+                "dump",
+                "raw",
+                "no_newline"
+            ]
+        )]
+        batch_jsonl: bool,
+    },
+    /// Convert base64 from one alphabet or padding style to another
+    Recode {
+        /// The Base64 string to convert, read from stdin if not given
+        #[clap(allow_hyphen_values = true)]
+        base64: Option<String>,
+        /// The output file for the converted base64
+        #[clap(short, long)]
+        output: Option<PathBuf>,
+        /// The base64 alphabet the input was encoded in, or `auto`
+        /// to detect it
+        #[clap(
+            long,
+            default_value = "auto",
+            ignore_case = true,
+            value_parser = source_alphabet_parser()
+        )]
+        from: SourceAlphabet,
+        /// The base64 alphabet to convert to
+        #[clap(
+            long,
+            default_value = "standard",
+            ignore_case = true,
+            value_parser = alphabet_parser()
+        )]
+        to: AnyAlphabet,
+        /// Return the converted base64 without padding
+        #[clap(long)]
+        no_padding: bool,
+        /// Wrap the output at this many columns
+        #[clap(long, value_name = "N", value_parser = clap::value_parser!(u16).range(1..))]
+        wrap: Option<u16>,
+    },
+    /// Check whether two Base64 strings decode to the same bytes,
+    /// exiting with 1 if they don't
+    #[clap(visible_alias = "diff")]
+    Compare {
+        /// The Base64 strings to compare, either of which can be
+        /// `@<FILE>` to read it from a file. Put `--` before them if
+        /// one starts with `-`
+        #[clap(value_name = "BASE64", num_args = 0..=2)]
+        operands: Vec<String>,
+        /// Read the first Base64 string from a file
+        #[clap(long, value_name = "FILE")]
+        file_a: Option<PathBuf>,
+        /// Read the second Base64 string from a file
+        #[clap(long, value_name = "FILE")]
+        file_b: Option<PathBuf>,
+        /// The base64 alphabet of the first string, or `auto` to
+        /// detect it
+        #[clap(
+            long,
+            default_value = "auto",
+            ignore_case = true,
+            value_parser = source_alphabet_parser()
+        )]
+        alphabet_a: SourceAlphabet,
+        /// The base64 alphabet of the second string, or `auto` to
+        /// detect it
+        #[clap(
+            long,
+            default_value = "auto",
+            ignore_case = true,
+            value_parser = source_alphabet_parser()
+        )]
+        alphabet_b: SourceAlphabet,
+    },
+    /// Find base64 in text read from stdin, like logs or HTTP headers,
+    /// printing each match on its own line
+    Extract {
+        /// The fewest characters a match can have, counting padding
+        #[clap(long, value_name = "N", default_value_t = 16)]
+        min_len: usize,
+        /// Print what each match decodes to rather than the match
+        #[clap(short, long)]
+        decode: bool,
+        /// The base64 alphabet to look for
+        #[clap(
+            short,
+            long,
+            default_value = "standard",
+            ignore_case = true,
+            value_parser = alphabet_parser()
+        )]
+        alphabet: AnyAlphabet,
+    },
+    /// Print a completion script for SHELL
+    #[clap(hide = true)]
+    Completions {
+        /// The shell to complete in
+        shell: Shell,
+    },
+    /// Print a man page in roff format
+    #[clap(hide = true)]
+    Manpage,
+}
+
+/// The alphabet `recode` & `compare` read their input with
+#[derive(Debug, Clone, Copy)]
+pub enum SourceAlphabet {
+    /// Detect the alphabet from the input
+    Auto,
+    /// Use a specific alphabet
+    Named(AnyAlphabet),
+}
+
+/// Parse one of the library's alphabet names, ignoring case
+fn alphabet_parser() -> impl TypedValueParser<Value = AnyAlphabet> {
+    PossibleValuesParser::new(alphabet::names().iter().copied())
+        // Only known names get past the possible values check
+        .map(|name| alphabet::by_name(&name).unwrap())
+}
+
+/// Parse `auto` or one of the library's alphabet names, ignoring case
+fn source_alphabet_parser() -> impl TypedValueParser<Value = SourceAlphabet> {
+    let names = std::iter::once("auto").chain(alphabet::names().iter().copied());
+
+    PossibleValuesParser::new(names).map(|name| match alphabet::by_name(&name) {
+        Some(alphabet) => SourceAlphabet::Named(alphabet),
+        None => SourceAlphabet::Auto,
+    })
+}
+
+/// Parse the name of a decoder to copy for `--compat`
+fn compat_parser() -> impl TypedValueParser<Value = DecodeCompat> {
+    PossibleValuesParser::new(["python", "java-mime"]).map(|name| match name.as_str() {
+        "python" => DecodeCompat::PythonForgiving,
+        _ => DecodeCompat::JavaMime,
+    })
+}
+
+/// Parse the name of a text encoding for `--text-encoding`
+fn text_encoding_parser() -> impl TypedValueParser<Value = TextEncoding> {
+    PossibleValuesParser::new(["utf8", "utf16le", "utf16be"]).map(|name| match name.as_str() {
+        "utf16le" => TextEncoding::Utf16Le,
+        "utf16be" => TextEncoding::Utf16Be,
+        _ => TextEncoding::Utf8,
+    })
+}
@@ -0,0 +1,657 @@
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use baze64::{
+    alphabet::{self, AnyAlphabet},
+    checked,
+    data_uri::DataUri,
+    Base64String, Codec, Divergence, HexCase, HexDumpConfig, TextEncoding,
+};
+use clap::{CommandFactory, Parser};
+use cli::{Args, Command, SourceAlphabet};
+use color_eyre::{eyre::bail, Result};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use stream::{Lines, MmapMode, Source};
+
+mod batch;
+mod cli;
+mod report;
+mod stream;
+mod watch;
+
+fn main() {
+    color_eyre::install().unwrap();
+
+    let args = Args::parse();
+    let json = args.json;
+    let quiet = matches!(args.cmd, Command::Decode { quiet: true, .. });
+
+    if let Err(e) = baze64(args) {
+        // Whatever was reading the output stopped, like `head`, which
+        // isn't worth reporting
+        if e.downcast_ref::<io::Error>()
+            .is_some_and(|e| e.kind() == io::ErrorKind::BrokenPipe)
+        {
+            std::process::exit(0);
+        }
+        if json && !quiet {
+            eprintln!("{}", report::error_json(&e));
+        } else if !quiet {
+            eprintln!("Error: {e}");
+        }
+        std::process::exit(report::exit_code(&e));
+    }
+}
+
+fn baze64(args: Args) -> Result<()> {
+    let json = args.json;
+
+    match args.cmd {
+        Command::Encode {
+            string,
+            file,
+            output,
+            watch,
+            mmap,
+            no_mmap,
+            alphabet,
+            no_padding,
+            hex,
+            data_uri,
+            mime,
+            wrap,
+            text_encoding,
+            crc,
+            sha256,
+            batch_jsonl,
+            no_newline,
+        } => {
+            let format = EncodeFormat {
+                codec: Codec::new(alphabet)
+                    .padding(!no_padding)
+                    .wrap(wrap.map_or(0, usize::from)),
+                data_uri,
+                mime,
+                newline: !no_newline,
+            };
+
+            if watch {
+                // clap makes sure there's a file to watch
+                let path = file.unwrap();
+                return watch::watch(&path, output.as_deref(), json, |path| {
+                    Ok(format.encode(&fs::read(path)?, false))
+                });
+            }
+
+            // The bytes a string stands for, & whether they're UTF-8
+            let string_data = |txt: &str| -> Result<(Vec<u8>, bool)> {
+                if hex {
+                    let bytes = Base64String::encode_hex(txt, *format.codec.alphabet())?;
+                    Ok((bytes.decode()?, false))
+                } else {
+                    let encoding = text_encoding.unwrap_or_default();
+                    Ok((encoding.encode_text(txt), encoding == TextEncoding::Utf8))
+                }
+            };
+            let encode_data = |bytes: &[u8], is_text| {
+                if crc {
+                    format.encode(&checked::frame(bytes), false)
+                } else {
+                    format.encode(bytes, is_text)
+                }
+            };
+
+            if batch_jsonl {
+                let summary = batch::run(io::stdin().lock(), io::stdout().lock(), |data| {
+                    let (bytes, is_text) = string_data(data)?;
+                    Ok(json!({"encoded": encode_data(&bytes, is_text)}))
+                })?;
+                return batch_result(summary);
+            }
+
+            let (encoded, digest) = match string {
+                Some(txt) => {
+                    let (bytes, is_text) = string_data(&txt)?;
+                    (
+                        encode_data(&bytes, is_text),
+                        sha256.then(|| sha256_hex(&bytes)),
+                    )
+                }
+                None => {
+                    let Some(path) = file else {
+                        bail!("Either provide a string or use `-f <FILE>` to provide a file to encode");
+                    };
+                    if !crc {
+                        let source =
+                            Source::new(File::open(path)?, MmapMode::from_flags(mmap, no_mmap))?;
+                        return format.stream(source, output.as_deref(), json, sha256);
+                    }
+                    // The checksum goes on the end, so the whole file is
+                    // needed before anything can be written
+                    let bytes = fs::read(path)?;
+                    (
+                        encode_data(&bytes, false),
+                        sha256.then(|| sha256_hex(&bytes)),
+                    )
+                }
+            };
+
+            let end = if no_newline { "" } else { "\n" };
+            if let Some(path) = output {
+                fs::write(path, format!("{encoded}{end}"))?;
+                if json {
+                    let result = json!({"ok": true, "written": encoded.len()});
+                    writeln!(
+                        io::stdout().lock(),
+                        "{}",
+                        with_digest(result, digest.as_deref())
+                    )?;
+                }
+            } else {
+                let mut out = io::stdout().lock();
+                if json {
+                    let result = json!({"ok": true, "encoded": encoded});
+                    writeln!(out, "{}", with_digest(result, digest.as_deref()))?;
+                } else {
+                    write!(out, "{encoded}{end}")?;
+                }
+                out.flush()?;
+            }
+            if !json {
+                print_digest(digest.as_deref());
+            }
+        }
+        Command::Decode {
+            base64,
+            output,
+            alphabet,
+            hex,
+            bytes,
+            dump,
+            raw,
+            no_newline,
+            verify,
+            quiet,
+            max_size,
+            compat,
+            verify_crc,
+            text_encoding,
+            sha256,
+            batch_jsonl,
+        } => {
+            // `-o -` writes to stdout, the same as `--raw`
+            let raw = raw || output.as_deref() == Some(Path::new("-"));
+            let output = output.filter(|_| !raw);
+            if raw && json {
+                bail!("`--raw` only outputs the decoded data, so can't be used with `--json`");
+            }
+
+            let codec = Codec::new(alphabet)
+                .normalize_padding(true)
+                .limit(max_size.unwrap_or(usize::MAX))
+                .compat(compat.unwrap_or_default());
+            let decode = |base64: &str| -> Result<Vec<u8>> {
+                let mut decoded = codec.decode(base64)?;
+                if verify_crc {
+                    decoded = checked::unframe(decoded)?;
+                }
+                if let Some(encoding) = text_encoding {
+                    // Transcode to UTF-8, which also rejects invalid text
+                    // rather than replacing it like the default output does
+                    decoded = encoding.decode_text(decoded)?.into_bytes();
+                }
+                Ok(decoded)
+            };
+
+            if batch_jsonl {
+                let summary = batch::run(io::stdin().lock(), io::stdout().lock(), |data| {
+                    let decoded = match String::from_utf8(decode(data)?) {
+                        Ok(text) => json!(text),
+                        Err(e) => json!({ "hex": HexCase::Lower.format(e.as_bytes()) }),
+                    };
+                    Ok(json!({ "decoded": decoded }))
+                })?;
+                return batch_result(summary);
+            }
+
+            let base64 = match base64 {
+                Some(base64) => base64,
+                None => read_stdin()?,
+            };
+
+            if verify {
+                // Decode the same way as without `--verify`, so exactly
+                // what would decode is accepted
+                decode(&base64)?;
+                if json && !quiet {
+                    writeln!(io::stdout().lock(), "{}", json!({"ok": true}))?;
+                }
+                return Ok(());
+            }
+
+            let decoded = decode(&base64)?;
+            let digest = sha256.then(|| sha256_hex(&decoded));
+
+            if raw {
+                let mut out = io::stdout().lock();
+                out.write_all(&decoded)?;
+                out.flush()?;
+            } else if let Some(path) = output {
+                // Only create the file once decoding has fully succeeded,
+                // so invalid input never leaves a partial file behind
+                let mut f = File::create(path)?;
+                f.write_all(&decoded)?;
+                f.flush()?;
+
+                if json {
+                    let result = json!({"ok": true, "written": decoded.len()});
+                    writeln!(
+                        io::stdout().lock(),
+                        "{}",
+                        with_digest(result, digest.as_deref())
+                    )?;
+                }
+            } else {
+                let text = if hex {
+                    HexCase::Lower.format(&decoded)
+                } else if dump {
+                    HexDumpConfig::new().dump(&decoded)
+                } else if bytes {
+                    decoded.iter().map(|b| format!("{b:0>8b}")).collect()
+                } else {
+                    String::from_utf8_lossy(&decoded).into_owned()
+                };
+
+                let mut out = io::stdout().lock();
+                if json {
+                    let result = json!({"ok": true, "decoded": text});
+                    writeln!(out, "{}", with_digest(result, digest.as_deref()))?;
+                } else if hex || bytes || dump || no_newline {
+                    write!(out, "{text}")?;
+                } else {
+                    writeln!(out, "{text}")?;
+                }
+                out.flush()?;
+            }
+            if !json {
+                print_digest(digest.as_deref());
+            }
+        }
+        Command::Recode {
+            base64,
+            output,
+            from,
+            to,
+            no_padding,
+            wrap,
+        } => {
+            let base64 = match base64 {
+                Some(base64) => base64,
+                None => read_stdin()?,
+            };
+            let from = resolve_alphabet(from, &base64);
+
+            let decoded = Codec::new(from).normalize_padding(true).decode(&base64)?;
+            let recoded = Codec::new(to)
+                .padding(!no_padding)
+                .wrap(wrap.map_or(0, usize::from))
+                .encode_to_string(decoded);
+
+            let mut out = io::stdout().lock();
+            if let Some(path) = output {
+                let mut f = File::create(path)?;
+                writeln!(f, "{recoded}")?;
+                f.flush()?;
+
+                if json {
+                    writeln!(out, "{}", json!({"ok": true, "written": recoded.len()}))?;
+                }
+            } else if json {
+                writeln!(out, "{}", json!({"ok": true, "encoded": recoded}))?;
+            } else {
+                writeln!(out, "{recoded}")?;
+            }
+            out.flush()?;
+        }
+        Command::Compare {
+            operands,
+            file_a,
+            file_b,
+            alphabet_a,
+            alphabet_b,
+        } => {
+            let mut operands = operands.into_iter();
+            let a = read_operand(file_a, &mut operands)?;
+            let b = read_operand(file_b, &mut operands)?;
+            if operands.next().is_some() {
+                bail!("Only two Base64 strings can be compared, including any given with `--file-a` or `--file-b`");
+            }
+
+            let alphabet_a = resolve_alphabet(alphabet_a, &a);
+            let alphabet_b = resolve_alphabet(alphabet_b, &b);
+            let a = Base64String::from_encoded_normalized_with(a, alphabet_a)?;
+            let b = Base64String::from_encoded_normalized_with(b, alphabet_b)?;
+
+            let mut out = io::stdout().lock();
+            let Some(divergence) = baze64::diff_decoded(&a, &b)? else {
+                if json {
+                    writeln!(out, "{}", json!({"ok": true, "identical": true}))?;
+                } else {
+                    writeln!(out, "identical")?;
+                }
+                out.flush()?;
+                return Ok(());
+            };
+
+            if json {
+                writeln!(
+                    out,
+                    "{}",
+                    json!({
+                        "ok": true,
+                        "identical": false,
+                        "offset": divergence.offset,
+                        "len_a": divergence.len_a,
+                        "len_b": divergence.len_b,
+                        "context_start": divergence.context_start,
+                        "context_a": HexCase::Lower.format(&divergence.context_a),
+                        "context_b": HexCase::Lower.format(&divergence.context_b),
+                    })
+                )?;
+            } else {
+                print_divergence(&mut out, &divergence)?;
+            }
+            out.flush()?;
+            std::process::exit(report::EXIT_DIFFERENT);
+        }
+        Command::Extract {
+            min_len,
+            decode,
+            alphabet,
+        } => {
+            let mut haystack = String::new();
+            io::stdin().read_to_string(&mut haystack)?;
+            let found = baze64::extract(&haystack, min_len, &alphabet);
+
+            if json {
+                let matches = found
+                    .iter()
+                    .map(|(range, b64)| {
+                        let mut found = json!({
+                            "start": range.start,
+                            "end": range.end,
+                            "text": &haystack[range.clone()],
+                        });
+                        if decode {
+                            found["decoded"] = String::from_utf8_lossy(&b64.decode()?).into();
+                        }
+                        Ok(found)
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                let mut out = io::stdout().lock();
+                writeln!(out, "{}", json!({"ok": true, "matches": matches}))?;
+                out.flush()?;
+            } else {
+                let mut out = io::stdout().lock();
+                for (range, b64) in &found {
+                    if decode {
+                        writeln!(out, "{}", String::from_utf8_lossy(&b64.decode()?))?;
+                    } else {
+                        writeln!(out, "{}", &haystack[range.clone()])?;
+                    }
+                }
+                out.flush()?;
+            }
+        }
+        Command::Completions { shell } => {
+            clap_complete::generate(shell, &mut Args::command(), "baze64", &mut io::stdout());
+        }
+        Command::Manpage => {
+            clap_mangen::Man::new(Args::command()).render(&mut io::stdout())?;
+        }
+    }
+
+    Ok(())
+}
+
+/// How `encode` formats its output
+struct EncodeFormat {
+    codec: Codec<AnyAlphabet>,
+    data_uri: bool,
+    mime: Option<String>,
+    /// Whether the output ends with a newline
+    newline: bool,
+}
+
+impl EncodeFormat {
+    /// Encode `bytes` for output, where `is_text` is whether they're
+    /// a UTF-8 string
+    fn encode(&self, bytes: &[u8], is_text: bool) -> String {
+        if !self.data_uri {
+            return self.codec.encode_to_string(bytes);
+        }
+
+        let uri = match &self.mime {
+            Some(mime) => DataUri::new(mime.clone(), bytes),
+            None if is_text => DataUri::new("text/plain", bytes).with_charset("UTF-8"),
+            None => DataUri::new("application/octet-stream", bytes),
+        }
+        .to_string();
+        match self.codec.wrap_columns() {
+            Some(columns) => wrap_lines(&uri, columns),
+            None => uri,
+        }
+    }
+
+    /// Encode all of a file straight to `output` or stdout, formatted
+    /// the same as [`EncodeFormat::encode`], hashing it in the same
+    /// pass for `--sha256`
+    fn stream(
+        &self,
+        source: Source,
+        output: Option<&Path>,
+        json: bool,
+        sha256: bool,
+    ) -> Result<()> {
+        let (alphabet, prefix) = if self.data_uri {
+            let mime = self.mime.as_deref().unwrap_or("application/octet-stream");
+            (AnyAlphabet::default(), format!("data:{mime};base64,"))
+        } else {
+            (*self.codec.alphabet(), String::new())
+        };
+        let mut hasher = sha256.then(Sha256::new);
+
+        match output {
+            Some(path) => {
+                let f = BufWriter::new(File::create(path)?);
+                let mut lines = Lines::new(f, self.codec.wrap_columns(), "\n");
+                lines.write_all(prefix.as_bytes())?;
+                let lines =
+                    stream::encode(source, alphabet, !self.codec.pads(), lines, hasher.as_mut())?;
+
+                let written = lines.written();
+                let mut f = lines.into_inner();
+                if self.newline {
+                    writeln!(f)?;
+                }
+                f.flush()?;
+                let digest = hasher.map(|hasher| HexCase::Lower.format(&hasher.finalize()));
+                if json {
+                    let result = json!({"ok": true, "written": written});
+                    writeln!(
+                        io::stdout().lock(),
+                        "{}",
+                        with_digest(result, digest.as_deref())
+                    )?;
+                } else {
+                    print_digest(digest.as_deref());
+                }
+            }
+            None => {
+                let mut out = BufWriter::new(io::stdout().lock());
+                // Base64 never needs escaping in JSON, so only the
+                // data URI's prefix & the newlines between lines do
+                let newline = if json {
+                    let prefix = serde_json::to_string(&prefix)?;
+                    write!(out, "{{\"encoded\":{}", &prefix[..prefix.len() - 1])?;
+                    "\\n"
+                } else {
+                    write!(out, "{prefix}")?;
+                    "\n"
+                };
+                let lines = Lines::new(out, self.codec.wrap_columns(), newline);
+                let mut out =
+                    stream::encode(source, alphabet, !self.codec.pads(), lines, hasher.as_mut())?
+                        .into_inner();
+
+                let digest = hasher.map(|hasher| HexCase::Lower.format(&hasher.finalize()));
+                if json {
+                    match &digest {
+                        Some(digest) => writeln!(out, "\",\"ok\":true,\"sha256\":\"{digest}\"}}")?,
+                        None => writeln!(out, "\",\"ok\":true}}")?,
+                    }
+                } else if self.newline {
+                    writeln!(out)?;
+                }
+                out.flush()?;
+                if !json {
+                    print_digest(digest.as_deref());
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Hash `bytes` for `--sha256`
+fn sha256_hex(bytes: &[u8]) -> String {
+    HexCase::Lower.format(&Sha256::digest(bytes))
+}
+
+/// Add the digest from `--sha256`, if there is one, to a JSON result
+fn with_digest(mut result: Value, digest: Option<&str>) -> Value {
+    if let Some(digest) = digest {
+        result["sha256"] = digest.into();
+    }
+    result
+}
+
+/// Print the digest from `--sha256`, if there is one, to stderr so it
+/// stays out of the output
+fn print_digest(digest: Option<&str>) {
+    if let Some(digest) = digest {
+        eprintln!("SHA-256: {digest}");
+    }
+}
+
+/// Read all of stdin, without surrounding whitespace
+fn read_stdin() -> Result<String> {
+    let mut input = String::new();
+    std::io::stdin().read_to_string(&mut input)?;
+    trim_in_place(&mut input);
+
+    Ok(input)
+}
+
+/// Fail once a batch is done if any of its lines did
+fn batch_result(summary: batch::Summary) -> Result<()> {
+    if summary.failed > 0 {
+        bail!("{} of {} lines failed", summary.failed, summary.lines);
+    }
+
+    Ok(())
+}
+
+/// Read all of the file at `path`, without surrounding whitespace
+fn read_file(path: &Path) -> Result<String> {
+    let mut input = fs::read_to_string(path)?;
+    trim_in_place(&mut input);
+
+    Ok(input)
+}
+
+/// Trim whitespace from `input` without copying it, as it may be large
+fn trim_in_place(input: &mut String) {
+    input.truncate(input.trim_end().len());
+    input.drain(..input.len() - input.trim_start().len());
+}
+
+/// Get one of the Base64 strings for `compare`, from `file` if given,
+/// otherwise from the next operand, which may be `@<FILE>`
+fn read_operand(
+    file: Option<PathBuf>,
+    operands: &mut impl Iterator<Item = String>,
+) -> Result<String> {
+    if let Some(path) = file {
+        return read_file(&path);
+    }
+
+    match operands.next() {
+        Some(operand) => match operand.strip_prefix('@') {
+            Some(path) => read_file(Path::new(path)),
+            None => Ok(operand),
+        },
+        None => bail!("Provide two Base64 strings to compare, or use `--file-a` & `--file-b`"),
+    }
+}
+
+/// Get the alphabet to read `base64` with, detecting it if need be
+fn resolve_alphabet(alphabet: SourceAlphabet, base64: &str) -> AnyAlphabet {
+    match alphabet {
+        SourceAlphabet::Named(alphabet) => alphabet,
+        // Undetectable input is invalid in every alphabet, so any of
+        // them will report where
+        SourceAlphabet::Auto => alphabet::detect(base64)
+            .map(AnyAlphabet::from)
+            .unwrap_or_default(),
+    }
+}
+
+/// Write where two Base64 strings' data first differs to `out`, with
+/// the bytes around it & the first differing byte in brackets
+fn print_divergence(out: &mut impl Write, divergence: &Divergence) -> io::Result<()> {
+    writeln!(
+        out,
+        "different: first difference at byte {}",
+        divergence.offset
+    )?;
+    writeln!(
+        out,
+        "lengths: {} & {} bytes",
+        divergence.len_a, divergence.len_b
+    )?;
+    writeln!(out, "context from byte {}:", divergence.context_start)?;
+
+    let marker = divergence.offset - divergence.context_start;
+    for (name, context) in [("a", &divergence.context_a), ("b", &divergence.context_b)] {
+        let bytes = context
+            .iter()
+            .enumerate()
+            .map(|(i, b)| {
+                if i == marker {
+                    format!("[{b:02x}]")
+                } else {
+                    format!("{b:02x}")
+                }
+            })
+            .collect::<Vec<_>>();
+        writeln!(out, "  {name}: {}", bytes.join(" "))?;
+    }
+
+    Ok(())
+}
+
+/// Split `text` into lines of `columns` characters
+fn wrap_lines(text: &str, columns: usize) -> String {
+    text.chars()
+        .collect::<Vec<_>>()
+        .chunks(columns)
+        .map(|line| line.iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
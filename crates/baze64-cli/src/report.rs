@@ -0,0 +1,104 @@
+use baze64::{
+    alphabet::{Alphabet, AnyAlphabet},
+    B64Error, DecodeError, EncodeReaderError, ErrorKind, HexError,
+};
+use color_eyre::Report;
+use serde_json::{json, Value};
+
+/// Exit code for failures not covered by a more specific code
+pub const EXIT_FAILURE: i32 = 1;
+/// Exit code for `compare` finding the data differs
+pub const EXIT_DIFFERENT: i32 = 1;
+/// Exit code for input that isn't valid base64 or hex
+pub const EXIT_INVALID_INPUT: i32 = 3;
+/// Exit code for failing to read or write a file
+pub const EXIT_IO: i32 = 4;
+
+/// Get the exit code to use when failing with `err`
+pub fn exit_code(err: &Report) -> i32 {
+    match error_kind(err) {
+        Some(ErrorKind::Io) => EXIT_IO,
+        Some(_) => EXIT_INVALID_INPUT,
+        None => EXIT_FAILURE,
+    }
+}
+
+/// Describe `err` as a JSON object for `--json` output
+pub fn error_json(err: &Report) -> Value {
+    let message = err.to_string();
+
+    let b64_err =
+        err.downcast_ref::<B64Error>()
+            .or_else(|| match err.downcast_ref::<DecodeError>() {
+                Some(DecodeError::Base64Error(e)) => Some(e),
+                _ => None,
+            });
+    if let Some(e) = b64_err {
+        return b64_error_json(e, message);
+    }
+    if let Some(DecodeError::WrongAlphabet {
+        char,
+        position,
+        suggestion,
+    }) = err.downcast_ref::<DecodeError>()
+    {
+        return json!({
+            "ok": false,
+            "error": "InvalidChar",
+            "message": message,
+            "char": char.to_string(),
+            "index": position,
+            "suggestion": AnyAlphabet::from(*suggestion).name(),
+        });
+    }
+
+    match error_kind(err) {
+        Some(kind) => json!({"ok": false, "error": format!("{kind:?}"), "message": message}),
+        None => json!({"ok": false, "error": "Other", "message": message}),
+    }
+}
+
+/// Get the kind of library error `err` is, treating any I/O error
+/// as [`ErrorKind::Io`]
+fn error_kind(err: &Report) -> Option<ErrorKind> {
+    if let Some(e) = err.downcast_ref::<B64Error>() {
+        Some(e.kind())
+    } else if let Some(e) = err.downcast_ref::<DecodeError>() {
+        Some(e.kind())
+    } else if let Some(e) = err.downcast_ref::<HexError>() {
+        Some(e.kind())
+    } else if let Some(e) = err.downcast_ref::<EncodeReaderError>() {
+        Some(e.kind())
+    } else if err.downcast_ref::<std::io::Error>().is_some() {
+        Some(ErrorKind::Io)
+    } else {
+        None
+    }
+}
+
+fn b64_error_json(err: &B64Error, message: String) -> Value {
+    let mut json = json!({
+        "ok": false,
+        "error": format!("{:?}", err.kind()),
+        "message": message,
+    });
+
+    if let Some(c) = err.invalid_char() {
+        json["char"] = c.to_string().into();
+    }
+    if let Some(index) = err.position() {
+        json["index"] = index.into();
+    }
+    match err {
+        B64Error::Truncated { expected, actual } => {
+            json["expected"] = (*expected).into();
+            json["actual"] = (*actual).into();
+        }
+        B64Error::WrongAlphabet { suggestion, .. } => {
+            json["suggestion"] = AnyAlphabet::from(*suggestion).name().into();
+        }
+        _ => {}
+    }
+
+    json
+}
@@ -0,0 +1,397 @@
+//! Encode files straight to the output, without holding the file or
+//! its encoding in memory
+//!
+//! Files are memory mapped when asked to, or when they're at least
+//! [`MMAP_THRESHOLD`] bytes, falling back to reading them a chunk at
+//! a time if mapping fails. A mapped file is encoded at the length it
+//! had when it was mapped, so anything appended while it's encoded is
+//! left out, & truncating it while it's encoded can kill the process
+//! with `SIGBUS` on Unix. Files read a chunk at a time are encoded up
+//! to wherever their end is when it's reached, so `--no-mmap` is the
+//! safer choice for files that are still being written
+
+use std::{
+    fs::File,
+    io::{self, Read, Write},
+};
+
+use baze64::{alphabet::Alphabet, stream::Base64Encoder, B64Error};
+use memmap2::Mmap;
+use sha2::{Digest, Sha256};
+
+/// Files at least this many bytes are mapped unless `--no-mmap` is
+/// given
+pub const MMAP_THRESHOLD: u64 = 256 * 1024 * 1024;
+
+/// How many bytes of the file are encoded at a time, a multiple of 3
+/// so no bytes are carried from one chunk to the next
+const CHUNK_LEN: usize = 3 * 256 * 1024;
+
+/// Whether to memory map the file being encoded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MmapMode {
+    /// Map files of at least [`MMAP_THRESHOLD`] bytes
+    Auto,
+    Always,
+    Never,
+}
+
+impl MmapMode {
+    /// The mode for the `--mmap` & `--no-mmap` flags
+    pub fn from_flags(mmap: bool, no_mmap: bool) -> Self {
+        match (mmap, no_mmap) {
+            (true, _) => Self::Always,
+            (_, true) => Self::Never,
+            _ => Self::Auto,
+        }
+    }
+
+    /// Whether to map a file of `len` bytes
+    pub fn maps(self, len: u64) -> bool {
+        match self {
+            Self::Auto => len >= MMAP_THRESHOLD,
+            Self::Always => true,
+            Self::Never => false,
+        }
+    }
+}
+
+/// The contents of a file being encoded
+pub enum Source {
+    Mapped(Mmap),
+    Read(File),
+}
+
+impl Source {
+    /// Map `file` if `mode` says to, otherwise read it a chunk at a
+    /// time
+    pub fn new(file: File, mode: MmapMode) -> io::Result<Self> {
+        let len = file.metadata()?.len();
+        // Empty files can't be mapped on every platform, & there's
+        // nothing to gain from it anyway
+        if len == 0 || !mode.maps(len) {
+            return Ok(Self::Read(file));
+        }
+
+        // SAFETY: the map is only read from, & the docs for this
+        // module warn about the file changing while it's mapped
+        match unsafe { Mmap::map(&file) } {
+            Ok(map) => {
+                #[cfg(unix)]
+                let _ = map.advise(memmap2::Advice::Sequential);
+                Ok(Self::Mapped(map))
+            }
+            Err(_) => Ok(Self::Read(file)),
+        }
+    }
+
+    /// Write all of the file's bytes to `writer`
+    fn copy_to<W>(self, writer: &mut W) -> io::Result<()>
+    where
+        W: Write,
+    {
+        match self {
+            Self::Mapped(map) => {
+                for (i, chunk) in map.chunks(CHUNK_LEN).enumerate() {
+                    writer.write_all(chunk)?;
+                    // Let the OS drop the pages that have been encoded,
+                    // so they don't stay resident until the end
+                    #[cfg(unix)]
+                    // SAFETY: the map is a shared mapping of a file, so
+                    // dropped pages are read back from the file if
+                    // they're used again, & these never are
+                    let _ = unsafe {
+                        map.unchecked_advise_range(
+                            memmap2::UncheckedAdvice::DontNeed,
+                            i * CHUNK_LEN,
+                            chunk.len(),
+                        )
+                    };
+                }
+            }
+            Self::Read(mut file) => {
+                let mut chunk = vec![0; CHUNK_LEN];
+                loop {
+                    match file.read(&mut chunk) {
+                        Ok(0) => break,
+                        Ok(n) => writer.write_all(&chunk[..n])?,
+                        Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                        Err(e) => return Err(e),
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Encode all of `source` to `writer`, without padding if
+/// `no_padding` is set, feeding the file's bytes to `hasher` too if
+/// there is one
+pub fn encode<A, W>(
+    source: Source,
+    alphabet: A,
+    no_padding: bool,
+    writer: W,
+    hasher: Option<&mut Sha256>,
+) -> io::Result<W>
+where
+    A: Alphabet,
+    W: Write,
+{
+    if no_padding {
+        encode_with(source, Unpadded(alphabet), writer, hasher)
+    } else {
+        encode_with(source, alphabet, writer, hasher)
+    }
+}
+
+fn encode_with<A, W>(
+    source: Source,
+    alphabet: A,
+    writer: W,
+    hasher: Option<&mut Sha256>,
+) -> io::Result<W>
+where
+    A: Alphabet,
+    W: Write,
+{
+    let mut encoder = Base64Encoder::new_with(writer, alphabet);
+    match hasher {
+        Some(hasher) => source.copy_to(&mut Hashing {
+            inner: &mut encoder,
+            hasher,
+        })?,
+        None => source.copy_to(&mut encoder)?,
+    }
+    encoder.finish()
+}
+
+/// Hashes everything written through it on the way to `inner`, so
+/// a file's only read once to both encode & hash it
+struct Hashing<'a, W> {
+    inner: W,
+    hasher: &'a mut Sha256,
+}
+
+impl<W> Write for Hashing<'_, W>
+where
+    W: Write,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// An alphabet with its padding taken away, for `--no-padding`
+struct Unpadded<A>(A);
+
+impl<A> Alphabet for Unpadded<A>
+where
+    A: Alphabet,
+{
+    fn padding(&self) -> Option<char> {
+        None
+    }
+
+    fn encode_bits(&self, bits: u8) -> char {
+        self.0.encode_bits(bits)
+    }
+
+    fn decode_char(&self, c: char) -> Result<u8, B64Error> {
+        self.0.decode_char(c)
+    }
+
+    fn name(&self) -> &'static str {
+        self.0.name()
+    }
+}
+
+/// Splits the text written through it into lines, counting the bytes
+/// written
+pub struct Lines<W> {
+    writer: W,
+    columns: Option<usize>,
+    /// What's written between lines, which is escaped inside JSON
+    newline: &'static str,
+    column: usize,
+    written: usize,
+}
+
+impl<W> Lines<W>
+where
+    W: Write,
+{
+    /// Split the text into lines of `columns` bytes, or leave it in
+    /// one line if that's [`None`]
+    pub fn new(writer: W, columns: Option<usize>, newline: &'static str) -> Self {
+        Self {
+            writer,
+            columns,
+            newline,
+            column: 0,
+            written: 0,
+        }
+    }
+
+    /// How many bytes have been written, including newlines
+    pub fn written(&self) -> usize {
+        self.written
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn write_counted(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.writer.write_all(bytes)?;
+        self.written += bytes.len();
+        Ok(())
+    }
+}
+
+impl<W> Write for Lines<W>
+where
+    W: Write,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let Some(columns) = self.columns else {
+            self.write_counted(buf)?;
+            return Ok(buf.len());
+        };
+
+        let mut rest = buf;
+        while !rest.is_empty() {
+            // Only start a new line once there's something to put on
+            // it, so the text never ends with a newline
+            if self.column == columns {
+                self.write_counted(self.newline.as_bytes())?;
+                self.column = 0;
+            }
+            let (line, next) = rest.split_at((columns - self.column).min(rest.len()));
+            self.write_counted(line)?;
+            self.column += line.len();
+            rest = next;
+        }
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use baze64::{alphabet::Standard, Base64String};
+
+    use super::*;
+
+    fn temp_file(data: &[u8]) -> (tempfile::TempDir, File) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data");
+        std::fs::write(&path, data).unwrap();
+        let file = File::open(path).unwrap();
+        (dir, file)
+    }
+
+    #[test]
+    fn auto_maps_large_files() {
+        assert!(!MmapMode::Auto.maps(MMAP_THRESHOLD - 1));
+        assert!(MmapMode::Auto.maps(MMAP_THRESHOLD));
+        assert!(MmapMode::Always.maps(1));
+        assert!(!MmapMode::Never.maps(u64::MAX));
+
+        assert_eq!(MmapMode::from_flags(false, false), MmapMode::Auto);
+        assert_eq!(MmapMode::from_flags(true, false), MmapMode::Always);
+        assert_eq!(MmapMode::from_flags(false, true), MmapMode::Never);
+    }
+
+    #[test]
+    fn empty_file_is_read() {
+        let (_dir, file) = temp_file(b"");
+        let source = Source::new(file, MmapMode::Always).unwrap();
+
+        assert!(matches!(source, Source::Read(_)));
+        assert_eq!(
+            encode(source, Standard::new(), false, Vec::new(), None).unwrap(),
+            b""
+        );
+    }
+
+    #[test]
+    fn mapped_matches_read() {
+        let data = (0..CHUNK_LEN * 2 + 1000)
+            .map(|i| (i * 7 % 251) as u8)
+            .collect::<Vec<_>>();
+        let expected = Base64String::<Standard>::encode(&data).to_string();
+
+        for mode in [MmapMode::Always, MmapMode::Never] {
+            let (_dir, file) = temp_file(&data);
+            let source = Source::new(file, mode).unwrap();
+            let encoded = encode(source, Standard::new(), false, Vec::new(), None).unwrap();
+
+            assert_eq!(String::from_utf8(encoded).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn hashes_while_encoding() {
+        let data = vec![0xa5; CHUNK_LEN + 2];
+        let (_dir, file) = temp_file(&data);
+        let source = Source::new(file, MmapMode::Never).unwrap();
+        let mut hasher = Sha256::new();
+
+        let encoded = encode(
+            source,
+            Standard::new(),
+            false,
+            Vec::new(),
+            Some(&mut hasher),
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(encoded).unwrap(),
+            Base64String::<Standard>::encode(&data).to_string()
+        );
+        assert_eq!(hasher.finalize(), Sha256::digest(&data));
+    }
+
+    #[test]
+    fn encodes_without_padding() {
+        let (_dir, file) = temp_file(b"fo");
+        let source = Source::new(file, MmapMode::Never).unwrap();
+
+        assert_eq!(
+            encode(source, Standard::new(), true, Vec::new(), None).unwrap(),
+            b"Zm8"
+        );
+    }
+
+    #[test]
+    fn wraps_lines() {
+        let mut lines = Lines::new(Vec::new(), Some(4), "\n");
+        lines.write_all(b"Zm9vY").unwrap();
+        lines.write_all(b"mFy").unwrap();
+        lines.write_all(b"YmF6").unwrap();
+
+        assert_eq!(lines.written(), 14);
+        assert_eq!(lines.into_inner(), b"Zm9v\nYmFy\nYmF6");
+    }
+
+    #[test]
+    fn escaped_newlines() {
+        let mut lines = Lines::new(Vec::new(), Some(2), "\\n");
+        lines.write_all(b"Zm9v").unwrap();
+
+        assert_eq!(lines.into_inner(), b"Zm\\n9v");
+    }
+}
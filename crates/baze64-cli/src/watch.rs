@@ -0,0 +1,291 @@
+use std::{
+    ffi::OsStr,
+    fs,
+    io::{self, ErrorKind, Write},
+    path::Path,
+    sync::mpsc::{self, RecvTimeoutError},
+    time::{Duration, Instant},
+};
+
+use color_eyre::{eyre::eyre, Report, Result};
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use serde_json::json;
+
+use crate::report;
+
+/// How long the file must go without changing before it's
+/// re-encoded, so a save that writes several times is encoded once
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// What the event loop is woken up by
+enum Message {
+    File(notify::Result<Event>),
+    Interrupt,
+}
+
+/// The result of re-encoding the watched file
+#[derive(Debug)]
+pub enum Refresh {
+    /// The file was encoded to this output
+    Encoded(String),
+    /// The file doesn't exist, as happens part way through an editor
+    /// replacing it
+    Missing,
+    /// Encoding failed
+    Failed(Report),
+}
+
+/// Collects file events, deciding when they've settled enough for
+/// the file to be re-encoded
+#[derive(Debug)]
+pub struct Debounce {
+    delay: Duration,
+    last_event: Option<Instant>,
+}
+
+impl Debounce {
+    pub fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            last_event: None,
+        }
+    }
+
+    /// Note the file changed at `at`
+    pub fn event(&mut self, at: Instant) {
+        self.last_event = Some(at);
+    }
+
+    /// How long to wait at `now` for more events before re-encoding,
+    /// or [`None`] to wait until there's an event
+    pub fn timeout(&self, now: Instant) -> Option<Duration> {
+        self.last_event
+            .map(|at| (at + self.delay).saturating_duration_since(now))
+    }
+
+    /// Whether the file should be re-encoded at `now`, starting to
+    /// wait for the next event if so
+    pub fn take_ready(&mut self, now: Instant) -> bool {
+        match self.last_event {
+            Some(at) if now >= at + self.delay => {
+                self.last_event = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Whether `event` is a change to the file called `file_name`
+///
+/// The directory holding the file is what's watched, so the file
+/// can be followed through being deleted & recreated
+pub fn is_change_to(event: &Event, file_name: &OsStr) -> bool {
+    matches!(
+        event.kind,
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_) | EventKind::Any
+    ) && event
+        .paths
+        .iter()
+        .any(|path| path.file_name() == Some(file_name))
+}
+
+/// Re-encode the file at `input` with `encode`
+pub fn refresh<F>(input: &Path, encode: &mut F) -> Refresh
+where
+    F: FnMut(&Path) -> Result<String>,
+{
+    match encode(input) {
+        Ok(encoded) => Refresh::Encoded(encoded),
+        Err(e) if is_not_found(&e) => Refresh::Missing,
+        Err(e) => Refresh::Failed(e),
+    }
+}
+
+fn is_not_found(err: &Report) -> bool {
+    err.chain().any(|e| {
+        e.downcast_ref::<std::io::Error>()
+            .is_some_and(|e| e.kind() == ErrorKind::NotFound)
+    })
+}
+
+/// Encode the file at `input` with `encode` whenever it changes,
+/// writing the output to `output` or stdout, until interrupted
+pub fn watch<F>(input: &Path, output: Option<&Path>, json: bool, mut encode: F) -> Result<()>
+where
+    F: FnMut(&Path) -> Result<String>,
+{
+    let file_name = input
+        .file_name()
+        .ok_or_else(|| eyre!("`{}` isn't a file", input.display()))?;
+    let dir = match input.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+
+    let (tx, rx) = mpsc::channel();
+    let interrupt = tx.clone();
+    ctrlc::set_handler(move || {
+        let _ = interrupt.send(Message::Interrupt);
+    })?;
+    let mut watcher = notify::recommended_watcher(move |event| {
+        let _ = tx.send(Message::File(event));
+    })?;
+    watcher.watch(dir, RecursiveMode::NonRecursive)?;
+
+    let mut debounce = Debounce::new(DEBOUNCE);
+    // Encode straight away, rather than waiting for a change
+    debounce.event(Instant::now() - DEBOUNCE);
+    let mut missing = false;
+
+    loop {
+        let now = Instant::now();
+        if debounce.take_ready(now) {
+            match refresh(input, &mut encode) {
+                Refresh::Encoded(encoded) => {
+                    missing = false;
+                    emit(&encoded, output, json)?;
+                }
+                // Only say so once, as an editor may take a while
+                // to write the new file
+                Refresh::Missing if !missing => {
+                    missing = true;
+                    eprintln!("`{}` is missing, waiting for it to return", input.display());
+                }
+                Refresh::Missing => {}
+                Refresh::Failed(e) if json => eprintln!("{}", report::error_json(&e)),
+                Refresh::Failed(e) => eprintln!("Error: {e}"),
+            }
+        }
+
+        let message = match debounce.timeout(now) {
+            Some(timeout) => rx.recv_timeout(timeout),
+            None => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+        match message {
+            Ok(Message::File(Ok(event))) if is_change_to(&event, file_name) => {
+                debounce.event(Instant::now());
+            }
+            Ok(Message::File(Ok(_))) | Err(RecvTimeoutError::Timeout) => {}
+            Ok(Message::File(Err(e))) => eprintln!("Error: {e}"),
+            Ok(Message::Interrupt) | Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+
+    watcher.unwatch(dir)?;
+    Ok(())
+}
+
+/// Write one encoding of the watched file to `output`, or to stdout
+/// followed by an empty line to separate it from the next
+fn emit(encoded: &str, output: Option<&Path>, json: bool) -> Result<()> {
+    match output {
+        Some(path) => {
+            fs::write(path, format!("{encoded}\n"))?;
+            if json {
+                writeln!(
+                    io::stdout().lock(),
+                    "{}",
+                    json!({"ok": true, "written": encoded.len()})
+                )?;
+            } else {
+                eprintln!("Wrote `{}`", path.display());
+            }
+        }
+        None if json => writeln!(
+            io::stdout().lock(),
+            "{}",
+            json!({"ok": true, "encoded": encoded})
+        )?,
+        None => writeln!(io::stdout().lock(), "{encoded}\n")?,
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use notify::event::{CreateKind, DataChange, ModifyKind, RemoveKind};
+
+    use super::*;
+
+    fn event(kind: EventKind, path: &str) -> Event {
+        Event::new(kind).add_path(PathBuf::from(path))
+    }
+
+    #[test]
+    fn changes_to_the_file() {
+        let name = OsStr::new("logo.svg");
+
+        for kind in [
+            EventKind::Create(CreateKind::File),
+            EventKind::Modify(ModifyKind::Data(DataChange::Content)),
+            EventKind::Remove(RemoveKind::File),
+        ] {
+            assert!(is_change_to(&event(kind, "/site/logo.svg"), name));
+        }
+    }
+
+    #[test]
+    fn ignores_other_events() {
+        let name = OsStr::new("logo.svg");
+        let modify = EventKind::Modify(ModifyKind::Data(DataChange::Content));
+
+        assert!(!is_change_to(&event(modify, "/site/index.html"), name));
+        assert!(!is_change_to(
+            &event(
+                EventKind::Access(notify::event::AccessKind::Read),
+                "/site/logo.svg"
+            ),
+            name
+        ));
+    }
+
+    #[test]
+    fn debounces_bursts() {
+        let start = Instant::now();
+        let mut debounce = Debounce::new(Duration::from_millis(100));
+        assert_eq!(debounce.timeout(start), None);
+
+        debounce.event(start);
+        debounce.event(start + Duration::from_millis(60));
+        assert!(!debounce.take_ready(start + Duration::from_millis(120)));
+        assert_eq!(
+            debounce.timeout(start + Duration::from_millis(120)),
+            Some(Duration::from_millis(40))
+        );
+
+        assert!(debounce.take_ready(start + Duration::from_millis(160)));
+        assert!(!debounce.take_ready(start + Duration::from_millis(300)));
+        assert_eq!(debounce.timeout(start), None);
+    }
+
+    #[test]
+    fn refresh_missing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut encode = |path: &Path| Ok(fs::read_to_string(path)?);
+
+        assert!(matches!(
+            refresh(&dir.path().join("gone"), &mut encode),
+            Refresh::Missing
+        ));
+    }
+
+    #[test]
+    fn refresh_reports_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("file");
+        fs::write(&path, "data").unwrap();
+
+        assert!(matches!(
+            refresh(&path, &mut |path: &Path| Ok(fs::read_to_string(path)?)),
+            Refresh::Encoded(s) if s == "data"
+        ));
+        assert!(matches!(
+            refresh(&path, &mut |_: &Path| Err(eyre!("oops"))),
+            Refresh::Failed(_)
+        ));
+    }
+}
@@ -0,0 +1,105 @@
+#ifndef BAZE64_H
+#define BAZE64_H
+
+/* Generated by cbindgen, don't edit by hand */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Use the standard base64 alphabet
+#define BAZE64_ALPHABET_STANDARD 0
+
+// Use the URL & filename safe base64 alphabet
+#define BAZE64_ALPHABET_URL_SAFE 1
+
+// The operation succeeded
+#define BAZE64_OK 0
+
+// A required pointer was null
+#define BAZE64_ERR_NULL_POINTER -1
+
+// The alphabet wasn't one of the `BAZE64_ALPHABET_*` constants
+#define BAZE64_ERR_INVALID_ALPHABET -2
+
+// The output buffer was too small. `out_len` is set to the
+// capacity needed
+#define BAZE64_ERR_BUFFER_TOO_SMALL -3
+
+// The alphabet decoded a character to a value that doesn't fit
+// in 6 bits, which the built-in alphabets never do
+#define BAZE64_ERR_BITS_OOB -4
+
+// The input contained a character outside the alphabet, or
+// wasn't valid UTF-8
+#define BAZE64_ERR_INVALID_CHAR -5
+
+// The input ended part way through a quad
+#define BAZE64_ERR_TRUNCATED -6
+
+// Writing the output failed
+#define BAZE64_ERR_IO -7
+
+// The decoded output wasn't valid UTF-8
+#define BAZE64_ERR_INVALID_UTF8 -8
+
+// The library panicked, which is always a bug
+#define BAZE64_ERR_PANIC -9
+
+// An error not covered by any other code
+#define BAZE64_ERR_OTHER -10
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// The number of bytes needed to hold `in_len` bytes encoded
+// as padded base64
+//
+// Returns `SIZE_MAX` if the size would overflow
+size_t baze64_encoded_len(size_t in_len);
+
+// The most bytes `in_len` bytes of base64 can decode to
+//
+// Padded input decodes to up to 2 fewer bytes than this
+size_t baze64_decoded_len(size_t in_len);
+
+// Encode `in_len` bytes from `in` as base64 into `out`, writing
+// the number of bytes used to `out_len`
+//
+// The output isn't null-terminated
+//
+// # Safety
+// `in` must be valid for reads of `in_len` bytes & `out` must be
+// valid for writes of `out_cap` bytes, though either may be null
+// if its length is 0. `out_len` must be valid for writes
+int32_t baze64_encode(const uint8_t *in,
+                      size_t in_len,
+                      uint8_t *out,
+                      size_t out_cap,
+                      size_t *out_len,
+                      int32_t alphabet);
+
+// Decode `in_len` bytes of base64 text from `in` into `out`,
+// writing the number of bytes used to `out_len`
+//
+// The input doesn't need to be null-terminated, & may be unpadded
+//
+// # Safety
+// `in` must be valid for reads of `in_len` bytes & `out` must be
+// valid for writes of `out_cap` bytes, though either may be null
+// if its length is 0. `out_len` must be valid for writes
+int32_t baze64_decode(const uint8_t *in,
+                      size_t in_len,
+                      uint8_t *out,
+                      size_t out_cap,
+                      size_t *out_len,
+                      int32_t alphabet);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* BAZE64_H */
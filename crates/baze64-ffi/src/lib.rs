@@ -0,0 +1,347 @@
+//! A C-compatible interface for encoding & decoding base64 with
+//! [`baze64`]
+//!
+//! Every function writes into a buffer provided by the caller, so
+//! nothing is ever allocated across the boundary. Use
+//! [`baze64_encoded_len`] & [`baze64_decoded_len`] to size buffers.
+//! The C header is at `include/baze64.h`, & is regenerated with
+//! `cbindgen --config cbindgen.toml --output include/baze64.h`
+//!
+//! Functions return [`BAZE64_OK`] on success, or one of the negative
+//! `BAZE64_ERR_*` codes on failure
+
+use std::{panic, slice};
+
+use baze64::{
+    alphabet::{Standard, UrlSafe},
+    fixed::{self, DecodeSliceError},
+    B64Error, ErrorKind,
+};
+
+/// Use the standard base64 alphabet
+pub const BAZE64_ALPHABET_STANDARD: i32 = 0;
+/// Use the URL & filename safe base64 alphabet
+pub const BAZE64_ALPHABET_URL_SAFE: i32 = 1;
+
+/// The operation succeeded
+pub const BAZE64_OK: i32 = 0;
+/// A required pointer was null
+pub const BAZE64_ERR_NULL_POINTER: i32 = -1;
+/// The alphabet wasn't one of the `BAZE64_ALPHABET_*` constants
+pub const BAZE64_ERR_INVALID_ALPHABET: i32 = -2;
+/// The output buffer was too small. `out_len` is set to the
+/// capacity needed
+pub const BAZE64_ERR_BUFFER_TOO_SMALL: i32 = -3;
+/// The alphabet decoded a character to a value that doesn't fit
+/// in 6 bits, which the built-in alphabets never do
+pub const BAZE64_ERR_BITS_OOB: i32 = -4;
+/// The input contained a character outside the alphabet, or
+/// wasn't valid UTF-8
+pub const BAZE64_ERR_INVALID_CHAR: i32 = -5;
+/// The input ended part way through a quad
+pub const BAZE64_ERR_TRUNCATED: i32 = -6;
+/// Writing the output failed
+pub const BAZE64_ERR_IO: i32 = -7;
+/// The decoded output wasn't valid UTF-8
+pub const BAZE64_ERR_INVALID_UTF8: i32 = -8;
+/// The library panicked, which is always a bug
+pub const BAZE64_ERR_PANIC: i32 = -9;
+/// An error not covered by any other code
+pub const BAZE64_ERR_OTHER: i32 = -10;
+
+/// The number of bytes needed to hold `in_len` bytes encoded
+/// as padded base64
+///
+/// Returns `SIZE_MAX` if the size would overflow
+#[no_mangle]
+pub extern "C" fn baze64_encoded_len(in_len: usize) -> usize {
+    in_len.div_ceil(3).saturating_mul(4)
+}
+
+/// The most bytes `in_len` bytes of base64 can decode to
+///
+/// Padded input decodes to up to 2 fewer bytes than this
+#[no_mangle]
+pub extern "C" fn baze64_decoded_len(in_len: usize) -> usize {
+    let tail = match in_len % 4 {
+        2 => 1,
+        3 => 2,
+        _ => 0,
+    };
+    in_len / 4 * 3 + tail
+}
+
+/// Encode `in_len` bytes from `in` as base64 into `out`, writing
+/// the number of bytes used to `out_len`
+///
+/// The output isn't null-terminated
+///
+/// # Safety
+/// `in` must be valid for reads of `in_len` bytes & `out` must be
+/// valid for writes of `out_cap` bytes, though either may be null
+/// if its length is 0. `out_len` must be valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn baze64_encode(
+    r#in: *const u8,
+    in_len: usize,
+    out: *mut u8,
+    out_cap: usize,
+    out_len: *mut usize,
+    alphabet: i32,
+) -> i32 {
+    guard(|| {
+        let (Some(input), Some(out)) = (unsafe { input_slice(r#in, in_len) }, unsafe {
+            output_slice(out, out_cap)
+        }) else {
+            return BAZE64_ERR_NULL_POINTER;
+        };
+        if out_len.is_null() {
+            return BAZE64_ERR_NULL_POINTER;
+        }
+
+        let result = match alphabet {
+            BAZE64_ALPHABET_STANDARD => fixed::encode_to_slice(input, out, &Standard::new()),
+            BAZE64_ALPHABET_URL_SAFE => fixed::encode_to_slice(input, out, &UrlSafe::new()),
+            _ => return BAZE64_ERR_INVALID_ALPHABET,
+        };
+
+        match result {
+            Ok(len) => {
+                unsafe { out_len.write(len) };
+                BAZE64_OK
+            }
+            Err(e) => {
+                unsafe { out_len.write(e.required) };
+                BAZE64_ERR_BUFFER_TOO_SMALL
+            }
+        }
+    })
+}
+
+/// Decode `in_len` bytes of base64 text from `in` into `out`,
+/// writing the number of bytes used to `out_len`
+///
+/// The input doesn't need to be null-terminated, & may be unpadded
+///
+/// # Safety
+/// `in` must be valid for reads of `in_len` bytes & `out` must be
+/// valid for writes of `out_cap` bytes, though either may be null
+/// if its length is 0. `out_len` must be valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn baze64_decode(
+    r#in: *const u8,
+    in_len: usize,
+    out: *mut u8,
+    out_cap: usize,
+    out_len: *mut usize,
+    alphabet: i32,
+) -> i32 {
+    guard(|| {
+        let (Some(input), Some(out)) = (unsafe { input_slice(r#in, in_len) }, unsafe {
+            output_slice(out, out_cap)
+        }) else {
+            return BAZE64_ERR_NULL_POINTER;
+        };
+        if out_len.is_null() {
+            return BAZE64_ERR_NULL_POINTER;
+        }
+        let Ok(input) = core::str::from_utf8(input) else {
+            return BAZE64_ERR_INVALID_CHAR;
+        };
+
+        let result = match alphabet {
+            BAZE64_ALPHABET_STANDARD => fixed::decode_to_slice(input, out, &Standard::new()),
+            BAZE64_ALPHABET_URL_SAFE => fixed::decode_to_slice(input, out, &UrlSafe::new()),
+            _ => return BAZE64_ERR_INVALID_ALPHABET,
+        };
+
+        match result {
+            Ok(len) => {
+                unsafe { out_len.write(len) };
+                BAZE64_OK
+            }
+            Err(DecodeSliceError::BufferTooSmall { required, .. }) => {
+                unsafe { out_len.write(required) };
+                BAZE64_ERR_BUFFER_TOO_SMALL
+            }
+            Err(DecodeSliceError::Base64Error(e)) => error_code(&e),
+            Err(_) => BAZE64_ERR_OTHER,
+        }
+    })
+}
+
+/// Get the error code for `err`
+fn error_code(err: &B64Error) -> i32 {
+    match err.kind() {
+        ErrorKind::InvalidChar => BAZE64_ERR_INVALID_CHAR,
+        ErrorKind::Truncated => BAZE64_ERR_TRUNCATED,
+        ErrorKind::DecodedValueOOB => BAZE64_ERR_BITS_OOB,
+        ErrorKind::Io => BAZE64_ERR_IO,
+        ErrorKind::InvalidUtf8 => BAZE64_ERR_INVALID_UTF8,
+        _ => BAZE64_ERR_OTHER,
+    }
+}
+
+/// Run `f`, returning [`BAZE64_ERR_PANIC`] rather than unwinding
+/// into the caller if it panics
+fn guard<F>(f: F) -> i32
+where
+    F: FnOnce() -> i32 + panic::UnwindSafe,
+{
+    panic::catch_unwind(f).unwrap_or(BAZE64_ERR_PANIC)
+}
+
+/// Build a slice from a C pointer & length, allowing null when
+/// the length is 0
+///
+/// # Safety
+/// If `ptr` isn't null, it must be valid for reads of `len` bytes
+unsafe fn input_slice<'a>(ptr: *const u8, len: usize) -> Option<&'a [u8]> {
+    if len == 0 {
+        Some(&[])
+    } else if ptr.is_null() {
+        None
+    } else {
+        Some(unsafe { slice::from_raw_parts(ptr, len) })
+    }
+}
+
+/// Build a mutable slice from a C pointer & length, allowing null
+/// when the length is 0
+///
+/// # Safety
+/// If `ptr` isn't null, it must be valid for writes of `len` bytes
+unsafe fn output_slice<'a>(ptr: *mut u8, len: usize) -> Option<&'a mut [u8]> {
+    if len == 0 {
+        Some(&mut [])
+    } else if ptr.is_null() {
+        None
+    } else {
+        Some(unsafe { slice::from_raw_parts_mut(ptr, len) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ptr;
+
+    use super::*;
+
+    fn encode(input: &[u8], out_cap: usize, alphabet: i32) -> (i32, Vec<u8>, usize) {
+        let mut out = vec![0; out_cap];
+        let mut out_len = 0;
+        let code = unsafe {
+            baze64_encode(
+                input.as_ptr(),
+                input.len(),
+                out.as_mut_ptr(),
+                out.len(),
+                &mut out_len,
+                alphabet,
+            )
+        };
+        out.truncate(out_len.min(out_cap));
+        (code, out, out_len)
+    }
+
+    fn decode(input: &[u8], out_cap: usize, alphabet: i32) -> (i32, Vec<u8>, usize) {
+        let mut out = vec![0; out_cap];
+        let mut out_len = 0;
+        let code = unsafe {
+            baze64_decode(
+                input.as_ptr(),
+                input.len(),
+                out.as_mut_ptr(),
+                out.len(),
+                &mut out_len,
+                alphabet,
+            )
+        };
+        out.truncate(out_len.min(out_cap));
+        (code, out, out_len)
+    }
+
+    #[test]
+    fn roundtrip() {
+        let data = (0..=255).collect::<Vec<u8>>();
+        for alphabet in [BAZE64_ALPHABET_STANDARD, BAZE64_ALPHABET_URL_SAFE] {
+            for size in 0..=data.len() {
+                let payload = &data[..size];
+                let (code, encoded, _) = encode(payload, baze64_encoded_len(size), alphabet);
+                assert_eq!(code, BAZE64_OK);
+
+                let (code, decoded, _) =
+                    decode(&encoded, baze64_decoded_len(encoded.len()), alphabet);
+                assert_eq!(code, BAZE64_OK);
+                assert_eq!(decoded, payload);
+            }
+        }
+    }
+
+    #[test]
+    fn lengths() {
+        assert_eq!(baze64_encoded_len(0), 0);
+        assert_eq!(baze64_encoded_len(1), 4);
+        assert_eq!(baze64_encoded_len(3), 4);
+        assert_eq!(baze64_encoded_len(4), 8);
+        assert_eq!(baze64_encoded_len(usize::MAX), usize::MAX);
+        assert_eq!(baze64_decoded_len(0), 0);
+        assert_eq!(baze64_decoded_len(2), 1);
+        assert_eq!(baze64_decoded_len(3), 2);
+        assert_eq!(baze64_decoded_len(8), 6);
+    }
+
+    #[test]
+    fn buffer_too_small() {
+        let (code, _, required) = encode(b"foobar", 7, BAZE64_ALPHABET_STANDARD);
+        assert_eq!((code, required), (BAZE64_ERR_BUFFER_TOO_SMALL, 8));
+        let (code, _, required) = decode(b"Zm9vYmFy", 5, BAZE64_ALPHABET_STANDARD);
+        assert_eq!((code, required), (BAZE64_ERR_BUFFER_TOO_SMALL, 6));
+    }
+
+    #[test]
+    fn decode_errors() {
+        assert_eq!(
+            decode(b"Zm9v#mFy", 6, BAZE64_ALPHABET_STANDARD).0,
+            BAZE64_ERR_INVALID_CHAR
+        );
+        assert_eq!(
+            decode(b"Zm9v-_8=", 6, BAZE64_ALPHABET_STANDARD).0,
+            BAZE64_ERR_INVALID_CHAR
+        );
+        assert_eq!(
+            decode(b"Zm9v\xff", 6, BAZE64_ALPHABET_STANDARD).0,
+            BAZE64_ERR_INVALID_CHAR
+        );
+        assert_eq!(
+            decode(b"Zm9vY", 6, BAZE64_ALPHABET_STANDARD).0,
+            BAZE64_ERR_TRUNCATED
+        );
+    }
+
+    #[test]
+    fn invalid_alphabet() {
+        assert_eq!(encode(b"foo", 4, 2).0, BAZE64_ERR_INVALID_ALPHABET);
+        assert_eq!(decode(b"Zm9v", 3, -1).0, BAZE64_ERR_INVALID_ALPHABET);
+    }
+
+    #[test]
+    fn null_pointers() {
+        let mut out = [0; 4];
+        let mut out_len = 0;
+
+        assert_eq!(
+            unsafe { baze64_encode(ptr::null(), 3, out.as_mut_ptr(), 4, &mut out_len, 0) },
+            BAZE64_ERR_NULL_POINTER
+        );
+        assert_eq!(
+            unsafe { baze64_encode(b"foo".as_ptr(), 3, out.as_mut_ptr(), 4, ptr::null_mut(), 0) },
+            BAZE64_ERR_NULL_POINTER
+        );
+        assert_eq!(
+            unsafe { baze64_decode(ptr::null(), 0, ptr::null_mut(), 0, &mut out_len, 0) },
+            BAZE64_OK
+        );
+        assert_eq!(out_len, 0);
+    }
+}
@@ -0,0 +1,39 @@
+//! Compile & run the C program in `tests/c` against the library,
+//! checking the header & exported symbols work end to end
+
+#![cfg(unix)]
+
+use std::{env, path::PathBuf, process::Command};
+
+#[test]
+fn c_roundtrip() {
+    // Integration tests are built into `target/<profile>/deps`, which
+    // is where cargo builds the library itself too
+    let exe = env::current_exe().unwrap();
+    let lib_dir = exe.parent().unwrap();
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let out_dir = tempfile::tempdir().unwrap();
+    let program = out_dir.path().join("roundtrip");
+
+    let status = Command::new(env::var("CC").unwrap_or_else(|_| "cc".to_string()))
+        .arg(manifest_dir.join("tests/c/roundtrip.c"))
+        .arg("-I")
+        .arg(manifest_dir.join("include"))
+        .arg("-L")
+        .arg(lib_dir)
+        .arg(format!("-Wl,-rpath,{}", lib_dir.display()))
+        .arg("-lbaze64_ffi")
+        .arg("-o")
+        .arg(&program)
+        .status()
+        .expect("a C compiler should be installed");
+    assert!(status.success(), "failed to compile the C test program");
+
+    let output = Command::new(&program).output().unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(output.stdout, b"ok\n");
+}
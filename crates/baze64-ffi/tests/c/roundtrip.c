@@ -0,0 +1,56 @@
+#include <stdio.h>
+#include <string.h>
+
+#include "baze64.h"
+
+#define CHECK(cond)                                                       \
+    do {                                                                  \
+        if (!(cond)) {                                                    \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__,        \
+                    __LINE__, #cond);                                     \
+            return 1;                                                     \
+        }                                                                 \
+    } while (0)
+
+int main(void) {
+    const char *text = "foobar";
+    size_t text_len = strlen(text);
+    uint8_t encoded[16];
+    uint8_t decoded[16];
+    size_t len = 0;
+
+    CHECK(baze64_encoded_len(text_len) == 8);
+    CHECK(baze64_encode((const uint8_t *)text, text_len, encoded,
+                        sizeof(encoded), &len, BAZE64_ALPHABET_STANDARD) ==
+          BAZE64_OK);
+    CHECK(len == 8);
+    CHECK(memcmp(encoded, "Zm9vYmFy", len) == 0);
+
+    CHECK(baze64_decode(encoded, len, decoded, baze64_decoded_len(len), &len,
+                        BAZE64_ALPHABET_STANDARD) == BAZE64_OK);
+    CHECK(len == text_len);
+    CHECK(memcmp(decoded, text, len) == 0);
+
+    const uint8_t bytes[] = {0xfb, 0xff};
+    CHECK(baze64_encode(bytes, sizeof(bytes), encoded, sizeof(encoded), &len,
+                        BAZE64_ALPHABET_URL_SAFE) == BAZE64_OK);
+    CHECK(memcmp(encoded, "-_8=", len) == 0);
+
+    CHECK(baze64_encode((const uint8_t *)text, text_len, encoded, 4, &len,
+                        BAZE64_ALPHABET_STANDARD) ==
+          BAZE64_ERR_BUFFER_TOO_SMALL);
+    CHECK(len == 8);
+
+    const char *invalid = "Zm9v#mFy";
+    CHECK(baze64_decode((const uint8_t *)invalid, strlen(invalid), decoded,
+                        sizeof(decoded), &len, BAZE64_ALPHABET_STANDARD) ==
+          BAZE64_ERR_INVALID_CHAR);
+    CHECK(baze64_decode((const uint8_t *)invalid, strlen(invalid), decoded,
+                        sizeof(decoded), &len, 7) ==
+          BAZE64_ERR_INVALID_ALPHABET);
+    CHECK(baze64_decode(NULL, 4, decoded, sizeof(decoded), &len,
+                        BAZE64_ALPHABET_STANDARD) == BAZE64_ERR_NULL_POINTER);
+
+    puts("ok");
+    return 0;
+}
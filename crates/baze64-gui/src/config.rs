@@ -0,0 +1,103 @@
+use std::{fs, io, path::PathBuf};
+
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+
+/// Settings kept between launches
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Whether to decode base64 in the clipboard on launch
+    pub decode_clipboard: bool,
+    /// Whether to keep the history of conversions between launches
+    pub save_history: bool,
+    /// How many conversions the history keeps
+    pub history_limit: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            decode_clipboard: false,
+            save_history: true,
+            history_limit: 20,
+        }
+    }
+}
+
+impl Config {
+    /// Load the config, falling back to the defaults if there isn't
+    /// one or it can't be read
+    pub fn load() -> Self {
+        let Some(path) = path() else {
+            return Self::default();
+        };
+
+        match fs::read_to_string(&path) {
+            Ok(text) => Self::parse(&text),
+            Err(e) => {
+                debug!(?e, ?path, "no config loaded");
+                Self::default()
+            }
+        }
+    }
+
+    /// Parse the text of a config file, using the defaults for
+    /// anything missing or invalid
+    fn parse(text: &str) -> Self {
+        toml::from_str(text).unwrap_or_else(|e| {
+            debug!(?e, "invalid config, using defaults");
+            Self::default()
+        })
+    }
+
+    /// Save the config, creating its directory if needed
+    pub fn save(&self) -> io::Result<()> {
+        let path = path()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no config directory found"))?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let text = toml::to_string(self).map_err(io::Error::other)?;
+        fs::write(&path, text)?;
+        info!(?path, ?self, "saved config");
+
+        Ok(())
+    }
+}
+
+/// Where the config file lives, if there's a config directory
+fn path() -> Option<PathBuf> {
+    ProjectDirs::from("", "", "baze64").map(|dirs| dirs.config_dir().join("gui.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let config = Config {
+            decode_clipboard: true,
+            save_history: false,
+            history_limit: 5,
+        };
+
+        assert_eq!(Config::parse(&toml::to_string(&config).unwrap()), config);
+    }
+
+    #[test]
+    fn missing_or_invalid() {
+        assert_eq!(Config::parse(""), Config::default());
+        assert_eq!(Config::parse("decode_clipboard = 3"), Config::default());
+        assert_eq!(Config::parse("[[[]"), Config::default());
+        assert_eq!(
+            Config::parse("history_limit = 50"),
+            Config {
+                history_limit: 50,
+                ..Config::default()
+            }
+        );
+    }
+}
@@ -0,0 +1,373 @@
+use std::{
+    collections::VecDeque,
+    fs, io,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+
+use crate::Alpha;
+
+/// The most text either side of an entry can have & still be saved.
+/// Bigger entries can still be restored until the GUI's closed, but
+/// aren't written to disk
+pub const MAX_SAVED_LEN: usize = 64 * 1024;
+
+/// How many characters of an entry are shown in the history panel
+const PREVIEW_CHARS: usize = 40;
+
+/// Which way a conversion went
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    Encode,
+    Decode,
+}
+
+/// A conversion, with both sides of it so it can be restored
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    pub direction: Direction,
+    pub alphabet: Alpha,
+    pub plaintext: String,
+    pub base64: String,
+    /// When the conversion happened, in seconds since the Unix epoch
+    pub timestamp: u64,
+}
+
+impl Entry {
+    /// Record a conversion that happened just now
+    pub fn now(direction: Direction, alphabet: Alpha, plaintext: String, base64: String) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs());
+
+        Self {
+            direction,
+            alphabet,
+            plaintext,
+            base64,
+            timestamp,
+        }
+    }
+
+    /// The start of whichever side was converted from, on one line
+    pub fn preview(&self) -> String {
+        let input = match self.direction {
+            Direction::Encode => &self.plaintext,
+            Direction::Decode => &self.base64,
+        };
+        let line = input.split_whitespace().collect::<Vec<_>>().join(" ");
+
+        match line.char_indices().nth(PREVIEW_CHARS) {
+            Some((end, _)) => format!("{}…", &line[..end]),
+            None => line,
+        }
+    }
+
+    /// What kind of conversion this was & when, like
+    /// `Encode · Standard · 2024-01-02 03:04 UTC`
+    pub fn label(&self) -> String {
+        let direction = match self.direction {
+            Direction::Encode => "Encode",
+            Direction::Decode => "Decode",
+        };
+        let alphabet = match self.alphabet {
+            Alpha::Standard => "Standard",
+            Alpha::UrlSafe => "URL safe",
+        };
+
+        format!("{direction} · {alphabet} · {}", format_utc(self.timestamp))
+    }
+
+    /// Whether the entry is small enough to be written to disk
+    fn saveable(&self) -> bool {
+        self.plaintext.len() <= MAX_SAVED_LEN && self.base64.len() <= MAX_SAVED_LEN
+    }
+}
+
+/// The most recent conversions, newest first, optionally kept in a
+/// JSON file between launches
+#[derive(Debug)]
+pub struct HistoryStore {
+    entries: VecDeque<Entry>,
+    limit: usize,
+    /// Where the history is saved, or [`None`] if it isn't
+    path: Option<PathBuf>,
+}
+
+impl HistoryStore {
+    /// An empty history of up to `limit` entries that's only kept in
+    /// memory
+    pub fn new(limit: usize) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            limit,
+            path: None,
+        }
+    }
+
+    /// Load the history saved at `path`, keeping it there as entries
+    /// are added. Starts empty if there isn't one or it can't be read
+    pub fn load(path: PathBuf, limit: usize) -> Self {
+        let mut history = Self::new(limit);
+        match fs::read(&path) {
+            Ok(json) => match serde_json::from_slice::<Vec<Entry>>(&json) {
+                Ok(entries) => history.entries = entries.into_iter().take(limit).collect(),
+                Err(e) => debug!(?e, ?path, "invalid history, starting empty"),
+            },
+            Err(e) => debug!(?e, ?path, "no history loaded"),
+        }
+        history.path = Some(path);
+
+        history
+    }
+
+    /// Record a conversion, dropping the oldest entries past the
+    /// limit. A repeat of the newest entry only updates its time
+    pub fn push(&mut self, entry: Entry) {
+        if let Some(newest) = self.entries.front_mut() {
+            if newest.direction == entry.direction
+                && newest.alphabet == entry.alphabet
+                && newest.plaintext == entry.plaintext
+                && newest.base64 == entry.base64
+            {
+                newest.timestamp = entry.timestamp;
+                return;
+            }
+        }
+
+        self.entries.push_front(entry);
+        self.entries.truncate(self.limit);
+    }
+
+    /// Get the entry `index` places from the newest
+    pub fn get(&self, index: usize) -> Option<&Entry> {
+        self.entries.get(index)
+    }
+
+    /// The entries, newest first
+    pub fn entries(&self) -> impl Iterator<Item = &Entry> {
+        self.entries.iter()
+    }
+
+    /// Remove every entry, deleting the saved history too
+    pub fn clear(&mut self) -> io::Result<()> {
+        self.entries.clear();
+        match &self.path {
+            Some(path) => remove_file(path),
+            None => Ok(()),
+        }
+    }
+
+    /// Save the history, if it's being kept on disk, leaving out
+    /// entries too big to save
+    pub fn save(&self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let entries = self
+            .entries
+            .iter()
+            .filter(|entry| entry.saveable())
+            .collect::<Vec<_>>();
+        let json = serde_json::to_vec(&entries).map_err(io::Error::other)?;
+        fs::write(path, json)?;
+        debug!(?path, entries = entries.len(), "saved history");
+
+        Ok(())
+    }
+
+    /// Start or stop keeping the history at `path` on disk. Stopping
+    /// deletes what was saved, but keeps the entries in memory
+    pub fn set_path(&mut self, path: Option<PathBuf>) -> io::Result<()> {
+        if let Some(old) = self.path.take() {
+            remove_file(&old)?;
+            info!(path = ?old, "deleted saved history");
+        }
+        self.path = path;
+        self.save()
+    }
+}
+
+/// Where the history is saved, if there's a data directory
+pub fn path() -> Option<PathBuf> {
+    ProjectDirs::from("", "", "baze64").map(|dirs| dirs.data_dir().join("history.json"))
+}
+
+/// Delete `path`, which is fine if it's already gone
+fn remove_file(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// Format seconds since the Unix epoch as a UTC date & time, like
+/// `2024-01-02 03:04 UTC`
+fn format_utc(timestamp: u64) -> String {
+    let (days, seconds) = (timestamp / 86_400, timestamp % 86_400);
+
+    // Howard Hinnant's `civil_from_days`, for days since 1970-01-01
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02} UTC",
+        seconds / 3600,
+        seconds % 3600 / 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(plaintext: &str, timestamp: u64) -> Entry {
+        Entry {
+            direction: Direction::Encode,
+            alphabet: Alpha::Standard,
+            plaintext: plaintext.to_string(),
+            base64: crate::encode_text(
+                plaintext,
+                Alpha::Standard,
+                crate::Settings {
+                    pad: true,
+                    wrap: None,
+                },
+            ),
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn newest_first_up_to_limit() {
+        let mut history = HistoryStore::new(3);
+        for (i, text) in ["a", "b", "c", "d"].into_iter().enumerate() {
+            history.push(entry(text, i as u64));
+        }
+
+        let texts = history
+            .entries()
+            .map(|e| e.plaintext.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(texts, ["d", "c", "b"]);
+        assert_eq!(history.get(0).unwrap().base64, "ZA==");
+        assert_eq!(history.get(3), None);
+    }
+
+    #[test]
+    fn repeats_update_time() {
+        let mut history = HistoryStore::new(20);
+        history.push(entry("foo", 1));
+        history.push(entry("foo", 2));
+
+        assert_eq!(history.entries().count(), 1);
+        assert_eq!(history.get(0).unwrap().timestamp, 2);
+    }
+
+    #[test]
+    fn save_load_clear() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("history.json");
+
+        let mut history = HistoryStore::load(path.clone(), 20);
+        assert_eq!(history.entries().count(), 0);
+        history.push(entry("foo", 1));
+        history.push(entry("bar", 2));
+        history.save().unwrap();
+
+        let loaded = HistoryStore::load(path.clone(), 1);
+        assert_eq!(loaded.entries().collect::<Vec<_>>(), [&entry("bar", 2)]);
+
+        history.clear().unwrap();
+        assert_eq!(history.entries().count(), 0);
+        assert!(!path.exists());
+        history.clear().unwrap();
+    }
+
+    #[test]
+    fn big_entries_not_saved() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.json");
+        let mut history = HistoryStore::load(path.clone(), 20);
+
+        history.push(entry("small", 1));
+        history.push(entry(&"x".repeat(MAX_SAVED_LEN + 1), 2));
+        history.save().unwrap();
+
+        assert_eq!(history.entries().count(), 2);
+        assert!(fs::metadata(&path).unwrap().len() < 1024);
+        let loaded = HistoryStore::load(path, 20);
+        assert_eq!(loaded.entries().collect::<Vec<_>>(), [&entry("small", 1)]);
+    }
+
+    #[test]
+    fn stop_saving() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.json");
+        let mut history = HistoryStore::load(path.clone(), 20);
+        history.push(entry("secret", 1));
+        history.save().unwrap();
+
+        history.set_path(None).unwrap();
+        assert!(!path.exists());
+        assert_eq!(history.entries().count(), 1);
+        history.save().unwrap();
+        assert!(!path.exists());
+
+        history.set_path(Some(path.clone())).unwrap();
+        assert!(path.exists());
+    }
+
+    #[test]
+    fn invalid_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.json");
+        fs::write(&path, "{not json").unwrap();
+
+        assert_eq!(HistoryStore::load(path, 20).entries().count(), 0);
+    }
+
+    #[test]
+    fn previews() {
+        let mut long = entry(&"é".repeat(50), 0);
+        assert_eq!(long.preview(), format!("{}…", "é".repeat(40)));
+
+        long.direction = Direction::Decode;
+        assert!(long.preview().starts_with("w6nDqcOp"));
+        assert_eq!(
+            entry("two\nlines", 0).preview(),
+            "two lines",
+            "previews are on one line"
+        );
+    }
+
+    #[test]
+    fn labels() {
+        assert_eq!(
+            entry("", 0).label(),
+            "Encode · Standard · 1970-01-01 00:00 UTC"
+        );
+        assert_eq!(format_utc(951_782_400), "2000-02-29 00:00 UTC");
+        assert_eq!(format_utc(1_704_164_640), "2024-01-02 03:04 UTC");
+    }
+}
@@ -0,0 +1,152 @@
+use std::path::PathBuf;
+
+use baze64::{Base64String, DecodeError};
+use clap::Parser;
+
+use crate::{decode_text, format_base64, sanitized_notice, Alpha, Settings};
+
+/// A GUI for encoding & decoding base64
+#[derive(Debug, Parser)]
+#[command(version)]
+pub struct Args {
+    /// Open with STRING in the base64 pane, decoded
+    #[arg(long, value_name = "STRING", conflicts_with = "encode_file")]
+    pub decode: Option<String>,
+    /// Open with the contents of PATH encoded in the base64 pane
+    #[arg(long, value_name = "PATH")]
+    pub encode_file: Option<PathBuf>,
+}
+
+/// What to fill the panes with on launch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Panes {
+    pub plaintext: String,
+    pub base64: String,
+    /// The alphabet the base64 is in
+    pub alphabet: Alpha,
+    pub notice: String,
+}
+
+/// Fill the panes with `base64` & what it decodes to, detecting
+/// the alphabet if it isn't standard
+pub fn decoded(base64: &str) -> Result<Panes, DecodeError> {
+    let (plaintext, alphabet, sanitized) = decode_text(base64, Alpha::Standard)?;
+
+    Ok(Panes {
+        plaintext,
+        base64: base64.trim().to_string(),
+        alphabet,
+        notice: sanitized_notice(sanitized),
+    })
+}
+
+/// Fill the panes with a file's `contents` & their encoding, leaving
+/// the plaintext pane empty if they aren't UTF-8 text
+pub fn encoded_file(contents: Vec<u8>, settings: Settings) -> Panes {
+    let b64 = Base64String::encode_with(&contents, Alpha::Standard);
+    let base64 = format_base64(&b64, settings);
+    let (plaintext, notice) = match String::from_utf8(contents) {
+        Ok(text) => (text, String::new()),
+        Err(_) => (String::new(), "(The file isn't UTF-8 text)".to_string()),
+    };
+
+    Panes {
+        plaintext,
+        base64,
+        alphabet: Alpha::Standard,
+        notice,
+    }
+}
+
+/// Fill the panes from the clipboard's `text`, if it's base64 in
+/// either built-in alphabet that decodes to text
+///
+/// Anything else, including text that just happens to be valid
+/// base64 but doesn't decode to UTF-8, is left alone so the app
+/// opens empty
+pub fn from_clipboard(text: &str) -> Option<Panes> {
+    if text.trim().is_empty() {
+        return None;
+    }
+
+    decoded(text).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn args() {
+        let args = Args::try_parse_from(["baze64-gui", "--decode", "Zm9v"]).unwrap();
+        assert_eq!(args.decode.as_deref(), Some("Zm9v"));
+
+        let args = Args::try_parse_from(["baze64-gui", "--encode-file", "a.txt"]).unwrap();
+        assert_eq!(args.encode_file, Some(PathBuf::from("a.txt")));
+
+        assert!(
+            Args::try_parse_from(["baze64-gui", "--decode", "Zm9v", "--encode-file", "a.txt"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn decoded_detects_alphabet() {
+        assert_eq!(
+            decoded(" Pz8_\n").unwrap(),
+            Panes {
+                plaintext: "???".to_string(),
+                base64: "Pz8_".to_string(),
+                alphabet: Alpha::UrlSafe,
+                notice: String::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn decoded_invalid() {
+        assert!(decoded("Zm9v#").is_err());
+    }
+
+    #[test]
+    fn encoded_file_text() {
+        let panes = encoded_file(b"foobar".to_vec(), Settings::from_ui(true, 0));
+
+        assert_eq!(panes.plaintext, "foobar");
+        assert_eq!(panes.base64, "Zm9vYmFy");
+        assert_eq!(panes.notice, "");
+    }
+
+    #[test]
+    fn encoded_file_binary() {
+        let panes = encoded_file(vec![0xff, 0xfe], Settings::from_ui(false, 0));
+
+        assert_eq!(panes.plaintext, "");
+        assert_eq!(panes.base64, "//4");
+        assert_eq!(panes.notice, "(The file isn't UTF-8 text)");
+    }
+
+    #[test]
+    fn clipboard_base64() {
+        let panes = from_clipboard("aGVsbG8gd29ybGQ=\n").unwrap();
+
+        assert_eq!(panes.plaintext, "hello world");
+        assert_eq!(panes.alphabet, Alpha::Standard);
+        assert_eq!(
+            from_clipboard("-_-_LQ").map(|p| p.alphabet),
+            None,
+            "doesn't decode to text"
+        );
+        assert_eq!(
+            from_clipboard("aGk_").map(|p| p.alphabet),
+            Some(Alpha::UrlSafe)
+        );
+    }
+
+    #[test]
+    fn clipboard_not_base64() {
+        for text in ["", "  \n", "hello world", "Zm9v#", "a b c"] {
+            assert_eq!(from_clipboard(text), None, "{text:?}");
+        }
+    }
+}
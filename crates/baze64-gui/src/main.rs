@@ -1,76 +1,576 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use baze64::{
-    alphabet::{Alphabet, Standard, UrlSafe},
-    Base64String,
+    alphabet::{self, Alphabet, AlphabetKind, Standard, UrlSafe},
+    sanitize::{sanitize, Sanitized},
+    B64Error, Base64Str, Base64String, Codec, DecodeError,
+};
+use clap::Parser;
+use config::Config;
+use history::{Direction, Entry, HistoryStore};
+use launch::Args;
+use serde::{Deserialize, Serialize};
+use slint::{Timer, TimerMode, VecModel};
+use std::{
+    cell::{Cell, RefCell},
+    fs,
+    rc::Rc,
+    time::Duration,
 };
 use tracing::{debug, error, info};
 
-slint::include_modules!();
+mod config;
+mod history;
+mod launch;
+
+mod ui {
+    // The code generated by slint 1.2 trips a few lints on newer compilers
+    #![allow(
+        dead_code,
+        unused_imports,
+        non_local_definitions,
+        mismatched_lifetime_syntaxes
+    )]
+    slint::include_modules!();
+}
+
+use ui::*;
 
 fn main() {
     tracing_subscriber::fmt().init();
+    let args = Args::parse();
+    let config = Rc::new(Cell::new(Config::load()));
+    let history = Rc::new(RefCell::new(open_history(config.get())));
 
     let main_window = MainWindow::new().unwrap();
     debug!("main window created");
+    main_window.set_decode_clipboard(config.get().decode_clipboard);
+    main_window.set_save_history(config.get().save_history);
+    show_history(&main_window, &history.borrow());
 
     let mw_weak = main_window.as_weak();
+    let history_handle = Rc::clone(&history);
     main_window.on_encode_plaintext(move |text| {
         let mw = mw_weak.unwrap();
-        let text = text.trim();
-        let encoded = match mw.invoke_get_current_alphabet() {
-            0 => Base64String::encode_with(text, Alpha::Standard),
-            1 => Base64String::encode_with(text, Alpha::UrlSafe),
-            _ => unreachable!(),
-        };
-        let encoded = encoded.to_string();
-        info!(?text, ?encoded, "encoded plaintext");
-        mw.invoke_set_base64(encoded.into());
-        info!("set base64 text field");
+        encode_pane(&mw, &text);
+        if !text.trim().is_empty() {
+            record(&mw, &history_handle, Direction::Encode);
+        }
     });
 
     let mw_weak = main_window.as_weak();
+    let history_handle = Rc::clone(&history);
     main_window.on_decode_base64(move |base64| {
         let mw = mw_weak.unwrap();
-        let decoded = match mw.invoke_get_current_alphabet() {
-            0 => Base64String::from_encoded_with(&base64, Alpha::Standard),
-            1 => Base64String::from_encoded_with(&base64, Alpha::UrlSafe),
-            _ => unreachable!(),
-        }
-        .map_or_else(
-            |e| {
-                error!(?e);
+        let decoded = decode_pane(&mw, &base64).unwrap_or_else(|e| {
+            error!(?e);
+            if let DecodeError::InvalidUtf8(_) = e {
+                mw.invoke_show_error("Invalid UTF-8 text ".into());
+            } else {
                 mw.invoke_show_error(e.to_string().into());
-                "".to_string()
-            },
-            |b64| {
-                b64.decode_to_string().map_or_else(
-                    |e| {
-                        error!(?e);
-                        if let baze64::DecodeError::InvalidUtf8(_) = e {
-                            mw.invoke_show_error("Invalid UTF-8 text ".into());
-                        } else {
-                            mw.invoke_show_error(e.to_string().into());
-                        }
-                        "".to_string()
-                    },
-                    |s| s.to_string(),
-                )
-            },
-        );
-        info!(?base64, ?decoded, "decoded base64");
+            }
+            "".to_string()
+        });
+        let recordable = !decoded.is_empty();
         mw.invoke_set_plaintext(decoded.into());
         info!("set plaintext text field");
+        if recordable {
+            record(&mw, &history_handle, Direction::Decode);
+        }
+    });
+
+    let mw_weak = main_window.as_weak();
+    let history_handle = Rc::clone(&history);
+    main_window.on_history_selected(move |index| {
+        let mw = mw_weak.unwrap();
+        let history = history_handle.borrow();
+        let Some(entry) = usize::try_from(index).ok().and_then(|i| history.get(i)) else {
+            return;
+        };
+        mw.invoke_set_current_alphabet(entry.alphabet.index());
+        mw.invoke_set_plaintext(entry.plaintext.as_str().into());
+        mw.invoke_set_base64(entry.base64.as_str().into());
+        mw.set_notice("".into());
+        info!(index, "restored history entry");
+    });
+
+    let mw_weak = main_window.as_weak();
+    let history_handle = Rc::clone(&history);
+    main_window.on_clear_history(move || {
+        let mw = mw_weak.unwrap();
+        let mut history = history_handle.borrow_mut();
+        if let Err(e) = history.clear() {
+            error!(?e, "failed to delete history");
+            mw.invoke_show_error(format!("Failed to delete the saved history: {e}").into());
+        }
+        show_history(&mw, &history);
+    });
+
+    let mw_weak = main_window.as_weak();
+    let config_handle = Rc::clone(&config);
+    main_window.on_save_history_toggled(move |on| {
+        let mw = mw_weak.unwrap();
+        config_handle.set(Config {
+            save_history: on,
+            ..config_handle.get()
+        });
+        save_config(&mw, config_handle.get());
+
+        let path = history::path().filter(|_| on);
+        if let Err(e) = history.borrow_mut().set_path(path) {
+            error!(?e, "failed to change where history is saved");
+            mw.invoke_show_error(format!("Failed to update the saved history: {e}").into());
+        }
+    });
+
+    let live = Rc::new(RefCell::new(LiveState::default()));
+    let timer = Rc::new(Timer::default());
+    for pane in [Pane::Plaintext, Pane::Base64] {
+        let mw_weak = main_window.as_weak();
+        let live = Rc::clone(&live);
+        let timer = Rc::clone(&timer);
+        let on_edit = move || {
+            let mw = mw_weak.unwrap();
+            if !mw.get_live_mode() || !live.borrow_mut().edited(pane) {
+                return;
+            }
+
+            // Restarting the timer on every edit means only the
+            // last of a burst of edits is converted
+            let mw_weak = mw_weak.clone();
+            let live = Rc::clone(&live);
+            timer.start(TimerMode::SingleShot, LIVE_DEBOUNCE, move || {
+                convert_live(&mw_weak.unwrap(), &live);
+            });
+        };
+        match pane {
+            Pane::Plaintext => main_window.on_plaintext_edited(on_edit),
+            Pane::Base64 => main_window.on_base64_edited(on_edit),
+        }
+    }
+
+    let mw_weak = main_window.as_weak();
+    main_window.on_alphabet_changed(move |from, to| {
+        let mw = mw_weak.unwrap();
+        let settings = Settings::from_ui(mw.get_pad_output(), mw.get_wrap_index());
+        update_base64(
+            &mw,
+            Alpha::from_index(from),
+            Alpha::from_index(to),
+            settings,
+        );
+    });
+
+    let mw_weak = main_window.as_weak();
+    main_window.on_settings_changed(move || {
+        let mw = mw_weak.unwrap();
+        let settings = Settings::from_ui(mw.get_pad_output(), mw.get_wrap_index());
+        let alphabet = Alpha::from_index(mw.invoke_get_current_alphabet());
+        update_base64(&mw, alphabet, alphabet, settings);
+    });
+
+    let mut clipboard = arboard::Clipboard::new()
+        .map_err(|e| error!(?e, "failed to access clipboard"))
+        .ok();
+    prefill(&main_window, &args, config.get(), clipboard.as_mut());
+
+    let mw_weak = main_window.as_weak();
+    main_window.on_decode_clipboard_toggled(move |on| {
+        config.set(Config {
+            decode_clipboard: on,
+            ..config.get()
+        });
+        save_config(&mw_weak.unwrap(), config.get());
+    });
+
+    let mw_weak = main_window.as_weak();
+    main_window.on_copy_to_clipboard(move |text| {
+        let mw = mw_weak.unwrap();
+        let Some(clipboard) = clipboard.as_mut() else {
+            mw.invoke_show_error("The clipboard is unavailable".into());
+            return;
+        };
+        if let Err(e) = clipboard.set_text(text.as_str()) {
+            error!(?e);
+            mw.invoke_show_error(e.to_string().into());
+        } else {
+            info!(?text, "copied text to clipboard");
+        }
     });
 
     main_window.run().unwrap();
 }
 
+/// How long live mode waits after the last edit before converting
+const LIVE_DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// Save `config`, showing an error if it can't be
+fn save_config(mw: &MainWindow, config: Config) {
+    if let Err(e) = config.save() {
+        error!(?e, "failed to save config");
+        mw.invoke_show_error(format!("Failed to save the setting: {e}").into());
+    }
+}
+
+/// Load the saved history, or start an empty one that's only kept in
+/// memory if saving it is turned off
+fn open_history(config: Config) -> HistoryStore {
+    match history::path().filter(|_| config.save_history) {
+        Some(path) => HistoryStore::load(path, config.history_limit),
+        None => HistoryStore::new(config.history_limit),
+    }
+}
+
+/// Add the conversion now in the panes to the history
+///
+/// Failing to save is only logged, so a read-only data directory
+/// doesn't interrupt every conversion
+fn record(mw: &MainWindow, history: &RefCell<HistoryStore>, direction: Direction) {
+    let alphabet = Alpha::from_index(mw.invoke_get_current_alphabet());
+    let entry = Entry::now(
+        direction,
+        alphabet,
+        mw.invoke_get_plaintext().into(),
+        mw.invoke_get_base64().into(),
+    );
+
+    let mut history = history.borrow_mut();
+    history.push(entry);
+    if let Err(e) = history.save() {
+        error!(?e, "failed to save history");
+    }
+    show_history(mw, &history);
+}
+
+/// Show the entries of `history` in the history panel
+fn show_history(mw: &MainWindow, history: &HistoryStore) {
+    let items = history
+        .entries()
+        .map(|entry| HistoryItem {
+            label: entry.label().into(),
+            preview: entry.preview().into(),
+        })
+        .collect::<Vec<_>>();
+    mw.set_history(Rc::new(VecModel::from(items)).into());
+}
+
+/// Fill the panes on launch from the command line arguments, or
+/// otherwise from the clipboard if that's turned on
+///
+/// Only problems with the arguments are shown, as the clipboard is
+/// just left alone if it doesn't hold base64
+fn prefill(
+    mw: &MainWindow,
+    args: &Args,
+    config: Config,
+    clipboard: Option<&mut arboard::Clipboard>,
+) {
+    let panes = if let Some(base64) = &args.decode {
+        launch::decoded(base64).map_err(|e| {
+            mw.invoke_set_base64(base64.as_str().into());
+            e.to_string()
+        })
+    } else if let Some(path) = &args.encode_file {
+        let settings = Settings::from_ui(mw.get_pad_output(), mw.get_wrap_index());
+        fs::read(path)
+            .map(|contents| launch::encoded_file(contents, settings))
+            .map_err(|e| format!("Failed to read {}: {e}", path.display()))
+    } else if config.decode_clipboard {
+        let Some(text) = clipboard.and_then(|c| c.get_text().ok()) else {
+            return;
+        };
+        match launch::from_clipboard(&text) {
+            Some(panes) => Ok(panes),
+            None => {
+                debug!("clipboard doesn't hold base64");
+                return;
+            }
+        }
+    } else {
+        return;
+    };
+
+    match panes {
+        Ok(panes) => {
+            info!(?panes, "prefilled panes");
+            mw.invoke_set_current_alphabet(panes.alphabet.index());
+            mw.invoke_set_base64(panes.base64.into());
+            mw.invoke_set_plaintext(panes.plaintext.into());
+            mw.set_notice(panes.notice.into());
+        }
+        Err(e) => {
+            error!(?e, "failed to prefill panes");
+            mw.invoke_show_error(e.into());
+        }
+    }
+}
+
+/// Encode `text` into the base64 pane with the current alphabet &
+/// settings
+fn encode_pane(mw: &MainWindow, text: &str) {
+    let alphabet = Alpha::from_index(mw.invoke_get_current_alphabet());
+    let settings = Settings::from_ui(mw.get_pad_output(), mw.get_wrap_index());
+    let encoded = encode_text(text, alphabet, settings);
+    info!(?text, ?encoded, "encoded plaintext");
+    mw.invoke_set_base64(encoded.into());
+    info!("set base64 text field");
+}
+
+/// Decode `base64` with the selected alphabet, switching the
+/// alphabet dropdown to whichever one was actually used
+fn decode_pane(mw: &MainWindow, base64: &str) -> Result<String, DecodeError> {
+    let selected = Alpha::from_index(mw.invoke_get_current_alphabet());
+    let (decoded, used, sanitized) = decode_text(base64, selected)?;
+    mw.invoke_set_current_alphabet(used.index());
+    mw.set_notice(sanitized_notice(sanitized).into());
+    info!(?base64, ?decoded, ?sanitized, "decoded base64");
+
+    Ok(decoded)
+}
+
+/// Convert whichever pane was last edited in live mode into the
+/// other one
+///
+/// Errors are only logged, as the input is usually just part way
+/// through being typed
+fn convert_live(mw: &MainWindow, live: &RefCell<LiveState>) {
+    let Some(pane) = live.borrow_mut().take_pending() else {
+        return;
+    };
+
+    live.borrow_mut().updating = true;
+    match pane {
+        Pane::Plaintext => encode_pane(mw, &mw.invoke_get_plaintext()),
+        Pane::Base64 => match decode_pane(mw, &mw.invoke_get_base64()) {
+            Ok(decoded) => mw.invoke_set_plaintext(decoded.into()),
+            Err(e) => debug!(?e, "live decode failed"),
+        },
+    }
+    live.borrow_mut().updating = false;
+}
+
+/// A pane of the main window that can be edited
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Pane {
+    Plaintext,
+    Base64,
+}
+
+/// Tracks edits made in live mode, so a burst of edits is only
+/// converted once, & text set by a conversion isn't treated as an
+/// edit that needs converting back
+#[derive(Debug, Default)]
+struct LiveState {
+    /// The pane edited since the last conversion, if any
+    pending: Option<Pane>,
+    /// Whether a conversion is currently setting a pane's text
+    updating: bool,
+}
+
+impl LiveState {
+    /// Record an edit to `pane`, returning whether a conversion
+    /// should be scheduled for it
+    fn edited(&mut self, pane: Pane) -> bool {
+        if self.updating {
+            return false;
+        }
+        self.pending = Some(pane);
+        true
+    }
+
+    /// Take the pane that needs converting, if any
+    fn take_pending(&mut self) -> Option<Pane> {
+        self.pending.take()
+    }
+}
+
+/// Encode the plaintext `text`, ignoring surrounding whitespace,
+/// formatted according to `settings`
+fn encode_text(text: &str, alphabet: Alpha, settings: Settings) -> String {
+    let encoded = Base64String::encode_str_with(text.trim(), alphabet);
+    format_base64(&encoded, settings)
+}
+
+/// Decode `base64` into text, returning it along with the alphabet
+/// used to decode it & what was sanitized
+fn decode_text(base64: &str, selected: Alpha) -> Result<(String, Alpha, Sanitized), DecodeError> {
+    let (b64, used, sanitized) = parse_base64(base64, selected)?;
+
+    Ok((b64.decode_to_string()?, used, sanitized))
+}
+
+/// Describe what sanitizing pasted base64 changed, or nothing if it
+/// didn't change anything
+fn sanitized_notice(sanitized: Sanitized) -> String {
+    let count = |n: usize, what: &str| match n {
+        1 => format!("1 {what} character"),
+        n => format!("{n} {what} characters"),
+    };
+
+    match (sanitized.removed, sanitized.replaced) {
+        (0, 0) => String::new(),
+        (removed, 0) => format!("({} removed)", count(removed, "invisible")),
+        (0, replaced) => format!("({} replaced)", count(replaced, "look-alike")),
+        (removed, replaced) => format!(
+            "({} removed, {} replaced)",
+            count(removed, "invisible"),
+            count(replaced, "look-alike")
+        ),
+    }
+}
+
+/// Re-encode the base64 pane's content from one alphabet to another
+/// using `settings`, showing an error & leaving it as is if it's
+/// not valid base64
+fn update_base64(mw: &MainWindow, from: Alpha, to: Alpha, settings: Settings) {
+    let base64 = mw.invoke_get_base64();
+    match reencode(&base64, from, to, settings) {
+        Ok(reencoded) => {
+            info!(?base64, ?reencoded, ?from, ?to, "re-encoded base64");
+            mw.invoke_set_base64(reencoded.into());
+        }
+        Err(e) => {
+            error!(?e);
+            mw.invoke_show_error(e.to_string().into());
+        }
+    }
+}
+
+/// How encoded base64 should be displayed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Settings {
+    /// Whether to include padding
+    pad: bool,
+    /// The column to wrap lines at, if any
+    wrap: Option<usize>,
+}
+
+impl Settings {
+    /// Get the settings matching the "pad output" toggle & the
+    /// index in the wrapping dropdown
+    fn from_ui(pad: bool, wrap_index: i32) -> Self {
+        let wrap = match wrap_index {
+            0 => None,
+            1 => Some(64),
+            2 => Some(76),
+            _ => unreachable!(),
+        };
+        Self { pad, wrap }
+    }
+}
+
+/// Format `b64` for display according to `settings`
+fn format_base64<A>(b64: &Base64String<A>, settings: Settings) -> String
+where
+    A: Alphabet,
+{
+    let content = if settings.pad {
+        b64.to_string()
+    } else {
+        b64.without_padding()
+    };
+
+    match settings.wrap {
+        Some(columns) => content
+            .chars()
+            .collect::<Vec<_>>()
+            .chunks(columns)
+            .map(|line| line.iter().collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        None => content,
+    }
+}
+
+/// Re-encode the base64 text `base64` from the `from` alphabet
+/// to the `to` alphabet, formatted according to `settings`
+fn reencode(
+    base64: &str,
+    from: Alpha,
+    to: Alpha,
+    settings: Settings,
+) -> Result<String, DecodeError> {
+    // Decode with a Codec, so base64 in the other alphabet gets an
+    // error suggesting it
+    let decoded = Codec::new(from)
+        .normalize_padding(true)
+        .decode(&strip_whitespace(base64))?;
+    let b64 = Base64String::encode_with(decoded, to);
+
+    Ok(format_base64(&b64, settings))
+}
+
+/// Remove the line breaks & other whitespace wrapping can leave in
+/// base64 text
+fn strip_whitespace(base64: &str) -> String {
+    base64.chars().filter(|c| !c.is_whitespace()).collect()
+}
+
+/// Parse `base64` using the `selected` alphabet, falling back to
+/// whichever built-in alphabet the input looks to be encoded with
+///
+/// Returns the [`Base64String`] along with the alphabet actually used
+/// & what was sanitized from the pasted input
+fn parse_base64(
+    base64: &str,
+    selected: Alpha,
+) -> Result<(Base64String<Alpha>, Alpha, Sanitized), B64Error> {
+    // Sanitize before stripping whitespace, so no-break spaces are
+    // counted along with the other invisible characters
+    let (base64, sanitized) = sanitize(base64);
+    let base64 = strip_whitespace(&base64);
+    // Check which alphabet to use before handing over the text, so
+    // it's only parsed into a Base64String once
+    let used = match Base64Str::from_encoded_with(&base64, selected) {
+        Ok(_) => selected,
+        Err(e) => {
+            let detected = alphabet::detect(&base64)
+                .map(Alpha::from)
+                .filter(|&a| a != selected)
+                .ok_or(e)?;
+            info!(?detected, "falling back to detected alphabet");
+            detected
+        }
+    };
+
+    Base64String::from_encoded_normalized_with(base64, used).map(|b64| (b64, used, sanitized))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 enum Alpha {
     Standard,
     UrlSafe,
 }
 
+impl Alpha {
+    /// Get the alphabet at `index` in the alphabet dropdown
+    fn from_index(index: i32) -> Self {
+        match index {
+            0 => Alpha::Standard,
+            1 => Alpha::UrlSafe,
+            _ => unreachable!(),
+        }
+    }
+
+    /// The index of the alphabet in the alphabet dropdown
+    fn index(self) -> i32 {
+        match self {
+            Alpha::Standard => 0,
+            Alpha::UrlSafe => 1,
+        }
+    }
+}
+
+impl From<AlphabetKind> for Alpha {
+    fn from(kind: AlphabetKind) -> Self {
+        match kind {
+            AlphabetKind::Standard => Alpha::Standard,
+            AlphabetKind::UrlSafe => Alpha::UrlSafe,
+        }
+    }
+}
+
 impl Alphabet for Alpha {
     fn padding(&self) -> Option<char> {
         match self {
@@ -79,7 +579,7 @@ impl Alphabet for Alpha {
         }
     }
 
-    fn encode_bits(&self, bits: u8) -> Result<char, baze64::B64Error> {
+    fn encode_bits(&self, bits: u8) -> char {
         match self {
             Alpha::Standard => Standard::new().encode_bits(bits),
             Alpha::UrlSafe => UrlSafe::new().encode_bits(bits),
@@ -93,3 +593,200 @@ impl Alphabet for Alpha {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAIN: Settings = Settings {
+        pad: true,
+        wrap: None,
+    };
+
+    #[test]
+    fn format_unpadded() {
+        let b64 = Base64String::encode_with("fo", Alpha::Standard);
+        let settings = Settings {
+            pad: false,
+            ..PLAIN
+        };
+
+        assert_eq!(format_base64(&b64, PLAIN), "Zm8=");
+        assert_eq!(format_base64(&b64, settings), "Zm8");
+    }
+
+    #[test]
+    fn format_wrapped() {
+        let b64 = Base64String::encode_with([0; 60], Alpha::Standard);
+
+        for columns in [64, 76] {
+            let settings = Settings {
+                wrap: Some(columns),
+                ..PLAIN
+            };
+            let formatted = format_base64(&b64, settings);
+            let lines = formatted.lines().collect::<Vec<_>>();
+
+            assert_eq!(lines.len(), 2);
+            assert_eq!(lines[0].len(), columns);
+            assert_eq!(formatted.replace('\n', ""), b64.to_string());
+        }
+    }
+
+    #[test]
+    fn reencode_switches_alphabet() {
+        assert_eq!(
+            reencode("-_8=", Alpha::UrlSafe, Alpha::Standard, PLAIN).unwrap(),
+            "+/8="
+        );
+    }
+
+    #[test]
+    fn reencode_wrapped_input() {
+        let b64 = Base64String::encode_with([0xfb; 60], Alpha::Standard);
+        let wrapped = Settings {
+            wrap: Some(64),
+            ..PLAIN
+        };
+        let input = format_base64(&b64, wrapped);
+
+        assert_eq!(
+            reencode(&input, Alpha::Standard, Alpha::UrlSafe, PLAIN).unwrap(),
+            b64.to_string().replace('+', "-").replace('/', "_")
+        );
+    }
+
+    #[test]
+    fn reencode_invalid() {
+        assert!(matches!(
+            reencode("Zm9v#", Alpha::Standard, Alpha::UrlSafe, PLAIN),
+            Err(DecodeError::Base64Error(B64Error::InvalidCharAt {
+                char: '#',
+                index: 4
+            }))
+        ));
+    }
+
+    #[test]
+    fn reencode_suggests_alphabet() {
+        assert_eq!(
+            reencode("Zm9v-_8", Alpha::Standard, Alpha::UrlSafe, PLAIN)
+                .unwrap_err()
+                .to_string(),
+            "Invalid Base64 character '-' at index 4 for the standard alphabet \
+             (input looks like URL safe base64)"
+        );
+    }
+
+    /// Run `edits` through a [`LiveState`] as if the debounce timer
+    /// fired after all of them, returning what would be converted
+    fn simulate(live: &mut LiveState, edits: &[(Pane, &str)]) -> Option<(Pane, String)> {
+        let mut plaintext = "";
+        let mut base64 = "";
+        for &(pane, text) in edits {
+            match pane {
+                Pane::Plaintext => plaintext = text,
+                Pane::Base64 => base64 = text,
+            }
+            live.edited(pane);
+        }
+
+        live.take_pending().map(|pane| match pane {
+            Pane::Plaintext => (pane, encode_text(plaintext, Alpha::Standard, PLAIN)),
+            Pane::Base64 => (pane, decode_text(base64, Alpha::Standard).unwrap().0),
+        })
+    }
+
+    #[test]
+    fn live_converts_last_edit() {
+        let mut live = LiveState::default();
+        let edits = [
+            (Pane::Plaintext, "f"),
+            (Pane::Plaintext, "fo"),
+            (Pane::Plaintext, "foo"),
+        ];
+
+        assert_eq!(
+            simulate(&mut live, &edits),
+            Some((Pane::Plaintext, "Zm9v".to_string()))
+        );
+        assert_eq!(live.take_pending(), None);
+    }
+
+    #[test]
+    fn live_switches_direction() {
+        let mut live = LiveState::default();
+        let edits = [(Pane::Plaintext, "foo"), (Pane::Base64, "YmFy")];
+
+        assert_eq!(
+            simulate(&mut live, &edits),
+            Some((Pane::Base64, "bar".to_string()))
+        );
+    }
+
+    #[test]
+    fn live_ignores_own_updates() {
+        let mut live = LiveState::default();
+        assert!(live.edited(Pane::Plaintext));
+        assert_eq!(live.take_pending(), Some(Pane::Plaintext));
+
+        // Setting the base64 pane from the conversion
+        live.updating = true;
+        assert!(!live.edited(Pane::Base64));
+        live.updating = false;
+
+        assert_eq!(live.take_pending(), None);
+    }
+
+    #[test]
+    fn decode_text_detects_alphabet() {
+        assert_eq!(
+            decode_text("Pz8_", Alpha::Standard).unwrap(),
+            ("???".to_string(), Alpha::UrlSafe, Sanitized::default())
+        );
+    }
+
+    #[test]
+    fn decode_text_sanitizes() {
+        let pasted = "\u{200b}Zm9v\u{00a0}YmFy\u{feff}";
+        let (decoded, _, sanitized) = decode_text(pasted, Alpha::Standard).unwrap();
+
+        assert_eq!(decoded, "foobar");
+        assert_eq!(
+            sanitized_notice(sanitized),
+            "(3 invisible characters removed)"
+        );
+    }
+
+    #[test]
+    fn sanitized_notices() {
+        let notice = |removed, replaced| sanitized_notice(Sanitized { removed, replaced });
+
+        assert_eq!(notice(0, 0), "");
+        assert_eq!(notice(1, 0), "(1 invisible character removed)");
+        assert_eq!(notice(0, 2), "(2 look-alike characters replaced)");
+        assert_eq!(
+            notice(2, 1),
+            "(2 invisible characters removed, 1 look-alike character replaced)"
+        );
+    }
+
+    #[test]
+    fn alpha_conforms() {
+        for index in 0..2 {
+            alphabet::conformance::assert_alphabet_conformance(&Alpha::from_index(index));
+        }
+    }
+
+    #[test]
+    fn settings_from_ui() {
+        assert_eq!(Settings::from_ui(true, 0), PLAIN);
+        assert_eq!(
+            Settings::from_ui(false, 2),
+            Settings {
+                pad: false,
+                wrap: Some(76)
+            }
+        );
+    }
+}
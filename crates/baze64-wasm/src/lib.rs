@@ -0,0 +1,95 @@
+//! WebAssembly bindings for encoding & decoding base64 with
+//! [`baze64`], so browsers can match a Rust backend byte for byte
+//!
+//! Alphabets are selected by any of the names in
+//! [`baze64::alphabet::names`]. Failures are thrown as JS `Error`s
+//! with a `kind` property naming the [`ErrorKind`](baze64::ErrorKind),
+//! along with `char` & `position` properties when the input had an
+//! invalid character, & a `suggestion` property naming the other
+//! built-in alphabet when the input looks to be encoded with it
+
+use baze64::{
+    alphabet::{Alphabet, AnyAlphabet, ParseAlphabetError},
+    Base64Str, Base64String, DecodeError,
+};
+use wasm_bindgen::prelude::*;
+
+/// Encode `data` as base64 using the named `alphabet`
+#[wasm_bindgen]
+pub fn encode(data: &[u8], alphabet: &str) -> Result<String, JsValue> {
+    let alphabet = parse_alphabet(alphabet)?;
+
+    Ok(Base64String::encode_with(data, alphabet).to_string())
+}
+
+/// Decode the base64 text `b64` using the named `alphabet`
+#[wasm_bindgen]
+pub fn decode(b64: &str, alphabet: &str) -> Result<Vec<u8>, JsValue> {
+    let alphabet = parse_alphabet(alphabet)?;
+
+    Base64Str::from_encoded_with(b64, alphabet)
+        .map_err(DecodeError::from)
+        .and_then(|b64| b64.decode())
+        .map_err(|e| decode_error(&e))
+}
+
+/// Decode the base64 text `b64` using the named `alphabet` into
+/// a string, failing if it isn't valid UTF-8
+#[wasm_bindgen(js_name = decodeToString)]
+pub fn decode_to_string(b64: &str, alphabet: &str) -> Result<String, JsValue> {
+    let alphabet = parse_alphabet(alphabet)?;
+
+    Base64Str::from_encoded_with(b64, alphabet)
+        .map_err(DecodeError::from)
+        .and_then(|b64| b64.decode_to_string())
+        .map_err(|e| decode_error(&e))
+}
+
+fn parse_alphabet(alphabet: &str) -> Result<AnyAlphabet, JsValue> {
+    alphabet
+        .parse()
+        .map_err(|e: ParseAlphabetError| js_error(&e.to_string(), "InvalidAlphabet").into())
+}
+
+/// Convert `err` into a JS `Error` describing it
+fn decode_error(err: &DecodeError) -> JsValue {
+    let js_err = js_error(&err.to_string(), &format!("{:?}", err.kind()));
+
+    match err {
+        DecodeError::Base64Error(e) => {
+            if let Some(c) = e.invalid_char() {
+                set(&js_err, "char", &c.to_string().into());
+            }
+            if let Some(position) = e.position() {
+                set(&js_err, "position", &(position as f64).into());
+            }
+        }
+        DecodeError::WrongAlphabet {
+            char,
+            position,
+            suggestion,
+        } => {
+            set(&js_err, "char", &char.to_string().into());
+            set(&js_err, "position", &(*position as f64).into());
+            set(
+                &js_err,
+                "suggestion",
+                &AnyAlphabet::from(*suggestion).name().into(),
+            );
+        }
+        _ => {}
+    }
+
+    js_err.into()
+}
+
+fn js_error(message: &str, kind: &str) -> js_sys::Error {
+    let err = js_sys::Error::new(message);
+    set(&err, "kind", &kind.into());
+    err
+}
+
+fn set(err: &js_sys::Error, key: &str, value: &JsValue) {
+    // Setting a property on a fresh `Error` can't fail
+    let _ = js_sys::Reflect::set(err, &key.into(), value);
+}
@@ -0,0 +1,71 @@
+//! Run with `wasm-pack test --node`
+
+#![cfg(target_arch = "wasm32")]
+
+use baze64_wasm::{decode, decode_to_string, encode};
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+fn property(err: &JsValue, key: &str) -> JsValue {
+    js_sys::Reflect::get(err, &key.into()).unwrap()
+}
+
+#[wasm_bindgen_test]
+fn roundtrip() {
+    let data = (0..=255).collect::<Vec<u8>>();
+
+    for alphabet in ["standard", "urlsafe"] {
+        for size in 0..=data.len() {
+            let encoded = encode(&data[..size], alphabet).unwrap();
+            assert_eq!(decode(&encoded, alphabet).unwrap(), &data[..size]);
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+fn unicode_text() {
+    let text = "Grüße, 世界 🌍";
+    let encoded = encode(text.as_bytes(), "standard").unwrap();
+
+    assert_eq!(encoded, "R3LDvMOfZSwg5LiW55WMIPCfjI0=");
+    assert_eq!(decode_to_string(&encoded, "standard").unwrap(), text);
+}
+
+#[wasm_bindgen_test]
+fn url_safe() {
+    assert_eq!(encode(&[0xfb, 0xff], "urlsafe").unwrap(), "-_8=");
+    assert_eq!(decode("-_8=", "urlsafe").unwrap(), [0xfb, 0xff]);
+}
+
+#[wasm_bindgen_test]
+fn invalid_char() {
+    let err = decode("Zm9v#mFy", "standard").unwrap_err();
+
+    assert_eq!(property(&err, "kind"), "InvalidChar");
+    assert_eq!(property(&err, "char"), "#");
+    assert_eq!(property(&err, "position"), 4.0);
+}
+
+#[wasm_bindgen_test]
+fn wrong_alphabet() {
+    let err = decode("Zm9v-_8", "standard").unwrap_err();
+
+    assert_eq!(property(&err, "kind"), "InvalidChar");
+    assert_eq!(property(&err, "char"), "-");
+    assert_eq!(property(&err, "position"), 4.0);
+    assert_eq!(property(&err, "suggestion"), "urlsafe");
+}
+
+#[wasm_bindgen_test]
+fn invalid_utf8() {
+    let err = decode_to_string("/w==", "standard").unwrap_err();
+
+    assert_eq!(property(&err, "kind"), "InvalidUtf8");
+}
+
+#[wasm_bindgen_test]
+fn invalid_alphabet() {
+    let err = encode(b"foo", "base32").unwrap_err();
+
+    assert_eq!(property(&err, "kind"), "InvalidAlphabet");
+}
@@ -0,0 +1,96 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use baze64::{
+    alphabet::{Standard, UrlSafe},
+    Base64String,
+};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+const SIZES: [(&str, usize); 3] = [
+    ("1 KB", 1024),
+    ("1 MB", 1024 * 1024),
+    ("64 MB", 64 * 1024 * 1024),
+];
+
+/// Fewer samples for the bigger sizes, so a full run doesn't take
+/// all day
+fn sample_size(len: usize) -> usize {
+    if len > 1024 * 1024 {
+        10
+    } else {
+        50
+    }
+}
+
+fn data(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i as u8).wrapping_mul(0x9d)).collect()
+}
+
+fn encode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("encode");
+
+    for (name, len) in SIZES {
+        let data = data(len);
+        group
+            .sample_size(sample_size(len))
+            .throughput(Throughput::Bytes(len as u64));
+
+        group.bench_with_input(BenchmarkId::new("baze64", name), &data, |b, data| {
+            b.iter(|| Base64String::encode_with(data, Standard::new()))
+        });
+        group.bench_with_input(BenchmarkId::new("base64", name), &data, |b, data| {
+            b.iter(|| STANDARD.encode(data))
+        });
+    }
+    group.finish();
+}
+
+fn decode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("decode");
+
+    for (name, len) in SIZES {
+        let b64 = Base64String::encode_with(data(len), Standard::new());
+        let encoded = b64.to_string();
+        group
+            .sample_size(sample_size(len))
+            .throughput(Throughput::Bytes(len as u64));
+
+        group.bench_with_input(BenchmarkId::new("baze64", name), &b64, |b, b64| {
+            b.iter(|| b64.decode().unwrap())
+        });
+        group.bench_with_input(
+            BenchmarkId::new("baze64 validated", name),
+            &encoded,
+            |b, encoded| {
+                b.iter(|| {
+                    Base64String::<Standard>::from_encoded(encoded.as_str())
+                        .unwrap()
+                        .decode()
+                        .unwrap()
+                })
+            },
+        );
+        group.bench_with_input(BenchmarkId::new("base64", name), &encoded, |b, encoded| {
+            b.iter(|| STANDARD.decode(encoded).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn change_alphabet(c: &mut Criterion) {
+    let mut group = c.benchmark_group("change_alphabet_with");
+
+    for (name, len) in SIZES {
+        let b64 = Base64String::encode_with(data(len), Standard::new());
+        group
+            .sample_size(sample_size(len))
+            .throughput(Throughput::Bytes(len as u64));
+
+        group.bench_with_input(BenchmarkId::new("baze64", name), &b64, |b, b64| {
+            b.iter(|| b64.change_alphabet_with(UrlSafe::new()).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, encode, decode, change_alphabet);
+criterion_main!(benches);
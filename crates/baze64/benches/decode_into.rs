@@ -0,0 +1,34 @@
+use std::io::Write;
+
+use baze64::{alphabet::Standard, Base64String};
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+
+const LEN: usize = 64 * 1024 * 1024;
+
+fn decode_into(c: &mut Criterion) {
+    let data = (0..LEN).map(|i| i as u8).collect::<Vec<_>>();
+    let b64 = Base64String::<Standard>::encode(&data);
+    let mut group = c.benchmark_group("decode_into 64 MB");
+    group
+        .sample_size(10)
+        .throughput(Throughput::Bytes(LEN as u64));
+
+    group.bench_function("Vec", |b| {
+        b.iter(|| {
+            let mut out = Vec::with_capacity(LEN);
+            b64.decode_into(&mut out).unwrap();
+            out
+        })
+    });
+    group.bench_function("File", |b| {
+        b.iter(|| {
+            let mut file = tempfile::tempfile().unwrap();
+            b64.decode_into(&mut file).unwrap();
+            file.flush().unwrap();
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, decode_into);
+criterion_main!(benches);
@@ -0,0 +1,31 @@
+use baze64::{alphabet::Standard, Base64Str, Base64String};
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+
+const LEN: usize = 50 * 1024 * 1024;
+
+fn from_encoded(c: &mut Criterion) {
+    let data = (0..LEN / 4 * 3).map(|i| i as u8).collect::<Vec<_>>();
+    let encoded = Base64String::<Standard>::encode(&data).to_string();
+    let mut group = c.benchmark_group("from_encoded 50 MB");
+    group
+        .sample_size(10)
+        .throughput(Throughput::Bytes(encoded.len() as u64));
+
+    group.bench_function("owned String", |b| {
+        b.iter_batched(
+            || encoded.clone(),
+            |s| Base64String::<Standard>::from_encoded(s).unwrap(),
+            BatchSize::LargeInput,
+        )
+    });
+    group.bench_function("&str", |b| {
+        b.iter(|| Base64String::<Standard>::from_encoded(encoded.as_str()).unwrap())
+    });
+    group.bench_function("Base64Str", |b| {
+        b.iter(|| Base64Str::<Standard>::from_encoded(&encoded).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, from_encoded);
+criterion_main!(benches);
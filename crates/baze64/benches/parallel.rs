@@ -0,0 +1,23 @@
+use baze64::{alphabet::Standard, Base64String};
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+
+const LEN: usize = 256 * 1024 * 1024;
+
+fn encode(c: &mut Criterion) {
+    let data = (0..LEN).map(|i| i as u8).collect::<Vec<_>>();
+    let mut group = c.benchmark_group("encode 256 MB");
+    group
+        .sample_size(10)
+        .throughput(Throughput::Bytes(LEN as u64));
+
+    group.bench_function("sequential", |b| {
+        b.iter(|| Base64String::encode_with(&data, Standard::new()))
+    });
+    group.bench_function("parallel", |b| {
+        b.iter(|| Base64String::encode_parallel(&data, Standard::new()))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, encode);
+criterion_main!(benches);
@@ -0,0 +1,77 @@
+use baze64::{
+    alphabet::{Alphabet, Standard},
+    B64Error, Base64String,
+};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+const SIZES: [(&str, usize); 3] = [("1 KB", 1024), ("64 KB", 64 * 1024), ("1 MB", 1024 * 1024)];
+
+/// The standard alphabet with `+` & `/` swapped, which isn't
+/// vectorised, so it shows what the scalar path does with the same
+/// amount of work
+#[derive(Debug, Clone, Copy, Default)]
+struct Swapped;
+
+impl Alphabet for Swapped {
+    fn padding(&self) -> Option<char> {
+        Some('=')
+    }
+
+    fn encode_bits(&self, bits: u8) -> char {
+        match bits {
+            62 => '/',
+            63 => '+',
+            _ => Standard::new().encode_bits(bits),
+        }
+    }
+
+    fn decode_char(&self, c: char) -> Result<u8, B64Error> {
+        match c {
+            '/' => Ok(62),
+            '+' => Ok(63),
+            _ => Standard::new().decode_char(c),
+        }
+    }
+}
+
+fn data(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i as u8).wrapping_mul(0x9d)).collect()
+}
+
+fn encode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("simd encode");
+
+    for (name, len) in SIZES {
+        let data = data(len);
+        group.throughput(Throughput::Bytes(len as u64));
+
+        group.bench_with_input(BenchmarkId::new("simd", name), &data, |b, data| {
+            b.iter(|| Base64String::encode_with(data, Standard::new()))
+        });
+        group.bench_with_input(BenchmarkId::new("scalar", name), &data, |b, data| {
+            b.iter(|| Base64String::encode_with(data, Swapped))
+        });
+    }
+    group.finish();
+}
+
+fn decode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("simd decode");
+
+    for (name, len) in SIZES {
+        let simd = Base64String::encode_with(data(len), Standard::new());
+        let scalar = Base64String::encode_with(data(len), Swapped);
+        group.throughput(Throughput::Bytes(len as u64));
+
+        group.bench_with_input(BenchmarkId::new("simd", name), &simd, |b, b64| {
+            b.iter(|| b64.decode().unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("scalar", name), &scalar, |b, b64| {
+            b.iter(|| b64.decode().unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, encode, decode);
+criterion_main!(benches);
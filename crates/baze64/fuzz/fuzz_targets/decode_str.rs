@@ -0,0 +1,23 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use baze64::{
+    alphabet::{Permissive, Standard, UrlSafe},
+    Base64String,
+};
+
+fuzz_target!(|input: &str| {
+    // Only checking nothing panics, so the results don't matter
+    if let Ok(b64) = Base64String::<Standard>::from_encoded(input) {
+        let _ = b64.decode();
+        let _ = b64.decode_to_string();
+    }
+    if let Ok(b64) = Base64String::<UrlSafe>::from_encoded(input) {
+        let _ = b64.decode_concatenated();
+    }
+    if let Ok(b64) = Base64String::<Permissive>::from_encoded(input) {
+        let _ = b64.matches_data(input.as_bytes());
+    }
+    let _ = baze64::validate(input, &Standard::new());
+});
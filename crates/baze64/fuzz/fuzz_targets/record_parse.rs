@@ -0,0 +1,23 @@
+//! Parse arbitrary base64 as a record, which must never panic, &
+//! must never give back more field data than the record holds
+
+#![no_main]
+
+use baze64::{alphabet::Standard, record::RecordReader, Base64String};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    // Arbitrary bytes are mostly invalid base64, so also try them as
+    // the record's decoded bytes
+    let as_text = std::str::from_utf8(data)
+        .ok()
+        .and_then(|text| Base64String::<Standard>::from_encoded(text).ok());
+    let as_bytes = Some(Base64String::<Standard>::encode(data));
+
+    for record in [as_text, as_bytes].into_iter().flatten() {
+        if let Ok(fields) = RecordReader::parse(&record) {
+            let len = fields.iter().map(Vec::len).sum::<usize>();
+            assert!(len + fields.len() < record.decoded_len());
+        }
+    }
+});
@@ -1,7 +1,13 @@
-use crate::B64Error;
+use crate::{base64string::ascii_decode_table, B64Error};
+
+pub mod conformance;
 
 /// Trait for a base64 alphabet that can be used
 /// to encode & decode a [`Base64String`](crate::Base64String)
+///
+/// The rules implementations need to follow are listed on
+/// [`conformance::assert_alphabet_conformance`], which can be
+/// used to test them
 pub trait Alphabet {
     /// The padding character used for the alphabet
     fn padding(&self) -> Option<char>;
@@ -9,16 +15,339 @@ pub trait Alphabet {
     /// Returns the base64 character corresponding to a set of 6
     /// bits
     ///
-    /// [`Base64String`](crate::Base64String) makes sure to never
-    /// call this with an out-of-range value, but this method
-    /// should still handle it gracefully in case that does happen
-    fn encode_bits(&self, bits: u8) -> Result<char, B64Error>;
+    /// `bits` is always below 64, as [`Base64String`](crate::Base64String)
+    /// masks it first, so encoding can't fail. Implementations are
+    /// free to panic for anything bigger
+    fn encode_bits(&self, bits: u8) -> char;
 
     /// Decodes a base64 character into it's decoded value
-    /// The returned value must fit in a 6-bit number,
-    /// as [`Base64String`](crate::Base64String) will assume
-    /// that this happens
+    /// The returned value must fit in a 6-bit number. Debug builds
+    /// & the `strict-alphabet-checks` feature report any that don't
+    /// as [`B64Error::DecodedValueOOB`], & other builds mask them to
+    /// 6 bits
+    ///
+    /// The padding character isn't data, so this must return an
+    /// error for it. [`Base64String`](crate::Base64String) handles
+    /// padding itself, so it can check where padding appears
     fn decode_char(&self, c: char) -> Result<u8, B64Error>;
+
+    /// The name of the alphabet, used to look it up with [`by_name`]
+    ///
+    /// Defaults to the name of the implementing type
+    fn name(&self) -> &'static str {
+        core::any::type_name::<Self>()
+    }
+}
+
+/// Allows a single alphabet instance to be shared between many
+/// [`Base64String`](crate::Base64String)s by reference, which is
+/// useful for alphabets that are expensive to construct or clone
+///
+/// # Examples
+/// ```
+/// # use baze64::{Base64String, alphabet::Standard};
+/// let alphabet = Standard::new();
+/// let first = Base64String::encode_with(b"first", &alphabet);
+/// let second = Base64String::encode_with(b"second", &alphabet);
+/// ```
+impl<T> Alphabet for &T
+where
+    T: Alphabet + ?Sized,
+{
+    fn padding(&self) -> Option<char> {
+        (**self).padding()
+    }
+
+    fn encode_bits(&self, bits: u8) -> char {
+        (**self).encode_bits(bits)
+    }
+
+    fn decode_char(&self, c: char) -> Result<u8, B64Error> {
+        (**self).decode_char(c)
+    }
+
+    fn name(&self) -> &'static str {
+        (**self).name()
+    }
+}
+
+/// One of the alphabets built in to this crate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlphabetKind {
+    /// The [`Standard`] alphabet
+    Standard,
+    /// The [`UrlSafe`] alphabet
+    UrlSafe,
+}
+
+impl AlphabetKind {
+    /// Recognise the built-in alphabet that `alphabet` decodes ASCII
+    /// the same as, if there is one
+    pub(crate) fn of<A>(alphabet: &A) -> Option<Self>
+    where
+        A: Alphabet,
+    {
+        let table = ascii_decode_table(alphabet);
+        [AlphabetKind::Standard, AlphabetKind::UrlSafe]
+            .into_iter()
+            .find(|kind| ascii_decode_table(kind) == table)
+    }
+
+    /// The other built-in alphabet
+    pub(crate) fn other(self) -> Self {
+        match self {
+            AlphabetKind::Standard => AlphabetKind::UrlSafe,
+            AlphabetKind::UrlSafe => AlphabetKind::Standard,
+        }
+    }
+}
+
+/// Detect which built-in alphabet `b64` was encoded with, based on
+/// whether it contains the URL safe `-` & `_` or the standard `+` & `/`
+///
+/// Input using neither is valid in both alphabets & is reported as
+/// [`AlphabetKind::Standard`]. Returns [`None`] if `b64` isn't valid
+/// in the detected alphabet, which includes input mixing characters
+/// specific to each
+///
+/// # Examples
+/// ```
+/// # use baze64::alphabet::{detect, AlphabetKind};
+/// assert_eq!(detect("a+b/"), Some(AlphabetKind::Standard));
+/// assert_eq!(detect("a-b_"), Some(AlphabetKind::UrlSafe));
+/// assert_eq!(detect("a+b_"), None);
+/// ```
+pub fn detect(b64: &str) -> Option<AlphabetKind> {
+    if b64.contains(['-', '_']) {
+        is_valid(b64, &UrlSafe::new()).then_some(AlphabetKind::UrlSafe)
+    } else {
+        is_valid(b64, &Standard::new()).then_some(AlphabetKind::Standard)
+    }
+}
+
+/// Whether every character in `b64` is part of `alphabet`, or is
+/// its padding
+fn is_valid<A>(b64: &str, alphabet: &A) -> bool
+where
+    A: Alphabet,
+{
+    b64.chars()
+        .all(|c| Some(c) == alphabet.padding() || alphabet.decode_char(c).is_ok())
+}
+
+impl Alphabet for AlphabetKind {
+    fn padding(&self) -> Option<char> {
+        match self {
+            AlphabetKind::Standard => Standard::new().padding(),
+            AlphabetKind::UrlSafe => UrlSafe::new().padding(),
+        }
+    }
+
+    fn encode_bits(&self, bits: u8) -> char {
+        match self {
+            AlphabetKind::Standard => Standard::new().encode_bits(bits),
+            AlphabetKind::UrlSafe => UrlSafe::new().encode_bits(bits),
+        }
+    }
+
+    fn decode_char(&self, c: char) -> Result<u8, B64Error> {
+        match self {
+            AlphabetKind::Standard => Standard::new().decode_char(c),
+            AlphabetKind::UrlSafe => UrlSafe::new().decode_char(c),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            AlphabetKind::Standard => Standard::new().name(),
+            AlphabetKind::UrlSafe => UrlSafe::new().name(),
+        }
+    }
+}
+
+/// The error returned when parsing an unknown [`AlphabetKind`]
+/// or [`AnyAlphabet`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseAlphabetError {
+    name: String,
+    expected: &'static [&'static str],
+}
+
+impl core::fmt::Display for ParseAlphabetError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "Unknown alphabet `{}`, use one of {}",
+            self.name,
+            quoted(self.expected)
+        )
+    }
+}
+
+impl std::error::Error for ParseAlphabetError {}
+
+/// Join `names` as a list of code-quoted names
+fn quoted(names: &[&str]) -> String {
+    names
+        .iter()
+        .map(|name| format!("`{name}`"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Parses `standard` or `urlsafe`, ignoring case
+///
+/// # Examples
+/// ```
+/// # use baze64::alphabet::AlphabetKind;
+/// assert_eq!("urlsafe".parse(), Ok(AlphabetKind::UrlSafe));
+/// assert!("base32".parse::<AlphabetKind>().is_err());
+/// ```
+impl core::str::FromStr for AlphabetKind {
+    type Err = ParseAlphabetError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("standard") {
+            Ok(AlphabetKind::Standard)
+        } else if s.eq_ignore_ascii_case("urlsafe") {
+            Ok(AlphabetKind::UrlSafe)
+        } else {
+            Err(ParseAlphabetError {
+                name: s.to_string(),
+                expected: &["standard", "urlsafe"],
+            })
+        }
+    }
+}
+
+/// The alphabets [`by_name`] can find, in the same order as
+/// [`names`]
+///
+/// Add new built-in alphabets here to make them available everywhere
+/// alphabets are chosen by name
+static REGISTRY: [&(dyn Alphabet + Sync); 3] =
+    [&Standard::new(), &UrlSafe::new(), &Permissive::new()];
+
+/// The names of every alphabet [`by_name`] can find
+static NAMES: [&str; 3] = ["standard", "urlsafe", "permissive"];
+
+/// The names of every alphabet that can be looked up with
+/// [`by_name`]
+///
+/// # Examples
+/// ```
+/// # use baze64::alphabet;
+/// for name in alphabet::names() {
+///     assert!(alphabet::by_name(name).is_some());
+/// }
+/// ```
+pub fn names() -> &'static [&'static str] {
+    &NAMES
+}
+
+/// Look up a built-in alphabet by its [`name`](Alphabet::name),
+/// ignoring case
+///
+/// # Examples
+/// ```
+/// # use baze64::{Base64String, alphabet};
+/// let alphabet = alphabet::by_name("URLSAFE").unwrap();
+/// let b64 = Base64String::encode_with([0xfb, 0xff], alphabet);
+///
+/// assert_eq!(b64.to_string(), "-_8=");
+/// assert!(alphabet::by_name("base32").is_none());
+/// ```
+pub fn by_name(name: &str) -> Option<AnyAlphabet> {
+    REGISTRY
+        .iter()
+        .find(|alphabet| alphabet.name().eq_ignore_ascii_case(name))
+        .map(|&alphabet| AnyAlphabet(alphabet))
+}
+
+/// Any of the built-in alphabets, chosen at runtime with [`by_name`]
+/// or by parsing its name
+#[derive(Clone, Copy)]
+pub struct AnyAlphabet(&'static (dyn Alphabet + Sync));
+
+impl Alphabet for AnyAlphabet {
+    fn padding(&self) -> Option<char> {
+        self.0.padding()
+    }
+
+    fn encode_bits(&self, bits: u8) -> char {
+        self.0.encode_bits(bits)
+    }
+
+    fn decode_char(&self, c: char) -> Result<u8, B64Error> {
+        self.0.decode_char(c)
+    }
+
+    fn name(&self) -> &'static str {
+        self.0.name()
+    }
+}
+
+impl From<AlphabetKind> for AnyAlphabet {
+    fn from(kind: AlphabetKind) -> Self {
+        match kind {
+            AlphabetKind::Standard => Self(REGISTRY[0]),
+            AlphabetKind::UrlSafe => Self(REGISTRY[1]),
+        }
+    }
+}
+
+impl Default for AnyAlphabet {
+    /// The [`Standard`] alphabet
+    fn default() -> Self {
+        Self(REGISTRY[0])
+    }
+}
+
+impl PartialEq for AnyAlphabet {
+    fn eq(&self, other: &Self) -> bool {
+        self.name() == other.name()
+    }
+}
+
+impl Eq for AnyAlphabet {}
+
+impl core::fmt::Debug for AnyAlphabet {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("AnyAlphabet").field(&self.name()).finish()
+    }
+}
+
+impl core::fmt::Display for AnyAlphabet {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Parses any of the [`names`], ignoring case
+impl core::str::FromStr for AnyAlphabet {
+    type Err = ParseAlphabetError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        by_name(s).ok_or_else(|| ParseAlphabetError {
+            name: s.to_string(),
+            expected: names(),
+        })
+    }
+}
+
+/// Decode a character of one of the RFC 4648 alphabets, which only
+/// differ in the characters for 62 & 63
+///
+/// Matching on ranges is much faster than searching the encode map
+fn decode_rfc4648(c: char, [c62, c63]: [char; 2]) -> Result<u8, B64Error> {
+    match c {
+        'A'..='Z' => Ok(c as u8 - b'A'),
+        'a'..='z' => Ok(c as u8 - b'a' + 26),
+        '0'..='9' => Ok(c as u8 - b'0' + 52),
+        c if c == c62 => Ok(62),
+        c if c == c63 => Ok(63),
+        c => Err(B64Error::InvalidChar(c)),
+    }
 }
 
 /// The standard base64 alphabet as defined in
@@ -39,6 +368,14 @@ impl Standard {
             ],
         }
     }
+
+    /// Get a [`Standard`] alphabet that pads with `padding`
+    /// instead of `=`
+    ///
+    /// Fails if `padding` is one of the alphabet's characters
+    pub fn with_padding(self, padding: char) -> Result<WithPadding<Self>, PaddingCollisionError> {
+        WithPadding::new(self, padding)
+    }
 }
 
 impl Default for Standard {
@@ -60,6 +397,14 @@ impl UrlSafe {
             ],
         }
     }
+
+    /// Get a [`UrlSafe`] alphabet that pads with `padding`
+    /// instead of `=`
+    ///
+    /// Fails if `padding` is one of the alphabet's characters
+    pub fn with_padding(self, padding: char) -> Result<WithPadding<Self>, PaddingCollisionError> {
+        WithPadding::new(self, padding)
+    }
 }
 
 impl Default for UrlSafe {
@@ -80,25 +425,16 @@ impl Alphabet for Standard {
         Some('=')
     }
 
-    fn encode_bits(&self, bits: u8) -> Result<char, B64Error> {
-        if bits > 63 {
-            Err(B64Error::BitsOOB(bits))
-        } else {
-            Ok(self.encode_map[bits as usize])
-        }
+    fn encode_bits(&self, bits: u8) -> char {
+        self.encode_map[bits as usize]
     }
 
     fn decode_char(&self, c: char) -> Result<u8, B64Error> {
-        if c == self.padding().unwrap() {
-            Ok(0)
-        } else if c == '\0' {
-            Ok(0x64)
-        } else {
-            self.encode_map
-                .iter()
-                .position(|&ch| ch == c)
-                .map_or_else(|| Err(B64Error::InvalidChar(c)), |i| Ok(i as u8))
-        }
+        decode_rfc4648(c, [self.encode_map[62], self.encode_map[63]])
+    }
+
+    fn name(&self) -> &'static str {
+        "standard"
     }
 }
 
@@ -107,24 +443,385 @@ impl Alphabet for UrlSafe {
         Some('=')
     }
 
-    fn encode_bits(&self, bits: u8) -> Result<char, B64Error> {
-        if bits > 63 {
-            Err(B64Error::BitsOOB(bits))
-        } else {
-            Ok(self.encode_map[bits as usize])
+    fn encode_bits(&self, bits: u8) -> char {
+        self.encode_map[bits as usize]
+    }
+
+    fn decode_char(&self, c: char) -> Result<u8, B64Error> {
+        decode_rfc4648(c, [self.encode_map[62], self.encode_map[63]])
+    }
+
+    fn name(&self) -> &'static str {
+        "urlsafe"
+    }
+}
+
+/// The error returned when a padding character is already used
+/// by the alphabet it's meant to pad
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaddingCollisionError(char);
+
+impl core::fmt::Display for PaddingCollisionError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "Padding character `{}` is already part of the alphabet",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for PaddingCollisionError {}
+
+/// An alphabet with its padding character replaced
+///
+/// Create one with [`WithPadding::new`], or [`Standard::with_padding`]
+/// & [`UrlSafe::with_padding`]. The original padding character is no
+/// longer accepted when decoding
+///
+/// # Examples
+/// ```
+/// # use baze64::{Base64String, alphabet::Standard};
+/// let dotted = Standard::new().with_padding('.')?;
+/// let b64 = Base64String::encode_with(b"fo", dotted);
+///
+/// assert_eq!(b64.to_string(), "Zm8.");
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct WithPadding<A> {
+    alphabet: A,
+    padding: char,
+}
+
+impl<A> WithPadding<A>
+where
+    A: Alphabet,
+{
+    /// Wrap `alphabet` so it pads with `padding` instead of its
+    /// own padding character
+    ///
+    /// Fails if `alphabet` already uses `padding` to encode data
+    pub fn new(alphabet: A, padding: char) -> Result<Self, PaddingCollisionError> {
+        if alphabet.decode_char(padding).is_ok() {
+            return Err(PaddingCollisionError(padding));
         }
+
+        Ok(Self { alphabet, padding })
+    }
+
+    /// Get the wrapped alphabet back
+    pub fn into_inner(self) -> A {
+        self.alphabet
+    }
+}
+
+impl<A> Alphabet for WithPadding<A>
+where
+    A: Alphabet,
+{
+    fn padding(&self) -> Option<char> {
+        Some(self.padding)
+    }
+
+    fn encode_bits(&self, bits: u8) -> char {
+        self.alphabet.encode_bits(bits)
     }
 
     fn decode_char(&self, c: char) -> Result<u8, B64Error> {
-        if c == self.padding().unwrap() {
-            Ok(0)
-        } else if c == '\0' {
-            Ok(0x64)
+        if c == self.padding {
+            Err(B64Error::InvalidChar(c))
         } else {
-            self.encode_map
-                .iter()
-                .position(|&ch| ch == c)
-                .map_or_else(|| Err(B64Error::InvalidChar(c)), |i| Ok(i as u8))
+            self.alphabet.decode_char(c)
+        }
+    }
+}
+
+/// An alphabet for decoding base64 that may use either the
+/// [`Standard`] or [`UrlSafe`] alphabet, without knowing which
+///
+/// Both `+` & `-` decode as 62 and both `/` & `_` as 63, so input
+/// mixing the two alphabets is accepted too. This is meant for
+/// decoding; encoding always produces [`Standard`] output
+///
+/// # Examples
+/// ```
+/// # use baze64::{Base64String, alphabet::{Permissive, Standard, UrlSafe}};
+/// let standard = Base64String::<Standard>::encode([0xfb, 0xff]);
+/// let url_safe = Base64String::<UrlSafe>::encode([0xfb, 0xff]);
+///
+/// for b64 in [standard.to_string(), url_safe.to_string()] {
+///     let decoded = Base64String::<Permissive>::from_encoded(b64)?.decode()?;
+///     assert_eq!(decoded, [0xfb, 0xff]);
+/// }
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Permissive {
+    standard: Standard,
+}
+
+impl Permissive {
+    /// Get a new [`Permissive`] alphabet
+    pub const fn new() -> Self {
+        Self {
+            standard: Standard::new(),
         }
     }
 }
+
+impl Alphabet for Permissive {
+    fn padding(&self) -> Option<char> {
+        self.standard.padding()
+    }
+
+    fn encode_bits(&self, bits: u8) -> char {
+        self.standard.encode_bits(bits)
+    }
+
+    fn decode_char(&self, c: char) -> Result<u8, B64Error> {
+        match c {
+            '-' => Ok(62),
+            '_' => Ok(63),
+            c => self.standard.decode_char(c),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "permissive"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn detect_standard() {
+        assert_eq!(detect("ab+/cd=="), Some(AlphabetKind::Standard));
+        assert_eq!(detect("abcd"), Some(AlphabetKind::Standard));
+    }
+
+    #[test]
+    fn detect_url_safe() {
+        assert_eq!(detect("ab-_cd=="), Some(AlphabetKind::UrlSafe));
+        assert_eq!(detect("ab-c"), Some(AlphabetKind::UrlSafe));
+    }
+
+    #[test]
+    fn parse_kind() {
+        assert_eq!("Standard".parse(), Ok(AlphabetKind::Standard));
+        assert_eq!("URLSAFE".parse(), Ok(AlphabetKind::UrlSafe));
+        assert_eq!(
+            "url".parse::<AlphabetKind>(),
+            Err(ParseAlphabetError {
+                name: "url".to_string(),
+                expected: &["standard", "urlsafe"]
+            })
+        );
+    }
+
+    #[test]
+    fn kind_matches_alphabet() {
+        for bits in 0..64 {
+            assert_eq!(
+                AlphabetKind::Standard.encode_bits(bits),
+                Standard::new().encode_bits(bits)
+            );
+            assert_eq!(
+                AlphabetKind::UrlSafe.encode_bits(bits),
+                UrlSafe::new().encode_bits(bits)
+            );
+        }
+    }
+
+    #[test]
+    fn permissive_matches_both() {
+        let permissive = Permissive::new();
+        for bits in 0..64 {
+            let standard = Standard::new().encode_bits(bits);
+            let url_safe = UrlSafe::new().encode_bits(bits);
+
+            assert_eq!(permissive.decode_char(standard), Ok(bits));
+            assert_eq!(permissive.decode_char(url_safe), Ok(bits));
+            assert_eq!(permissive.encode_bits(bits), standard);
+        }
+    }
+
+    #[test]
+    fn with_padding_collision() {
+        assert_eq!(
+            Standard::new().with_padding('+').unwrap_err(),
+            PaddingCollisionError('+')
+        );
+        assert_eq!(
+            UrlSafe::new().with_padding('A').unwrap_err(),
+            PaddingCollisionError('A')
+        );
+        assert!(UrlSafe::new().with_padding('+').is_ok());
+        assert!(Standard::new().with_padding('=').is_ok());
+    }
+
+    #[test]
+    fn with_padding_rejects_original() {
+        let dotted = Standard::new().with_padding('.').unwrap();
+
+        assert_eq!(dotted.decode_char('.'), Err(B64Error::InvalidChar('.')));
+        assert_eq!(dotted.decode_char('='), Err(B64Error::InvalidChar('=')));
+        assert_eq!(dotted.decode_char('Z'), Ok(25));
+    }
+
+    #[test]
+    fn names_match_registry() {
+        assert_eq!(names().len(), REGISTRY.len());
+        for (name, alphabet) in names().iter().zip(REGISTRY) {
+            assert_eq!(*name, alphabet.name());
+        }
+    }
+
+    #[test]
+    fn by_name_ignores_case() {
+        for name in ["standard", "Standard", "STANDARD"] {
+            assert_eq!(by_name(name).unwrap().name(), "standard");
+        }
+        assert_eq!(by_name("UrlSafe").unwrap().name(), "urlsafe");
+        assert_eq!(by_name("pErMiSsIvE").unwrap().name(), "permissive");
+        assert_eq!(by_name("url"), None);
+    }
+
+    #[test]
+    fn parse_any() {
+        assert_eq!("urlsafe".parse(), Ok(by_name("urlsafe").unwrap()));
+        assert_eq!(
+            "url".parse::<AnyAlphabet>().unwrap_err().to_string(),
+            "Unknown alphabet `url`, use one of `standard`, `urlsafe`, `permissive`"
+        );
+    }
+
+    #[test]
+    fn any_from_kind() {
+        assert_eq!(AnyAlphabet::from(AlphabetKind::Standard).name(), "standard");
+        assert_eq!(AnyAlphabet::from(AlphabetKind::UrlSafe).name(), "urlsafe");
+    }
+
+    #[test]
+    fn default_name() {
+        let dotted = Standard::new().with_padding('.').unwrap();
+
+        assert_eq!(
+            dotted.name(),
+            "baze64::alphabet::WithPadding<baze64::alphabet::Standard>"
+        );
+    }
+
+    #[test]
+    fn padding_isnt_data() {
+        assert_eq!(
+            Standard::new().decode_char('='),
+            Err(B64Error::InvalidChar('='))
+        );
+        assert_eq!(
+            UrlSafe::new().decode_char('='),
+            Err(B64Error::InvalidChar('='))
+        );
+        assert_eq!(
+            Permissive::new().decode_char('='),
+            Err(B64Error::InvalidChar('='))
+        );
+    }
+
+    #[test]
+    fn built_ins_conform() {
+        conformance::assert_alphabet_conformance(&Standard::new());
+        conformance::assert_alphabet_conformance(&UrlSafe::new());
+        conformance::assert_alphabet_conformance(&Permissive::new());
+        conformance::assert_alphabet_conformance(&AlphabetKind::Standard);
+        conformance::assert_alphabet_conformance(&AlphabetKind::UrlSafe);
+        conformance::assert_alphabet_conformance(&Standard::new().with_padding('.').unwrap());
+        conformance::assert_alphabet_conformance(&UrlSafe::new().with_padding('~').unwrap());
+        for name in names() {
+            conformance::assert_alphabet_conformance(&by_name(name).unwrap());
+        }
+    }
+
+    /// Gives the same character for 62 & 63
+    struct Collides;
+
+    impl Alphabet for Collides {
+        fn padding(&self) -> Option<char> {
+            Some('=')
+        }
+
+        fn encode_bits(&self, bits: u8) -> char {
+            Standard::new().encode_bits(if bits == 63 { 62 } else { bits })
+        }
+
+        fn decode_char(&self, c: char) -> Result<u8, B64Error> {
+            Standard::new().decode_char(c)
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "for both 62 & 63")]
+    fn collision_doesnt_conform() {
+        conformance::assert_alphabet_conformance(&Collides);
+    }
+
+    /// An alphabet missing its last character
+    struct Short;
+
+    impl Alphabet for Short {
+        fn padding(&self) -> Option<char> {
+            Some('=')
+        }
+
+        fn encode_bits(&self, bits: u8) -> char {
+            assert!(bits < 63, "no character for {bits}");
+            Standard::new().encode_bits(bits)
+        }
+
+        fn decode_char(&self, c: char) -> Result<u8, B64Error> {
+            Standard::new().decode_char(c)
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "encode_bits(63) panicked")]
+    fn missing_character_doesnt_conform() {
+        conformance::assert_alphabet_conformance(&Short);
+    }
+
+    /// Decodes `!` to a value too big for 6 bits
+    struct Overflows;
+
+    impl Alphabet for Overflows {
+        fn padding(&self) -> Option<char> {
+            Some('=')
+        }
+
+        fn encode_bits(&self, bits: u8) -> char {
+            Standard::new().encode_bits(bits)
+        }
+
+        fn decode_char(&self, c: char) -> Result<u8, B64Error> {
+            match c {
+                '!' => Ok(64),
+                c => Standard::new().decode_char(c),
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "`!` decodes to 64, which doesn't fit in 6 bits")]
+    fn out_of_range_value_doesnt_conform() {
+        conformance::assert_alphabet_conformance(&Overflows);
+    }
+
+    #[test]
+    fn detect_invalid() {
+        assert_eq!(detect("ab+_"), None);
+        assert_eq!(detect("ab#c"), None);
+        assert_eq!(detect("ab-#"), None);
+    }
+}
@@ -0,0 +1,127 @@
+//! Checks that an [`Alphabet`] implementation follows the rules
+//! [`Base64String`] relies on
+//!
+//! Call [`assert_alphabet_conformance`] from a test when implementing
+//! [`Alphabet`] for your own type:
+//!
+//! ```
+//! # use baze64::{B64Error, alphabet::{Alphabet, Standard, conformance}};
+//! /// The standard alphabet, padded with `.` instead of `=`
+//! struct Dotted;
+//!
+//! impl Alphabet for Dotted {
+//!     fn padding(&self) -> Option<char> {
+//!         Some('.')
+//!     }
+//!
+//!     fn encode_bits(&self, bits: u8) -> char {
+//!         Standard::new().encode_bits(bits)
+//!     }
+//!
+//!     fn decode_char(&self, c: char) -> Result<u8, B64Error> {
+//!         Standard::new().decode_char(c)
+//!     }
+//! }
+//!
+//! conformance::assert_alphabet_conformance(&Dotted);
+//! ```
+
+use std::{
+    collections::HashMap,
+    panic::{self, AssertUnwindSafe},
+};
+
+use crate::{alphabet::Alphabet, Base64String};
+
+/// The lengths of data round tripped, covering every remainder
+/// when divided by 3 several times over
+const ROUND_TRIP_LENS: [usize; 12] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 63, 64, 65];
+
+/// Assert that `alphabet` follows the contract of [`Alphabet`],
+/// panicking with a description of the first rule it breaks
+///
+/// The rules are that:
+/// - [`encode_bits`](Alphabet::encode_bits) gives a different
+///   character for every value from 0 to 63, without panicking
+/// - [`decode_char`](Alphabet::decode_char) turns every character
+///   [`encode_bits`](Alphabet::encode_bits) gives back into the
+///   value it came from
+/// - The [`padding`](Alphabet::padding) character, if there is one,
+///   isn't one [`encode_bits`](Alphabet::encode_bits) gives, & is
+///   rejected by [`decode_char`](Alphabet::decode_char)
+/// - Every character [`decode_char`](Alphabet::decode_char) accepts
+///   decodes to a value below 64, so other characters may also
+///   decode as long as they fit in 6 bits
+/// - Data of every length encodes to a [`Base64String`] that can be
+///   parsed back from its text & decoded to the same bytes
+///
+/// # Panics
+///
+/// If `alphabet` breaks any of the rules above
+pub fn assert_alphabet_conformance<A>(alphabet: &A)
+where
+    A: Alphabet + ?Sized,
+{
+    let name = alphabet.name();
+    let mut encoded = HashMap::new();
+
+    for bits in 0..64 {
+        let c = panic::catch_unwind(AssertUnwindSafe(|| alphabet.encode_bits(bits)))
+            .unwrap_or_else(|_| panic!("{name}: encode_bits({bits}) panicked"));
+        if let Some(other) = encoded.insert(c, bits) {
+            panic!("{name}: encode_bits gives `{c}` for both {other} & {bits}");
+        }
+
+        match alphabet.decode_char(c) {
+            Ok(decoded) if decoded == bits => {}
+            Ok(decoded) => {
+                panic!("{name}: `{c}` decodes to {decoded}, but encode_bits({bits}) gave it")
+            }
+            Err(e) => panic!("{name}: decode_char(`{c}`) failed: {e}"),
+        }
+    }
+
+    if let Some(p) = alphabet.padding() {
+        if let Some(bits) = encoded.get(&p) {
+            panic!("{name}: the padding `{p}` is also what encode_bits({bits}) gives");
+        }
+        if let Ok(decoded) = alphabet.decode_char(p) {
+            panic!("{name}: the padding `{p}` decodes to {decoded}, but should be an error");
+        }
+    }
+
+    for c in char::MIN..=char::MAX {
+        if let Ok(value @ 64..) = alphabet.decode_char(c) {
+            panic!("{name}: `{c}` decodes to {value}, which doesn't fit in 6 bits");
+        }
+    }
+
+    for len in ROUND_TRIP_LENS {
+        for data in patterns(len) {
+            let b64 = Base64String::encode_with(&data, alphabet);
+            let text = b64.to_string();
+            let decoded = Base64String::from_encoded_with(text.as_str(), alphabet)
+                .unwrap_or_else(|e| panic!("{name}: failed to parse `{text}`: {e}"))
+                .decode()
+                .unwrap_or_else(|e| panic!("{name}: failed to decode `{text}`: {e}"));
+
+            assert!(
+                decoded == data,
+                "{name}: `{text}` decoded to {decoded:?} rather than {data:?}"
+            );
+        }
+    }
+}
+
+/// Byte patterns of length `len` that between them use every
+/// 6-bit value
+fn patterns(len: usize) -> [Vec<u8>; 4] {
+    [
+        vec![0; len],
+        vec![0xff; len],
+        (0..len).map(|i| i as u8).collect(),
+        (0..len)
+            .map(|i| (i as u8).wrapping_mul(0x9d) ^ 0xa5)
+            .collect(),
+    ]
+}
@@ -0,0 +1,690 @@
+use std::{io::Write, ops::Range};
+
+use crate::{
+    alphabet::Alphabet,
+    base64string::{
+        ascii_decode_table, decode_chars, redact, short_type_name, DecodedQuads, DECODE_CHUNK_LEN,
+        INVALID_BITS,
+    },
+    B64Error, Base64String, DecodeError, DecodeIntoError,
+};
+
+/// A borrowed view of already encoded Base64, for decoding text
+/// without copying it into a [`Base64String`]
+///
+/// The text is checked against the alphabet when the view is
+/// created, but never copied, so it's always kept as given, the
+/// same as by [`Base64String::from_encoded_with`]. Like
+/// [`Base64String`], any `=` padding at the end is left out of the
+/// view if the alphabet doesn't use padding
+///
+/// # Examples
+/// ```
+/// # use baze64::{Base64Str, alphabet::Standard};
+/// let input = String::from("aGVsbG8");
+/// let b64 = Base64Str::from_encoded_with(&input, Standard::new())?;
+///
+/// assert_eq!(b64.decode()?, b"hello");
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Clone, Copy)]
+pub struct Base64Str<'a, A> {
+    content: &'a str,
+    alphabet: A,
+}
+
+impl<'a, A> Base64Str<'a, A>
+where
+    A: Alphabet,
+{
+    /// Borrow already encoded Base64 as a [`Base64Str`] using a
+    /// given `alphabet` instance
+    ///
+    /// Whitespace around the text is left out, the same as
+    /// [`Base64String::from_encoded_with`]
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64Str, B64Error, alphabet::UrlSafe};
+    /// let b64 = Base64Str::from_encoded_with("-_8", UrlSafe::new())?;
+    ///
+    /// assert_eq!(b64.as_str(), "-_8");
+    /// assert!(Base64Str::from_encoded_with("+/8", UrlSafe::new()).is_err());
+    /// # Ok::<(), B64Error>(())
+    /// ```
+    pub fn from_encoded_with(b64: &'a str, alphabet: A) -> Result<Self, B64Error> {
+        let trimmed = b64.trim_end_matches(|c| Base64String::is_trimmed(c, &alphabet));
+        let start = trimmed.len()
+            - trimmed
+                .trim_start_matches(|c| Base64String::is_trimmed(c, &alphabet))
+                .len();
+        let b64 = Base64String::trim_rfc_padding(&trimmed[start..], &alphabet);
+        Base64String::check_chars(b64, &alphabet)
+            .map_err(|e| e.suggest_alphabet(b64, &alphabet).offset_by(start))?;
+
+        Ok(Self {
+            content: b64,
+            alphabet,
+        })
+    }
+
+    /// Decode the contents of `self` into a [`Vec<u8>`]
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64Str, alphabet::Standard};
+    /// let b64 = Base64Str::<Standard>::from_encoded("Zm9vYmFy")?;
+    ///
+    /// assert_eq!(b64.decode()?, b"foobar");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn decode(&self) -> Result<Vec<u8>, DecodeError> {
+        if self.content.is_ascii() {
+            return self.decode_with_table(&ascii_decode_table(&self.alphabet));
+        }
+
+        self.decode_quads()
+    }
+
+    /// Decode the contents of `self` using a `table` from
+    /// [`ascii_decode_table`] that's already been built for the alphabet
+    pub(crate) fn decode_with_table(&self, table: &[u8; 128]) -> Result<Vec<u8>, DecodeError> {
+        match self.decode_ascii(table) {
+            Some(decoded) => Ok(decoded),
+            None => self.decode_quads(),
+        }
+    }
+
+    /// Decode the contents of `self` a quad at a time, asking the
+    /// alphabet about every character
+    fn decode_quads(&self) -> Result<Vec<u8>, DecodeError> {
+        let mut decoded = Vec::with_capacity(self.content.len() / 4 * 3);
+
+        for quad in DecodedQuads::new(self.content, &self.alphabet) {
+            let (tri, len) =
+                quad.map_err(|e| DecodeError::suggest_alphabet(e, self.content, &self.alphabet))?;
+            decoded.extend_from_slice(&tri[..len]);
+        }
+
+        Ok(decoded)
+    }
+
+    /// Decode the contents of `self` into a [`Vec<u8>`], failing
+    /// with [`DecodeError::TooLarge`] before allocating anything if
+    /// it would be more than `max_bytes` long
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64Str, DecodeError, alphabet::Standard};
+    /// let b64 = Base64Str::<Standard>::from_encoded("Zm9vYmFy")?;
+    ///
+    /// assert_eq!(b64.decode_with_limit(6)?, b"foobar");
+    /// assert!(matches!(
+    ///     b64.decode_with_limit(5),
+    ///     Err(DecodeError::TooLarge { needed: 6, limit: 5 })
+    /// ));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn decode_with_limit(&self, max_bytes: usize) -> Result<Vec<u8>, DecodeError> {
+        self.check_limit(max_bytes)?;
+        self.decode()
+    }
+
+    /// Decode the contents of `self` into the `buf` provided,
+    /// returning the number of bytes written
+    ///
+    /// Behaves the same as [`Base64String::decode_into`], including
+    /// what's left in `buf` on failure
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64Str, alphabet::Standard};
+    /// let b64 = Base64Str::<Standard>::from_encoded("Zm9vYmE=")?;
+    /// let mut out = Vec::new();
+    ///
+    /// assert_eq!(b64.decode_into(&mut out)?, 5);
+    /// assert_eq!(out, b"fooba");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn decode_into<O>(&self, buf: &mut O) -> Result<usize, DecodeIntoError>
+    where
+        O: Write,
+    {
+        let mut chunk = [0; DECODE_CHUNK_LEN];
+        let mut filled = 0;
+        let mut bytes_written = 0;
+
+        let mut flush = |chunk: &[u8], bytes_written: &mut usize| {
+            buf.write_all(chunk)
+                .map_err(|e| DecodeIntoError::new(e.into(), *bytes_written))?;
+            *bytes_written += chunk.len();
+            Ok(())
+        };
+
+        for quad in DecodedQuads::new(self.content, &self.alphabet) {
+            let (tri, len) = match quad {
+                Ok(quad) => quad,
+                Err(e) => {
+                    // Keep everything decoded before the error
+                    flush(&chunk[..filled], &mut bytes_written)?;
+                    return Err(DecodeIntoError::new(e.into(), bytes_written));
+                }
+            };
+
+            if filled + len > chunk.len() {
+                flush(&chunk[..filled], &mut bytes_written)?;
+                filled = 0;
+            }
+            chunk[filled..filled + len].copy_from_slice(&tri[..len]);
+            filled += len;
+        }
+        flush(&chunk[..filled], &mut bytes_written)?;
+
+        Ok(bytes_written)
+    }
+
+    /// Decode the contents of `self` into the `buf` provided, failing
+    /// with [`DecodeError::TooLarge`] before writing anything if it
+    /// would be more than `max_bytes` long
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64Str, alphabet::Standard};
+    /// let b64 = Base64Str::<Standard>::from_encoded("Zm9vYmFy")?;
+    /// let mut out = Vec::new();
+    ///
+    /// assert!(b64.decode_into_with_limit(&mut out, 5).is_err());
+    /// assert!(out.is_empty());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn decode_into_with_limit<O>(
+        &self,
+        buf: &mut O,
+        max_bytes: usize,
+    ) -> Result<usize, DecodeIntoError>
+    where
+        O: Write,
+    {
+        self.check_limit(max_bytes)
+            .map_err(|e| DecodeIntoError::new(e, 0))?;
+        self.decode_into(buf)
+    }
+
+    /// The number of bytes the contents of `self` decode to, worked
+    /// out from their length without decoding them
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64Str, alphabet::Standard};
+    /// assert_eq!(Base64Str::<Standard>::from_encoded("Zm9vYg==")?.decoded_len(), 4);
+    /// assert_eq!(Base64Str::<Standard>::from_encoded("Zm9vYg")?.decoded_len(), 4);
+    /// # Ok::<(), baze64::B64Error>(())
+    /// ```
+    pub fn decoded_len(&self) -> usize {
+        let padding = self.alphabet.padding();
+        let (len, data_len): (usize, usize) =
+            self.content.chars().fold((0, 0), |(len, data_len), c| {
+                (len + 1, data_len + usize::from(Some(c) != padding))
+            });
+
+        // Every quad decodes to one byte fewer than it has data
+        // characters, whether it's padded or not
+        data_len.saturating_sub(len.div_ceil(4))
+    }
+
+    /// Decode only the bytes in `range` of the data, without decoding
+    /// the rest of it
+    ///
+    /// Every 3 bytes of data are encoded by 4 characters, so only the
+    /// quads covering `range` are decoded. Fails with
+    /// [`DecodeError::OutOfBounds`] if `range` isn't within the
+    /// [`decoded_len`](Base64Str::decoded_len)
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64Str, DecodeError, alphabet::Standard};
+    /// let b64 = Base64Str::<Standard>::from_encoded("Zm9vYmFyYmF6")?;
+    ///
+    /// assert_eq!(b64.decode_range(2..7)?, b"obarb");
+    /// assert!(matches!(
+    ///     b64.decode_range(8..10),
+    ///     Err(DecodeError::OutOfBounds { start: 8, end: 10, len: 9 })
+    /// ));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[doc(alias = "slice_decoded")]
+    pub fn decode_range(&self, range: Range<usize>) -> Result<Vec<u8>, DecodeError> {
+        let len = self.decoded_len();
+        if range.start > range.end || range.end > len {
+            return Err(DecodeError::OutOfBounds {
+                start: range.start,
+                end: range.end,
+                len,
+            });
+        }
+        if range.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Padding before the last quad is invalid, so decode it all
+        // to find where
+        if let Some(p) = self.alphabet.padding() {
+            if self.content.trim_end_matches(p).contains(p) {
+                return Ok(self.decode()?[range].to_vec());
+            }
+        }
+
+        let first_quad = range.start / 3;
+        let last_quad = range.end.div_ceil(3);
+        let start = first_quad * 4;
+        let text = char_slice(self.content, start, last_quad * 4);
+
+        let mut decoded = Vec::with_capacity((last_quad - first_quad) * 3);
+        for quad in DecodedQuads::new(text, &self.alphabet) {
+            let (tri, len) = quad.map_err(|e| e.offset_by(start))?;
+            decoded.extend_from_slice(&tri[..len]);
+        }
+        // Trim the bytes of the first & last triplets outside the range
+        decoded.drain(..range.start - first_quad * 3);
+        decoded.truncate(range.len());
+
+        Ok(decoded)
+    }
+
+    /// Decode through a lookup table of what the alphabet decodes
+    /// each ASCII character to, which is much faster than asking it
+    /// about every character
+    ///
+    /// Returns [`None`] if the text isn't all ASCII or doesn't
+    /// decode, leaving the slow path to find the error
+    fn decode_ascii(&self, table: &[u8; 128]) -> Option<Vec<u8>> {
+        let bytes = self.content.as_bytes();
+        if !bytes.is_ascii() {
+            return None;
+        }
+
+        // The last quad may be short or padded, so it's decoded
+        // separately
+        let body_len = bytes.len().saturating_sub(1) / 4 * 4;
+        let (body, tail) = bytes.split_at(body_len);
+        let mut decoded = Vec::with_capacity(body_len / 4 * 3 + 3);
+        #[cfg(feature = "simd")]
+        let body = &body[crate::simd::decode_prefix(body, table, &mut decoded)?..];
+
+        for quad in body.chunks_exact(4) {
+            let mut concated = 0;
+            let mut invalid = 0;
+            for &c in quad {
+                let bits = table[c as usize];
+                invalid |= bits;
+                concated = concated << 6 | bits as u32;
+            }
+            if invalid == INVALID_BITS {
+                return None;
+            }
+            decoded.extend_from_slice(&[
+                (concated >> 16) as u8,
+                (concated >> 8) as u8,
+                concated as u8,
+            ]);
+        }
+
+        if !tail.is_empty() {
+            let mut quad = ['\0'; 4];
+            for (slot, &c) in quad.iter_mut().zip(tail) {
+                *slot = c.into();
+            }
+            let (tri, len) = decode_chars(&quad[..tail.len()], body_len, &self.alphabet).ok()?;
+            decoded.extend_from_slice(&tri[..len]);
+        }
+
+        Some(decoded)
+    }
+
+    /// Check `self` decodes to no more than `max_bytes`
+    fn check_limit(&self, max_bytes: usize) -> Result<(), DecodeError> {
+        let needed = self.decoded_len();
+        if needed > max_bytes {
+            Err(DecodeError::TooLarge {
+                needed,
+                limit: max_bytes,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Decode the contents of `self` into a [`String`]
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64Str, alphabet::Standard};
+    /// let b64 = Base64Str::<Standard>::from_encoded("aGk=")?;
+    ///
+    /// assert_eq!(b64.decode_to_string()?, "hi");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn decode_to_string(&self) -> Result<String, DecodeError> {
+        let string = String::from_utf8(self.decode()?)?;
+        Ok(string)
+    }
+
+    /// Copy the contents of `self` into an owned [`Base64String`],
+    /// padding them if the alphabet uses padding
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64Str, alphabet::Standard};
+    /// let b64 = Base64Str::<Standard>::from_encoded("aGk")?;
+    ///
+    /// assert_eq!(b64.to_base64_string(), "aGk=");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn to_base64_string(self) -> Base64String<A> {
+        // Already checked, so this can't fail
+        let mut b64 = Base64String::from_encoded_with(self.content, self.alphabet)
+            .expect("content was validated on construction");
+        b64.canonicalize();
+        b64
+    }
+
+    /// Construct a [`Base64Str`] from content that's already
+    /// known to be valid for `alphabet`
+    pub(crate) fn from_parts(content: &'a str, alphabet: A) -> Self {
+        Self { content, alphabet }
+    }
+
+    /// Get the borrowed text
+    pub fn as_str(&self) -> &'a str {
+        self.content
+    }
+
+    /// Get a reference to the alphabet used by `self`
+    pub fn alphabet(&self) -> &A {
+        &self.alphabet
+    }
+}
+
+impl<'a, A> Base64Str<'a, A>
+where
+    A: Alphabet + Default,
+{
+    /// Borrow already encoded Base64 as a [`Base64Str`]
+    ///
+    /// Uses `A`'s [`Default`] impl as the alphabet to decode
+    /// with
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64Str, alphabet::Standard};
+    /// let b64 = Base64Str::<Standard>::from_encoded("aGk=")?;
+    /// # Ok::<(), baze64::B64Error>(())
+    /// ```
+    pub fn from_encoded(b64: &'a str) -> Result<Self, B64Error> {
+        Self::from_encoded_with(b64, A::default())
+    }
+}
+
+impl<A> core::fmt::Display for Base64Str<'_, A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.content)
+    }
+}
+
+/// Redacts the content the same way as [`Base64String`]'s
+/// [`Debug`](core::fmt::Debug) impl
+impl<A> core::fmt::Debug for Base64Str<'_, A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct(&format!("Base64Str<{}>", short_type_name::<A>()))
+            .field("len", &self.content.chars().count())
+            .field("content", &redact(self.content))
+            .finish()
+    }
+}
+
+impl<A> AsRef<str> for Base64Str<'_, A> {
+    fn as_ref(&self) -> &str {
+        self.content
+    }
+}
+
+/// Slice `text` from the character at index `start` up to the one at
+/// index `end`, or the end of `text` if it's shorter
+fn char_slice(text: &str, start: usize, end: usize) -> &str {
+    if text.is_ascii() {
+        return &text[start.min(text.len())..end.min(text.len())];
+    }
+
+    let byte_index = |n| text.char_indices().nth(n).map_or(text.len(), |(i, _)| i);
+    &text[byte_index(start)..byte_index(end)]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alphabet::{Standard, UrlSafe};
+
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn from_encoded_suggests_alphabet() {
+        assert_eq!(
+            Base64Str::<Standard>::from_encoded("\n-_8").unwrap_err(),
+            B64Error::WrongAlphabet {
+                char: '-',
+                index: 1,
+                suggestion: crate::alphabet::AlphabetKind::UrlSafe
+            }
+        );
+    }
+
+    #[test]
+    fn matches_owned_decode() {
+        for data in [&b""[..], b"f", b"fo", b"foo", b"foob", b"fooba", b"foobar"] {
+            let owned = Base64String::<UrlSafe>::encode(data);
+            let borrowed = Base64Str::<UrlSafe>::from_encoded(owned.as_ref()).unwrap();
+
+            assert_eq!(borrowed.decode().unwrap(), owned.decode().unwrap());
+        }
+    }
+
+    #[test]
+    fn ascii_fast_path_falls_back() {
+        // Only the fast path sees these, as they're never checked
+        for text in ["Zm9v-mFy", "Zm9vYmF-", "Zm9vY", "Zm9=YmFy"] {
+            let b64 = Base64Str::from_parts(text, Standard::new());
+            let slow = DecodedQuads::new(text, &Standard::new())
+                .map(|quad| quad.map(|(tri, len)| tri[..len].to_vec()))
+                .collect::<Result<Vec<_>, _>>()
+                .map(|quads| quads.concat());
+
+            assert_eq!(
+                b64.decode_ascii(&ascii_decode_table(&Standard::new())),
+                None
+            );
+            assert_eq!(
+                format!("{:?}", b64.decode()),
+                format!(
+                    "{:?}",
+                    slow.map_err(|e| DecodeError::suggest_alphabet(e, text, &Standard::new()))
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn non_ascii_takes_slow_path() {
+        let alphabet = Standard::new().with_padding('…').unwrap();
+        let b64 = Base64Str::from_encoded_with("aGk…", alphabet).unwrap();
+
+        assert_eq!(b64.decode_ascii(&ascii_decode_table(&alphabet)), None);
+        assert_eq!(b64.decode().unwrap(), b"hi");
+    }
+
+    #[test]
+    fn trims_whitespace() {
+        let b64 = Base64Str::<Standard>::from_encoded("\tZXZlbg==\r\n").unwrap();
+
+        assert_eq!(b64.as_str(), "ZXZlbg==");
+        assert_eq!(b64.decode().unwrap(), b"even");
+        assert_eq!(
+            Base64Str::<Standard>::from_encoded("  Zm9v#").unwrap_err(),
+            B64Error::InvalidCharAt {
+                char: '#',
+                index: 6
+            }
+        );
+    }
+
+    #[test]
+    fn decode_range_mid_triplet() {
+        let b64 = Base64Str::<Standard>::from_encoded("Zm9vYmFyYmF6").unwrap();
+
+        assert_eq!(b64.decode_range(0..9).unwrap(), b"foobarbaz");
+        assert_eq!(b64.decode_range(1..2).unwrap(), b"o");
+        assert_eq!(b64.decode_range(2..4).unwrap(), b"ob");
+        assert_eq!(b64.decode_range(5..5).unwrap(), b"");
+    }
+
+    #[test]
+    fn decode_range_padded_tail() {
+        let padded = Base64Str::<Standard>::from_encoded("Zm9vYg==").unwrap();
+        let unpadded = Base64Str::<Standard>::from_encoded("Zm9vYg").unwrap();
+
+        for b64 in [padded, unpadded] {
+            assert_eq!(b64.decode_range(3..4).unwrap(), b"b");
+            assert_eq!(b64.decode_range(2..4).unwrap(), b"ob");
+        }
+    }
+
+    #[test]
+    fn decode_range_out_of_bounds() {
+        let b64 = Base64Str::<Standard>::from_encoded("Zm9vYg==").unwrap();
+
+        assert!(matches!(
+            b64.decode_range(2..5),
+            Err(DecodeError::OutOfBounds {
+                start: 2,
+                end: 5,
+                len: 4
+            })
+        ));
+        assert!(matches!(
+            b64.decode_range(Range { start: 3, end: 2 }),
+            Err(DecodeError::OutOfBounds { .. })
+        ));
+    }
+
+    #[test]
+    fn decode_range_interior_padding() {
+        let b64 = Base64Str::<Standard>::from_encoded("Zm8=YmFy").unwrap();
+        let err = B64Error::InvalidCharAt {
+            char: '=',
+            index: 3,
+        };
+
+        assert!(matches!(b64.decode(), Err(DecodeError::Base64Error(e)) if e == err));
+        assert!(matches!(
+            b64.decode_range(1..4),
+            Err(DecodeError::Base64Error(e)) if e == err
+        ));
+    }
+
+    #[test]
+    fn decode_range_multi_byte_padding() {
+        let alphabet = Standard::new().with_padding('…').unwrap();
+        let b64 = Base64Str::from_encoded_with("Zm9vYmFyYg……", alphabet).unwrap();
+
+        assert_eq!(b64.decode_range(4..7).unwrap(), b"arb");
+    }
+
+    #[test]
+    fn unpadded_isnt_copied() {
+        let input = "Zm9vYg";
+        let b64 = Base64Str::<Standard>::from_encoded(input).unwrap();
+
+        assert_eq!(b64.as_str().as_ptr(), input.as_ptr());
+        assert_eq!(b64.decode().unwrap(), b"foob");
+    }
+
+    #[test]
+    fn reports_invalid_index() {
+        assert!(matches!(
+            Base64Str::<Standard>::from_encoded("Zm9v-mFy"),
+            Err(B64Error::WrongAlphabet {
+                char: '-',
+                index: 4,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn decode_into_keeps_prefix() {
+        let b64 = Base64Str::<Standard>::from_encoded("Zm9v=mFy").unwrap();
+        let mut out = vec![];
+        let err = b64.decode_into(&mut out).unwrap_err();
+
+        assert_eq!(err.bytes_written, 3);
+        assert_eq!(out, b"foo");
+    }
+
+    #[test]
+    fn decoded_len_exact() {
+        for len in 0..16 {
+            let data = vec![0xa5; len];
+            let padded = Base64String::<Standard>::encode(&data);
+            let unpadded = padded.without_padding();
+
+            assert_eq!(padded.as_base64_str().decoded_len(), len);
+            assert_eq!(
+                Base64Str::<Standard>::from_encoded(&unpadded)
+                    .unwrap()
+                    .decoded_len(),
+                len
+            );
+        }
+    }
+
+    #[test]
+    fn decoded_len_concatenated() {
+        let b64 = Base64String::<Standard>::from_encoded("Zm8=YmFyYg==").unwrap();
+
+        assert_eq!(
+            b64.as_base64_str().decoded_len(),
+            b64.decode_concatenated().unwrap().concat().len()
+        );
+    }
+
+    #[test]
+    fn decode_with_limit_boundary() {
+        let b64 = Base64Str::<Standard>::from_encoded("Zm9vYg==").unwrap();
+
+        assert_eq!(b64.decode_with_limit(4).unwrap(), b"foob");
+        assert!(matches!(
+            b64.decode_with_limit(3),
+            Err(DecodeError::TooLarge {
+                needed: 4,
+                limit: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn decode_into_with_limit_writes_nothing() {
+        let b64 = Base64Str::<Standard>::from_encoded("Zm9vYg==").unwrap();
+        let mut out = vec![];
+        let err = b64.decode_into_with_limit(&mut out, 3).unwrap_err();
+
+        assert_eq!(err.bytes_written, 0);
+        assert!(out.is_empty());
+        assert_eq!(b64.decode_into_with_limit(&mut out, 4).unwrap(), 4);
+    }
+
+    #[test]
+    fn debug_hides_content() {
+        let b64 = Base64Str::<Standard>::from_encoded("c2VjcmV0").unwrap();
+
+        assert!(!format!("{b64:?}").contains("c2VjcmV0"));
+    }
+}
@@ -1,22 +1,59 @@
-use std::io::Write;
+use std::{
+    io::{Read, Write},
+    ops::Range,
+};
 
-use crate::{alphabet::Alphabet, B64Error};
+use crate::{
+    alphabet::Alphabet, primitives, B64Error, Base64Str, DecodeError, DecodeIntoError,
+    EncodeReaderError, TransformError,
+};
+
+/// The number of decoded bytes [`Base64String::decode_into`] collects
+/// before writing them out
+pub(crate) const DECODE_CHUNK_LEN: usize = 8 * 1024;
+
+/// The number of bytes [`Base64String::encode_reader_with`] reads
+/// at a time, a multiple of 3 so whole chunks never need padding
+const ENCODE_READER_CHUNK_LEN: usize = 3 * 1024;
+
+/// The padding character from RFC 4648, which is dropped from the
+/// end of text parsed with an alphabet that doesn't use padding
+const RFC_PADDING: char = '=';
 
 /// A string of Base64 encoded data
-#[derive(Debug, Clone)]
+///
+/// The [`Debug`](core::fmt::Debug) impl redacts all but the first &
+/// last 4 characters of the content, as it's often secret. Use
+/// [`Base64String::debug_unredacted`] to debug print all of it
+#[derive(Clone)]
 pub struct Base64String<A> {
     content: String,
     alphabet: A,
+    /// The bytes written through the [`Write`] impl that don't fill a
+    /// triplet yet, or [`None`] if nothing's been written since the
+    /// content was last complete
+    pending: Option<Pending>,
+}
+
+/// Up to 2 bytes written to a [`Base64String`] that are held back
+/// until more are written or [`Base64String::finish`] is called
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Pending {
+    bytes: [u8; 2],
+    len: usize,
 }
 
-#[derive(Debug, thiserror::Error)]
-pub enum DecodeError {
-    #[error(transparent)]
-    Base64Error(#[from] B64Error),
-    #[error(transparent)]
-    WriteError(#[from] std::io::Error),
-    #[error(transparent)]
-    InvalidUtf8(#[from] std::string::FromUtf8Error),
+impl Pending {
+    fn new(bytes: &[u8]) -> Self {
+        let mut pending = Self::default();
+        pending.bytes[..bytes.len()].copy_from_slice(bytes);
+        pending.len = bytes.len();
+        pending
+    }
+
+    fn as_slice(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
 }
 
 impl<A> Base64String<A>
@@ -26,47 +63,234 @@ where
     /// Encode a sequence of bytes into a [`Base64String`] using a
     /// given `alphabet` instance
     ///
+    /// Encoding can't fail, so there's nothing to unwrap
+    ///
     /// # Examples
     /// ```
     /// # use baze64::*;
     /// # type MyAlphabet = baze64::alphabet::Standard;
     ///
-    /// let data = "secret message".as_bytes();
+    /// let data = [0xde, 0xad, 0xbe, 0xef];
     /// let alphabet = MyAlphabet::new();
     /// let encoded = Base64String::encode_with(&data, alphabet);
-    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    ///
+    /// assert_eq!(encoded, "3q2+7w==");
     /// ```
     pub fn encode_with<B>(bytes: B, alphabet: A) -> Self
     where
         B: AsRef<[u8]>,
     {
         let bytes = bytes.as_ref();
-        let padding = alphabet.padding().unwrap_or_default();
+        let content = Self::encode_ascii(bytes, &alphabet).unwrap_or_else(|| {
+            let mut content = String::new();
+            Self::push_encoded(&mut content, bytes, &alphabet);
+            content
+        });
 
-        let chunks = bytes.chunks(3);
-        let mut encoded = vec![];
+        Self::from_parts(content, alphabet)
+    }
 
-        for chunk in chunks {
-            match chunk.len() {
-                3 => encoded.push(Self::encode_triplet(
-                    [chunk[0], chunk[1], chunk[2]],
-                    &alphabet,
-                )),
-                2 => {
-                    let res = Self::encode_triplet([chunk[0], chunk[1], 0x00], &alphabet);
-                    encoded.push([res[0], res[1], res[2], padding])
-                }
-                1 => {
-                    let res = Self::encode_triplet([chunk[0], 0x00, 0x00], &alphabet);
-                    encoded.push([res[0], res[1], padding, padding])
+    /// Encode the UTF-8 bytes of `s` into a [`Base64String`] using a
+    /// given `alphabet` instance
+    ///
+    /// There's deliberately no `From<&str>` doing the same, as
+    /// [`str::parse`] reads already encoded text instead
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, alphabet::UrlSafe};
+    /// let encoded = Base64String::encode_str_with("héllo 👋", UrlSafe::new());
+    ///
+    /// assert_eq!(encoded, "aMOpbGxvIPCfkYs=");
+    /// ```
+    pub fn encode_str_with(s: &str, alphabet: A) -> Self {
+        Self::encode_with(s, alphabet)
+    }
+
+    /// Encode the UTF-8 bytes of `s` into a [`Base64String`] using a
+    /// given `alphabet` instance
+    ///
+    /// Encodes within `s`'s allocation where the alphabet allows,
+    /// only growing it to fit the longer output
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, alphabet::Standard};
+    /// let message = String::from("こんにちは");
+    /// let encoded = Base64String::encode_string_with(message, Standard::new());
+    ///
+    /// assert_eq!(encoded.decode_to_string()?, "こんにちは");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn encode_string_with(s: String, alphabet: A) -> Self {
+        match Self::encode_ascii_in_place(s.into_bytes(), &alphabet) {
+            Ok(content) => Self::from_parts(content, alphabet),
+            Err(bytes) => Self::encode_with(bytes, alphabet),
+        }
+    }
+
+    /// Encode several buffers into one [`Base64String`] using a
+    /// given `alphabet` instance, as if they'd been concatenated
+    ///
+    /// Bytes left over at the end of a buffer are carried into the
+    /// next, so only the end of the output is ever padded
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, alphabet::Standard};
+    /// let bufs: [&[u8]; 3] = [b"fo", b"ob", b"ar"];
+    /// let encoded = Base64String::encode_vectored_with(bufs, Standard::new());
+    ///
+    /// assert_eq!(encoded, Base64String::encode(b"foobar"));
+    /// ```
+    pub fn encode_vectored_with<I, B>(bufs: I, alphabet: A) -> Self
+    where
+        I: IntoIterator<Item = B>,
+        B: AsRef<[u8]>,
+    {
+        let mut content = String::new();
+        let mut carry = [0; 3];
+        let mut carry_len = 0;
+
+        for buf in bufs {
+            let mut buf = buf.as_ref();
+            if carry_len > 0 {
+                let take = buf.len().min(3 - carry_len);
+                carry[carry_len..carry_len + take].copy_from_slice(&buf[..take]);
+                carry_len += take;
+                buf = &buf[take..];
+                if carry_len < 3 {
+                    continue;
                 }
-                _ => unreachable!("Mathematically impossible"),
+                Self::push_encoded(&mut content, &carry, &alphabet);
             }
+
+            let whole = buf.len() - buf.len() % 3;
+            Self::push_encoded(&mut content, &buf[..whole], &alphabet);
+            carry_len = buf.len() - whole;
+            carry[..carry_len].copy_from_slice(&buf[whole..]);
         }
+        Self::push_encoded(&mut content, &carry[..carry_len], &alphabet);
 
-        Self {
-            content: encoded.iter().flatten().collect(),
-            alphabet,
+        Self::from_parts(content, alphabet)
+    }
+
+    /// Encode everything read from `reader` into a [`Base64String`]
+    /// using a given `alphabet` instance
+    ///
+    /// The data is read & encoded a chunk at a time, so it never has
+    /// to be held in memory all at once
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use baze64::{Base64String, alphabet::Standard};
+    /// # use std::fs::File;
+    /// let file = File::open("path/to/file.ext")?;
+    /// let encoded = Base64String::encode_reader_with(file, Standard::new())?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn encode_reader_with<R>(reader: R, alphabet: A) -> Result<Self, EncodeReaderError>
+    where
+        R: Read,
+    {
+        Self::encode_reader_with_len(reader, 0, alphabet)
+    }
+
+    /// Encode everything read from `reader` into a [`Base64String`]
+    /// using a given `alphabet` instance, expecting to read about
+    /// `len` bytes
+    ///
+    /// `len` is only used to reserve space for the output up front,
+    /// reading always continues until the end of `reader`. See
+    /// [`Base64String::encode_reader_with`] for more
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use baze64::{Base64String, alphabet::Standard};
+    /// # use std::fs::File;
+    /// let file = File::open("path/to/file.ext")?;
+    /// let len = file.metadata()?.len();
+    /// let encoded = Base64String::encode_reader_with_len(file, len, Standard::new())?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn encode_reader_with_len<R>(
+        mut reader: R,
+        len: u64,
+        alphabet: A,
+    ) -> Result<Self, EncodeReaderError>
+    where
+        R: Read,
+    {
+        let capacity = usize::try_from(len).unwrap_or_default().div_ceil(3) * 4;
+        let mut content = String::with_capacity(capacity);
+        let mut chunk = [0; ENCODE_READER_CHUNK_LEN];
+        let mut filled = 0;
+
+        loop {
+            let read = match reader.read(&mut chunk[filled..]) {
+                Ok(0) => break,
+                Ok(read) => read,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e.into()),
+            };
+            filled += read;
+
+            // Carry any bytes that don't make a whole triplet into
+            // the next read, so only the end is ever padded
+            let whole = filled - filled % 3;
+            Self::push_encoded(&mut content, &chunk[..whole], &alphabet);
+            chunk.copy_within(whole..filled, 0);
+            filled -= whole;
+        }
+        Self::push_encoded(&mut content, &chunk[..filled], &alphabet);
+
+        Ok(Self::from_parts(content, alphabet))
+    }
+
+    /// Create an empty [`Base64String`] using a given `alphabet`
+    /// instance, ready to have bytes written to it through its
+    /// [`Write`] impl
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, alphabet::Standard};
+    /// use std::io::Write;
+    ///
+    /// let mut b64 = Base64String::new_with(Standard::new());
+    /// b64.write_all(b"foo")?;
+    /// b64.write_all(b"bar")?;
+    /// b64.finish();
+    ///
+    /// assert_eq!(b64.to_string(), "Zm9vYmFy");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn new_with(alphabet: A) -> Self {
+        Self::from_parts(String::new(), alphabet)
+    }
+
+    /// Encode & pad the last bytes written through the [`Write`] impl,
+    /// which are held back until this is called in case more follow
+    /// them
+    ///
+    /// Writing more afterwards carries on the data, re-encoding the
+    /// padded quad along with the new bytes
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, alphabet::Standard};
+    /// use std::io::Write;
+    ///
+    /// let mut b64 = Base64String::new_with(Standard::new());
+    /// b64.write_all(b"food")?;
+    /// assert_eq!(b64.to_string(), "Zm9v");
+    ///
+    /// b64.finish();
+    /// assert_eq!(b64.to_string(), "Zm9vZA==");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn finish(&mut self) {
+        if let Some(pending) = self.pending.take() {
+            Self::push_encoded(&mut self.content, pending.as_slice(), &self.alphabet);
         }
     }
 
@@ -76,21 +300,27 @@ where
     /// ```
     /// # use baze64::{Base64String, alphabet::Standard};
     /// let data = "Pretend this is important";
-    /// let base64 = Base64String::<Standard>::encode(data.as_bytes());
+    /// let base64 = Base64String::<Standard>::encode_str(data);
     /// let decoded_bytes = base64.decode()?;
     ///
     /// assert_eq!(data.as_bytes(), &decoded_bytes);
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn decode(&self) -> Result<Vec<u8>, DecodeError> {
-        let mut decoded = vec![];
-
-        self.decode_into(&mut decoded)?;
-
-        Ok(decoded)
+        self.as_base64_str().decode()
     }
 
-    /// Decode the contents of `self` into the `buf` provided
+    /// Decode the contents of `self` into the `buf` provided,
+    /// returning the number of bytes written
+    ///
+    /// Decoded bytes are collected into an 8 KiB chunk that's written
+    /// to `buf` each time it fills up, so `buf` doesn't need to be
+    /// wrapped in a [`BufWriter`](std::io::BufWriter). If an error
+    /// occurs part way through, `buf` will already contain everything
+    /// decoded before it. The returned [`DecodeIntoError`] reports how
+    /// many bytes that was, so the output can be truncated or resumed.
+    /// Use [`Base64String::decode_into_buffered`] if nothing should be
+    /// written on failure
     ///
     /// # Examples
     /// ```no_run
@@ -98,419 +328,2635 @@ where
     /// # use std::{fs::File, io::Read};
     ///
     /// let data = "Definitely not contrived";
-    /// let base64 = Base64String::<Standard>::encode(data.as_bytes());
+    /// let base64 = Base64String::<Standard>::encode_str(data);
     /// let mut file = File::open("some/file.txt")?;
     /// base64.decode_into(&mut file)?;
     ///
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
-    pub fn decode_into<O>(&self, buf: &mut O) -> Result<(), DecodeError>
+    pub fn decode_into<O>(&self, buf: &mut O) -> Result<usize, DecodeIntoError>
     where
         O: Write,
     {
-        let padding = self.alphabet.padding().unwrap_or_default();
-        let tmp = self.content.chars().collect::<Vec<_>>();
-        let segments = tmp.chunks_exact(4);
-
-        for seg in segments {
-            if seg.ends_with(&[padding, padding]) || seg.len() % 4 == 2 {
-                let tri =
-                    Self::decode_quad([seg[0], seg[1], 0 as char, 0 as char], &self.alphabet)?;
-                buf.write_all(&[tri[0]])?;
-            } else if seg.ends_with(&[padding]) || seg.len() % 4 == 3 {
-                let tri = Self::decode_quad([seg[0], seg[1], seg[2], 0 as char], &self.alphabet)?;
-                buf.write_all(&tri[0..2])?;
-            } else {
-                let tri = Self::decode_quad([seg[0], seg[1], seg[2], seg[3]], &self.alphabet)?;
-                buf.write_all(&tri)?;
-            }
-        }
+        self.as_base64_str().decode_into(buf)
+    }
 
-        Ok(())
+    /// Decode the contents of `self` into a [`Vec<u8>`], failing
+    /// with [`DecodeError::TooLarge`] before allocating anything if
+    /// it would be more than `max_bytes` long
+    ///
+    /// Use this to cap how much memory decoding untrusted input can
+    /// use
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, ErrorKind, alphabet::Standard};
+    /// let base64 = Base64String::<Standard>::encode([0; 1024]);
+    ///
+    /// assert_eq!(base64.decode_with_limit(1024)?.len(), 1024);
+    /// assert_eq!(
+    ///     base64.decode_with_limit(1023).unwrap_err().kind(),
+    ///     ErrorKind::TooLarge
+    /// );
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn decode_with_limit(&self, max_bytes: usize) -> Result<Vec<u8>, DecodeError> {
+        self.as_base64_str().decode_with_limit(max_bytes)
     }
 
-    /// Decode the contents of `self` into a [`String`]
+    /// Decode the contents of `self` into the `buf` provided, failing
+    /// with [`DecodeError::TooLarge`] before writing anything if it
+    /// would be more than `max_bytes` long
+    ///
+    /// Otherwise the same as [`Base64String::decode_into`]
+    pub fn decode_into_with_limit<O>(
+        &self,
+        buf: &mut O,
+        max_bytes: usize,
+    ) -> Result<usize, DecodeIntoError>
+    where
+        O: Write,
+    {
+        self.as_base64_str().decode_into_with_limit(buf, max_bytes)
+    }
+
+    /// The number of bytes the contents of `self` decode to, worked
+    /// out from their length without decoding them
     ///
     /// # Examples
     /// ```
     /// # use baze64::{Base64String, alphabet::Standard};
-    /// let message = "Secret message :D";
-    /// let encoded = Base64String::<Standard>::encode(message.as_bytes());
-    /// let decoded = encoded.decode_to_string()?;
+    /// let base64 = Base64String::<Standard>::encode(b"fooba");
     ///
-    /// assert_eq!(message, decoded.as_str());
+    /// assert_eq!(base64.decoded_len(), 5);
+    /// ```
+    pub fn decoded_len(&self) -> usize {
+        self.as_base64_str().decoded_len()
+    }
+
+    /// Decode only the bytes in `range` of the data, without decoding
+    /// the rest of it
+    ///
+    /// Every 3 bytes of data are encoded by 4 characters, so only the
+    /// quads covering `range` are decoded. Fails with
+    /// [`DecodeError::OutOfBounds`] if `range` isn't within the
+    /// [`decoded_len`](Base64String::decoded_len)
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, alphabet::Standard};
+    /// let asset = Base64String::<Standard>::encode([7; 4096]);
+    ///
+    /// assert_eq!(asset.decode_range(1024..2048)?, [7; 1024]);
+    /// assert!(asset.decode_range(4000..5000).is_err());
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
-    pub fn decode_to_string(&self) -> Result<String, DecodeError> {
-        let string = String::from_utf8(self.decode()?)?;
-        Ok(string)
+    #[doc(alias = "slice_decoded")]
+    pub fn decode_range(&self, range: Range<usize>) -> Result<Vec<u8>, DecodeError> {
+        self.as_base64_str().decode_range(range)
     }
 
-    /// Contruct a [`Base64String`] from already encoded
-    /// Base64
+    /// Decode the contents of `self` into the `buf` provided,
+    /// returning the number of bytes written
+    ///
+    /// Unlike [`Base64String::decode_into`], the whole string is
+    /// decoded into memory first & only written to `buf` once decoding
+    /// has succeeded, so nothing is written if the contents are invalid
     ///
     /// # Examples
     /// ```
     /// # use baze64::{Base64String, alphabet::Standard};
-    /// // Pretend this does something useful
-    /// fn do_something(something_encoded: &str) {
-    ///     let base64 = Base64String::from_encoded_with(something_encoded, Standard::new());
-    ///     // Now to use the Base64String!
-    /// }
+    /// let data = "Small enough to hold in memory";
+    /// let base64 = Base64String::<Standard>::encode_str(data);
+    /// let mut out = Vec::new();
+    /// base64.decode_into_buffered(&mut out)?;
+    ///
+    /// assert_eq!(data.as_bytes(), &out);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
-    pub fn from_encoded_with<S>(b64: S, alphabet: A) -> Result<Self, B64Error>
+    pub fn decode_into_buffered<O>(&self, buf: &mut O) -> Result<usize, DecodeError>
     where
-        S: ToString,
+        O: Write,
     {
-        let mut content = b64.to_string();
-        if let Some(c) = content.chars().find(|&c| alphabet.decode_char(c).is_err()) {
-            return Err(B64Error::InvalidChar(c));
-        }
+        let decoded = self.decode()?;
+        buf.write_all(&decoded)?;
+
+        Ok(decoded.len())
+    }
+
+    /// Decode several base64 documents concatenated together in
+    /// `self`, returning each document's bytes separately
+    ///
+    /// A document ends wherever padding does, so a document whose
+    /// length is a multiple of 3 bytes (which has no padding) is
+    /// joined with the one after it. Alphabets without padding
+    /// always produce a single document
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, alphabet::Standard};
+    /// let base64 = Base64String::<Standard>::from_encoded("Zm8=YmFyYg==")?;
+    /// let documents = base64.decode_concatenated()?;
+    ///
+    /// assert_eq!(documents, [&b"fo"[..], b"barb"]);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn decode_concatenated(&self) -> Result<Vec<Vec<u8>>, DecodeError> {
+        let mut documents = vec![];
+        let mut document = vec![];
 
-        if let Some(p) = alphabet.padding() {
-            while content.len() % 4 != 0 {
-                content.push(p)
+        for quad in DecodedQuads::new_concatenated(&self.content, &self.alphabet) {
+            let (tri, len) = quad?;
+            document.extend_from_slice(&tri[..len]);
+            // Only a padded quad, or the very last one, can be short
+            if len < 3 {
+                documents.push(std::mem::take(&mut document));
             }
         }
+        if !document.is_empty() {
+            documents.push(document);
+        }
 
-        Ok(Self { content, alphabet })
+        Ok(documents)
     }
 
-    /// Returns the encoded string with the padding removed
+    /// Check whether the contents of `self` decode to `bytes`,
+    /// without allocating
     ///
-    /// # Example
+    /// Every byte is compared even once a difference is found, so
+    /// how long this takes doesn't reveal where `bytes` differs,
+    /// which matters when checking something like a signature
+    ///
+    /// # Examples
     /// ```
     /// # use baze64::{Base64String, alphabet::Standard};
-    /// let padded = Base64String::<Standard>::encode("Something important".as_bytes());
-    /// let unpadded = padded.without_padding();
+    /// let signature = Base64String::<Standard>::from_encoded("3q2+7w==")?;
     ///
+    /// assert!(signature.matches_data(&[0xde, 0xad, 0xbe, 0xef])?);
+    /// assert!(!signature.matches_data(&[0xde, 0xad, 0xbe, 0xee])?);
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
-    pub fn without_padding(&self) -> String {
-        self.content
-            .chars()
-            .filter(|&c| c != self.alphabet.padding().unwrap_or_default())
-            .collect()
+    pub fn matches_data(&self, bytes: &[u8]) -> Result<bool, DecodeError> {
+        let mut offset = 0;
+        let mut diff = 0;
+
+        for quad in DecodedQuads::new(&self.content, &self.alphabet) {
+            let (tri, len) = quad?;
+            for &b in &tri[..len] {
+                diff |= b ^ bytes.get(offset).copied().unwrap_or(!b);
+                offset += 1;
+            }
+        }
+
+        Ok(diff == 0 && offset == bytes.len())
     }
 
-    /// Change a [`Base64String`] to the specified
-    /// alphabet `B` using the given `target_alphabet` instance of `B`
+    /// Check whether `self` & `other` decode to the same bytes, even
+    /// if they use different alphabets or padding
+    ///
+    /// Like [`Base64String::matches_data`], every byte is compared
+    /// even once a difference is found, & neither side is decoded
+    /// into memory, so this is the comparison to use for something
+    /// like a signature. Only the lengths of the data can be told
+    /// apart by timing
     ///
     /// # Examples
     /// ```
     /// # use baze64::{Base64String, alphabet::{Standard, UrlSafe}};
-    /// let data = "Something important".as_bytes();
-    /// let standard = Base64String::<Standard>::encode(&data);
-    /// let url_safe = standard.change_alphabet_with(UrlSafe::new())?;
+    /// let ours = Base64String::<Standard>::encode([0xfb, 0xff]);
+    /// let theirs = Base64String::<UrlSafe>::from_encoded("-_8")?;
     ///
-    /// assert_eq!(data, url_safe.decode()?);
+    /// assert!(ours.represents_same_data(&theirs)?);
+    /// assert!(!ours.represents_same_data(&Base64String::<UrlSafe>::from_encoded("-_4")?)?);
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
-    pub fn change_alphabet_with<B>(self, target_alphabet: B) -> Result<Base64String<B>, DecodeError>
+    pub fn represents_same_data<B>(&self, other: &Base64String<B>) -> Result<bool, DecodeError>
     where
         B: Alphabet,
     {
-        let inner = self.decode()?;
+        let mut same_len = self.decoded_len() == other.decoded_len();
+        let mut diff = 0;
+        let mut ours = DecodedQuads::new(&self.content, &self.alphabet);
+        let mut theirs = DecodedQuads::new(&other.content, &other.alphabet);
 
-        Ok(Base64String::encode_with(inner, target_alphabet))
-    }
+        // Keep going until both sides are used up, so an error on
+        // either side is reported whatever the lengths
+        loop {
+            match (ours.next().transpose()?, theirs.next().transpose()?) {
+                (Some((a, a_len)), Some((b, b_len))) => {
+                    same_len &= a_len == b_len;
+                    for (a, b) in a.iter().zip(b).take(a_len.min(b_len)) {
+                        diff |= a ^ b;
+                    }
+                }
+                (None, None) => break,
+                _ => same_len = false,
+            }
+        }
 
-    /// Decode a set of 4 bytes
-    ///
-    /// Bit fuckery courtesey of
-    /// [Matheus Gomes](https://matgomes.com/base64-encode-decode-cpp)
-    fn decode_quad([a, b, c, d]: [char; 4], alphabet: &A) -> Result<[u8; 3], B64Error> {
-        let concat_bytes = ((alphabet.decode_char(a)? as u32) << 18)
-            | ((alphabet.decode_char(b)? as u32) << 12)
-            | ((alphabet.decode_char(c)? as u32) << 6)
-            | alphabet.decode_char(d)? as u32;
-        Ok([
-            ((concat_bytes >> 16) & 0b1111_1111) as u8,
-            ((concat_bytes >> 8) & 0b1111_1111) as u8,
-            (concat_bytes & 0b1111_1111) as u8,
-        ])
-    }
-
-    /// Encodes a set of 3 bytes
-    fn encode_triplet([a, b, c]: [u8; 3], alphabet: &A) -> [char; 4] {
-        let concated = ((a as u32) << 16) | ((b as u32) << 8) | c as u32;
-        // These unwraps are fine because 8*3 == 6*4
-        let first = ((concated >> 18) & 0b0011_1111) as u8;
-        let second = ((concated >> 12) & 0b0011_1111) as u8;
-        let third = ((concated >> 6) & 0b0011_1111) as u8;
-        let fourth = (concated & 0b0011_1111) as u8;
-
-        [
-            alphabet.encode_bits(first).unwrap(),
-            alphabet.encode_bits(second).unwrap(),
-            alphabet.encode_bits(third).unwrap(),
-            alphabet.encode_bits(fourth).unwrap(),
-        ]
+        Ok(same_len && diff == 0)
     }
-}
 
-impl<A> Base64String<A>
-where
-    A: Alphabet + Default,
-{
-    /// Encode a sequence of bytes into a [`Base64String`]
+    /// Decode the contents of `self` into an array of exactly `N`
+    /// bytes, for data with a fixed length like keys & digests
     ///
-    /// Uses `A`'s [`Default`] impl as the alphabet
-    /// to encode with
+    /// The length is checked against [`Base64String::decoded_len`]
+    /// before decoding anything, failing with
+    /// [`DecodeError::WrongLength`] if it isn't `N`
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, DecodeError, alphabet::Standard};
+    /// let b64 = Base64String::<Standard>::encode([7; 32]);
+    /// let key: [u8; 32] = b64.decode_array()?;
+    /// assert_eq!(key, [7; 32]);
+    ///
+    /// assert!(matches!(
+    ///     b64.decode_array::<16>(),
+    ///     Err(DecodeError::WrongLength { expected: 16, actual: 32 })
+    /// ));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn decode_array<const N: usize>(&self) -> Result<[u8; N], DecodeError> {
+        let actual = self.decoded_len();
+        if actual != N {
+            return Err(DecodeError::WrongLength {
+                expected: N,
+                actual,
+            });
+        }
+
+        let mut out = [0; N];
+        let mut written = 0;
+        for quad in DecodedQuads::new(&self.content, &self.alphabet) {
+            let (tri, len) = quad?;
+            // Valid content decodes to exactly `decoded_len` bytes, but
+            // bound the copy in case it doesn't
+            let len = len.min(N - written);
+            out[written..written + len].copy_from_slice(&tri[..len]);
+            written += len;
+        }
+
+        Ok(out)
+    }
+
+    /// Decode the contents of `self` into a [`String`]
     ///
     /// # Examples
     /// ```
     /// # use baze64::{Base64String, alphabet::Standard};
+    /// let message = "Secret message :D";
+    /// let encoded = Base64String::<Standard>::encode_str(message);
+    /// let decoded = encoded.decode_to_string()?;
     ///
-    /// let data = "secret message".as_bytes();
-    /// let encoded = Base64String::<Standard>::encode(data);
+    /// assert_eq!(message, decoded.as_str());
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
-    pub fn encode<B>(bytes: B) -> Self
-    where
-        B: AsRef<[u8]>,
-    {
-        Self::encode_with(bytes, A::default())
+    pub fn decode_to_string(&self) -> Result<String, DecodeError> {
+        let string = String::from_utf8(self.decode()?)?;
+        Ok(string)
     }
 
     /// Contruct a [`Base64String`] from already encoded
     /// Base64
     ///
-    /// Uses `A`'s [`Default`] impl as the alphabet to encode
-    /// with
+    /// ASCII whitespace around the text, like the newline at the end
+    /// of a file, is removed unless the alphabet uses it. Errors give
+    /// the index of the invalid character in `b64` as it was passed
+    /// in, & text that's valid in the other built-in alphabet fails
+    /// with [`B64Error::WrongAlphabet`], suggesting it
+    ///
+    /// The text is otherwise kept as given, so missing padding isn't
+    /// added. Unpadded text decodes fine, but text with only some of
+    /// its padding fails to decode, as it's been cut short. Use
+    /// [`Base64String::from_encoded_normalized_with`] to fix the
+    /// padding up instead
+    ///
+    /// Text that's empty once the whitespace is removed decodes to no
+    /// bytes. Text that's only padding fails to decode with
+    /// [`B64Error::InvalidCharAt`] at its first character, & a lone
+    /// character, with or without padding after it, with
+    /// [`B64Error::Truncated`], as neither holds any data
     ///
     /// # Examples
     /// ```
     /// # use baze64::{Base64String, alphabet::Standard};
     /// // Pretend this does something useful
     /// fn do_something(something_encoded: &str) {
-    ///     let base64 = Base64String::<Standard>::from_encoded(something_encoded);
+    ///     let base64 = Base64String::from_encoded_with(something_encoded, Standard::new());
     ///     // Now to use the Base64String!
     /// }
+    ///
+    /// let unpadded = Base64String::from_encoded_with("ZXZlbg", Standard::new())?;
+    /// assert_eq!(unpadded, "ZXZlbg");
+    /// assert_eq!(unpadded.decode()?, b"even");
+    ///
+    /// let cut_short = Base64String::from_encoded_with("ZXZlbg=", Standard::new())?;
+    /// assert!(cut_short.decode().is_err());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
-    pub fn from_encoded<S>(b64: S) -> Result<Self, B64Error>
+    pub fn from_encoded_with<S>(b64: S, alphabet: A) -> Result<Self, B64Error>
     where
-        S: ToString,
+        S: Into<String>,
     {
-        Self::from_encoded_with(b64, A::default())
-    }
-}
+        let mut b64 = Self::from_parts(b64.into(), alphabet);
+        // Trim in place, as the text may be large
+        let trimmed = |c| Self::is_trimmed(c, &b64.alphabet);
+        b64.content
+            .truncate(b64.content.trim_end_matches(trimmed).len());
+        let start = b64.content.len() - b64.content.trim_start_matches(trimmed).len();
+        b64.content.drain(..start);
 
-impl<A> core::fmt::Display for Base64String<A>
-where
-    A: Alphabet,
-{
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.content)
+        let kept = Self::trim_rfc_padding(&b64.content, &b64.alphabet).len();
+        b64.content.truncate(kept);
+        Self::check_chars(&b64.content, &b64.alphabet).map_err(|e| {
+            e.suggest_alphabet(&b64.content, &b64.alphabet)
+                .offset_by(start)
+        })?;
+
+        Ok(b64)
     }
-}
 
-impl<A> PartialEq for Base64String<A>
-where
-    A: Alphabet,
-{
-    fn eq(&self, other: &Self) -> bool {
-        self.content == other.content
+    /// Contruct a [`Base64String`] from already encoded Base64,
+    /// fixing up its padding, for input typed or pasted by a person
+    ///
+    /// Works like [`Base64String::from_encoded_with`], then replaces
+    /// whatever padding the text has with what it needs, as described
+    /// by [`Base64String::normalize_padding`]. Text with only some of
+    /// its padding is accepted, so input that was cut short can
+    /// decode to the wrong data
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, alphabet::Standard};
+    /// let b64 = Base64String::from_encoded_normalized_with("ZXZlbg=", Standard::new())?;
+    ///
+    /// assert_eq!(b64, "ZXZlbg==");
+    /// assert_eq!(b64.decode()?, b"even");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn from_encoded_normalized_with<S>(b64: S, alphabet: A) -> Result<Self, B64Error>
+    where
+        S: Into<String>,
+    {
+        let mut b64 = Self::from_encoded_with(b64, alphabet)?;
+        b64.normalize_padding();
+
+        Ok(b64)
     }
-}
 
-impl<A> AsRef<str> for Base64String<A>
-where
-    A: Alphabet,
-{
-    fn as_ref(&self) -> &str {
-        &self.content
+    /// Replace the padding at the end of the content with however
+    /// much its length needs
+    ///
+    /// Partial padding is completed & padding after a whole quad is
+    /// removed, the same as [`Base64String::canonicalize`], leaving
+    /// the content in its canonical form
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, alphabet::Standard};
+    /// let mut b64 = Base64String::<Standard>::from_encoded("Zm9v=")?;
+    /// b64.normalize_padding();
+    ///
+    /// assert_eq!(b64, "Zm9v");
+    /// # Ok::<(), baze64::B64Error>(())
+    /// ```
+    pub fn normalize_padding(&mut self) {
+        self.canonicalize();
+    }
+
+    /// Remove all the padding from the end of the content, unless
+    /// the content is only padding
+    pub(crate) fn remove_padding(&mut self) {
+        if let Some(p) = self.alphabet.padding() {
+            self.content.truncate(trim_padding(&self.content, p).len());
+        }
+    }
+
+    /// Put the content of `self` into its canonical form
+    ///
+    /// If the alphabet has padding, any already at the end is replaced
+    /// with what the last quad needs to be 4 characters, so padding
+    /// after a whole quad is removed. If it doesn't, any `=` padding left at the end by
+    /// another encoder is removed, unless the alphabet uses `=` as
+    /// data. Values are canonical when encoded or parsed with
+    /// [`Base64String::from_encoded_normalized_with`], but text parsed
+    /// with [`Base64String::from_encoded_with`] is kept as given, &
+    /// the alphabet can be changed through [`Base64String::alphabet_mut`]
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, alphabet::Standard};
+    /// let mut b64 = Base64String::<Standard>::encode(b"hi");
+    /// b64.canonicalize();
+    ///
+    /// assert_eq!(b64, "aGk=");
+    /// ```
+    pub fn canonicalize(&mut self) {
+        let (kept, missing) = self.canonical_parts();
+        self.content.truncate(kept.len());
+
+        if let Some(p) = self.alphabet.padding().filter(|_| missing > 0) {
+            self.content.reserve_exact(missing * p.len_utf8());
+            self.content.extend(std::iter::repeat_n(p, missing));
+        }
+    }
+
+    /// Get a copy of `self` in its canonical form, as described by
+    /// [`Base64String::canonicalize`]
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, alphabet::Standard};
+    /// let b64 = Base64String::<Standard>::from_encoded("aGk")?;
+    ///
+    /// assert_eq!(b64.canonical(), "aGk=");
+    /// # Ok::<(), baze64::B64Error>(())
+    /// ```
+    pub fn canonical(&self) -> Self
+    where
+        A: Clone,
+    {
+        let mut canonical = self.clone();
+        canonical.canonicalize();
+        canonical
+    }
+
+    /// Split the content into the part kept in its canonical form &
+    /// the number of padding characters that need adding after it
+    fn canonical_parts(&self) -> (&str, usize) {
+        match self.alphabet.padding() {
+            Some(p) => {
+                // Padding already there may be partial, or follow a
+                // whole quad, so is counted again from scratch
+                let kept = trim_padding(&self.content, p);
+                // Count characters rather than bytes, as padding or a
+                // custom alphabet may use multi-byte characters
+                (kept, (4 - kept.chars().count() % 4) % 4)
+            }
+            None => (Self::trim_rfc_padding(&self.content, &self.alphabet), 0),
+        }
+    }
+
+    /// Whether `c` is whitespace trimmed from around encoded text,
+    /// which is ASCII whitespace `alphabet` doesn't use as data or
+    /// padding
+    pub(crate) fn is_trimmed(c: char, alphabet: &A) -> bool {
+        c.is_ascii_whitespace() && alphabet.padding() != Some(c) && alphabet.decode_char(c).is_err()
+    }
+
+    /// Remove any `=` padding from the end of `b64` if `alphabet`
+    /// has no padding & doesn't use `=` as data
+    pub(crate) fn trim_rfc_padding<'a>(b64: &'a str, alphabet: &A) -> &'a str {
+        if alphabet.padding().is_none() && alphabet.decode_char(RFC_PADDING).is_err() {
+            trim_padding(b64, RFC_PADDING)
+        } else {
+            b64
+        }
+    }
+
+    /// Borrow the contents of `self` as a [`Base64Str`]
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, alphabet::Standard};
+    /// let b64 = Base64String::<Standard>::encode(b"hi");
+    ///
+    /// assert_eq!(b64.as_base64_str().as_str(), "aGk=");
+    /// ```
+    pub fn as_base64_str(&self) -> Base64Str<'_, &A> {
+        Base64Str::from_parts(&self.content, &self.alphabet)
+    }
+
+    /// Take the encoded text out of `self` without copying it
+    ///
+    /// Unlike converting into a [`String`], which decodes the data,
+    /// this keeps it encoded
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, alphabet::Standard};
+    /// let b64 = Base64String::<Standard>::encode_str("fo");
+    ///
+    /// assert_eq!(b64.into_encoded(), "Zm8=");
+    /// ```
+    pub fn into_encoded(self) -> String {
+        self.content
+    }
+
+    /// Returns the encoded string with the padding removed
+    ///
+    /// # Example
+    /// ```
+    /// # use baze64::{Base64String, alphabet::Standard};
+    /// let padded = Base64String::<Standard>::encode_str("Something important");
+    /// let unpadded = padded.without_padding();
+    /// ```
+    pub fn without_padding(&self) -> String {
+        self.content
+            .chars()
+            .filter(|&c| c != self.alphabet.padding().unwrap_or_default())
+            .collect()
+    }
+
+    /// Check whether `self` & `other` have the same encoded text once
+    /// each one's padding is stripped, without decoding them
+    ///
+    /// Alphabet instances of the same type can pad differently, like
+    /// [`WithPadding`](crate::alphabet::WithPadding)s with different
+    /// padding characters, which `==` tells apart. This is a plain
+    /// string comparison that stops at the first difference, so use
+    /// [`Base64String::represents_same_data`] for security sensitive
+    /// comparisons like checking signatures
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, alphabet::Standard};
+    /// let equals = Base64String::encode_with(b"hi", Standard::new().with_padding('=')?);
+    /// let dots = Base64String::encode_with(b"hi", Standard::new().with_padding('.')?);
+    ///
+    /// assert_ne!(equals, dots);
+    /// assert!(equals.eq_ignore_padding(&dots));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn eq_ignore_padding(&self, other: &Base64String<A>) -> bool {
+        self.unpadded() == other.unpadded()
+    }
+
+    /// The content of `self` without its padding
+    fn unpadded(&self) -> &str {
+        match self.alphabet.padding() {
+            Some(p) => self.content.trim_end_matches(p),
+            None => &self.content,
+        }
+    }
+
+    /// Change a [`Base64String`] to the specified
+    /// alphabet `B` using the given `target_alphabet` instance of `B`
+    ///
+    /// `self` is left untouched, so its alphabet can still be used
+    /// afterwards
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, alphabet::{Standard, UrlSafe}};
+    /// let standard = Base64String::<Standard>::encode_str("Something important");
+    /// let url_safe = standard.change_alphabet_with(UrlSafe::new())?;
+    ///
+    /// assert_eq!(url_safe.decode_to_string()?, "Something important");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn change_alphabet_with<B>(
+        &self,
+        target_alphabet: B,
+    ) -> Result<Base64String<B>, DecodeError>
+    where
+        B: Alphabet,
+    {
+        let inner = self.decode()?;
+
+        Ok(Base64String::encode_with(inner, target_alphabet))
+    }
+
+    /// Decode `self`, pass the bytes to `f`, & encode whatever it
+    /// returns with the same alphabet
+    ///
+    /// `self` is consumed so its alphabet & the memory holding its
+    /// content can be reused for the result, which means the alphabet
+    /// doesn't need to be [`Clone`] or [`Default`]
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, alphabet::Standard};
+    /// let b64 = Base64String::<Standard>::encode(b"header:body");
+    /// let body = b64.transform(|bytes| bytes[7..].to_vec())?;
+    ///
+    /// assert_eq!(body.decode()?, b"body");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn transform<F>(self, f: F) -> Result<Self, DecodeError>
+    where
+        F: FnOnce(Vec<u8>) -> Vec<u8>,
+    {
+        self.try_transform(|bytes| Ok::<_, std::convert::Infallible>(f(bytes)))
+            .map_err(|e| match e {
+                TransformError::Decode(e) => e,
+                TransformError::Transform(never) => match never {},
+            })
+    }
+
+    /// Decode `self`, pass the bytes to `f`, & encode whatever it
+    /// returns with the same alphabet, stopping if `f` fails
+    ///
+    /// See [`Base64String::transform`] for more
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, TransformError, alphabet::Standard};
+    /// let b64 = Base64String::<Standard>::encode(b"no header");
+    /// let err = b64
+    ///     .try_transform(|bytes| match bytes.strip_prefix(b"header:") {
+    ///         Some(body) => Ok(body.to_vec()),
+    ///         None => Err("missing header"),
+    ///     })
+    ///     .unwrap_err();
+    ///
+    /// assert!(matches!(err, TransformError::Transform("missing header")));
+    /// ```
+    pub fn try_transform<F, E>(self, f: F) -> Result<Self, TransformError<E>>
+    where
+        F: FnOnce(Vec<u8>) -> Result<Vec<u8>, E>,
+    {
+        let decoded = self.decode().map_err(TransformError::Decode)?;
+        let transformed = f(decoded).map_err(TransformError::Transform)?;
+        let Self {
+            mut content,
+            alphabet,
+            ..
+        } = self;
+
+        content.clear();
+        Self::push_encoded(&mut content, &transformed, &alphabet);
+
+        Ok(Self::from_parts(content, alphabet))
+    }
+
+    /// Construct a [`Base64String`] from content that's already
+    /// known to be valid for `alphabet`
+    pub(crate) fn from_parts(content: String, alphabet: A) -> Self {
+        Self {
+            content,
+            alphabet,
+            pending: None,
+        }
+    }
+
+    /// Get a value that [`Debug`](core::fmt::Debug) prints `self`
+    /// with its full content, rather than the redacted form
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, alphabet::Standard};
+    /// let b64 = Base64String::<Standard>::encode(b"not a secret");
+    ///
+    /// assert!(format!("{:?}", b64.debug_unredacted()).contains("bm90IGEgc2VjcmV0"));
+    /// ```
+    pub fn debug_unredacted(&self) -> impl core::fmt::Debug + '_ {
+        Unredacted(self)
+    }
+
+    /// Get a reference to the alphabet used by `self`
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, alphabet::{Alphabet, Standard}};
+    /// let b64 = Base64String::<Standard>::encode(b"data");
+    ///
+    /// assert_eq!(b64.alphabet().padding(), Some('='));
+    /// ```
+    pub fn alphabet(&self) -> &A {
+        &self.alphabet
+    }
+
+    /// Get a mutable reference to the alphabet used by `self`
+    ///
+    /// Changing the alphabet in a way that changes how it encodes
+    /// will invalidate the existing contents
+    pub fn alphabet_mut(&mut self) -> &mut A {
+        &mut self.alphabet
+    }
+
+    /// Encode `bytes`, appending the result to `content`
+    pub(crate) fn push_encoded<E>(content: &mut E, bytes: &[u8], alphabet: &A)
+    where
+        E: Extend<char>,
+    {
+        let blocks = bytes.chunks_exact(3);
+        let rest = blocks.remainder();
+        for block in blocks {
+            // `chunks_exact` makes sure this can't fail
+            content.extend(primitives::encode_block(
+                block.try_into().unwrap(),
+                alphabet,
+            ));
+        }
+
+        if !rest.is_empty() {
+            let (chars, len) = primitives::encode_partial_block(rest, alphabet);
+            content.extend(chars[..len].iter().copied());
+        }
+    }
+
+    /// Encode `bytes` through a lookup table of the alphabet's
+    /// characters, which is much faster than asking the alphabet for
+    /// every one, but only possible if they're all ASCII
+    ///
+    /// Returns [`None`] for alphabets with non-ASCII characters
+    fn encode_ascii(bytes: &[u8], alphabet: &A) -> Option<String> {
+        let table = Self::ascii_encode_table(alphabet)?;
+
+        let mut encoded = Vec::with_capacity(bytes.len().div_ceil(3) * 4);
+        #[cfg(feature = "simd")]
+        let bytes = &bytes[crate::simd::encode_prefix(bytes, &table, &mut encoded)..];
+        let chunks = bytes.chunks_exact(3);
+        let rem = chunks.remainder();
+        for chunk in chunks {
+            let concated = (chunk[0] as usize) << 16 | (chunk[1] as usize) << 8 | chunk[2] as usize;
+            encoded.extend_from_slice(&[
+                table[(concated >> 18) & 0b0011_1111],
+                table[(concated >> 12) & 0b0011_1111],
+                table[(concated >> 6) & 0b0011_1111],
+                table[concated & 0b0011_1111],
+            ]);
+        }
+
+        // Every byte is ASCII, so this can't fail
+        let mut content = String::from_utf8(encoded).ok()?;
+        // Leave the last quad to the alphabet, which may pad it
+        // with any character
+        Self::push_encoded(&mut content, rem, alphabet);
+        Some(content)
+    }
+
+    /// Encode `bytes` within their own allocation, working back from
+    /// the end so no byte is overwritten before it's been encoded
+    ///
+    /// Gives `bytes` back untouched for alphabets with non-ASCII
+    /// characters
+    fn encode_ascii_in_place(mut bytes: Vec<u8>, alphabet: &A) -> Result<String, Vec<u8>> {
+        let Some(table) = Self::ascii_encode_table(alphabet) else {
+            return Err(bytes);
+        };
+
+        let triplets = bytes.len() / 3;
+        let rem = bytes[triplets * 3..].to_vec();
+        bytes.resize(triplets * 4, 0);
+        // Triplet `i` is encoded over bytes `4 * i..4 * i + 4`, which
+        // only holds triplets that have already been encoded
+        for i in (0..triplets).rev() {
+            let concated = (bytes[3 * i] as usize) << 16
+                | (bytes[3 * i + 1] as usize) << 8
+                | bytes[3 * i + 2] as usize;
+            bytes[4 * i..4 * i + 4].copy_from_slice(&[
+                table[(concated >> 18) & 0b0011_1111],
+                table[(concated >> 12) & 0b0011_1111],
+                table[(concated >> 6) & 0b0011_1111],
+                table[concated & 0b0011_1111],
+            ]);
+        }
+
+        // Every byte is ASCII, so this can't fail
+        let mut content = String::from_utf8(bytes).map_err(|e| e.into_bytes())?;
+        Self::push_encoded(&mut content, &rem, alphabet);
+        Ok(content)
+    }
+
+    /// The alphabet's characters as ASCII bytes, or [`None`] if
+    /// any of them aren't ASCII
+    fn ascii_encode_table(alphabet: &A) -> Option<[u8; 64]> {
+        let mut table = [0; 64];
+        for (bits, entry) in (0..64).zip(&mut table) {
+            let c = alphabet.encode_bits(bits);
+            *entry = u8::try_from(c).ok().filter(u8::is_ascii)?;
+        }
+
+        Some(table)
+    }
+
+    /// Remove a trailing partial quad from the content, returning
+    /// the 1 or 2 bytes it encoded so more data can be encoded
+    /// after them
+    fn pop_partial_quad(&mut self) -> Result<Vec<u8>, B64Error> {
+        let padded = matches!(self.alphabet.padding(), Some(p) if self.content.ends_with(p));
+        // Content parsed without its padding can end part way through
+        // a quad, even with an alphabet that has padding
+        let tail_len = match self.content.chars().count() % 4 {
+            0 if padded => 4,
+            rem => rem,
+        };
+        if tail_len == 0 {
+            return Ok(vec![]);
+        }
+        let mut tail = self
+            .content
+            .chars()
+            .rev()
+            .take(tail_len)
+            .collect::<Vec<_>>();
+        tail.reverse();
+        // Decode before removing the tail, so the content's left as it
+        // was if it can't be
+        let (tri, len) = primitives::decode_partial_block(&tail, &self.alphabet)?;
+
+        let new_len = self.content.len() - tail.iter().map(|c| c.len_utf8()).sum::<usize>();
+        self.content.truncate(new_len);
+        Ok(tri[..len].to_vec())
+    }
+
+    /// Check every character of `b64` is either part of `alphabet`
+    /// or its padding
+    pub(crate) fn check_chars(b64: &str, alphabet: &A) -> Result<(), B64Error> {
+        let table = b64.is_ascii().then(|| ascii_decode_table(alphabet));
+        Self::check_chars_with_table(b64, alphabet, table.as_ref())
+    }
+
+    /// Check the characters of `b64` like [`Base64String::check_chars`],
+    /// using a `table` from [`ascii_decode_table`] that's already been
+    /// built for `alphabet`
+    pub(crate) fn check_chars_with_table(
+        b64: &str,
+        alphabet: &A,
+        table: Option<&[u8; 128]>,
+    ) -> Result<(), B64Error> {
+        let padding = alphabet.padding();
+        let invalid = if let Some(table) = table.filter(|_| b64.is_ascii()) {
+            // Character indices are byte indices in ASCII, so a lookup
+            // table can be used on the bytes
+            b64.bytes()
+                .position(|c| table[c as usize] == INVALID_BITS && Some(c.into()) != padding)
+                .map(|index| (index, b64.as_bytes()[index].into()))
+        } else {
+            b64.chars()
+                .enumerate()
+                .find(|&(_, c)| Some(c) != padding && primitives::decode_bits(alphabet, c).is_err())
+        };
+
+        match invalid {
+            // The table can't tell a character that's out of the
+            // alphabet from one it decodes out of range
+            Some((index, c)) => match primitives::decode_bits(alphabet, c) {
+                Err(e @ B64Error::DecodedValueOOB { .. }) => Err(e),
+                _ => Err(B64Error::InvalidCharAt { char: c, index }),
+            },
+            None => Ok(()),
+        }
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use crate::alphabet::Standard;
+impl<A> Base64String<A>
+where
+    A: Alphabet + Default,
+{
+    /// Encode a sequence of bytes into a [`Base64String`]
+    ///
+    /// Uses `A`'s [`Default`] impl as the alphabet
+    /// to encode with
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, alphabet::Standard};
+    ///
+    /// let data = [0xde, 0xad, 0xbe, 0xef];
+    /// let encoded = Base64String::<Standard>::encode(data);
+    /// ```
+    pub fn encode<B>(bytes: B) -> Self
+    where
+        B: AsRef<[u8]>,
+    {
+        Self::encode_with(bytes, A::default())
+    }
+
+    /// Encode the UTF-8 bytes of `s` into a [`Base64String`]
+    ///
+    /// Uses `A`'s [`Default`] impl as the alphabet
+    /// to encode with
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, alphabet::Standard};
+    /// let encoded = Base64String::<Standard>::encode_str("secret message");
+    ///
+    /// assert_eq!(encoded.decode_to_string()?, "secret message");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn encode_str(s: &str) -> Self {
+        Self::encode_str_with(s, A::default())
+    }
+
+    /// Encode the UTF-8 bytes of `s` into a [`Base64String`],
+    /// reusing its allocation where possible
+    ///
+    /// Uses `A`'s [`Default`] impl as the alphabet
+    /// to encode with. Otherwise the same as
+    /// [`Base64String::encode_string_with`]
+    pub fn encode_string(s: String) -> Self {
+        Self::encode_string_with(s, A::default())
+    }
+
+    /// Encode several buffers into one [`Base64String`], as if
+    /// they'd been concatenated
+    ///
+    /// Uses `A`'s [`Default`] impl as the alphabet
+    /// to encode with
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, alphabet::Standard};
+    /// let chunks = vec![b"foo".to_vec(), b"b".to_vec(), b"ar".to_vec()];
+    /// let encoded = Base64String::<Standard>::encode_vectored(&chunks);
+    ///
+    /// assert_eq!(encoded.to_string(), "Zm9vYmFy");
+    /// ```
+    pub fn encode_vectored<I, B>(bufs: I) -> Self
+    where
+        I: IntoIterator<Item = B>,
+        B: AsRef<[u8]>,
+    {
+        Self::encode_vectored_with(bufs, A::default())
+    }
+
+    /// Encode everything read from `reader` into a [`Base64String`]
+    ///
+    /// Uses `A`'s [`Default`] impl as the alphabet
+    /// to encode with
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, alphabet::Standard};
+    /// let reader = std::io::Cursor::new(b"foobar");
+    /// let encoded = Base64String::<Standard>::encode_reader(reader)?;
+    ///
+    /// assert_eq!(encoded, "Zm9vYmFy");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn encode_reader<R>(reader: R) -> Result<Self, EncodeReaderError>
+    where
+        R: Read,
+    {
+        Self::encode_reader_with(reader, A::default())
+    }
+
+    /// Contruct a [`Base64String`] from already encoded
+    /// Base64
+    ///
+    /// Uses `A`'s [`Default`] impl as the alphabet to encode
+    /// with
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, alphabet::Standard};
+    /// // Pretend this does something useful
+    /// fn do_something(something_encoded: &str) {
+    ///     let base64 = Base64String::<Standard>::from_encoded(something_encoded);
+    ///     // Now to use the Base64String!
+    /// }
+    /// ```
+    pub fn from_encoded<S>(b64: S) -> Result<Self, B64Error>
+    where
+        S: Into<String>,
+    {
+        Self::from_encoded_with(b64, A::default())
+    }
+
+    /// Contruct a [`Base64String`] from already encoded Base64,
+    /// fixing up its padding, as described by
+    /// [`Base64String::from_encoded_normalized_with`]
+    ///
+    /// Uses `A`'s [`Default`] impl as the alphabet to encode
+    /// with
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, alphabet::Standard};
+    /// let b64 = Base64String::<Standard>::from_encoded_normalized("ZXZlbg")?;
+    ///
+    /// assert_eq!(b64, "ZXZlbg==");
+    /// # Ok::<(), baze64::B64Error>(())
+    /// ```
+    pub fn from_encoded_normalized<S>(b64: S) -> Result<Self, B64Error>
+    where
+        S: Into<String>,
+    {
+        Self::from_encoded_normalized_with(b64, A::default())
+    }
+
+    /// Create an empty [`Base64String`], ready to have bytes written
+    /// to it through its [`Write`] impl
+    ///
+    /// Uses `A`'s [`Default`] impl as the alphabet to encode
+    /// with
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, alphabet::Standard};
+    /// let mut reader = "Some streamed data".as_bytes();
+    /// let mut b64 = Base64String::<Standard>::new();
+    /// std::io::copy(&mut reader, &mut b64)?;
+    /// b64.finish();
+    ///
+    /// assert_eq!(b64.decode()?, b"Some streamed data");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn new() -> Self {
+        Self::new_with(A::default())
+    }
+}
+
+impl<A> Default for Base64String<A>
+where
+    A: Alphabet + Default,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Appends the encoding of the written bytes to the [`Base64String`],
+/// as if they had been part of the data it was originally encoded from
+///
+/// Like a streaming encoder, the last 0–2 bytes written are held back
+/// until more are written, as they don't fill a triplet. Call
+/// [`Base64String::finish`] once writing is done to encode & pad them.
+/// Until then, the content & everything using it, like decoding or
+/// [`Display`](core::fmt::Display), leaves them out
+impl<A> Write for Base64String<A>
+where
+    A: Alphabet,
+{
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        // Leave the content alone, rather than holding back its tail
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let pending = match self.pending.take() {
+            Some(pending) => pending,
+            // Carry on from a partial quad the content already ends with
+            None => Pending::new(
+                &self
+                    .pop_partial_quad()
+                    .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?,
+            ),
+        };
+
+        let mut rest = buf;
+        if pending.len > 0 {
+            let take = (3 - pending.len).min(buf.len());
+            let tri = [pending.as_slice(), &buf[..take]].concat();
+            if tri.len() < 3 {
+                self.pending = Some(Pending::new(&tri));
+                return Ok(buf.len());
+            }
+            Self::push_encoded(&mut self.content, &tri, &self.alphabet);
+            rest = &buf[take..];
+        }
+        let whole = rest.len() - rest.len() % 3;
+        Self::push_encoded(&mut self.content, &rest[..whole], &self.alphabet);
+        self.pending = Some(Pending::new(&rest[whole..]));
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Iterator decoding base64 text one quad at a time, without
+/// allocating
+///
+/// Yields each decoded triplet along with how many of its bytes
+/// are actually part of the data
+pub(crate) struct DecodedQuads<'a, A> {
+    chars: std::str::Chars<'a>,
+    alphabet: &'a A,
+    index: usize,
+    /// Whether a padded quad can be followed by more, as it can when
+    /// decoding concatenated documents
+    concatenated: bool,
+}
+
+impl<'a, A> DecodedQuads<'a, A>
+where
+    A: Alphabet,
+{
+    /// Decode `content`, which can only have padding at the end
+    pub(crate) fn new(content: &'a str, alphabet: &'a A) -> Self {
+        Self {
+            chars: content.chars(),
+            alphabet,
+            index: 0,
+            concatenated: false,
+        }
+    }
+
+    /// Decode `content` as documents concatenated together, each of
+    /// which can end with padding
+    pub(crate) fn new_concatenated(content: &'a str, alphabet: &'a A) -> Self {
+        Self {
+            concatenated: true,
+            ..Self::new(content, alphabet)
+        }
+    }
+}
+
+impl<A> Iterator for DecodedQuads<'_, A>
+where
+    A: Alphabet,
+{
+    type Item = Result<([u8; 3], usize), B64Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut quad = ['\0'; 4];
+        let mut count = 0;
+        for c in self.chars.by_ref().take(4) {
+            quad[count] = c;
+            count += 1;
+        }
+        let start = self.index;
+        self.index += count;
+        if count == 0 {
+            return None;
+        }
+
+        let decoded = decode_chars(&quad[..count], start, self.alphabet);
+        match decoded {
+            // Only the last quad can be short, so anything after one
+            // means its padding is in the middle of the input
+            Ok((_, len)) if len < 3 && !self.concatenated && !self.chars.as_str().is_empty() => {
+                Some(Err(B64Error::InvalidCharAt {
+                    char: quad[len + 1],
+                    index: start + len + 1,
+                }))
+            }
+            decoded => Some(decoded),
+        }
+    }
+}
+
+/// Marks characters an alphabet doesn't decode in the table from
+/// [`ascii_decode_table`]. Any valid value ORed with it gives it
+/// back, so a whole quad can be checked at once
+pub(crate) const INVALID_BITS: u8 = 0xff;
+
+/// Build a table of what `alphabet` decodes each ASCII character
+/// to, with [`INVALID_BITS`] for those it doesn't, so decoding
+/// ASCII text doesn't need to ask the alphabet about every character
+pub(crate) fn ascii_decode_table<A>(alphabet: &A) -> [u8; 128]
+where
+    A: Alphabet,
+{
+    let mut table = [INVALID_BITS; 128];
+    for (c, entry) in (0..128u8).zip(&mut table) {
+        if let Ok(bits) = primitives::decode_bits(alphabet, c.into()) {
+            *entry = bits;
+        }
+    }
+
+    table
+}
+
+/// Remove the `padding` from the end of `b64`, unless there's no
+/// data before it
+///
+/// Padding on its own isn't an empty value, so it's kept to be
+/// reported as invalid rather than decoding to nothing
+pub(crate) fn trim_padding(b64: &str, padding: char) -> &str {
+    match b64.trim_end_matches(padding) {
+        "" => b64,
+        data => data,
+    }
+}
+
+/// Decode a quad of up to 4 characters that starts at index `start`
+/// of the input, returning the decoded triplet along with how many
+/// of its bytes are actually part of the data
+///
+/// A quad shorter than 4 characters must be the end of the input
+pub(crate) fn decode_chars<A>(
+    quad: &[char],
+    start: usize,
+    alphabet: &A,
+) -> Result<([u8; 3], usize), B64Error>
+where
+    A: Alphabet,
+{
+    primitives::decode_partial_block(quad, alphabet).map_err(|e| e.offset_by(start))
+}
+
+/// The number of columns alternate [`Display`](core::fmt::Display)
+/// output is wrapped at
+const WRAP_COLUMNS: usize = 64;
+
+/// Formats the encoded content on a single line, or with the
+/// alternate flag (`{:#}`) wrapped at 64 columns
+///
+/// The content is printed exactly as it's stored, so parsing the
+/// output with the same alphabet gives back an equal [`Base64String`].
+/// Format [`Base64String::canonical`] to print it padded
+///
+/// # Examples
+/// ```
+/// # use baze64::{Base64String, alphabet::Standard};
+/// let b64 = Base64String::<Standard>::encode([0; 60]);
+///
+/// assert_eq!(format!("{b64}").lines().count(), 1);
+/// assert_eq!(format!("{b64:#}").lines().count(), 2);
+/// ```
+impl<A> core::fmt::Display for Base64String<A>
+where
+    A: Alphabet,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if f.alternate() {
+            let chars = self.content.chars().collect::<Vec<_>>();
+            for (i, line) in chars.chunks(WRAP_COLUMNS).enumerate() {
+                if i > 0 {
+                    writeln!(f)?;
+                }
+                write!(f, "{}", line.iter().collect::<String>())?;
+            }
+            Ok(())
+        } else {
+            f.write_str(&self.content)
+        }
+    }
+}
+
+/// Parses already encoded Base64 using `A`'s [`Default`] impl, the
+/// same as [`Base64String::from_encoded`]
+///
+/// # Examples
+/// ```
+/// # use baze64::{Base64String, alphabet::UrlSafe};
+/// let b64 = "-_8=".parse::<Base64String<UrlSafe>>()?;
+///
+/// assert_eq!(b64.to_string().parse::<Base64String<UrlSafe>>()?, b64);
+/// # Ok::<(), baze64::B64Error>(())
+/// ```
+impl<A> core::str::FromStr for Base64String<A>
+where
+    A: Alphabet + Default,
+{
+    type Err = B64Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_encoded(s)
+    }
+}
+
+impl<A> core::fmt::Debug for Base64String<A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct(&format!("Base64String<{}>", short_type_name::<A>()))
+            .field("len", &self.content.chars().count())
+            .field("content", &redact(&self.content))
+            .finish()
+    }
+}
+
+/// Hide all but the first & last 4 characters of `content`
+pub(crate) fn redact(content: &str) -> String {
+    let len = content.chars().count();
+    if len == 0 {
+        String::new()
+    } else if len <= 8 {
+        "…".to_string()
+    } else {
+        let start = content.chars().take(4).collect::<String>();
+        let end = content.chars().skip(len - 4).collect::<String>();
+        format!("{start}…{end}")
+    }
+}
+
+/// Debug prints a [`Base64String`] without redacting its content
+struct Unredacted<'a, A>(&'a Base64String<A>);
+
+impl<A> core::fmt::Debug for Unredacted<'_, A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct(&format!("Base64String<{}>", short_type_name::<A>()))
+            .field("len", &self.0.content.chars().count())
+            .field("content", &self.0.content)
+            .finish()
+    }
+}
+
+/// The name of `T` without the module paths of it or any of its
+/// generic parameters
+pub(crate) fn short_type_name<T>() -> String {
+    let name = std::any::type_name::<T>();
+    // Each part is a path followed by whatever ends it, like the `<`
+    // before a generic parameter
+    name.split_inclusive(|c: char| !(c.is_alphanumeric() || c == '_' || c == ':'))
+        .map(|part| part.rsplit("::").next().unwrap_or(part))
+        .collect()
+}
+
+impl<A> PartialEq for Base64String<A>
+where
+    A: Alphabet,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.content == other.content && self.pending == other.pending
+    }
+}
+
+/// Compares the encoded content with the text of a string
+///
+/// The comparison is exact, so padding has to match too. A padded
+/// [`Base64String`] isn't equal to the same data without its
+/// padding; compare against [`Base64String::without_padding`] for
+/// that
+///
+/// # Examples
+/// ```
+/// # use baze64::{Base64String, alphabet::Standard};
+/// let b64 = Base64String::<Standard>::encode(b"even");
+///
+/// assert_eq!(b64, "ZXZlbg==");
+/// assert_ne!(b64, "ZXZlbg");
+/// assert_eq!(b64.without_padding(), "ZXZlbg");
+/// ```
+impl<A> PartialEq<str> for Base64String<A>
+where
+    A: Alphabet,
+{
+    fn eq(&self, other: &str) -> bool {
+        self.content == other
+    }
+}
+
+impl<A> PartialEq<&str> for Base64String<A>
+where
+    A: Alphabet,
+{
+    fn eq(&self, other: &&str) -> bool {
+        self.content == *other
+    }
+}
+
+impl<A> PartialEq<String> for Base64String<A>
+where
+    A: Alphabet,
+{
+    fn eq(&self, other: &String) -> bool {
+        &self.content == other
+    }
+}
+
+impl<A> PartialEq<Base64String<A>> for str
+where
+    A: Alphabet,
+{
+    fn eq(&self, other: &Base64String<A>) -> bool {
+        other == self
+    }
+}
+
+impl<A> PartialEq<Base64String<A>> for &str
+where
+    A: Alphabet,
+{
+    fn eq(&self, other: &Base64String<A>) -> bool {
+        other == self
+    }
+}
+
+impl<A> PartialEq<Base64String<A>> for String
+where
+    A: Alphabet,
+{
+    fn eq(&self, other: &Base64String<A>) -> bool {
+        other == self
+    }
+}
+
+/// Decodes the data, which must be valid UTF-8, like
+/// [`Base64String::decode_to_string`]
+///
+/// # Examples
+/// ```
+/// # use baze64::{Base64String, DecodeError, alphabet::Standard};
+/// fn greeting(encoded: &str) -> Result<String, DecodeError> {
+///     let b64 = Base64String::<Standard>::from_encoded(encoded)?;
+///     String::try_from(b64)
+/// }
+///
+/// assert_eq!(greeting("5L2g5aW9")?, "你好");
+/// # Ok::<(), DecodeError>(())
+/// ```
+impl<A> TryFrom<Base64String<A>> for String
+where
+    A: Alphabet,
+{
+    type Error = DecodeError;
+
+    fn try_from(b64: Base64String<A>) -> Result<Self, Self::Error> {
+        b64.decode_to_string()
+    }
+}
+
+impl<A> TryFrom<&Base64String<A>> for Vec<u8>
+where
+    A: Alphabet,
+{
+    type Error = DecodeError;
+
+    fn try_from(b64: &Base64String<A>) -> Result<Self, Self::Error> {
+        b64.decode()
+    }
+}
+
+impl<A, const N: usize> TryFrom<&Base64String<A>> for [u8; N]
+where
+    A: Alphabet,
+{
+    type Error = DecodeError;
+
+    fn try_from(b64: &Base64String<A>) -> Result<Self, Self::Error> {
+        b64.decode_array()
+    }
+}
+
+impl<A> AsRef<str> for Base64String<A>
+where
+    A: Alphabet,
+{
+    fn as_ref(&self) -> &str {
+        &self.content
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alphabet::{AlphabetKind, AnyAlphabet, Standard, UrlSafe, WithPadding};
+
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn encode_triplet() {
+        let triplet = ['A', 'B', 'C'];
+        let expected_encoded = ['Q', 'U', 'J', 'D'];
+
+        let encoded = primitives::encode_block(
+            &[triplet[0] as u8, triplet[1] as u8, triplet[2] as u8],
+            &Standard::new(),
+        );
+
+        assert_eq!(encoded, expected_encoded);
+    }
+
+    #[test]
+    fn encode_is_infallible() {
+        // Encoding gives a `Base64String` directly, not a `Result`
+        let standard: Base64String<Standard> = Base64String::encode(b"foo");
+        let url_safe: Base64String<UrlSafe> = Base64String::encode_with([0xfb], UrlSafe::new());
+
+        assert_eq!(standard, "Zm9v");
+        assert_eq!(url_safe, "-w==");
+    }
+
+    #[test]
+    fn encode_long() {
+        let input = "everybody".chars().map(|c| c as u8);
+        let b64 = Base64String::encode(input.collect::<Vec<_>>());
+        let expected = Base64String {
+            content: String::from("ZXZlcnlib2R5"),
+            alphabet: Standard::new(),
+            pending: None,
+        };
+
+        assert_eq!(b64, expected)
+    }
+
+    #[test]
+    fn encode_2_rem() {
+        let input = "event".chars().map(|c| c as u8);
+        let b64 = Base64String::encode(input.collect::<Vec<_>>());
+        let expected = Base64String {
+            content: String::from("ZXZlbnQ="),
+            alphabet: Standard::new(),
+            pending: None,
+        };
+
+        assert_eq!(b64, expected)
+    }
+
+    #[test]
+    fn encode_1_rem() {
+        let input = "even".chars().map(|c| c as u8);
+        let b64 = Base64String::encode(input.collect::<Vec<_>>());
+        let expected = Base64String {
+            content: String::from("ZXZlbg=="),
+            alphabet: Standard::new(),
+            pending: None,
+        };
+
+        assert_eq!(b64, expected)
+    }
+
+    #[test]
+    fn decode_no_pad() {
+        let src = Base64String {
+            content: String::from("ZXZlcnlib2R5"),
+            alphabet: Standard::new(),
+            pending: None,
+        };
+        let expected = b"everybody".to_vec();
+        let decoded = src.decode().unwrap();
+
+        assert_eq!(decoded, expected)
+    }
+
+    #[test]
+    fn decode_one_pad() {
+        let src = Base64String {
+            content: String::from("ZXZlbnQ="),
+            alphabet: Standard::new(),
+            pending: None,
+        };
+        let expected = b"event".to_vec();
+        let decoded = src.decode().unwrap();
+
+        assert_eq!(decoded, expected)
+    }
+
+    #[test]
+    fn decode_two_pad() {
+        let src = Base64String {
+            content: String::from("ZXZlbg=="),
+            alphabet: Standard::new(),
+            pending: None,
+        };
+        let expected = b"even".to_vec();
+        let decoded = src.decode().unwrap();
+
+        assert_eq!(decoded, expected)
+    }
+
+    #[test]
+    fn encode_test_vectors() {
+        assert_eq!(
+            Base64String::<Standard>::encode(b"").to_string(),
+            "".to_string()
+        );
+        assert_eq!(
+            Base64String::<Standard>::encode(b"f").to_string(),
+            "Zg==".to_string()
+        );
+        assert_eq!(
+            Base64String::<Standard>::encode(b"fo").to_string(),
+            "Zm8=".to_string()
+        );
+        assert_eq!(
+            Base64String::<Standard>::encode(b"foo").to_string(),
+            "Zm9v".to_string()
+        );
+        assert_eq!(
+            Base64String::<Standard>::encode(b"foob").to_string(),
+            "Zm9vYg==".to_string()
+        );
+        assert_eq!(
+            Base64String::<Standard>::encode(b"fooba").to_string(),
+            "Zm9vYmE=".to_string()
+        );
+        assert_eq!(
+            Base64String::<Standard>::encode(b"foobar").to_string(),
+            "Zm9vYmFy".to_string()
+        );
+    }
+
+    #[test]
+    fn decode_test_vectors() {
+        assert_eq!(
+            Base64String {
+                content: "".into(),
+                alphabet: Standard::new(),
+                pending: None,
+            }
+            .decode()
+            .unwrap(),
+            b""
+        );
+        assert_eq!(
+            Base64String {
+                content: "Zg==".into(),
+                alphabet: Standard::new(),
+                pending: None,
+            }
+            .decode()
+            .unwrap(),
+            b"f"
+        );
+        assert_eq!(
+            Base64String {
+                content: "Zm8=".into(),
+                alphabet: Standard::new(),
+                pending: None,
+            }
+            .decode()
+            .unwrap(),
+            b"fo"
+        );
+        assert_eq!(
+            Base64String {
+                content: "Zm9v".into(),
+                alphabet: Standard::new(),
+                pending: None,
+            }
+            .decode()
+            .unwrap(),
+            b"foo"
+        );
+        assert_eq!(
+            Base64String {
+                content: "Zm9vYg==".into(),
+                alphabet: Standard::new(),
+                pending: None,
+            }
+            .decode()
+            .unwrap(),
+            b"foob"
+        );
+        assert_eq!(
+            Base64String {
+                content: "Zm9vYmE=".into(),
+                alphabet: Standard::new(),
+                pending: None,
+            }
+            .decode()
+            .unwrap(),
+            b"fooba"
+        );
+        assert_eq!(
+            Base64String {
+                content: "Zm9vYmFy".into(),
+                alphabet: Standard::new(),
+                pending: None,
+            }
+            .decode()
+            .unwrap(),
+            b"foobar"
+        );
+    }
+
+    #[test]
+    fn decode_into_reports_bytes_written() {
+        let src = Base64String {
+            content: String::from("Zm9vYmFy#mF6"),
+            alphabet: Standard::new(),
+            pending: None,
+        };
+        let mut out = vec![];
+        let err = src.decode_into(&mut out).unwrap_err();
+
+        assert_eq!(err.bytes_written, 6);
+        assert_eq!(out, b"foobar");
+        assert!(matches!(
+            err.error,
+            DecodeError::Base64Error(B64Error::InvalidCharAt {
+                char: '#',
+                index: 8
+            })
+        ));
+    }
+
+    #[test]
+    fn decode_into_returns_len() {
+        let src = Base64String::<Standard>::encode(b"fooba");
+        let mut out = vec![];
+
+        assert_eq!(src.decode_into(&mut out).unwrap(), 5);
+        assert_eq!(out, b"fooba");
+    }
+
+    #[test]
+    fn decode_into_buffered_writes_nothing_on_error() {
+        let src = Base64String {
+            content: String::from("Zm9vYmFy#mF6"),
+            alphabet: Standard::new(),
+            pending: None,
+        };
+        let mut out = vec![];
+
+        assert!(src.decode_into_buffered(&mut out).is_err());
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn decode_concatenated_tail_lengths() {
+        // One document for each number of padding characters
+        let docs: [&[u8]; 3] = [b"foo", b"ba", b"r"];
+        let orders = [
+            [0, 1, 2],
+            [0, 2, 1],
+            [1, 0, 2],
+            [1, 2, 0],
+            [2, 0, 1],
+            [2, 1, 0],
+        ];
+
+        for order in orders {
+            let content = order
+                .iter()
+                .map(|&i| Base64String::<Standard>::encode(docs[i]).to_string())
+                .collect::<String>();
+            let src = Base64String {
+                content,
+                alphabet: Standard::new(),
+                pending: None,
+            };
+
+            // The unpadded document is joined with whichever follows it
+            let mut expected = vec![];
+            let mut pending = vec![];
+            for &i in &order {
+                pending.extend_from_slice(docs[i]);
+                if !docs[i].len().is_multiple_of(3) {
+                    expected.push(std::mem::take(&mut pending));
+                }
+            }
+            if !pending.is_empty() {
+                expected.push(pending);
+            }
+
+            assert_eq!(src.decode_concatenated().unwrap(), expected, "{order:?}");
+            // Padding anywhere but the end is only allowed when asked for
+            if expected.len() > 1 {
+                assert_eq!(
+                    src.decode().unwrap_err().kind(),
+                    crate::ErrorKind::InvalidChar,
+                    "{order:?}"
+                );
+            } else {
+                assert_eq!(src.decode().unwrap(), expected.concat(), "{order:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn decode_concatenated_repeated() {
+        let src = Base64String::<Standard>::from_encoded("Zg==Zg==Zm8=Zm8=").unwrap();
+
+        assert_eq!(
+            src.decode_concatenated().unwrap(),
+            [&b"f"[..], b"f", b"fo", b"fo"]
+        );
+    }
+
+    #[test]
+    fn decode_concatenated_empty() {
+        let src = Base64String::<Standard>::new();
+
+        assert!(src.decode_concatenated().unwrap().is_empty());
+    }
+
+    #[test]
+    fn decode_concatenated_invalid() {
+        let src = Base64String {
+            content: String::from("Zm8=Ym#y"),
+            alphabet: Standard::new(),
+            pending: None,
+        };
+
+        assert!(matches!(
+            src.decode_concatenated(),
+            Err(DecodeError::Base64Error(B64Error::InvalidCharAt {
+                char: '#',
+                index: 6
+            }))
+        ));
+    }
+
+    #[test]
+    fn write_matches_encode() {
+        let data = b"The quick brown fox jumps over the lazy dog";
+        let expected = Base64String::<Standard>::encode(data);
+
+        for chunk_size in 1..=7 {
+            let mut b64 = Base64String::<Standard>::new();
+            for chunk in data.chunks(chunk_size) {
+                b64.write_all(chunk).unwrap();
+            }
+            b64.finish();
+
+            assert_eq!(b64, expected, "chunk size {chunk_size}");
+        }
+    }
+
+    #[test]
+    fn empty_write_leaves_content() {
+        let mut b64 = Base64String::<Standard>::from_encoded("Zm8=").unwrap();
+
+        assert_eq!(b64.write(&[]).unwrap(), 0);
+        assert_eq!(b64, "Zm8=");
+    }
+
+    #[test]
+    fn failed_write_leaves_content() {
+        // A lone character can't be carried on from
+        let mut b64 = Base64String::<Standard>::from_encoded("Zm9vZ").unwrap();
+
+        assert!(b64.write(b"o").is_err());
+        assert_eq!(b64, "Zm9vZ");
+    }
+
+    #[test]
+    fn write_holds_back_pending_tail() {
+        let mut b64 = Base64String::<Standard>::new();
+        assert_eq!(b64.to_string(), "");
+
+        b64.write_all(b"f").unwrap();
+        assert_eq!(b64.to_string(), "");
+        b64.write_all(b"o").unwrap();
+        assert_eq!(b64.to_string(), "");
+        b64.write_all(b"o").unwrap();
+        assert_eq!(b64.to_string(), "Zm9v");
+        b64.write_all(b"ba").unwrap();
+        assert_eq!(b64.to_string(), "Zm9v");
+        assert_ne!(b64, Base64String::<Standard>::encode(b"foo"));
+
+        b64.finish();
+        assert_eq!(b64.to_string(), "Zm9vYmE=");
+        b64.finish();
+        assert_eq!(b64.to_string(), "Zm9vYmE=");
+
+        // Carries on after the padded quad
+        b64.write_all(b"r").unwrap();
+        b64.finish();
+        assert_eq!(b64.to_string(), "Zm9vYmFy");
+    }
+
+    #[test]
+    fn many_small_writes() {
+        let data = (0..100_000).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+        let mut b64 = Base64String::<Standard>::new();
+        for chunk in data.chunks(7) {
+            b64.write_all(chunk).unwrap();
+        }
+        b64.finish();
+
+        assert_eq!(b64, Base64String::<Standard>::encode(&data));
+    }
+
+    #[test]
+    fn write_appends_to_encoded() {
+        let mut b64 = Base64String::<Standard>::from_encoded("Zm8=").unwrap();
+        std::io::copy(&mut &b"obar"[..], &mut b64).unwrap();
+        b64.finish();
+
+        assert_eq!(b64.to_string(), "Zm9vYmFy");
+    }
+
+    #[test]
+    fn shared_alphabet() {
+        let alphabet = Standard::new();
+        let by_ref = Base64String::encode_with(b"even", &alphabet);
+        let owned = Base64String::<Standard>::encode(b"even");
+
+        assert_eq!(by_ref.to_string(), owned.to_string());
+        assert_eq!(by_ref.decode().unwrap(), b"even");
+    }
+
+    #[test]
+    fn change_alphabet_keeps_source() {
+        let standard = Base64String::<Standard>::encode([0xfb, 0xff]);
+        let url_safe = standard.change_alphabet_with(UrlSafe::new()).unwrap();
+
+        assert_eq!(standard.to_string(), "+/8=");
+        assert_eq!(url_safe.to_string(), "-_8=");
+        assert_eq!(standard.alphabet().encode_bits(62), '+');
+        assert_eq!(url_safe.alphabet().encode_bits(62), '-');
+    }
+
+    #[test]
+    fn transform_identity() {
+        for size in 0..8 {
+            let data = (0..size).collect::<Vec<u8>>();
+            let b64 = Base64String::<Standard>::encode(&data);
+
+            assert_eq!(b64.clone().transform(|bytes| bytes).unwrap(), b64);
+        }
+    }
+
+    #[test]
+    fn transform_changes_padding() {
+        let b64 = Base64String::<Standard>::encode(b"foo");
+
+        let longer = b64.clone().transform(|mut bytes| {
+            bytes.push(b'b');
+            bytes
+        });
+        let shorter = b64.transform(|bytes| bytes[..2].to_vec());
+
+        assert_eq!(longer.unwrap().to_string(), "Zm9vYg==");
+        assert_eq!(shorter.unwrap().to_string(), "Zm8=");
+    }
+
+    #[test]
+    fn transform_keeps_alphabet() {
+        let alphabet = UrlSafe::new();
+        let b64 = Base64String::encode_with([0xfb], &alphabet);
+        let transformed = b64.transform(|_| vec![0xfb, 0xff]).unwrap();
+
+        assert_eq!(transformed.to_string(), "-_8=");
+    }
+
+    #[test]
+    fn try_transform_propagates_error() {
+        let b64 = Base64String::<Standard>::encode(b"foo");
+        let err = b64.try_transform(|_| Err("nope")).unwrap_err();
+
+        assert!(matches!(err, TransformError::Transform("nope")));
+    }
+
+    #[test]
+    fn try_transform_decode_error() {
+        let b64 = Base64String {
+            content: "Zm9#".into(),
+            alphabet: Standard::new(),
+            pending: None,
+        };
+        let err = b64.try_transform(Ok::<_, ()>).unwrap_err();
+
+        assert!(matches!(err, TransformError::Decode(_)));
+    }
+
+    #[test]
+    fn eq_str() {
+        let b64 = Base64String::<Standard>::encode(b"even");
+
+        assert_eq!(b64, "ZXZlbg==");
+        assert_eq!(b64, *"ZXZlbg==");
+        assert_eq!(b64, "ZXZlbg==".to_string());
+        assert_eq!("ZXZlbg==", b64);
+        assert_eq!(*"ZXZlbg==", b64);
+        assert_eq!("ZXZlbg==".to_string(), b64);
+    }
+
+    #[test]
+    fn eq_str_padding() {
+        let padded = Base64String::<Standard>::encode(b"even");
+        let reparsed = Base64String::<Standard>::from_encoded("ZXZlbg").unwrap();
+
+        assert_ne!(padded, "ZXZlbg");
+        assert_eq!(padded.without_padding(), "ZXZlbg");
+        // Parsing keeps the text as given, unless asked to normalize it
+        assert_eq!(reparsed, "ZXZlbg");
+        assert_eq!(
+            Base64String::<Standard>::from_encoded_normalized("ZXZlbg").unwrap(),
+            padded
+        );
+    }
+
+    #[test]
+    fn matches_data() {
+        let b64 = Base64String::<Standard>::encode(b"fooba");
+
+        assert!(b64.matches_data(b"fooba").unwrap());
+        assert!(!b64.matches_data(b"foobA").unwrap());
+        assert!(!b64.matches_data(b"foob").unwrap());
+        assert!(!b64.matches_data(b"foobar").unwrap());
+        assert!(Base64String::<Standard>::encode(b"")
+            .matches_data(b"")
+            .unwrap());
+    }
+
+    #[test]
+    fn matches_data_invalid() {
+        let b64 = Base64String {
+            content: "Zm9#".into(),
+            alphabet: Standard::new(),
+            pending: None,
+        };
+
+        assert!(b64.matches_data(b"foo").is_err());
+    }
+
+    #[test]
+    fn interior_padding_errors() {
+        let b64 = Base64String::<Standard>::from_encoded("QQ=a").unwrap();
+
+        assert!(matches!(
+            b64.decode(),
+            Err(DecodeError::Base64Error(B64Error::InvalidCharAt {
+                char: '=',
+                index: 2
+            }))
+        ));
+    }
+
+    #[test]
+    fn decode_suggests_alphabet() {
+        let mut b64 =
+            Base64String::encode_with([0xfb, 0xff], AnyAlphabet::from(AlphabetKind::UrlSafe));
+        *b64.alphabet_mut() = AlphabetKind::Standard.into();
+
+        let err = b64.decode().unwrap_err();
+        assert!(matches!(
+            err,
+            DecodeError::WrongAlphabet {
+                char: '-',
+                position: 0,
+                suggestion: AlphabetKind::UrlSafe
+            }
+        ));
+        assert_eq!(err.kind(), crate::ErrorKind::InvalidChar);
+        assert_eq!(err.position(), Some(0));
+    }
+
+    #[test]
+    fn from_encoded_suggests_alphabet() {
+        fn parse_and_decode(b64: &str) -> Result<Vec<u8>, DecodeError> {
+            Base64String::<Standard>::from_encoded(b64)?.decode()
+        }
+
+        // Indices are into the untrimmed text
+        assert!(matches!(
+            parse_and_decode(" Zm9v-_8"),
+            Err(DecodeError::WrongAlphabet {
+                char: '-',
+                position: 5,
+                suggestion: AlphabetKind::UrlSafe
+            })
+        ));
+        assert_eq!(
+            Base64String::<UrlSafe>::from_encoded("Zm9v+/8=").unwrap_err(),
+            B64Error::WrongAlphabet {
+                char: '+',
+                index: 4,
+                suggestion: AlphabetKind::Standard
+            }
+        );
+        assert!(matches!(
+            parse_and_decode("Zm9v-#8"),
+            Err(DecodeError::Base64Error(B64Error::InvalidCharAt {
+                char: '-',
+                index: 4
+            }))
+        ));
+    }
+
+    #[test]
+    fn lone_padding_errors() {
+        for padding in ["=", "===="] {
+            let b64 = Base64String::<Standard>::from_encoded(padding).unwrap();
+
+            assert!(matches!(
+                b64.decode(),
+                Err(DecodeError::Base64Error(B64Error::InvalidCharAt {
+                    char: '=',
+                    index: 0
+                }))
+            ));
+        }
+    }
+
+    #[test]
+    fn from_encoded_moves_owned() {
+        let input = String::from("Zm9vYmFy");
+        let ptr = input.as_ptr();
+        let b64 = Base64String::<Standard>::from_encoded(input).unwrap();
+
+        assert_eq!(b64.content.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn from_encoded_normalized_pads_exactly() {
+        let b64 =
+            Base64String::<Standard>::from_encoded_normalized(String::from("Zm9vYg")).unwrap();
 
-    use super::*;
-    use pretty_assertions::assert_eq;
+        assert_eq!(b64, "Zm9vYg==");
+        assert_eq!(b64.content.capacity(), 8);
+    }
 
     #[test]
-    fn encode_triplet() {
-        let triplet = ['A', 'B', 'C'];
-        let expected_encoded = ['Q', 'U', 'J', 'D'];
+    fn half_padded_is_truncated() {
+        let b64 = Base64String::<Standard>::from_encoded("ZXZlbg=").unwrap();
+        let err = b64.decode().unwrap_err();
 
-        let encoded = Base64String::<Standard>::encode_triplet(
-            [triplet[0] as u8, triplet[1] as u8, triplet[2] as u8],
-            &Standard::new(),
+        assert!(matches!(
+            err,
+            DecodeError::Base64Error(B64Error::Truncated {
+                expected: 8,
+                actual: 7
+            })
+        ));
+        assert_eq!(
+            err.to_string(),
+            "Base64 input is truncated, it's 7 characters long but should be 8"
         );
+    }
 
-        assert_eq!(encoded, expected_encoded);
+    #[test]
+    fn padding_combinations() {
+        // A whole quad, then every length of partial quad with every
+        // amount of padding after it
+        for rem in 0..4 {
+            for pad_len in 0..4 {
+                let text = format!("Zm9v{}{}", &"YmFy"[..rem], "=".repeat(pad_len));
+                let data = &b"fooba"[..(3 + rem.saturating_sub(1))];
+                let valid = match rem {
+                    0 => pad_len == 0,
+                    1 => false,
+                    _ => pad_len == 0 || rem + pad_len == 4,
+                };
+
+                let b64 = Base64String::<Standard>::from_encoded(text.as_str()).unwrap();
+                match b64.decode() {
+                    Ok(decoded) => {
+                        assert!(valid, "{text} decoded");
+                        assert_eq!(decoded, data, "{text}");
+                    }
+                    Err(e) => assert!(!valid, "{text} failed to decode: {e}"),
+                }
+                assert_eq!(
+                    crate::validate(&text, &Standard::new()).is_ok(),
+                    valid,
+                    "{text}"
+                );
+
+                let normalized =
+                    Base64String::<Standard>::from_encoded_normalized(text.as_str()).unwrap();
+                match rem {
+                    1 => assert!(normalized.decode().is_err(), "{text}"),
+                    _ => {
+                        assert_eq!(normalized.decode().unwrap(), data, "{text}");
+                        assert_eq!(normalized, normalized.canonical(), "{text}");
+                    }
+                }
+            }
+        }
     }
 
     #[test]
-    fn encode_long() {
-        let input = "everybody".chars().map(|c| c as u8);
-        let b64 = Base64String::encode(input.collect::<Vec<_>>());
-        let expected = Base64String {
-            content: String::from("ZXZlcnlib2R5"),
-            alphabet: Standard::new(),
-        };
+    fn write_after_unpadded() {
+        let mut b64 = Base64String::<Standard>::from_encoded("Zm8").unwrap();
+        b64.write_all(b"o").unwrap();
+        b64.finish();
 
-        assert_eq!(b64, expected)
+        assert_eq!(b64.decode().unwrap(), b"foo");
     }
 
     #[test]
-    fn encode_2_rem() {
-        let input = "event".chars().map(|c| c as u8);
-        let b64 = Base64String::encode(input.collect::<Vec<_>>());
-        let expected = Base64String {
-            content: String::from("ZXZlbnQ="),
+    fn canonicalize_pads() {
+        let mut b64 = Base64String {
+            content: String::from("aGk"),
             alphabet: Standard::new(),
+            pending: None,
         };
+        assert_eq!(b64.to_string(), "aGk");
+        assert_eq!(b64.canonical().to_string(), "aGk=");
+        assert_eq!(format!("{:#}", b64.canonical()), "aGk=");
 
-        assert_eq!(b64, expected)
+        b64.canonicalize();
+        assert_eq!(b64.content, "aGk=");
     }
 
     #[test]
-    fn encode_1_rem() {
-        let input = "even".chars().map(|c| c as u8);
-        let b64 = Base64String::encode(input.collect::<Vec<_>>());
-        let expected = Base64String {
-            content: String::from("ZXZlbg=="),
-            alphabet: Standard::new(),
-        };
+    fn canonicalize_partial_padding() {
+        for (text, canonical) in [
+            ("Zm9v=", "Zm9v"),
+            ("Zm9v====", "Zm9v"),
+            ("ZXZlbg=", "ZXZlbg=="),
+            ("Zg=", "Zg=="),
+            ("Zg===", "Zg=="),
+            ("====", "===="),
+        ] {
+            let b64 = Base64String::<Standard>::from_encoded(text).unwrap();
 
-        assert_eq!(b64, expected)
+            assert_eq!(b64.canonical(), canonical, "{text}");
+            assert_eq!(b64.canonical().canonical(), canonical, "{text}");
+        }
     }
 
     #[test]
-    fn decode_no_pad() {
-        let src = Base64String {
-            content: String::from("ZXZlcnlib2R5"),
-            alphabet: Standard::new(),
-        };
-        let expected = b"everybody".to_vec();
-        let decoded = src.decode().unwrap();
+    fn display_keeps_partial_padding() {
+        for text in ["ZXZlbg=", "Zg="] {
+            let b64 = Base64String::<Standard>::from_encoded(text).unwrap();
 
-        assert_eq!(decoded, expected)
+            assert_eq!(b64.to_string(), text);
+            assert_eq!(format!("{b64:#}"), text);
+            assert_eq!(
+                b64.to_string().parse::<Base64String<Standard>>().unwrap(),
+                b64
+            );
+        }
     }
 
     #[test]
-    fn decode_one_pad() {
-        let src = Base64String {
-            content: String::from("ZXZlbnQ="),
-            alphabet: Standard::new(),
-        };
-        let expected = b"event".to_vec();
-        let decoded = src.decode().unwrap();
+    fn display_as_stored() {
+        let b64 = Base64String::<Standard>::from_encoded("aGk").unwrap();
 
-        assert_eq!(decoded, expected)
+        assert_eq!(b64, "aGk");
+        assert_eq!(b64.to_string(), "aGk");
+        assert_eq!(format!("{b64:#}"), "aGk");
     }
 
     #[test]
-    fn decode_two_pad() {
-        let src = Base64String {
-            content: String::from("ZXZlbg=="),
-            alphabet: Standard::new(),
-        };
-        let expected = b"even".to_vec();
-        let decoded = src.decode().unwrap();
+    fn from_str_matches_from_encoded() {
+        let parsed = "aGk".parse::<Base64String<Standard>>().unwrap();
 
-        assert_eq!(decoded, expected)
+        assert_eq!(
+            parsed,
+            Base64String::<Standard>::from_encoded("aGk").unwrap()
+        );
+        assert_eq!(
+            parsed
+                .to_string()
+                .parse::<Base64String<Standard>>()
+                .unwrap(),
+            parsed
+        );
     }
 
     #[test]
-    fn encode_test_vectors() {
+    fn from_encoded_reports_index() {
+        let err = Base64String::<Standard>::from_encoded("Zm9v-mFy").unwrap_err();
+
+        assert!(matches!(
+            err,
+            B64Error::WrongAlphabet {
+                char: '-',
+                index: 4,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn from_encoded_trims_line_endings() {
+        for input in ["ZXZlbg==\n", "ZXZlbg==\r\n", " \tZXZlbg==\n\n", "ZXZlbg\n"] {
+            let b64 = Base64String::<Standard>::from_encoded(input).unwrap();
+
+            assert_eq!(b64.canonical().to_string(), "ZXZlbg==");
+            assert_eq!(b64.decode().unwrap(), b"even");
+        }
+        assert_eq!(Base64String::<Standard>::from_encoded("\r\n").unwrap(), "");
+    }
+
+    #[test]
+    fn from_encoded_trimmed_index() {
+        // Indices are into the untrimmed text
         assert_eq!(
-            Base64String::<Standard>::encode(b"").to_string(),
-            "".to_string()
+            Base64String::<Standard>::from_encoded("\n  Zm#v").unwrap_err(),
+            B64Error::InvalidCharAt {
+                char: '#',
+                index: 5
+            }
         );
+        // Whitespace inside the text is still invalid
         assert_eq!(
-            Base64String::<Standard>::encode(b"f").to_string(),
-            "Zg==".to_string()
+            Base64String::<Standard>::from_encoded(" Zm9v\nYmFy\n").unwrap_err(),
+            B64Error::InvalidCharAt {
+                char: '\n',
+                index: 5
+            }
         );
+    }
+
+    #[test]
+    fn from_encoded_keeps_whitespace_padding() {
+        let alphabet = Standard::new().with_padding(' ').unwrap();
+        let b64 = Base64String::from_encoded_with("aGk \n", alphabet).unwrap();
+
+        assert_eq!(b64, "aGk ");
+        assert_eq!(b64.decode().unwrap(), b"hi");
+    }
+
+    #[test]
+    fn eq_ignore_padding_across_padding() {
+        let equals = Standard::new().with_padding('=').unwrap();
+        let padded = Base64String::encode_with(b"hi", equals);
+        let dots = Base64String::encode_with(b"hi", Standard::new().with_padding('.').unwrap());
+        // Not canonical, as if the padding was lost on the way
+        let unpadded = Base64String {
+            content: String::from("aGk"),
+            alphabet: equals,
+            pending: None,
+        };
+
+        assert!(padded.eq_ignore_padding(&dots));
+        assert!(padded.eq_ignore_padding(&unpadded));
+        assert!(unpadded.eq_ignore_padding(&dots));
+        assert!(!padded.eq_ignore_padding(&Base64String::encode_with(b"ho", equals)));
+    }
+
+    #[test]
+    fn same_data_padded_or_not() {
+        let padded = Base64String::<Standard>::encode(b"foob");
+        let unpadded = Base64String::<Standard>::from_encoded("Zm9vYg").unwrap();
+
+        assert!(padded.represents_same_data(&unpadded).unwrap());
+    }
+
+    #[test]
+    fn different_data_same_length() {
+        let a = Base64String::<Standard>::encode(b"foobar");
+        let b = Base64String::<Standard>::encode(b"foobaz");
+
+        assert_eq!(a.decoded_len(), b.decoded_len());
+        assert!(!a.represents_same_data(&b).unwrap());
+        assert!(!a
+            .represents_same_data(&Base64String::<Standard>::encode(b"fooba"))
+            .unwrap());
+        assert!(!a
+            .represents_same_data(&Base64String::<Standard>::encode(b"foobarb"))
+            .unwrap());
+    }
+
+    #[test]
+    fn same_data_across_alphabets() {
+        let data = [0xfb, 0xff, 0xbf, 0x3e];
+        let standard = Base64String::<Standard>::encode(data);
+        let url_safe = Base64String::<UrlSafe>::encode(data);
+
+        assert_ne!(standard.to_string(), url_safe.to_string());
+        assert!(standard.represents_same_data(&url_safe).unwrap());
+        assert!(url_safe.represents_same_data(&standard).unwrap());
+    }
+
+    #[test]
+    fn same_data_ignores_spare_bits() {
+        let canonical = Base64String::<Standard>::from_encoded("Zg==").unwrap();
+        let spare_bits = Base64String::<Standard>::from_encoded("Zh==").unwrap();
+
+        assert!(canonical.represents_same_data(&spare_bits).unwrap());
+    }
+
+    #[test]
+    fn same_data_reports_errors() {
+        let valid = Base64String::<Standard>::encode(b"f");
+        let truncated = Base64String::<Standard>::from_encoded("Zm9vY").unwrap();
+
+        assert!(valid.represents_same_data(&truncated).is_err());
+        assert!(truncated.represents_same_data(&valid).is_err());
+    }
+
+    #[test]
+    fn display_wrapped() {
+        let b64 = Base64String::<Standard>::encode([0xff; 60]);
+        let wrapped = format!("{b64:#}");
+        let lines = wrapped.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines, ["/".repeat(64), "/".repeat(16)]);
+        assert_eq!(wrapped.replace('\n', ""), b64.to_string());
+    }
+
+    #[test]
+    fn display_wrapped_short() {
+        assert_eq!(format!("{:#}", Base64String::<Standard>::new()), "");
         assert_eq!(
-            Base64String::<Standard>::encode(b"fo").to_string(),
-            "Zm8=".to_string()
+            format!("{:#}", Base64String::<Standard>::encode(b"foo")),
+            "Zm9v"
         );
         assert_eq!(
-            Base64String::<Standard>::encode(b"foo").to_string(),
-            "Zm9v".to_string()
+            format!("{:#}", Base64String::<Standard>::encode([0; 48])),
+            "A".repeat(64)
+        );
+    }
+
+    #[test]
+    fn debug_redacted() {
+        let b64 = Base64String::<Standard>::encode(b"super secret token");
+
+        assert_eq!(
+            format!("{b64:?}"),
+            r#"Base64String<Standard> { len: 24, content: "c3Vw…a2Vu" }"#
         );
+    }
+
+    #[test]
+    fn debug_redacted_short() {
         assert_eq!(
-            Base64String::<Standard>::encode(b"foob").to_string(),
-            "Zm9vYg==".to_string()
+            format!("{:?}", Base64String::<Standard>::new()),
+            r#"Base64String<Standard> { len: 0, content: "" }"#
         );
         assert_eq!(
-            Base64String::<Standard>::encode(b"fooba").to_string(),
-            "Zm9vYmE=".to_string()
+            format!("{:?}", Base64String::<Standard>::encode(b"f")),
+            r#"Base64String<Standard> { len: 4, content: "…" }"#
         );
         assert_eq!(
-            Base64String::<Standard>::encode(b"foobar").to_string(),
-            "Zm9vYmFy".to_string()
+            format!("{:?}", Base64String::<Standard>::encode(b"foobar")),
+            r#"Base64String<Standard> { len: 8, content: "…" }"#
         );
     }
 
     #[test]
-    fn decode_test_vectors() {
+    fn debug_generic_alphabet() {
+        let alphabet = Standard::new().with_padding('.').unwrap();
+
         assert_eq!(
-            Base64String {
-                content: "".into(),
-                alphabet: Standard::new()
-            }
-            .decode()
-            .unwrap(),
-            b""
+            format!("{:?}", Base64String::encode_with(b"f", alphabet)),
+            r#"Base64String<WithPadding<Standard>> { len: 4, content: "…" }"#
         );
         assert_eq!(
-            Base64String {
-                content: "Zg==".into(),
-                alphabet: Standard::new()
-            }
-            .decode()
-            .unwrap(),
-            b"f"
+            short_type_name::<Result<WithPadding<Standard>, (u8, UrlSafe)>>(),
+            "Result<WithPadding<Standard>, (u8, UrlSafe)>"
         );
+    }
+
+    #[test]
+    fn debug_unredacted() {
+        let b64 = Base64String::<Standard>::encode(b"foobar");
+
         assert_eq!(
-            Base64String {
-                content: "Zm8=".into(),
-                alphabet: Standard::new()
+            format!("{:?}", b64.debug_unredacted()),
+            r#"Base64String<Standard> { len: 8, content: "Zm9vYmFy" }"#
+        );
+    }
+
+    const UTF8_SAMPLES: [&str; 5] = [
+        "",
+        "é",
+        "👋🏽",
+        "こんにちは世界",
+        "mixed ascii, 中文 & emoji 🦀!",
+    ];
+
+    #[test]
+    fn encode_str_roundtrip() {
+        for text in UTF8_SAMPLES {
+            let expected = Base64String::<Standard>::encode(text.as_bytes());
+
+            assert_eq!(Base64String::<Standard>::encode_str(text), expected);
+            assert_eq!(
+                Base64String::encode_str_with(text, Standard::new()),
+                expected
+            );
+            assert_eq!(String::try_from(expected).unwrap(), text);
+        }
+    }
+
+    #[test]
+    fn encode_string_roundtrip() {
+        for text in UTF8_SAMPLES {
+            let expected = Base64String::<UrlSafe>::encode(text.as_bytes());
+
+            assert_eq!(
+                Base64String::<UrlSafe>::encode_string(text.into()),
+                expected
+            );
+            assert_eq!(
+                Base64String::encode_string_with(text.into(), UrlSafe::new()),
+                expected
+            );
+            assert_eq!(String::try_from(expected).unwrap(), text);
+        }
+    }
+
+    #[test]
+    fn encode_string_non_ascii_alphabet() {
+        /// The standard alphabet with `/` swapped for `÷`
+        #[derive(Debug, Clone, Copy)]
+        struct Divide;
+
+        impl Alphabet for Divide {
+            fn padding(&self) -> Option<char> {
+                Some('=')
             }
-            .decode()
-            .unwrap(),
-            b"fo"
+
+            fn encode_bits(&self, bits: u8) -> char {
+                match bits {
+                    63 => '÷',
+                    bits => Standard::new().encode_bits(bits),
+                }
+            }
+
+            fn decode_char(&self, c: char) -> Result<u8, B64Error> {
+                match c {
+                    '÷' => Ok(63),
+                    '/' => Err(B64Error::InvalidChar(c)),
+                    c => Standard::new().decode_char(c),
+                }
+            }
+        }
+
+        let encoded = Base64String::encode_string_with("👋🏽".into(), Divide);
+
+        assert_eq!(encoded, "8J+Ri÷Cfj70=");
+        assert_eq!(String::try_from(encoded).unwrap(), "👋🏽");
+    }
+
+    /// The standard alphabet, but decoding `/` to 255 rather than 63
+    #[derive(Debug, Clone, Copy)]
+    struct Overflowing;
+
+    impl Alphabet for Overflowing {
+        fn padding(&self) -> Option<char> {
+            Some('=')
+        }
+
+        fn encode_bits(&self, bits: u8) -> char {
+            Standard::new().encode_bits(bits)
+        }
+
+        fn decode_char(&self, c: char) -> Result<u8, B64Error> {
+            match c {
+                '/' => Ok(0xff),
+                c => Standard::new().decode_char(c),
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(any(debug_assertions, feature = "strict-alphabet-checks"))]
+    fn out_of_range_value_is_an_error() {
+        let oob = B64Error::DecodedValueOOB {
+            char: '/',
+            value: 0xff,
+        };
+
+        assert_eq!(
+            Base64String::from_encoded_with("AB//", Overflowing).unwrap_err(),
+            oob
         );
+        // Non-ASCII input takes a different path
         assert_eq!(
-            Base64String {
-                content: "Zm9v".into(),
-                alphabet: Standard::new()
-            }
-            .decode()
-            .unwrap(),
-            b"foo"
+            Base64String::from_encoded_with("AB//é", Overflowing).unwrap_err(),
+            oob
         );
+        assert_eq!(crate::validate("AB//", &Overflowing), Err(oob.clone()));
         assert_eq!(
-            Base64String {
-                content: "Zm9vYg==".into(),
-                alphabet: Standard::new()
-            }
-            .decode()
-            .unwrap(),
-            b"foob"
+            primitives::decode_block(['A', 'B', '/', '/'], &Overflowing),
+            Err(oob.clone())
         );
+
+        let b64 = Base64String::encode_with([0xff], Overflowing);
+        assert_eq!(b64.content, "/w==");
+        assert!(matches!(b64.decode(), Err(DecodeError::Base64Error(e)) if e == oob));
+    }
+
+    #[test]
+    #[cfg(not(any(debug_assertions, feature = "strict-alphabet-checks")))]
+    fn out_of_range_value_is_masked() {
+        let b64 = Base64String::from_encoded_with("AB//", Overflowing).unwrap();
+
+        assert_eq!(b64.decode().unwrap(), [0x00, 0x1f, 0xff]);
         assert_eq!(
-            Base64String {
-                content: "Zm9vYmE=".into(),
-                alphabet: Standard::new()
-            }
-            .decode()
-            .unwrap(),
-            b"fooba"
+            Base64String::encode_with([0xff], Overflowing)
+                .decode()
+                .unwrap(),
+            [0xff]
         );
+    }
+
+    #[test]
+    fn decode_array_exact() {
+        let b64 = Base64String::<Standard>::encode(b"foobar");
+
+        assert_eq!(b64.decode_array::<6>().unwrap(), *b"foobar");
+        assert_eq!(<[u8; 6]>::try_from(&b64).unwrap(), *b"foobar");
+        assert_eq!(Vec::try_from(&b64).unwrap(), b"foobar");
+    }
+
+    #[test]
+    fn decode_array_wrong_length() {
+        let b64 = Base64String::<Standard>::encode(b"foobar");
+
+        assert!(matches!(
+            b64.decode_array::<7>(),
+            Err(DecodeError::WrongLength {
+                expected: 7,
+                actual: 6
+            })
+        ));
+        assert!(matches!(
+            b64.decode_array::<5>(),
+            Err(DecodeError::WrongLength {
+                expected: 5,
+                actual: 6
+            })
+        ));
         assert_eq!(
-            Base64String {
-                content: "Zm9vYmFy".into(),
-                alphabet: Standard::new()
-            }
-            .decode()
-            .unwrap(),
-            b"foobar"
+            b64.decode_array::<32>().unwrap_err().to_string(),
+            "Decoded data is 6 bytes, but should be 32"
         );
     }
+
+    #[test]
+    fn decode_array_empty() {
+        let empty = Base64String::<Standard>::encode(b"");
+
+        assert_eq!(empty.decode_array::<0>().unwrap(), [0u8; 0]);
+        assert!(Base64String::<Standard>::encode(b"f")
+            .decode_array::<0>()
+            .is_err());
+    }
+
+    #[test]
+    fn decode_array_invalid() {
+        let b64 = Base64String {
+            content: "Zm9#".into(),
+            alphabet: Standard::new(),
+            pending: None,
+        };
+
+        assert!(matches!(
+            b64.decode_array::<3>(),
+            Err(DecodeError::Base64Error(B64Error::InvalidCharAt {
+                char: '#',
+                index: 3
+            }))
+        ));
+    }
+
+    #[test]
+    fn string_try_from_invalid_utf8() {
+        let b64 = Base64String::<Standard>::encode([0xff, 0xfe]);
+
+        assert!(matches!(
+            String::try_from(b64),
+            Err(DecodeError::InvalidUtf8(_))
+        ));
+    }
 }
@@ -0,0 +1,208 @@
+//! Base64 with a checksum of its data, for catching corruption in
+//! values that are copied & pasted, like config values or licence keys
+//!
+//! ## Format
+//!
+//! The data is framed by appending the CRC-32 of the data to it as 4
+//! big-endian bytes, & the framed bytes are then encoded as normal
+//! base64. The CRC-32 is the common one used by zlib, PNG & Ethernet
+//! (polynomial `0x04C11DB7` reflected, initial value `0xFFFFFFFF`,
+//! final XOR `0xFFFFFFFF`), so `crc32("123456789")` is `0xCBF43926`,
+//! & it matches Python's `zlib.crc32` & Java's `java.util.zip.CRC32`
+//!
+//! To check framed data, decode the base64, split off the last 4
+//! bytes, & compare them with the CRC-32 of the rest. Decoding framed
+//! base64 without checking it, like with [`Base64String::decode`],
+//! gives back the data with the 4 checksum bytes still on the end
+//!
+//! ## Examples
+//! ```
+//! # use baze64::{Base64String, alphabet::Standard};
+//! let key = Base64String::encode_checked(b"licence", Standard::new());
+//!
+//! assert_eq!(key.to_string(), "bGljZW5jZR2q5kg=");
+//! assert_eq!(key.decode_checked()?, b"licence");
+//! assert_eq!(key.decode()?.len(), 7 + 4);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::{alphabet::Alphabet, Base64String, DecodeError};
+
+/// How many bytes the checksum adds to the data
+pub const CHECKSUM_LEN: usize = 4;
+
+const CRC_TABLE: [u32; 256] = crc_table();
+
+const fn crc_table() -> [u32; 256] {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                crc >> 1 ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+
+    table
+}
+
+/// Get the CRC-32 of `bytes`, as described in the
+/// [module docs](self)
+///
+/// # Examples
+/// ```
+/// # use baze64::checked::crc32;
+/// assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+/// ```
+pub fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0, |crc, &b| {
+        CRC_TABLE[usize::from(crc as u8 ^ b)] ^ crc >> 8
+    })
+}
+
+/// Append the checksum of `bytes` to them, giving the framed bytes
+/// that [`Base64String::encode_checked`] encodes
+pub fn frame(bytes: &[u8]) -> Vec<u8> {
+    let mut framed = Vec::with_capacity(bytes.len() + CHECKSUM_LEN);
+    framed.extend_from_slice(bytes);
+    framed.extend_from_slice(&crc32(bytes).to_be_bytes());
+
+    framed
+}
+
+/// Check the checksum on the end of `framed` & strip it off, giving
+/// back the data
+///
+/// Fails with [`DecodeError::ChecksumMismatch`] if the checksum
+/// doesn't match, or [`DecodeError::WrongLength`] if `framed` is too
+/// short to have a checksum
+pub fn unframe(mut framed: Vec<u8>) -> Result<Vec<u8>, DecodeError> {
+    let Some(data_len) = framed.len().checked_sub(CHECKSUM_LEN) else {
+        return Err(DecodeError::WrongLength {
+            expected: CHECKSUM_LEN,
+            actual: framed.len(),
+        });
+    };
+
+    let mut checksum = [0; CHECKSUM_LEN];
+    checksum.copy_from_slice(&framed[data_len..]);
+    framed.truncate(data_len);
+
+    let expected = u32::from_be_bytes(checksum);
+    let actual = crc32(&framed);
+    if expected != actual {
+        return Err(DecodeError::ChecksumMismatch { expected, actual });
+    }
+
+    Ok(framed)
+}
+
+impl<A> Base64String<A>
+where
+    A: Alphabet,
+{
+    /// Encode `bytes` followed by their checksum into a
+    /// [`Base64String`] using a given `alphabet` instance, in the
+    /// format described in the [module docs](self)
+    pub fn encode_checked<B>(bytes: B, alphabet: A) -> Self
+    where
+        B: AsRef<[u8]>,
+    {
+        Self::encode_with(frame(bytes.as_ref()), alphabet)
+    }
+
+    /// Decode data encoded by [`Base64String::encode_checked`],
+    /// checking its checksum & stripping it off
+    ///
+    /// Fails with [`DecodeError::ChecksumMismatch`] if the checksum
+    /// doesn't match the data, or [`DecodeError::WrongLength`] if the
+    /// data is too short to have a checksum
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, DecodeError, alphabet::Standard};
+    /// let mut key = Base64String::encode_checked(b"licence", Standard::new()).to_string();
+    /// key.replace_range(0..1, "c");
+    /// let key = Base64String::<Standard>::from_encoded(key)?;
+    ///
+    /// assert!(matches!(
+    ///     key.decode_checked(),
+    ///     Err(DecodeError::ChecksumMismatch { .. })
+    /// ));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn decode_checked(&self) -> Result<Vec<u8>, DecodeError> {
+        unframe(self.decode()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{alphabet::Standard, ErrorKind};
+
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn crc32_vectors() {
+        // Checked against Python's `zlib.crc32`
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"a"), 0xE8B7_BE43);
+        assert_eq!(crc32(b"licence"), 0x1DAA_E648);
+        assert_eq!(
+            crc32(b"The quick brown fox jumps over the lazy dog"),
+            0x414F_A339
+        );
+    }
+
+    #[test]
+    fn frame_layout() {
+        assert_eq!(frame(b""), [0, 0, 0, 0]);
+        assert_eq!(frame(b"a"), [b'a', 0xE8, 0xB7, 0xBE, 0x43]);
+        assert_eq!(unframe(frame(b"")).unwrap(), b"");
+    }
+
+    #[test]
+    fn mismatch() {
+        let err = unframe(vec![b'b', 0xE8, 0xB7, 0xBE, 0x43]).unwrap_err();
+
+        assert!(matches!(
+            err,
+            DecodeError::ChecksumMismatch {
+                expected: 0xE8B7_BE43,
+                actual: 0x71BE_EFF9
+            }
+        ));
+        assert_eq!(err.kind(), ErrorKind::ChecksumMismatch);
+        assert_eq!(
+            err.to_string(),
+            "Decoded data has checksum 71beeff9, but should have e8b7be43"
+        );
+    }
+
+    #[test]
+    fn too_short() {
+        assert!(matches!(
+            unframe(vec![0; 3]),
+            Err(DecodeError::WrongLength {
+                expected: 4,
+                actual: 3
+            })
+        ));
+        assert!(matches!(
+            Base64String::<Standard>::new().decode_checked(),
+            Err(DecodeError::WrongLength {
+                expected: 4,
+                actual: 0
+            })
+        ));
+    }
+}
@@ -0,0 +1,829 @@
+use std::{borrow::Cow, io::Write, ops::Range};
+
+use crate::{
+    alphabet::Alphabet,
+    base64string::{ascii_decode_table, trim_padding},
+    primitives, B64Error, Base64Str, Base64String, DecodeCompat, DecodeError, DecodeIntoError,
+};
+
+/// A reusable set of options for encoding & decoding base64, built
+/// up once & then used as many times as needed
+///
+/// The alphabet's lookup table is built when the codec is created,
+/// rather than every time something is decoded. Codecs don't
+/// allocate, so they're cheap to clone & share between threads
+///
+/// With the default options, a codec encodes & decodes the same as
+/// [`Base64String::encode_with`] & [`Base64Str::decode`]
+///
+/// # Examples
+/// ```
+/// # use baze64::{Codec, alphabet::Standard};
+/// let codec = Codec::new(Standard::new())
+///     .padding(false)
+///     .wrap(8)
+///     .lenient_whitespace(true);
+///
+/// let encoded = codec.encode_to_string(b"hello world");
+/// assert_eq!(encoded, "aGVsbG8g\nd29ybGQ");
+/// assert_eq!(codec.decode(&encoded)?, b"hello world");
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Clone)]
+pub struct Codec<A> {
+    alphabet: A,
+    table: [u8; 128],
+    padding: bool,
+    wrap: Option<usize>,
+    lenient_whitespace: bool,
+    normalize_padding: bool,
+    strict_tail: bool,
+    tolerant: bool,
+    limit: Option<usize>,
+    compat: DecodeCompat,
+}
+
+impl<A> Codec<A>
+where
+    A: Alphabet,
+{
+    /// Create a codec for `alphabet` with the default options, which
+    /// pads output, doesn't wrap it & decodes input strictly
+    pub fn new(alphabet: A) -> Self {
+        Self {
+            table: ascii_decode_table(&alphabet),
+            alphabet,
+            padding: true,
+            wrap: None,
+            lenient_whitespace: false,
+            normalize_padding: false,
+            strict_tail: false,
+            tolerant: false,
+            limit: None,
+            compat: DecodeCompat::Strict,
+        }
+    }
+
+    /// Whether encoded output is padded, if the alphabet has padding.
+    /// On by default
+    ///
+    /// Decoding accepts unpadded input either way
+    pub fn padding(mut self, padding: bool) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Wrap the text from [`Codec::encode_to_string`] into lines of
+    /// `columns` characters, or don't wrap it if `columns` is 0. Off
+    /// by default
+    pub fn wrap(mut self, columns: usize) -> Self {
+        self.wrap = (columns > 0).then_some(columns);
+        self
+    }
+
+    /// Whether decoding ignores ASCII whitespace anywhere in the input,
+    /// like the line breaks in wrapped base64, rather than only around
+    /// it. Off by default
+    ///
+    /// Errors give indices into the input with the whitespace removed
+    pub fn lenient_whitespace(mut self, lenient: bool) -> Self {
+        self.lenient_whitespace = lenient;
+        self
+    }
+
+    /// Whether decoding ignores the padding at the end of the input,
+    /// so input with only some of its padding, or too much of it, is
+    /// accepted. Off by default
+    ///
+    /// Useful for input typed or pasted by a person, like
+    /// [`Base64String::from_encoded_normalized_with`]
+    pub fn normalize_padding(mut self, normalize: bool) -> Self {
+        self.normalize_padding = normalize;
+        self
+    }
+
+    /// Whether decoding rejects input whose last character has bits
+    /// set after the end of the data, with [`B64Error::TrailingBits`].
+    /// Off by default, so `Zh==` decodes the same as `Zg==`
+    ///
+    /// Turn this on when every piece of data must have exactly one
+    /// encoding, like when comparing encoded signatures
+    pub fn strict_tail(mut self, strict: bool) -> Self {
+        self.strict_tail = strict;
+        self
+    }
+
+    /// Whether decoding corrects hand-typed input, ignoring case &
+    /// taking `0`, `O` & `o` or `1`, `l` & `I` for each other when the
+    /// alphabet doesn't have the one typed. Off by default
+    ///
+    /// A character that could be more than one value fails as an
+    /// invalid character rather than being guessed at. [`Standard`] &
+    /// [`UrlSafe`] give each case of a letter a different value, so
+    /// every letter is ambiguous & only text without any decodes. This
+    /// is for alphabets made to be typed, with one case of each letter
+    ///
+    /// [`Standard`]: crate::alphabet::Standard
+    /// [`UrlSafe`]: crate::alphabet::UrlSafe
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{B64Error, Codec, alphabet::{Alphabet, Standard}};
+    /// /// Uppercase letters, digits & symbols, but no lowercase
+    /// #[derive(Clone)]
+    /// struct Shouty;
+    ///
+    /// const CHARS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789!#$%&()*+,-./:;<>?@[]^_`{|}~";
+    ///
+    /// impl Alphabet for Shouty {
+    ///     fn padding(&self) -> Option<char> {
+    ///         Some('=')
+    ///     }
+    ///
+    ///     fn encode_bits(&self, bits: u8) -> char {
+    ///         CHARS.as_bytes()[bits as usize].into()
+    ///     }
+    ///
+    ///     fn decode_char(&self, c: char) -> Result<u8, B64Error> {
+    ///         CHARS.find(c).map(|i| i as u8).ok_or(B64Error::InvalidChar(c))
+    ///     }
+    /// }
+    ///
+    /// let shouty = Codec::new(Shouty).tolerant(true);
+    /// assert_eq!(shouty.encode_to_string(b"Yes"), "WWV<");
+    /// assert_eq!(shouty.decode("wwV<")?, b"Yes");
+    /// // `o` could be `O` or `0`
+    /// assert!(shouty.decode("WWo<").is_err());
+    ///
+    /// // `a` & `A` are both in the standard alphabet
+    /// assert!(Codec::new(Standard::new()).tolerant(true).decode("aGk=").is_err());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn tolerant(mut self, tolerant: bool) -> Self {
+        self.tolerant = tolerant;
+        self
+    }
+
+    /// Fail with [`DecodeError::TooLarge`] before decoding input that
+    /// would decode to more than `max_bytes`. No limit by default
+    pub fn limit(mut self, max_bytes: usize) -> Self {
+        self.limit = Some(max_bytes);
+        self
+    }
+
+    /// Decode the way another language's decoder does rather than
+    /// strictly, for debugging what it made of some input. Strict by
+    /// default
+    ///
+    /// See [`DecodeCompat`] for exactly what each mode accepts
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Codec, DecodeCompat, alphabet::Standard};
+    /// let python = Codec::new(Standard::new()).compat(DecodeCompat::PythonForgiving);
+    ///
+    /// assert_eq!(python.decode("Zm9v!\nYmFy")?, b"foobar");
+    /// assert_eq!(python.decode("Zg==Zm9v")?, b"f");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn compat(mut self, mode: DecodeCompat) -> Self {
+        self.compat = mode;
+        self
+    }
+
+    /// The alphabet used to encode & decode
+    pub fn alphabet(&self) -> &A {
+        &self.alphabet
+    }
+
+    /// Whether encoded output is padded, as set by [`Codec::padding`]
+    pub fn pads(&self) -> bool {
+        self.padding
+    }
+
+    /// The column encoded text is wrapped at, as set by [`Codec::wrap`]
+    pub fn wrap_columns(&self) -> Option<usize> {
+        self.wrap
+    }
+
+    /// Encode `bytes` into a [`Base64String`], leaving out the padding
+    /// if [`Codec::padding`] is off
+    ///
+    /// The result can't hold line breaks, so isn't wrapped. Use
+    /// [`Codec::encode_to_string`] for that
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Codec, alphabet::Standard};
+    /// let codec = Codec::new(Standard::new()).padding(false);
+    ///
+    /// assert_eq!(codec.encode(b"hi"), "aGk");
+    /// ```
+    pub fn encode<B>(&self, bytes: B) -> Base64String<A>
+    where
+        A: Clone,
+        B: AsRef<[u8]>,
+    {
+        let mut b64 = Base64String::encode_with(bytes, self.alphabet.clone());
+        if !self.padding {
+            b64.remove_padding();
+        }
+
+        b64
+    }
+
+    /// Encode `bytes` into text, with all the options applied
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Codec, alphabet::Standard};
+    /// let codec = Codec::new(Standard::new()).wrap(4);
+    ///
+    /// assert_eq!(codec.encode_to_string(b"foobar"), "Zm9v\nYmFy");
+    /// ```
+    pub fn encode_to_string<B>(&self, bytes: B) -> String
+    where
+        A: Clone,
+        B: AsRef<[u8]>,
+    {
+        let encoded = self.encode(bytes).into_encoded();
+        match self.wrap {
+            Some(columns) => encoded
+                .chars()
+                .collect::<Vec<_>>()
+                .chunks(columns)
+                .map(|line| line.iter().collect::<String>())
+                .collect::<Vec<_>>()
+                .join("\n"),
+            None => encoded,
+        }
+    }
+
+    /// Decode the base64 text `b64` into a [`Vec<u8>`]
+    ///
+    /// Decoding with a built-in alphabet text that's valid in the other
+    /// one fails with [`DecodeError::WrongAlphabet`], suggesting it
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Codec, B64Error, DecodeError, alphabet::Standard};
+    /// let strict = Codec::new(Standard::new()).strict_tail(true);
+    ///
+    /// assert_eq!(strict.decode("Zg==")?, b"f");
+    /// assert!(matches!(
+    ///     strict.decode("Zh=="),
+    ///     Err(DecodeError::Base64Error(B64Error::TrailingBits { char: 'h', index: 1 }))
+    /// ));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn decode(&self, b64: &str) -> Result<Vec<u8>, DecodeError> {
+        if let Some(decoded) = self.decode_compat(b64) {
+            return decoded;
+        }
+        let (content, start) = self.prepare(b64)?;
+
+        Base64Str::from_parts(&content, &self.alphabet)
+            .decode_with_table(&self.table)
+            .map_err(|e| offset_decode_error(e, start))
+    }
+
+    /// Decode the base64 text `b64` into the `buf` provided, returning
+    /// the number of bytes written
+    ///
+    /// Behaves the same as [`Base64String::decode_into`], including
+    /// what's left in `buf` on failure
+    pub fn decode_into<O>(&self, b64: &str, buf: &mut O) -> Result<usize, DecodeIntoError>
+    where
+        O: Write,
+    {
+        if let Some(decoded) = self.decode_compat(b64) {
+            let decoded = decoded.map_err(|e| DecodeIntoError::new(e, 0))?;
+            buf.write_all(&decoded)
+                .map_err(|e| DecodeIntoError::new(e.into(), 0))?;
+            return Ok(decoded.len());
+        }
+        let (content, start) = self.prepare(b64).map_err(|e| DecodeIntoError::new(e, 0))?;
+
+        Base64Str::from_parts(&content, &self.alphabet)
+            .decode_into(buf)
+            .map_err(|e| DecodeIntoError::new(offset_decode_error(e.error, start), e.bytes_written))
+    }
+
+    /// Decode `b64` with the [`DecodeCompat`] mode, or [`None`] if
+    /// decoding is strict
+    fn decode_compat(&self, b64: &str) -> Option<Result<Vec<u8>, DecodeError>> {
+        let decoded = self.compat.decode(b64, &self.alphabet)?;
+
+        Some(match (decoded, self.limit) {
+            (Ok(decoded), Some(limit)) if decoded.len() > limit => Err(DecodeError::TooLarge {
+                needed: decoded.len(),
+                limit,
+            }),
+            (decoded, _) => decoded.map_err(DecodeError::from),
+        })
+    }
+
+    /// Apply the decoding options to `b64` & check it's ready to
+    /// decode, returning the content to decode along with where it
+    /// starts in `b64`
+    fn prepare<'t>(&self, b64: &'t str) -> Result<(Cow<'t, str>, usize), DecodeError> {
+        let is_trimmed = |c| Base64String::is_trimmed(c, &self.alphabet);
+        let (mut content, start) = if self.lenient_whitespace && b64.contains(is_trimmed) {
+            (Cow::Owned(b64.replace(is_trimmed, "")), 0)
+        } else {
+            let end = b64.trim_end_matches(is_trimmed);
+            let start = end.len() - end.trim_start_matches(is_trimmed).len();
+            (Cow::Borrowed(&b64[start..end.len()]), start)
+        };
+
+        if self.tolerant {
+            let corrected =
+                correct_typos(&content, &self.alphabet).map_err(|e| e.offset_by(start))?;
+            content = Cow::Owned(corrected);
+        }
+
+        let mut kept = Base64String::trim_rfc_padding(&content, &self.alphabet);
+        if let Some(p) = self.alphabet.padding().filter(|_| self.normalize_padding) {
+            kept = trim_padding(kept, p);
+        }
+        let kept_len = kept.len();
+        content = slice_cow(content, 0..kept_len);
+
+        let table = Some(&self.table);
+        Base64String::check_chars_with_table(&content, &self.alphabet, table).map_err(|e| {
+            DecodeError::suggest_alphabet(e.offset_by(start), &content, &self.alphabet)
+        })?;
+
+        if let Some(limit) = self.limit {
+            let needed = Base64Str::from_parts(&content, &self.alphabet).decoded_len();
+            if needed > limit {
+                return Err(DecodeError::TooLarge { needed, limit });
+            }
+        }
+        if self.strict_tail {
+            check_tail(&content, &self.alphabet).map_err(|e| e.offset_by(start))?;
+        }
+
+        Ok((content, start))
+    }
+}
+
+impl<A> Default for Codec<A>
+where
+    A: Alphabet + Default,
+{
+    fn default() -> Self {
+        Self::new(A::default())
+    }
+}
+
+impl<A> core::fmt::Debug for Codec<A>
+where
+    A: core::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Codec")
+            .field("alphabet", &self.alphabet)
+            .field("padding", &self.padding)
+            .field("wrap", &self.wrap)
+            .field("lenient_whitespace", &self.lenient_whitespace)
+            .field("normalize_padding", &self.normalize_padding)
+            .field("strict_tail", &self.strict_tail)
+            .field("tolerant", &self.tolerant)
+            .field("limit", &self.limit)
+            .field("compat", &self.compat)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Narrow `text` down to `range`, without copying it if it's borrowed
+fn slice_cow(text: Cow<'_, str>, range: Range<usize>) -> Cow<'_, str> {
+    match text {
+        Cow::Borrowed(text) => Cow::Borrowed(&text[range]),
+        Cow::Owned(mut text) => {
+            text.truncate(range.end);
+            text.drain(..range.start);
+            Cow::Owned(text)
+        }
+    }
+}
+
+/// Shift the position of a base64 error in `err` by `offset`
+fn offset_decode_error(err: DecodeError, offset: usize) -> DecodeError {
+    match err {
+        DecodeError::Base64Error(e) => e.offset_by(offset).into(),
+        e => e,
+    }
+}
+
+/// Groups of characters people commonly confuse when reading or
+/// typing them
+const LOOK_ALIKES: [&[char]; 2] = [&['0', 'O', 'o'], &['1', 'l', 'I']];
+
+/// Replace each character of hand-typed `b64` with the one `alphabet`
+/// encodes its value as, as described by [`Codec::tolerant`]
+///
+/// Characters with no value are left for the usual checks to reject
+fn correct_typos<A>(b64: &str, alphabet: &A) -> Result<String, B64Error>
+where
+    A: Alphabet,
+{
+    let padding = alphabet.padding();
+
+    b64.chars()
+        .enumerate()
+        .map(|(index, c)| {
+            let typed = primitives::decode_bits(alphabet, c);
+            // Look-alikes are only taken for characters that aren't
+            // in the alphabet themselves
+            let look_alikes = match typed {
+                Ok(_) => &[][..],
+                Err(_) => LOOK_ALIKES
+                    .iter()
+                    .find(|group| group.contains(&c))
+                    .map_or(&[][..], |group| *group),
+            };
+            let mut values = typed.into_iter().chain(
+                look_alikes
+                    .iter()
+                    .copied()
+                    .chain(other_cases(c))
+                    .filter(|&other| other != c && Some(other) != padding)
+                    .filter_map(|other| primitives::decode_bits(alphabet, other).ok()),
+            );
+
+            match values.next() {
+                Some(bits) if values.all(|other| other == bits) => Ok(alphabet.encode_bits(bits)),
+                Some(_) => Err(B64Error::InvalidCharAt { char: c, index }),
+                None => Ok(c),
+            }
+        })
+        .collect()
+}
+
+/// The other cases of `c` that are a single character & change back
+/// to `c`, so `ß` isn't taken as `SS` or `ſ` as `S`
+fn other_cases(c: char) -> impl Iterator<Item = char> {
+    fn single(mut chars: impl Iterator<Item = char>) -> Option<char> {
+        chars.next().filter(|_| chars.next().is_none())
+    }
+
+    [single(c.to_lowercase()), single(c.to_uppercase())]
+        .into_iter()
+        .flatten()
+        .filter(move |&other| {
+            other != c
+                && (single(other.to_lowercase()) == Some(c)
+                    || single(other.to_uppercase()) == Some(c))
+        })
+}
+
+/// Check the bits of the last character of `b64` that come after the
+/// end of the data are all zero
+fn check_tail<A>(b64: &str, alphabet: &A) -> Result<(), B64Error>
+where
+    A: Alphabet,
+{
+    let padding = alphabet.padding();
+    let data = b64.trim_end_matches(|c| Some(c) == padding);
+    let data_len = data.chars().count();
+    // A quad of 2 characters holds 1 byte, leaving 4 bits of the
+    // second unused, & one of 3 holds 2 bytes, leaving 2 bits
+    let unused = match data_len % 4 {
+        2 => 0b1111,
+        3 => 0b0011,
+        _ => return Ok(()),
+    };
+
+    let Some(c) = data.chars().next_back() else {
+        return Ok(());
+    };
+    if primitives::decode_bits(alphabet, c)? & unused != 0 {
+        return Err(B64Error::TrailingBits {
+            char: c,
+            index: data_len - 1,
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alphabet::{AlphabetKind, Standard, UrlSafe};
+
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn defaults_match_base64string() {
+        let codec = Codec::<Standard>::default();
+
+        for len in 0..10 {
+            let data = (0..len)
+                .map(|b: u8| b.wrapping_mul(37))
+                .collect::<Vec<u8>>();
+            let b64 = Base64String::<Standard>::encode(&data);
+
+            assert_eq!(codec.encode(&data), b64);
+            assert_eq!(codec.encode_to_string(&data), b64.to_string());
+            assert_eq!(codec.decode(b64.as_ref()).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn padding() {
+        let codec = Codec::new(Standard::new()).padding(false);
+
+        assert_eq!(codec.encode(b"f"), "Zg");
+        assert_eq!(codec.encode_to_string(b"fo"), "Zm8");
+        assert_eq!(codec.encode(b"foo"), "Zm9v");
+        // Decoding takes either
+        assert_eq!(codec.decode("Zg==").unwrap(), b"f");
+        assert_eq!(codec.decode("Zg").unwrap(), b"f");
+    }
+
+    #[test]
+    fn wrap() {
+        let codec = Codec::new(Standard::new()).wrap(4);
+
+        assert_eq!(codec.encode_to_string(b"foobarb"), "Zm9v\nYmFy\nYg==");
+        assert_eq!(codec.encode_to_string(b""), "");
+        // Only the text is wrapped
+        assert_eq!(codec.encode(b"foobarb"), "Zm9vYmFyYg==");
+        assert_eq!(
+            Codec::new(Standard::new()).wrap(4).wrap(0).wrap_columns(),
+            None
+        );
+    }
+
+    #[test]
+    fn lenient_whitespace() {
+        let wrapped = " Zm9v\nYmFy\r\nYg==\n";
+
+        assert_eq!(
+            Codec::new(Standard::new())
+                .decode(wrapped)
+                .unwrap_err()
+                .to_string(),
+            "Invalid Base64 character '\\u{a}' (line feed) at index 5"
+        );
+        assert_eq!(
+            Codec::new(Standard::new())
+                .lenient_whitespace(true)
+                .decode(wrapped)
+                .unwrap(),
+            b"foobarb"
+        );
+    }
+
+    #[test]
+    fn normalize_padding() {
+        let codec = Codec::new(Standard::new()).normalize_padding(true);
+
+        for text in ["ZXZlbg", "ZXZlbg=", "ZXZlbg==", "ZXZlbg==="] {
+            assert_eq!(codec.decode(text).unwrap(), b"even", "{text}");
+        }
+        assert!(Codec::new(Standard::new()).decode("ZXZlbg=").is_err());
+    }
+
+    #[test]
+    fn strict_tail() {
+        let codec = Codec::new(Standard::new()).strict_tail(true);
+
+        assert_eq!(Codec::new(Standard::new()).decode("Zh==").unwrap(), b"f");
+        assert_eq!(codec.decode("Zg==").unwrap(), b"f");
+        assert_eq!(codec.decode("Zm9vYmFy").unwrap(), b"foobar");
+        assert!(matches!(
+            codec.decode("  Zm9vYmF"),
+            Err(DecodeError::Base64Error(B64Error::TrailingBits {
+                char: 'F',
+                index: 8
+            }))
+        ));
+        assert!(matches!(
+            codec.decode("Zh"),
+            Err(DecodeError::Base64Error(B64Error::TrailingBits {
+                char: 'h',
+                index: 1
+            }))
+        ));
+    }
+
+    /// Uppercase letters, digits & symbols, but no lowercase
+    #[derive(Clone)]
+    struct Shouty;
+
+    const SHOUTY: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789!#$%&()*+,-./:;<>?@[]^_`{|}~";
+
+    impl Alphabet for Shouty {
+        fn padding(&self) -> Option<char> {
+            Some('=')
+        }
+
+        fn encode_bits(&self, bits: u8) -> char {
+            SHOUTY.as_bytes()[bits as usize].into()
+        }
+
+        fn decode_char(&self, c: char) -> Result<u8, B64Error> {
+            SHOUTY
+                .find(c)
+                .map(|i| i as u8)
+                .ok_or(B64Error::InvalidChar(c))
+        }
+    }
+
+    #[test]
+    fn tolerant_decodes_lowercased() {
+        let codec = Codec::new(Shouty).tolerant(true);
+
+        for data in [&b"Yes"[..], b"licence key", &[0xde, 0xad, 0xbe, 0xef]] {
+            let encoded = codec.encode_to_string(data);
+            let lower = encoded.to_lowercase();
+
+            if lower != encoded {
+                assert!(Codec::new(Shouty).decode(&lower).is_err(), "{lower}");
+            }
+            // `o` & `l` each look like more than one character
+            if lower.contains(['o', 'l']) {
+                assert!(codec.decode(&lower).is_err(), "{lower} should be ambiguous");
+            } else {
+                assert_eq!(codec.decode(&lower).unwrap(), data);
+            }
+        }
+    }
+
+    #[test]
+    fn tolerant_rejects_ambiguous() {
+        let codec = Codec::new(Shouty).tolerant(true);
+
+        assert_eq!(codec.decode("a0O=").unwrap(), codec.decode("A0O=").unwrap());
+        for (text, char, index) in [
+            (" WWo<", 'o', 3),
+            ("WWl<", 'l', 2),
+            // Case changes that aren't a single character both ways
+            ("WWß<", 'ß', 2),
+            ("WWſ<", 'ſ', 2),
+            ("WW\u{212a}<", '\u{212a}', 2),
+            ("WWé<", 'é', 2),
+        ] {
+            assert_eq!(
+                codec.decode(text).unwrap_err().to_string(),
+                B64Error::InvalidCharAt { char, index }.to_string(),
+                "{text}"
+            );
+        }
+    }
+
+    #[test]
+    fn tolerant_corrects_look_alikes() {
+        /// The standard alphabet with `O` & `o` swapped for `*` & `~`,
+        /// so they can't be confused with `0`
+        struct NoLetterO;
+
+        impl Alphabet for NoLetterO {
+            fn padding(&self) -> Option<char> {
+                Some('=')
+            }
+
+            fn encode_bits(&self, bits: u8) -> char {
+                match Standard::new().encode_bits(bits) {
+                    'O' => '*',
+                    'o' => '~',
+                    c => c,
+                }
+            }
+
+            fn decode_char(&self, c: char) -> Result<u8, B64Error> {
+                match c {
+                    '*' => Ok(14),
+                    '~' => Ok(40),
+                    'O' | 'o' => Err(B64Error::InvalidChar(c)),
+                    c => Standard::new().decode_char(c),
+                }
+            }
+        }
+
+        let codec = Codec::new(NoLetterO).tolerant(true);
+        let zeros = codec.decode("0000").unwrap();
+
+        assert_eq!(codec.decode("OOOO").unwrap(), zeros);
+        assert_eq!(codec.decode("oOo0").unwrap(), zeros);
+        assert_ne!(codec.decode("****").unwrap(), zeros);
+    }
+
+    #[test]
+    fn tolerant_uppercased_standard_errors() {
+        let token = Codec::new(Standard::new()).encode_to_string(b"licence key");
+        let upper = token.to_uppercase();
+
+        // Without it, the uppercased token decodes to other bytes
+        let strict = Codec::new(Standard::new()).decode(&upper).unwrap();
+        assert_ne!(strict, b"licence key");
+
+        let tolerant = Codec::new(Standard::new()).tolerant(true);
+        assert!(matches!(
+            tolerant.decode(&upper),
+            Err(DecodeError::Base64Error(B64Error::InvalidCharAt {
+                index: 0,
+                ..
+            }))
+        ));
+        // Text without letters still decodes
+        assert_eq!(tolerant.decode("++//").unwrap(), [0xfb, 0xef, 0xff]);
+        assert_eq!(
+            Codec::new(UrlSafe::new())
+                .tolerant(true)
+                .decode("-_8=")
+                .unwrap(),
+            [0xfb, 0xff]
+        );
+    }
+
+    #[test]
+    fn limit() {
+        let codec = Codec::new(Standard::new()).limit(5);
+
+        assert_eq!(codec.decode("Zm9vYmE=").unwrap(), b"fooba");
+        assert!(matches!(
+            codec.decode("Zm9vYmFy"),
+            Err(DecodeError::TooLarge {
+                needed: 6,
+                limit: 5
+            })
+        ));
+        assert!(Codec::new(Standard::new()).decode("Zm9vYmFy").is_ok());
+    }
+
+    #[test]
+    fn decode_into() {
+        let codec = Codec::new(UrlSafe::new()).lenient_whitespace(true);
+        let mut out = Vec::new();
+
+        assert_eq!(codec.decode_into("-_-_\n-_-_", &mut out).unwrap(), 6);
+        assert_eq!(out, [0xfb, 0xff, 0xbf, 0xfb, 0xff, 0xbf]);
+    }
+
+    #[test]
+    fn errors_index_input() {
+        let codec = Codec::new(Standard::new());
+
+        assert_eq!(codec.decode("\n Zm9v#").unwrap_err().position(), Some(6));
+        assert_eq!(
+            codec.decode("\n Zm9vY").unwrap_err().to_string(),
+            "Base64 input is truncated, it's 7 characters long but should be 8"
+        );
+    }
+
+    #[test]
+    fn suggests_other_alphabet() {
+        let urlsafe = Codec::new(UrlSafe::new()).encode_to_string([0xfb, 0xff, 0xbf]);
+        let standard = Codec::new(Standard::new()).encode_to_string([0xfb, 0xff, 0xbf]);
+        let wrong = |decoded| match decoded {
+            Err(DecodeError::WrongAlphabet {
+                char,
+                position,
+                suggestion,
+            }) => Some((char, position, suggestion)),
+            _ => None,
+        };
+
+        assert_eq!(
+            wrong(Codec::new(Standard::new()).decode(&urlsafe)),
+            Some(('-', 0, AlphabetKind::UrlSafe))
+        );
+        assert_eq!(
+            wrong(Codec::new(UrlSafe::new()).decode(&standard)),
+            Some(('+', 0, AlphabetKind::Standard))
+        );
+        assert_eq!(
+            Codec::new(Standard::new())
+                .decode(&format!(" Zm9v{urlsafe}"))
+                .unwrap_err()
+                .to_string(),
+            "Invalid Base64 character '-' at index 5 for the standard alphabet \
+             (input looks like URL safe base64)"
+        );
+    }
+
+    #[test]
+    fn no_suggestion_for_invalid_chars() {
+        let codec = Codec::new(Standard::new());
+
+        for b64 in ["Zm9v#", "Zm9v-#", "Zm-v+", "Z=9v-"] {
+            assert!(
+                matches!(codec.decode(b64), Err(DecodeError::Base64Error(_))),
+                "{b64}"
+            );
+        }
+    }
+
+    #[test]
+    fn send_sync_clone() {
+        fn assert_send_sync_clone<T: Send + Sync + Clone>() {}
+        assert_send_sync_clone::<Codec<Standard>>();
+
+        let codec = Codec::new(Standard::new()).padding(false);
+        assert_eq!(codec.clone().encode(b"f"), "Zg");
+    }
+}
@@ -0,0 +1,167 @@
+//! Interoperate with the [`base64`] crate, so code can move between
+//! it & baze64 one call site at a time
+//!
+//! ## Examples
+//! ```
+//! # use baze64::{Base64String, compat::EngineAlphabet};
+//! use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+//!
+//! let alphabet = EngineAlphabet::new(URL_SAFE_NO_PAD);
+//! let b64 = Base64String::encode_with([0xfb, 0xff], alphabet);
+//!
+//! assert_eq!(b64.to_string(), base64::Engine::encode(&URL_SAFE_NO_PAD, [0xfb, 0xff]));
+//! ```
+
+use base64::engine::{Config, Engine};
+
+use crate::{
+    alphabet::{Alphabet, Standard},
+    B64Error, Base64String,
+};
+
+/// An alphabet that encodes & decodes exactly like a [`base64`]
+/// [`Engine`]
+///
+/// The engine's symbols are found by encoding with it, & its padding
+/// comes from its config
+#[derive(Debug, Clone)]
+pub struct EngineAlphabet<E> {
+    engine: E,
+    encode_map: [char; 64],
+}
+
+impl<E> EngineAlphabet<E>
+where
+    E: Engine,
+{
+    /// Create an alphabet matching `engine`
+    pub fn new(engine: E) -> Self {
+        let mut encode_map = ['\0'; 64];
+        for (bits, symbol) in (0..).zip(encode_map.iter_mut()) {
+            // The first character of an encoded byte is its top 6 bits
+            let encoded = engine.encode([bits << 2]);
+            *symbol = encoded.chars().next().unwrap_or_default();
+        }
+
+        Self { engine, encode_map }
+    }
+
+    /// Get the engine this alphabet matches
+    pub fn engine(&self) -> &E {
+        &self.engine
+    }
+}
+
+impl<E> Alphabet for EngineAlphabet<E>
+where
+    E: Engine,
+{
+    fn padding(&self) -> Option<char> {
+        self.engine.config().encode_padding().then_some('=')
+    }
+
+    fn encode_bits(&self, bits: u8) -> char {
+        self.encode_map[bits as usize]
+    }
+
+    fn decode_char(&self, c: char) -> Result<u8, B64Error> {
+        self.encode_map
+            .iter()
+            .position(|&ch| ch == c)
+            .map_or_else(|| Err(B64Error::InvalidChar(c)), |i| Ok(i as u8))
+    }
+}
+
+/// Wrap the output of the `base64` crate's standard engine in a
+/// [`Base64String`]
+///
+/// # Examples
+/// ```
+/// # use baze64::compat::from_engine_output;
+/// use base64::{engine::general_purpose::STANDARD, Engine};
+///
+/// let b64 = from_engine_output(STANDARD.encode(b"foobar"))?;
+///
+/// assert_eq!(b64.decode()?, b"foobar");
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn from_engine_output(s: String) -> Result<Base64String<Standard>, B64Error> {
+    Base64String::from_encoded(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::engine::general_purpose::{STANDARD, STANDARD_NO_PAD, URL_SAFE, URL_SAFE_NO_PAD};
+
+    use super::*;
+    use crate::alphabet::{conformance, UrlSafe};
+    use pretty_assertions::assert_eq;
+
+    /// Payloads covering every tail length, along with every 6-bit
+    /// value in each position
+    fn payloads() -> impl Iterator<Item = Vec<u8>> {
+        let data = (0..=255).collect::<Vec<u8>>();
+        (0..=data.len()).map(move |size| data[..size].to_vec())
+    }
+
+    #[test]
+    fn standard_matches() {
+        for data in payloads() {
+            let b64 = Base64String::<Standard>::encode(&data);
+
+            assert_eq!(b64.to_string(), STANDARD.encode(&data));
+            assert_eq!(b64.without_padding(), STANDARD_NO_PAD.encode(&data));
+            assert_eq!(from_engine_output(STANDARD.encode(&data)).unwrap(), b64);
+        }
+    }
+
+    #[test]
+    fn url_safe_matches() {
+        for data in payloads() {
+            let b64 = Base64String::<UrlSafe>::encode(&data);
+
+            assert_eq!(b64.to_string(), URL_SAFE.encode(&data));
+            assert_eq!(b64.without_padding(), URL_SAFE_NO_PAD.encode(&data));
+        }
+    }
+
+    #[test]
+    fn engine_alphabets_match() {
+        fn check<E: Engine>(engine: E) {
+            let alphabet = EngineAlphabet::new(engine);
+            conformance::assert_alphabet_conformance(&alphabet);
+            for data in payloads() {
+                let b64 = Base64String::encode_with(&data, &alphabet);
+                let engine = alphabet.engine();
+                let encoded = engine.encode(&data);
+
+                assert_eq!(b64.to_string(), encoded);
+                assert_eq!(b64.decode().unwrap(), engine.decode(&encoded).unwrap());
+                let reparsed = Base64String::from_encoded_with(&encoded, &alphabet).unwrap();
+                assert_eq!(reparsed.decode().unwrap(), data);
+            }
+        }
+
+        check(STANDARD);
+        check(STANDARD_NO_PAD);
+        check(URL_SAFE);
+        check(URL_SAFE_NO_PAD);
+    }
+
+    #[test]
+    fn engine_alphabet_symbols() {
+        let alphabet = EngineAlphabet::new(URL_SAFE);
+
+        for bits in 0..64 {
+            assert_eq!(alphabet.encode_bits(bits), UrlSafe::new().encode_bits(bits));
+        }
+        assert_eq!(alphabet.decode_char('+'), Err(B64Error::InvalidChar('+')));
+    }
+
+    #[test]
+    fn into_engine_input() {
+        let b64 = Base64String::<Standard>::encode(b"fo");
+
+        assert_eq!(STANDARD.decode(b64.into_encoded()).unwrap(), b"fo");
+    }
+}
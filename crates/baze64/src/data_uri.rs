@@ -0,0 +1,342 @@
+//! Parse & produce `data:` URIs as defined in RFC 2397
+//!
+//! ## Examples
+//! ```
+//! # use baze64::data_uri::DataUri;
+//! let uri = DataUri::parse("data:text/plain;charset=UTF-8;base64,aGVsbG8=")?;
+//!
+//! assert_eq!(uri.media_type(), "text/plain");
+//! assert_eq!(uri.charset(), Some("UTF-8"));
+//! assert_eq!(uri.data().decode()?, b"hello");
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use core::fmt;
+
+use crate::{alphabet::Standard, B64Error, Base64String};
+
+/// The media type assumed when a data URI doesn't specify one
+pub const DEFAULT_MEDIA_TYPE: &str = "text/plain";
+/// The charset assumed when a data URI doesn't specify a media type
+pub const DEFAULT_CHARSET: &str = "US-ASCII";
+
+/// A `data:` URI with a base64 encoded payload
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataUri {
+    media_type: String,
+    charset: Option<String>,
+    data: Base64String<Standard>,
+}
+
+#[derive(Debug)]
+pub enum DataUriError {
+    MissingScheme,
+    MissingData,
+    NotBase64,
+    InvalidPercentEncoding,
+    Base64Error(B64Error),
+}
+
+impl fmt::Display for DataUriError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DataUriError::MissingScheme => write!(f, "Data URIs must start with `data:`"),
+            DataUriError::MissingData => {
+                write!(f, "Data URIs must separate their header & data with a `,`")
+            }
+            DataUriError::NotBase64 => write!(f, "Data URI isn't base64 encoded"),
+            DataUriError::InvalidPercentEncoding => {
+                write!(f, "Invalid percent encoding in data URI")
+            }
+            DataUriError::Base64Error(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for DataUriError {}
+
+impl From<B64Error> for DataUriError {
+    fn from(value: B64Error) -> Self {
+        DataUriError::Base64Error(value)
+    }
+}
+
+impl DataUri {
+    /// Create a [`DataUri`] encoding `bytes` with the given
+    /// `media_type`
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::data_uri::DataUri;
+    /// let uri = DataUri::new("image/png", [0x89, b'P', b'N', b'G']);
+    ///
+    /// assert_eq!(uri.to_string(), "data:image/png;base64,iVBORw==");
+    /// ```
+    pub fn new<S, B>(media_type: S, bytes: B) -> Self
+    where
+        S: ToString,
+        B: AsRef<[u8]>,
+    {
+        Self {
+            media_type: media_type.to_string(),
+            charset: None,
+            data: Base64String::encode(bytes),
+        }
+    }
+
+    /// Set the charset of the data
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::data_uri::DataUri;
+    /// let uri = DataUri::new("text/plain", "hi").with_charset("UTF-8");
+    ///
+    /// assert_eq!(uri.to_string(), "data:text/plain;charset=UTF-8;base64,aGk=");
+    /// ```
+    pub fn with_charset<S>(mut self, charset: S) -> Self
+    where
+        S: ToString,
+    {
+        self.charset = Some(charset.to_string());
+        self
+    }
+
+    /// Parse a base64 encoded `data:` URI
+    ///
+    /// A URI without a media type is given the default of
+    /// `text/plain;charset=US-ASCII`, & whitespace in the data is
+    /// ignored. URIs with percent encoded rather than base64 encoded
+    /// data are rejected with [`DataUriError::NotBase64`]
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::data_uri::DataUri;
+    /// let uri = DataUri::parse("data:;base64,aGk=")?;
+    ///
+    /// assert_eq!(uri.media_type(), "text/plain");
+    /// assert_eq!(uri.charset(), Some("US-ASCII"));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn parse(uri: &str) -> Result<Self, DataUriError> {
+        let rest = uri
+            .get(..5)
+            .filter(|scheme| scheme.eq_ignore_ascii_case("data:"))
+            .map(|_| &uri[5..])
+            .ok_or(DataUriError::MissingScheme)?;
+        let (header, data) = rest.split_once(',').ok_or(DataUriError::MissingData)?;
+
+        let mut params = header.split(';');
+        let media_type = params.next().unwrap_or_default().trim();
+        let mut charset = None;
+        let mut other_params = vec![];
+        let mut base64 = false;
+        for param in params {
+            let param = param.trim();
+            if param.eq_ignore_ascii_case("base64") {
+                base64 = true;
+            } else if param
+                .get(..8)
+                .is_some_and(|key| key.eq_ignore_ascii_case("charset="))
+            {
+                charset = Some(param[8..].to_string());
+            } else if !param.is_empty() {
+                other_params.push(param);
+            }
+        }
+
+        if !base64 {
+            return Err(DataUriError::NotBase64);
+        }
+
+        let media_type = if media_type.is_empty() {
+            charset.get_or_insert_with(|| DEFAULT_CHARSET.to_string());
+            DEFAULT_MEDIA_TYPE.to_string()
+        } else {
+            media_type.to_string()
+        };
+        let media_type = [media_type.as_str()]
+            .into_iter()
+            .chain(other_params)
+            .collect::<Vec<_>>()
+            .join(";");
+
+        let data = percent_decode(data)?
+            .chars()
+            .filter(|c| !c.is_ascii_whitespace())
+            .collect::<String>();
+
+        Ok(Self {
+            media_type,
+            charset,
+            data: Base64String::from_encoded(data)?,
+        })
+    }
+
+    /// The media type of the data, including any parameters
+    /// other than its charset
+    pub fn media_type(&self) -> &str {
+        &self.media_type
+    }
+
+    /// The charset of the data, if one was given
+    pub fn charset(&self) -> Option<&str> {
+        self.charset.as_deref()
+    }
+
+    /// The base64 encoded data
+    pub fn data(&self) -> &Base64String<Standard> {
+        &self.data
+    }
+}
+
+impl fmt::Display for DataUri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "data:{}", self.media_type)?;
+        if let Some(charset) = &self.charset {
+            write!(f, ";charset={charset}")?;
+        }
+        write!(f, ";base64,{}", self.data)
+    }
+}
+
+impl core::str::FromStr for DataUri {
+    type Err = DataUriError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// Decode any `%XX` escapes in `s`
+fn percent_decode(s: &str) -> Result<String, DataUriError> {
+    if !s.contains('%') {
+        return Ok(s.to_string());
+    }
+
+    let mut bytes = s.bytes();
+    let mut decoded = vec![];
+    while let Some(b) = bytes.next() {
+        if b == b'%' {
+            let hex = [bytes.next(), bytes.next()];
+            let [Some(hi), Some(lo)] = hex else {
+                return Err(DataUriError::InvalidPercentEncoding);
+            };
+            let byte = core::str::from_utf8(&[hi, lo])
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .ok_or(DataUriError::InvalidPercentEncoding)?;
+            decoded.push(byte);
+        } else {
+            decoded.push(b);
+        }
+    }
+
+    String::from_utf8(decoded).map_err(|_| DataUriError::InvalidPercentEncoding)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    /// A 1x1 transparent PNG
+    const PNG_URI: &str = "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNkYPhfDwAChwGA60e6kgAAAABJRU5ErkJggg==";
+
+    #[test]
+    fn png_roundtrip() {
+        let uri = DataUri::parse(PNG_URI).unwrap();
+        let png = uri.data().decode().unwrap();
+
+        assert_eq!(uri.media_type(), "image/png");
+        assert_eq!(uri.charset(), None);
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        assert_eq!(DataUri::new("image/png", &png).to_string(), PNG_URI);
+    }
+
+    #[test]
+    fn utf8_text_roundtrip() {
+        let text = "Grüße, 世界 🌍";
+        let uri = DataUri::new("text/plain", text).with_charset("UTF-8");
+        let parsed = DataUri::parse(&uri.to_string()).unwrap();
+
+        assert_eq!(parsed, uri);
+        assert_eq!(parsed.charset(), Some("UTF-8"));
+        assert_eq!(parsed.data().decode_to_string().unwrap(), text);
+    }
+
+    #[test]
+    fn default_media_type() {
+        let uri = DataUri::parse("data:;base64,aGk=").unwrap();
+
+        assert_eq!(uri.media_type(), DEFAULT_MEDIA_TYPE);
+        assert_eq!(uri.charset(), Some(DEFAULT_CHARSET));
+    }
+
+    #[test]
+    fn charset_without_media_type() {
+        let uri = DataUri::parse("data:;charset=UTF-8;base64,aGk=").unwrap();
+
+        assert_eq!(uri.media_type(), DEFAULT_MEDIA_TYPE);
+        assert_eq!(uri.charset(), Some("UTF-8"));
+    }
+
+    #[test]
+    fn other_params_kept() {
+        let uri = DataUri::parse("data:image/svg+xml;name=logo.svg;base64,PHN2Zy8+").unwrap();
+
+        assert_eq!(uri.media_type(), "image/svg+xml;name=logo.svg");
+        assert_eq!(
+            uri.to_string(),
+            "data:image/svg+xml;name=logo.svg;base64,PHN2Zy8+"
+        );
+    }
+
+    #[test]
+    fn whitespace_in_data() {
+        let uri = DataUri::parse("data:text/plain;base64,Zm9v\r\n YmFy").unwrap();
+
+        assert_eq!(uri.data().decode().unwrap(), b"foobar");
+    }
+
+    #[test]
+    fn percent_encoded_data() {
+        let uri = DataUri::parse("data:text/plain;base64,Zm8%3D").unwrap();
+
+        assert_eq!(uri.data().decode().unwrap(), b"fo");
+    }
+
+    #[test]
+    fn not_base64() {
+        assert!(matches!(
+            DataUri::parse("data:text/plain,hello%20world"),
+            Err(DataUriError::NotBase64)
+        ));
+    }
+
+    #[test]
+    fn missing_parts() {
+        assert!(matches!(
+            DataUri::parse("text/plain;base64,aGk="),
+            Err(DataUriError::MissingScheme)
+        ));
+        assert!(matches!(
+            DataUri::parse("data:text/plain;base64"),
+            Err(DataUriError::MissingData)
+        ));
+        assert!(matches!(
+            DataUri::parse("data:;base64,Zm8%3"),
+            Err(DataUriError::InvalidPercentEncoding)
+        ));
+    }
+
+    #[test]
+    fn invalid_base64() {
+        assert!(matches!(
+            DataUri::parse("data:;base64,Zm9v#mFy"),
+            Err(DataUriError::Base64Error(B64Error::InvalidCharAt {
+                char: '#',
+                index: 4
+            }))
+        ));
+    }
+}
@@ -0,0 +1,157 @@
+use crate::{alphabet::Alphabet, Base64String, DecodeError};
+
+/// The most bytes kept either side of a difference in
+/// [`Divergence`]'s context
+pub const DIFF_CONTEXT: usize = 8;
+
+/// Where two [`Base64String`]s first decode to different bytes,
+/// as found by [`diff_decoded`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Divergence {
+    /// The offset of the first byte that differs, or the length of
+    /// the shorter data if it's the start of the longer
+    pub offset: usize,
+    /// How many bytes the first string decodes to
+    pub len_a: usize,
+    /// How many bytes the second string decodes to
+    pub len_b: usize,
+    /// The offset the context starts at
+    pub context_start: usize,
+    /// Up to [`DIFF_CONTEXT`] bytes either side of `offset` from
+    /// the first string's data
+    pub context_a: Vec<u8>,
+    /// Up to [`DIFF_CONTEXT`] bytes either side of `offset` from
+    /// the second string's data
+    pub context_b: Vec<u8>,
+}
+
+/// Compare what `a` & `b` decode to, finding where they first
+/// differ
+///
+/// The two strings can use different alphabets & padding styles,
+/// as only the decoded bytes are compared. Returns [`None`] if
+/// they decode to the same bytes
+///
+/// # Examples
+/// ```
+/// # use baze64::{diff_decoded, Base64String, alphabet::{Standard, UrlSafe}};
+/// let a = Base64String::<Standard>::from_encoded("+/+/")?;
+/// let b = Base64String::<UrlSafe>::from_encoded("-_-_")?;
+/// assert_eq!(diff_decoded(&a, &b)?, None);
+///
+/// let c = Base64String::<UrlSafe>::from_encoded("-_-A")?;
+/// let divergence = diff_decoded(&a, &c)?.unwrap();
+/// assert_eq!(divergence.offset, 2);
+/// assert_eq!(divergence.context_a, [0xfb, 0xff, 0xbf]);
+/// assert_eq!(divergence.context_b, [0xfb, 0xff, 0x80]);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn diff_decoded<A, B>(
+    a: &Base64String<A>,
+    b: &Base64String<B>,
+) -> Result<Option<Divergence>, DecodeError>
+where
+    A: Alphabet,
+    B: Alphabet,
+{
+    Ok(diff_bytes(&a.decode()?, &b.decode()?))
+}
+
+/// Find where `a` & `b` first differ
+fn diff_bytes(a: &[u8], b: &[u8]) -> Option<Divergence> {
+    let offset = a
+        .iter()
+        .zip(b)
+        .position(|(x, y)| x != y)
+        .unwrap_or(a.len().min(b.len()));
+    if offset == a.len() && offset == b.len() {
+        return None;
+    }
+
+    let context_start = offset.saturating_sub(DIFF_CONTEXT);
+    let context = |data: &[u8]| {
+        let end = data.len().min(offset + DIFF_CONTEXT + 1);
+        data[context_start.min(end)..end].to_vec()
+    };
+
+    Some(Divergence {
+        offset,
+        len_a: a.len(),
+        len_b: b.len(),
+        context_start,
+        context_a: context(a),
+        context_b: context(b),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alphabet::{Standard, UrlSafe};
+
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn identical_across_alphabets() {
+        let data = (0..=255).collect::<Vec<u8>>();
+        let a = Base64String::<Standard>::encode(&data);
+        let url_safe = a.without_padding().replace('+', "-").replace('/', "_");
+        let b = Base64String::<UrlSafe>::from_encoded(url_safe).unwrap();
+
+        assert_eq!(diff_decoded(&a, &b).unwrap(), None);
+    }
+
+    #[test]
+    fn empty_identical() {
+        assert_eq!(diff_bytes(b"", b""), None);
+    }
+
+    #[test]
+    fn first_difference() {
+        let a = (0..32).collect::<Vec<u8>>();
+        let mut b = a.clone();
+        b[20] = 0xff;
+        b[25] = 0xff;
+
+        assert_eq!(
+            diff_bytes(&a, &b),
+            Some(Divergence {
+                offset: 20,
+                len_a: 32,
+                len_b: 32,
+                context_start: 12,
+                context_a: (12..=28).collect(),
+                context_b: [&a[12..20], &[0xff], &a[21..25], &[0xff], &a[26..29]].concat(),
+            })
+        );
+    }
+
+    #[test]
+    fn difference_near_start() {
+        let divergence = diff_bytes(b"abc", b"xbc").unwrap();
+
+        assert_eq!(divergence.offset, 0);
+        assert_eq!(divergence.context_start, 0);
+        assert_eq!(divergence.context_a, b"abc");
+        assert_eq!(divergence.context_b, b"xbc");
+    }
+
+    #[test]
+    fn prefix_differs_at_shorter_len() {
+        let divergence = diff_bytes(b"foo", b"foobar").unwrap();
+
+        assert_eq!(divergence.offset, 3);
+        assert_eq!((divergence.len_a, divergence.len_b), (3, 6));
+        assert_eq!(divergence.context_a, b"foo");
+        assert_eq!(divergence.context_b, b"foobar");
+    }
+
+    #[test]
+    fn empty_against_data() {
+        let divergence = diff_bytes(b"", b"a").unwrap();
+
+        assert_eq!(divergence.offset, 0);
+        assert_eq!(divergence.context_a, b"");
+        assert_eq!(divergence.context_b, b"a");
+    }
+}
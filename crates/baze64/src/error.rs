@@ -0,0 +1,937 @@
+use std::{error::Error, fmt, io, string::FromUtf8Error};
+
+use crate::alphabet::{Alphabet, AlphabetKind};
+
+/// An error from encoding or decoding base64
+///
+/// New variants may be added in future, so use [`B64Error::kind`]
+/// rather than matching exhaustively where possible
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum B64Error {
+    /// A character isn't part of the alphabet, as reported by
+    /// [`Alphabet::decode_char`](crate::alphabet::Alphabet::decode_char)
+    InvalidChar(char),
+    /// A character at a known position in the input isn't part
+    /// of the alphabet
+    InvalidCharAt { char: char, index: usize },
+    /// The input ends part way through a character quad that can't
+    /// encode any bytes
+    Truncated { expected: usize, actual: usize },
+    /// The alphabet decoded a character to a value that doesn't fit
+    /// in 6 bits, breaking the contract of
+    /// [`Alphabet::decode_char`](crate::alphabet::Alphabet::decode_char).
+    /// Only returned in debug builds or with the `strict-alphabet-checks`
+    /// feature
+    DecodedValueOOB { char: char, value: u8 },
+    /// The last character has bits set that aren't part of the data,
+    /// so the input isn't how any data would be encoded. Only checked
+    /// by a [`Codec`](crate::Codec) with
+    /// [`strict_tail`](crate::Codec::strict_tail) on
+    TrailingBits { char: char, index: usize },
+    /// A character at `index` isn't part of the built-in alphabet
+    /// being parsed with, but the whole input is valid in the other
+    /// one, `suggestion`, so it was probably encoded with that.
+    /// Converts into [`DecodeError::WrongAlphabet`]
+    WrongAlphabet {
+        char: char,
+        index: usize,
+        suggestion: AlphabetKind,
+    },
+}
+
+/// The error returned when decoding fails
+///
+/// New variants may be added in future, so use [`DecodeError::kind`]
+/// rather than matching exhaustively where possible
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum DecodeError {
+    Base64Error(B64Error),
+    WriteError(io::Error),
+    InvalidUtf8(FromUtf8Error),
+    /// The decoded data would be longer than the limit given
+    TooLarge {
+        needed: usize,
+        limit: usize,
+    },
+    /// A range of the decoded data was asked for that isn't within it
+    OutOfBounds {
+        start: usize,
+        end: usize,
+        len: usize,
+    },
+    /// The decoded data isn't an integer of `bits` bits as encoded by
+    /// [`Base64String::encode_u64`](crate::Base64String::encode_u64)
+    /// or [`Base64String::encode_u128`](crate::Base64String::encode_u128),
+    /// because it's empty, too long, or has leading zero bytes
+    InvalidInteger {
+        bits: u32,
+        len: usize,
+    },
+    /// The decoded data isn't the length it needs to be, as checked
+    /// by [`Base64String::decode_array`](crate::Base64String::decode_array),
+    /// or is shorter than the `expected` length of a checksum, as
+    /// checked by [`Base64String::decode_checked`](crate::Base64String::decode_checked)
+    WrongLength {
+        expected: usize,
+        actual: usize,
+    },
+    /// The decoded data isn't valid UTF-16, because it has an odd
+    /// number of bytes or an unpaired surrogate starting at `offset`
+    InvalidUtf16 {
+        offset: usize,
+    },
+    /// The checksum on the end of the decoded data, `expected`, isn't
+    /// the `actual` checksum of the rest of it, as checked by
+    /// [`Base64String::decode_checked`](crate::Base64String::decode_checked)
+    ChecksumMismatch {
+        expected: u32,
+        actual: u32,
+    },
+    /// The decoded data isn't a valid OS string on this platform, as
+    /// checked by [`Base64String::decode_to_os_string`](crate::Base64String::decode_to_os_string),
+    /// starting at byte `offset`
+    InvalidOsString {
+        offset: usize,
+    },
+    /// A character at `position` isn't part of the built-in alphabet
+    /// being decoded with, but the whole input is valid in the other
+    /// one, `suggestion`, so it was probably encoded with that
+    WrongAlphabet {
+        char: char,
+        position: usize,
+        suggestion: AlphabetKind,
+    },
+}
+
+/// The error returned when encoding from a reader fails
+///
+/// New variants may be added in future, so use [`EncodeReaderError::kind`]
+/// rather than matching exhaustively where possible
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum EncodeReaderError {
+    Base64Error(B64Error),
+    ReadError(io::Error),
+}
+
+/// The error returned by [`Base64String::decode_into`](crate::Base64String::decode_into),
+/// carrying how many bytes had been written before decoding failed
+#[derive(Debug)]
+pub struct DecodeIntoError {
+    /// The number of bytes successfully written to the output
+    pub bytes_written: usize,
+    /// The error that stopped decoding
+    pub error: DecodeError,
+}
+
+/// The error returned when parsing hex text fails
+///
+/// New variants may be added in future, so use [`HexError::kind`]
+/// rather than matching exhaustively where possible
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum HexError {
+    /// A character isn't a hex digit, whitespace, or part of a
+    /// leading `0x`
+    InvalidChar { char: char, index: usize },
+}
+
+/// The error returned when reading base64 back from YAML with
+/// [`Base64String::from_yaml_safe`](crate::Base64String::from_yaml_safe)
+///
+/// New variants may be added in future, so use [`YamlError::kind`]
+/// rather than matching exhaustively where possible
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum YamlError {
+    /// The text isn't a double-quoted scalar
+    NotQuoted,
+    /// The escape sequence starting at `index` isn't one that's
+    /// understood, or doesn't give a valid character
+    InvalidEscape {
+        index: usize,
+    },
+    Base64Error(B64Error),
+}
+
+/// The error returned by [`Base64String::try_transform`](crate::Base64String::try_transform),
+/// from either decoding or the transformation itself
+#[derive(Debug)]
+pub enum TransformError<E> {
+    /// Decoding the original content failed
+    Decode(DecodeError),
+    /// The transformation returned an error
+    Transform(E),
+}
+
+/// A stable, broad category of error, so errors can be handled
+/// without matching every variant
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The input contained a character outside the alphabet
+    InvalidChar,
+    /// The input ended part way through a quad
+    Truncated,
+    /// Reading the input or writing the output failed
+    Io,
+    /// The decoded output wasn't valid UTF-8
+    InvalidUtf8,
+    /// The decoded output wasn't valid UTF-16
+    InvalidUtf16,
+    /// Hex input contained a character that isn't a hex digit
+    InvalidHex,
+    /// The decoded data would be over a size limit
+    TooLarge,
+    /// A range of the decoded data was outside it
+    OutOfBounds,
+    /// The decoded data wasn't an encoded integer
+    InvalidInteger,
+    /// The alphabet decoded a character to a value over 6 bits
+    DecodedValueOOB,
+    /// The decoded data wasn't the length it needed to be
+    WrongLength,
+    /// The last character had bits set after the end of the data
+    TrailingBits,
+    /// Quoted text wasn't quoted or escaped properly
+    InvalidQuoting,
+    /// The decoded data didn't match its checksum
+    ChecksumMismatch,
+    /// The decoded data wasn't a valid OS string
+    InvalidOsString,
+}
+
+impl B64Error {
+    /// The category of error this is
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, ErrorKind, alphabet::Standard};
+    /// let err = Base64String::<Standard>::from_encoded("Zm9v#").unwrap_err();
+    ///
+    /// assert_eq!(err.kind(), ErrorKind::InvalidChar);
+    /// assert_eq!(err.position(), Some(4));
+    /// ```
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            B64Error::InvalidChar(_)
+            | B64Error::InvalidCharAt { .. }
+            | B64Error::WrongAlphabet { .. } => ErrorKind::InvalidChar,
+            B64Error::Truncated { .. } => ErrorKind::Truncated,
+            B64Error::DecodedValueOOB { .. } => ErrorKind::DecodedValueOOB,
+            B64Error::TrailingBits { .. } => ErrorKind::TrailingBits,
+        }
+    }
+
+    /// The index of the character in the input that caused
+    /// the error, or where the input was cut short for
+    /// [`B64Error::Truncated`], if known
+    pub fn position(&self) -> Option<usize> {
+        match self {
+            B64Error::InvalidCharAt { index, .. }
+            | B64Error::TrailingBits { index, .. }
+            | B64Error::WrongAlphabet { index, .. } => Some(*index),
+            B64Error::Truncated { actual, .. } => Some(*actual),
+            B64Error::InvalidChar(_) | B64Error::DecodedValueOOB { .. } => None,
+        }
+    }
+
+    /// The invalid character that caused the error, if there was one
+    pub fn invalid_char(&self) -> Option<char> {
+        match self {
+            B64Error::InvalidChar(c)
+            | B64Error::InvalidCharAt { char: c, .. }
+            | B64Error::TrailingBits { char: c, .. }
+            | B64Error::WrongAlphabet { char: c, .. } => Some(*c),
+            B64Error::Truncated { .. } | B64Error::DecodedValueOOB { .. } => None,
+        }
+    }
+
+    /// Shift the position reported by `self` by `offset`, for
+    /// errors found in a slice of a larger input
+    pub(crate) fn offset_by(self, offset: usize) -> Self {
+        match self {
+            B64Error::InvalidCharAt { char, index } => B64Error::InvalidCharAt {
+                char,
+                index: index + offset,
+            },
+            B64Error::Truncated { expected, actual } => B64Error::Truncated {
+                expected: expected + offset,
+                actual: actual + offset,
+            },
+            B64Error::TrailingBits { char, index } => B64Error::TrailingBits {
+                char,
+                index: index + offset,
+            },
+            B64Error::WrongAlphabet {
+                char,
+                index,
+                suggestion,
+            } => B64Error::WrongAlphabet {
+                char,
+                index: index + offset,
+                suggestion,
+            },
+            e => e,
+        }
+    }
+
+    /// Turn `self`, from decoding `b64` with `alphabet`, into
+    /// [`B64Error::WrongAlphabet`] if it's an invalid character
+    /// that's part of the other built-in alphabet, & the rest of `b64`
+    /// is valid in it too
+    ///
+    /// Anything else, including errors from alphabets that aren't
+    /// built in, is returned as it is
+    pub(crate) fn suggest_alphabet<A>(self, b64: &str, alphabet: &A) -> Self
+    where
+        A: Alphabet,
+    {
+        let B64Error::InvalidCharAt { char, index } = self else {
+            return self;
+        };
+        // Padding is only allowed at the end, where it's trimmed
+        let fits = |other: &AlphabetKind| {
+            let data = other.padding().map_or(b64, |p| b64.trim_end_matches(p));
+            other.decode_char(char).is_ok() && data.chars().all(|c| other.decode_char(c).is_ok())
+        };
+        let suggestion = AlphabetKind::of(alphabet)
+            .map(AlphabetKind::other)
+            .filter(fits);
+
+        match suggestion {
+            Some(suggestion) => B64Error::WrongAlphabet {
+                char,
+                index,
+                suggestion,
+            },
+            None => self,
+        }
+    }
+}
+
+impl DecodeError {
+    /// The category of error this is
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, ErrorKind, alphabet::Standard};
+    /// let b64 = Base64String::<Standard>::encode([0xff]);
+    /// let err = b64.decode_to_string().unwrap_err();
+    ///
+    /// assert_eq!(err.kind(), ErrorKind::InvalidUtf8);
+    /// ```
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            DecodeError::Base64Error(e) => e.kind(),
+            DecodeError::WriteError(_) => ErrorKind::Io,
+            DecodeError::InvalidUtf8(_) => ErrorKind::InvalidUtf8,
+            DecodeError::TooLarge { .. } => ErrorKind::TooLarge,
+            DecodeError::OutOfBounds { .. } => ErrorKind::OutOfBounds,
+            DecodeError::InvalidInteger { .. } => ErrorKind::InvalidInteger,
+            DecodeError::WrongLength { .. } => ErrorKind::WrongLength,
+            DecodeError::InvalidUtf16 { .. } => ErrorKind::InvalidUtf16,
+            DecodeError::ChecksumMismatch { .. } => ErrorKind::ChecksumMismatch,
+            DecodeError::InvalidOsString { .. } => ErrorKind::InvalidOsString,
+            DecodeError::WrongAlphabet { .. } => ErrorKind::InvalidChar,
+        }
+    }
+
+    /// The index of the character in the input that caused
+    /// the error, if known
+    pub fn position(&self) -> Option<usize> {
+        match self {
+            DecodeError::Base64Error(e) => e.position(),
+            DecodeError::WrongAlphabet { position, .. } => Some(*position),
+            DecodeError::WriteError(_)
+            | DecodeError::InvalidUtf8(_)
+            | DecodeError::TooLarge { .. }
+            | DecodeError::OutOfBounds { .. }
+            | DecodeError::InvalidInteger { .. }
+            | DecodeError::WrongLength { .. }
+            | DecodeError::InvalidUtf16 { .. }
+            | DecodeError::ChecksumMismatch { .. }
+            | DecodeError::InvalidOsString { .. } => None,
+        }
+    }
+
+    /// Copy `self`, giving an I/O error the same kind & message as
+    /// the original, as [`io::Error`] can't be cloned
+    pub(crate) fn duplicate(&self) -> Self {
+        match self {
+            DecodeError::Base64Error(e) => DecodeError::Base64Error(e.clone()),
+            DecodeError::WriteError(e) => {
+                DecodeError::WriteError(io::Error::new(e.kind(), e.to_string()))
+            }
+            DecodeError::InvalidUtf8(e) => DecodeError::InvalidUtf8(e.clone()),
+            &DecodeError::TooLarge { needed, limit } => DecodeError::TooLarge { needed, limit },
+            &DecodeError::OutOfBounds { start, end, len } => {
+                DecodeError::OutOfBounds { start, end, len }
+            }
+            &DecodeError::InvalidInteger { bits, len } => DecodeError::InvalidInteger { bits, len },
+            &DecodeError::WrongLength { expected, actual } => {
+                DecodeError::WrongLength { expected, actual }
+            }
+            &DecodeError::InvalidUtf16 { offset } => DecodeError::InvalidUtf16 { offset },
+            &DecodeError::ChecksumMismatch { expected, actual } => {
+                DecodeError::ChecksumMismatch { expected, actual }
+            }
+            &DecodeError::InvalidOsString { offset } => DecodeError::InvalidOsString { offset },
+            &DecodeError::WrongAlphabet {
+                char,
+                position,
+                suggestion,
+            } => DecodeError::WrongAlphabet {
+                char,
+                position,
+                suggestion,
+            },
+        }
+    }
+
+    /// Turn `error`, from decoding `b64` with `alphabet`, into
+    /// [`DecodeError::WrongAlphabet`] as described by
+    /// [`B64Error::suggest_alphabet`]
+    pub(crate) fn suggest_alphabet<A>(error: B64Error, b64: &str, alphabet: &A) -> Self
+    where
+        A: Alphabet,
+    {
+        error.suggest_alphabet(b64, alphabet).into()
+    }
+}
+
+impl EncodeReaderError {
+    /// The category of error this is
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            EncodeReaderError::Base64Error(e) => e.kind(),
+            EncodeReaderError::ReadError(_) => ErrorKind::Io,
+        }
+    }
+}
+
+impl DecodeIntoError {
+    pub(crate) fn new(error: DecodeError, bytes_written: usize) -> Self {
+        Self {
+            bytes_written,
+            error,
+        }
+    }
+
+    /// The category of error this is
+    pub fn kind(&self) -> ErrorKind {
+        self.error.kind()
+    }
+}
+
+impl HexError {
+    /// The category of error this is
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            HexError::InvalidChar { .. } => ErrorKind::InvalidHex,
+        }
+    }
+
+    /// The index of the invalid character in the input
+    pub fn position(&self) -> Option<usize> {
+        match self {
+            HexError::InvalidChar { index, .. } => Some(*index),
+        }
+    }
+}
+
+impl YamlError {
+    /// The category of error this is
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            YamlError::NotQuoted | YamlError::InvalidEscape { .. } => ErrorKind::InvalidQuoting,
+            YamlError::Base64Error(e) => e.kind(),
+        }
+    }
+}
+
+impl From<DecodeIntoError> for DecodeError {
+    fn from(value: DecodeIntoError) -> Self {
+        value.error
+    }
+}
+
+impl fmt::Display for B64Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            B64Error::InvalidChar(c) => write!(f, "Invalid Base64 character {}", Quoted(*c)),
+            B64Error::InvalidCharAt { char, index } => {
+                write!(
+                    f,
+                    "Invalid Base64 character {} at index {index}",
+                    Quoted(*char)
+                )
+            }
+            B64Error::Truncated { expected, actual } => write!(
+                f,
+                "Base64 input is truncated, it's {actual} characters long but should be {expected}"
+            ),
+            B64Error::DecodedValueOOB { char, value } => write!(
+                f,
+                "Alphabet decoded {} to {value}, which doesn't fit in 6 bits",
+                Quoted(*char)
+            ),
+            B64Error::TrailingBits { char, index } => write!(
+                f,
+                "Base64 character {} at index {index} has bits set after the end of the data",
+                Quoted(*char)
+            ),
+            B64Error::WrongAlphabet {
+                char,
+                index,
+                suggestion,
+            } => wrong_alphabet(f, *char, *index, *suggestion),
+        }
+    }
+}
+
+impl Error for B64Error {}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::Base64Error(e) => e.fmt(f),
+            DecodeError::WriteError(e) => e.fmt(f),
+            DecodeError::InvalidUtf8(e) => e.fmt(f),
+            DecodeError::TooLarge { needed, limit } => write!(
+                f,
+                "Decoded data would be {needed} bytes, over the limit of {limit}"
+            ),
+            DecodeError::OutOfBounds { start, end, len } => write!(
+                f,
+                "Range {start}..{end} is out of bounds for {len} bytes of decoded data"
+            ),
+            DecodeError::InvalidInteger { bits, len } => write!(
+                f,
+                "Decoded data isn't a minimal big-endian {bits}-bit integer, it's {len} bytes"
+            ),
+            DecodeError::WrongLength { expected, actual } => write!(
+                f,
+                "Decoded data is {actual} bytes, but should be {expected}"
+            ),
+            DecodeError::InvalidUtf16 { offset } => {
+                write!(f, "Decoded data isn't valid UTF-16, at byte {offset}")
+            }
+            DecodeError::ChecksumMismatch { expected, actual } => write!(
+                f,
+                "Decoded data has checksum {actual:08x}, but should have {expected:08x}"
+            ),
+            DecodeError::InvalidOsString { offset } => write!(
+                f,
+                "Decoded data isn't a valid OS string on this platform, at byte {offset}"
+            ),
+            DecodeError::WrongAlphabet {
+                char,
+                position,
+                suggestion,
+            } => wrong_alphabet(f, *char, *position, *suggestion),
+        }
+    }
+}
+
+/// Describe `char` at `index` being invalid for the other alphabet
+/// to `suggestion`
+fn wrong_alphabet(
+    f: &mut fmt::Formatter<'_>,
+    char: char,
+    index: usize,
+    suggestion: AlphabetKind,
+) -> fmt::Result {
+    write!(
+        f,
+        "Invalid Base64 character {} at index {index} for the {} alphabet \
+         (input looks like {} base64)",
+        Quoted(char),
+        describe(suggestion.other()),
+        describe(suggestion)
+    )
+}
+
+/// How a built-in alphabet is named in error messages
+fn describe(kind: AlphabetKind) -> &'static str {
+    match kind {
+        AlphabetKind::Standard => "standard",
+        AlphabetKind::UrlSafe => "URL safe",
+    }
+}
+
+/// A character quoted for an error message. Anything but printable
+/// ASCII is escaped, so it can't be invisible or break the line
+struct Quoted(char);
+
+impl fmt::Display for Quoted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let c = self.0;
+        if c == ' ' || c.is_ascii_graphic() {
+            return write!(f, "'{c}'");
+        }
+
+        write!(f, "'\\u{{{:x}}}'", u32::from(c))?;
+        match char_name(c) {
+            Some(name) => write!(f, " ({name})"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The name of a character that's hard to recognise from its escape
+fn char_name(c: char) -> Option<&'static str> {
+    Some(match c {
+        '\0' => "null",
+        '\t' => "tab",
+        '\n' => "line feed",
+        '\u{b}' => "vertical tab",
+        '\u{c}' => "form feed",
+        '\r' => "carriage return",
+        '\u{1b}' => "escape",
+        '\u{7f}' => "delete",
+        '\u{85}' => "next line",
+        '\u{a0}' => "non-breaking space",
+        '\u{ad}' => "soft hyphen",
+        '\u{2002}' => "en space",
+        '\u{2003}' => "em space",
+        '\u{2009}' => "thin space",
+        '\u{200a}' => "hair space",
+        '\u{200b}' => "zero width space",
+        '\u{200c}' => "zero width non-joiner",
+        '\u{200d}' => "zero width joiner",
+        '\u{2028}' => "line separator",
+        '\u{2029}' => "paragraph separator",
+        '\u{202f}' => "narrow non-breaking space",
+        '\u{3000}' => "ideographic space",
+        '\u{feff}' => "byte order mark",
+        _ => return None,
+    })
+}
+
+impl Error for DecodeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        // Wrapped errors are shown as themselves, so skip straight
+        // to what caused them
+        match self {
+            DecodeError::Base64Error(e) => e.source(),
+            DecodeError::WriteError(e) => e.source(),
+            DecodeError::InvalidUtf8(e) => e.source(),
+            _ => None,
+        }
+    }
+}
+
+impl From<B64Error> for DecodeError {
+    fn from(value: B64Error) -> Self {
+        // Keep a single variant to match on for a suggested alphabet
+        match value {
+            B64Error::WrongAlphabet {
+                char,
+                index,
+                suggestion,
+            } => DecodeError::WrongAlphabet {
+                char,
+                position: index,
+                suggestion,
+            },
+            e => DecodeError::Base64Error(e),
+        }
+    }
+}
+
+impl From<io::Error> for DecodeError {
+    fn from(value: io::Error) -> Self {
+        DecodeError::WriteError(value)
+    }
+}
+
+impl From<FromUtf8Error> for DecodeError {
+    fn from(value: FromUtf8Error) -> Self {
+        DecodeError::InvalidUtf8(value)
+    }
+}
+
+impl fmt::Display for EncodeReaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EncodeReaderError::Base64Error(e) => e.fmt(f),
+            EncodeReaderError::ReadError(e) => e.fmt(f),
+        }
+    }
+}
+
+impl Error for EncodeReaderError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            EncodeReaderError::Base64Error(e) => e.source(),
+            EncodeReaderError::ReadError(e) => e.source(),
+        }
+    }
+}
+
+impl From<B64Error> for EncodeReaderError {
+    fn from(value: B64Error) -> Self {
+        EncodeReaderError::Base64Error(value)
+    }
+}
+
+impl From<io::Error> for EncodeReaderError {
+    fn from(value: io::Error) -> Self {
+        EncodeReaderError::ReadError(value)
+    }
+}
+
+impl fmt::Display for DecodeIntoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({} bytes written before failing)",
+            self.error, self.bytes_written
+        )
+    }
+}
+
+impl Error for DecodeIntoError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl fmt::Display for HexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HexError::InvalidChar { char, index } => {
+                write!(
+                    f,
+                    "Invalid hex character {} at index {index}",
+                    Quoted(*char)
+                )
+            }
+        }
+    }
+}
+
+impl Error for HexError {}
+
+impl fmt::Display for YamlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            YamlError::NotQuoted => write!(f, "YAML text isn't a double-quoted scalar"),
+            YamlError::InvalidEscape { index } => {
+                write!(f, "Invalid escape sequence at index {index} in YAML text")
+            }
+            YamlError::Base64Error(e) => e.fmt(f),
+        }
+    }
+}
+
+impl Error for YamlError {}
+
+impl From<B64Error> for YamlError {
+    fn from(value: B64Error) -> Self {
+        YamlError::Base64Error(value)
+    }
+}
+
+impl<E> fmt::Display for TransformError<E>
+where
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransformError::Decode(e) => e.fmt(f),
+            TransformError::Transform(e) => e.fmt(f),
+        }
+    }
+}
+
+impl<E> Error for TransformError<E>
+where
+    E: Error,
+{
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            TransformError::Decode(e) => e.source(),
+            TransformError::Transform(e) => e.source(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn b64_error_kinds() {
+        assert_eq!(B64Error::InvalidChar('#').kind(), ErrorKind::InvalidChar);
+        assert_eq!(
+            B64Error::InvalidCharAt {
+                char: '#',
+                index: 3
+            }
+            .kind(),
+            ErrorKind::InvalidChar
+        );
+        assert_eq!(
+            B64Error::Truncated {
+                expected: 6,
+                actual: 5
+            }
+            .kind(),
+            ErrorKind::Truncated
+        );
+    }
+
+    #[test]
+    fn b64_error_positions() {
+        assert_eq!(B64Error::InvalidChar('#').position(), None);
+        assert_eq!(
+            B64Error::InvalidCharAt {
+                char: '#',
+                index: 3
+            }
+            .position(),
+            Some(3)
+        );
+        assert_eq!(
+            B64Error::Truncated {
+                expected: 6,
+                actual: 5
+            }
+            .position(),
+            Some(5)
+        );
+    }
+
+    #[test]
+    fn decode_error_kinds() {
+        let io = std::io::Error::other("oops");
+        let utf8 = String::from_utf8(vec![0xff]).unwrap_err();
+
+        assert_eq!(DecodeError::from(io).kind(), ErrorKind::Io);
+        assert_eq!(DecodeError::from(utf8).kind(), ErrorKind::InvalidUtf8);
+        assert_eq!(
+            DecodeError::from(B64Error::InvalidChar('#')).kind(),
+            ErrorKind::InvalidChar
+        );
+        assert_eq!(
+            DecodeError::TooLarge {
+                needed: 2,
+                limit: 1
+            }
+            .kind(),
+            ErrorKind::TooLarge
+        );
+        assert_eq!(
+            DecodeError::OutOfBounds {
+                start: 2,
+                end: 4,
+                len: 3
+            }
+            .kind(),
+            ErrorKind::OutOfBounds
+        );
+        assert_eq!(
+            DecodeError::InvalidInteger { bits: 64, len: 9 }.kind(),
+            ErrorKind::InvalidInteger
+        );
+    }
+
+    #[test]
+    fn quoted_printable_ascii() {
+        for c in ' '..='~' {
+            assert_eq!(Quoted(c).to_string(), format!("'{c}'"));
+        }
+    }
+
+    #[test]
+    fn quoted_control_chars() {
+        for c in ('\0'..' ').chain(['\u{7f}']).chain('\u{80}'..='\u{9f}') {
+            let quoted = Quoted(c).to_string();
+
+            assert!(
+                quoted.starts_with(&format!("'\\u{{{:x}}}'", u32::from(c))),
+                "{quoted}"
+            );
+            assert!(!quoted.chars().any(char::is_control), "{quoted:?}");
+        }
+        assert_eq!(Quoted('\0').to_string(), "'\\u{0}' (null)");
+        assert_eq!(Quoted('\t').to_string(), "'\\u{9}' (tab)");
+        assert_eq!(Quoted('\n').to_string(), "'\\u{a}' (line feed)");
+        assert_eq!(Quoted('\r').to_string(), "'\\u{d}' (carriage return)");
+        assert_eq!(Quoted('\u{7f}').to_string(), "'\\u{7f}' (delete)");
+        assert_eq!(Quoted('\u{1}').to_string(), "'\\u{1}'");
+    }
+
+    #[test]
+    fn quoted_whitespace() {
+        for (c, expected) in [
+            ('\u{a0}', "'\\u{a0}' (non-breaking space)"),
+            ('\u{2003}', "'\\u{2003}' (em space)"),
+            ('\u{200b}', "'\\u{200b}' (zero width space)"),
+            ('\u{2028}', "'\\u{2028}' (line separator)"),
+            ('\u{3000}', "'\\u{3000}' (ideographic space)"),
+            ('\u{feff}', "'\\u{feff}' (byte order mark)"),
+        ] {
+            assert_eq!(Quoted(c).to_string(), expected);
+        }
+        for c in (char::MIN..=char::MAX).filter(|c| c.is_whitespace() && *c != ' ') {
+            assert!(Quoted(c).to_string().starts_with("'\\u{"), "{c:?}");
+        }
+    }
+
+    #[test]
+    fn quoted_multibyte() {
+        assert_eq!(Quoted('é').to_string(), "'\\u{e9}'");
+        assert_eq!(Quoted('€').to_string(), "'\\u{20ac}'");
+        assert_eq!(Quoted('😀').to_string(), "'\\u{1f600}'");
+        assert_eq!(Quoted(char::MAX).to_string(), "'\\u{10ffff}'");
+    }
+
+    #[test]
+    fn displays_chars_safely() {
+        assert_eq!(
+            B64Error::InvalidCharAt {
+                char: '\r',
+                index: 4
+            }
+            .to_string(),
+            "Invalid Base64 character '\\u{d}' (carriage return) at index 4"
+        );
+        assert_eq!(
+            B64Error::InvalidChar('#').to_string(),
+            "Invalid Base64 character '#'"
+        );
+        assert_eq!(
+            HexError::InvalidChar {
+                char: '\u{200b}',
+                index: 2
+            }
+            .to_string(),
+            "Invalid hex character '\\u{200b}' (zero width space) at index 2"
+        );
+    }
+
+    #[test]
+    fn offset_by() {
+        assert_eq!(
+            B64Error::InvalidCharAt {
+                char: '#',
+                index: 3
+            }
+            .offset_by(8),
+            B64Error::InvalidCharAt {
+                char: '#',
+                index: 11
+            }
+        );
+        assert_eq!(
+            B64Error::InvalidChar('#').offset_by(8),
+            B64Error::InvalidChar('#')
+        );
+    }
+}
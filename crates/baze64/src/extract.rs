@@ -0,0 +1,209 @@
+use std::ops::Range;
+
+use crate::{alphabet::Alphabet, primitives, Base64String};
+
+/// Find the base64 embedded in some text, like `data:` URIs,
+/// `Authorization: Basic` headers & blobs in JSON logs
+///
+/// Looks for runs of characters in `alphabet`, followed by as much
+/// padding as the run needs, that are at least `min_len` characters
+/// long counting padding. Each run is as long as it can be, so base64
+/// is only found between characters that aren't in the alphabet.
+/// Returns every run's byte range in `haystack`, along with it parsed
+///
+/// A run with a lone character in its last quad can't encode whole
+/// bytes, so it's truncated to the last whole quad, without any
+/// padding after it, & kept if it's still long enough
+///
+/// Anything made of the alphabet's characters is found, so words &
+/// hex strings are too. Raise `min_len` to skip short words, & check
+/// what matches decode to
+///
+/// # Examples
+/// ```
+/// # use baze64::{extract, alphabet::Standard};
+/// let header = "Authorization: Basic dXNlcjpwYXNz";
+/// let found = extract(header, 8, &Standard::new());
+///
+/// assert_eq!(found.len(), 2);
+/// let (range, b64) = &found[1];
+/// assert_eq!(&header[range.clone()], "dXNlcjpwYXNz");
+/// assert_eq!(b64.decode()?, b"user:pass");
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn extract<A>(
+    haystack: &str,
+    min_len: usize,
+    alphabet: &A,
+) -> Vec<(Range<usize>, Base64String<A>)>
+where
+    A: Alphabet + Clone,
+{
+    let padding = alphabet.padding();
+    let is_data = |c| Some(c) != padding && primitives::decode_bits(alphabet, c).is_ok();
+    let mut found = Vec::new();
+    let mut chars = haystack.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        if !is_data(c) {
+            continue;
+        }
+
+        let mut data_len = 1;
+        let mut end = start + c.len_utf8();
+        // Where the run would end without its last character, for
+        // truncating a lone character in the last quad
+        let mut before_last = start;
+        while let Some(&(i, c)) = chars.peek() {
+            if !is_data(c) {
+                break;
+            }
+            chars.next();
+            data_len += 1;
+            before_last = end;
+            end = i + c.len_utf8();
+        }
+
+        let mut len = data_len;
+        if data_len % 4 == 1 {
+            end = before_last;
+            len -= 1;
+        } else {
+            let mut missing = (4 - data_len % 4) % 4;
+            while let Some(&(i, c)) = chars
+                .peek()
+                .filter(|&&(_, c)| missing > 0 && Some(c) == padding)
+            {
+                chars.next();
+                end = i + c.len_utf8();
+                len += 1;
+                missing -= 1;
+            }
+        }
+
+        if len == 0 || len < min_len {
+            continue;
+        }
+        if let Ok(b64) = Base64String::from_encoded_with(&haystack[start..end], alphabet.clone()) {
+            found.push((start..end, b64));
+        }
+    }
+
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alphabet::{Standard, UrlSafe};
+
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    /// The text & decoded data of every match
+    fn matches<'a, A>(haystack: &'a str, min_len: usize, alphabet: &A) -> Vec<(&'a str, Vec<u8>)>
+    where
+        A: Alphabet + Clone,
+    {
+        extract(haystack, min_len, alphabet)
+            .into_iter()
+            .map(|(range, b64)| (&haystack[range], b64.decode().unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn several_candidates() {
+        let log = r#"{"user":"Zm9vYmFy","blob":"aGVsbG8gd29ybGQ=","note":"hi"}"#;
+
+        assert_eq!(
+            matches(log, 8, &Standard::new()),
+            [
+                ("Zm9vYmFy", b"foobar".to_vec()),
+                ("aGVsbG8gd29ybGQ=", b"hello world".to_vec())
+            ]
+        );
+    }
+
+    #[test]
+    fn data_uri() {
+        let html = r#"<img src="data:image/png;base64,iVBORw0KGgo=">"#;
+        let found = extract(html, 12, &Standard::new());
+
+        assert_eq!(found.len(), 1);
+        assert_eq!(&html[found[0].0.clone()], "iVBORw0KGgo=");
+        assert_eq!(
+            found[0].1.decode().unwrap(),
+            [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n']
+        );
+    }
+
+    #[test]
+    fn hex_strings_are_found() {
+        // Hex digits are all base64 characters, so these are real
+        // matches, just not ones that decode to anything meaningful
+        let text = "sha256: 9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+        let found = extract(text, 16, &Standard::new());
+
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].0, 8..text.len());
+        assert_eq!(found[0].1.decoded_len(), 48);
+    }
+
+    #[test]
+    fn run_at_end_of_input() {
+        let text = "token: Zm9vYg";
+
+        assert_eq!(
+            matches(text, 6, &Standard::new()),
+            [("Zm9vYg", b"foob".to_vec())]
+        );
+        assert_eq!(extract(text, 6, &Standard::new())[0].1, "Zm9vYg");
+    }
+
+    #[test]
+    fn lone_character_truncated() {
+        assert_eq!(
+            matches("!Zm9vYmFyQ!", 4, &Standard::new()),
+            [("Zm9vYmFy", b"foobar".to_vec())]
+        );
+        // Too short once truncated
+        assert!(extract("Zm9vY", 5, &Standard::new()).is_empty());
+        assert!(extract("a b c", 0, &Standard::new()).is_empty());
+    }
+
+    #[test]
+    fn only_needed_padding() {
+        assert_eq!(
+            matches("Zm8===", 4, &Standard::new()),
+            [("Zm8=", b"fo".to_vec())]
+        );
+        // Padding that isn't needed is left out
+        assert_eq!(
+            matches("Zm9v=", 4, &Standard::new()),
+            [("Zm9v", b"foo".to_vec())]
+        );
+        // As is padding after a truncated run
+        assert_eq!(
+            matches("Zm9vY=", 4, &Standard::new()),
+            [("Zm9v", b"foo".to_vec())]
+        );
+    }
+
+    #[test]
+    fn min_len_counts_padding() {
+        assert_eq!(extract("Zg==", 4, &Standard::new()).len(), 1);
+        assert!(extract("Zg==", 5, &Standard::new()).is_empty());
+    }
+
+    #[test]
+    fn alphabet_decides_runs() {
+        fn ranges<A: Alphabet + Clone>(alphabet: &A) -> Vec<Range<usize>> {
+            extract("ab+cd/ef-gh_ij", 0, alphabet)
+                .into_iter()
+                .map(|(range, _)| range)
+                .collect()
+        }
+
+        assert_eq!(ranges(&Standard::new()), [0..8, 9..11, 12..14]);
+        assert_eq!(ranges(&UrlSafe::new()), [0..2, 3..5, 6..14]);
+    }
+}
@@ -0,0 +1,307 @@
+//! Encode & decode using caller-provided fixed-capacity buffers,
+//! without allocating
+//!
+//! Useful for small payloads on targets where allocation isn't
+//! an option. Every function fails with the capacity required
+//! rather than truncating if the output doesn't fit
+//!
+//! ## Examples
+//! ```
+//! # use baze64::{fixed, alphabet::Standard};
+//! let (len, encoded) = fixed::encode_to_array::<8, _>(b"foobar", &Standard::new())?;
+//! assert_eq!(&encoded[..len], b"Zm9vYmFy");
+//!
+//! let text = core::str::from_utf8(&encoded[..len])?;
+//! let (len, decoded) = fixed::decode_to_array::<6, _>(text, &Standard::new())?;
+//! assert_eq!(&decoded[..len], b"foobar");
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use core::fmt;
+
+use crate::{alphabet::Alphabet, base64string::DecodedQuads, B64Error, Base64String};
+
+/// The error returned when encoding into a buffer that's too small
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EncodeSliceError {
+    /// The number of bytes needed to hold the output
+    pub required: usize,
+    /// The number of bytes the buffer can hold
+    pub capacity: usize,
+}
+
+impl fmt::Display for EncodeSliceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        buffer_too_small(f, self.required, self.capacity)
+    }
+}
+
+impl std::error::Error for EncodeSliceError {}
+
+/// The error returned when decoding into a fixed-capacity buffer
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DecodeSliceError {
+    Base64Error(B64Error),
+    BufferTooSmall { required: usize, capacity: usize },
+}
+
+impl fmt::Display for DecodeSliceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeSliceError::Base64Error(e) => e.fmt(f),
+            DecodeSliceError::BufferTooSmall { required, capacity } => {
+                buffer_too_small(f, *required, *capacity)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DecodeSliceError {}
+
+impl From<B64Error> for DecodeSliceError {
+    fn from(value: B64Error) -> Self {
+        DecodeSliceError::Base64Error(value)
+    }
+}
+
+/// Describe a buffer holding `capacity` bytes being too small for
+/// the `required` number of bytes
+fn buffer_too_small(f: &mut fmt::Formatter<'_>, required: usize, capacity: usize) -> fmt::Result {
+    write!(
+        f,
+        "Output buffer too small, {required} bytes are needed but it only holds {capacity}"
+    )
+}
+
+/// Encode `bytes` as UTF-8 base64 text into `out`, returning
+/// the number of bytes of `out` used
+///
+/// If `out` is too small, the returned error reports how big it
+/// needs to be. The contents of `out` are unspecified in that case
+///
+/// # Examples
+/// ```
+/// # use baze64::{fixed, alphabet::UrlSafe};
+/// let mut out = [0; 16];
+/// let len = fixed::encode_to_slice(&[0xfb, 0xff], &mut out, &UrlSafe::new())?;
+///
+/// assert_eq!(&out[..len], b"-_8=");
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn encode_to_slice<A>(
+    bytes: &[u8],
+    out: &mut [u8],
+    alphabet: &A,
+) -> Result<usize, EncodeSliceError>
+where
+    A: Alphabet,
+{
+    let mut sink = SliceSink { out, required: 0 };
+    Base64String::push_encoded(&mut sink, bytes, alphabet);
+
+    if sink.required > sink.out.len() {
+        Err(EncodeSliceError {
+            required: sink.required,
+            capacity: sink.out.len(),
+        })
+    } else {
+        Ok(sink.required)
+    }
+}
+
+/// Encode `bytes` as UTF-8 base64 text into an array of `OUT` bytes,
+/// returning the number of bytes used along with the array
+///
+/// # Examples
+/// ```
+/// # use baze64::{fixed, alphabet::Standard};
+/// let (len, encoded) = fixed::encode_to_array::<4, _>(b"fo", &Standard::new())?;
+/// assert_eq!(&encoded[..len], b"Zm8=");
+///
+/// let err = fixed::encode_to_array::<4, _>(b"foob", &Standard::new()).unwrap_err();
+/// assert_eq!(err.required, 8);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn encode_to_array<const OUT: usize, A>(
+    bytes: &[u8],
+    alphabet: &A,
+) -> Result<(usize, [u8; OUT]), EncodeSliceError>
+where
+    A: Alphabet,
+{
+    let mut out = [0; OUT];
+    let len = encode_to_slice(bytes, &mut out, alphabet)?;
+
+    Ok((len, out))
+}
+
+/// Decode the base64 text `b64` into `out`, returning the number
+/// of bytes of `out` used
+///
+/// If `out` is too small, the returned error reports how big it
+/// needs to be. The contents of `out` are unspecified in that case
+///
+/// # Examples
+/// ```
+/// # use baze64::{fixed, alphabet::Standard};
+/// let mut out = [0; 8];
+/// let len = fixed::decode_to_slice("Zm9vYg==", &mut out, &Standard::new())?;
+///
+/// assert_eq!(&out[..len], b"foob");
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn decode_to_slice<A>(
+    b64: &str,
+    out: &mut [u8],
+    alphabet: &A,
+) -> Result<usize, DecodeSliceError>
+where
+    A: Alphabet,
+{
+    let mut required = 0;
+
+    for quad in DecodedQuads::new(b64, alphabet) {
+        let (tri, len) = quad?;
+        if let Some(dest) = out.get_mut(required..required + len) {
+            dest.copy_from_slice(&tri[..len]);
+        }
+        required += len;
+    }
+
+    if required > out.len() {
+        Err(DecodeSliceError::BufferTooSmall {
+            required,
+            capacity: out.len(),
+        })
+    } else {
+        Ok(required)
+    }
+}
+
+/// Decode the base64 text `b64` into an array of `OUT` bytes,
+/// returning the number of bytes used along with the array
+///
+/// # Examples
+/// ```
+/// # use baze64::{fixed, alphabet::Standard};
+/// let (len, decoded) = fixed::decode_to_array::<4, _>("Zm9vYg==", &Standard::new())?;
+/// assert_eq!(&decoded[..len], b"foob");
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn decode_to_array<const OUT: usize, A>(
+    b64: &str,
+    alphabet: &A,
+) -> Result<(usize, [u8; OUT]), DecodeSliceError>
+where
+    A: Alphabet,
+{
+    let mut out = [0; OUT];
+    let len = decode_to_slice(b64, &mut out, alphabet)?;
+
+    Ok((len, out))
+}
+
+/// Writes UTF-8 encoded characters into a slice, keeping count
+/// of the space needed once it's full
+struct SliceSink<'a> {
+    out: &'a mut [u8],
+    required: usize,
+}
+
+impl Extend<char> for SliceSink<'_> {
+    fn extend<T: IntoIterator<Item = char>>(&mut self, iter: T) {
+        for c in iter {
+            let len = c.len_utf8();
+            if let Some(dest) = self.out.get_mut(self.required..self.required + len) {
+                c.encode_utf8(dest);
+            }
+            self.required += len;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alphabet::{Standard, UrlSafe};
+
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn encode_matches_base64string() {
+        let data = (0..=48u8).map(|b| b.wrapping_mul(97)).collect::<Vec<_>>();
+
+        for size in 0..=48 {
+            let payload = &data[..size];
+            let expected = Base64String::<Standard>::encode(payload).to_string();
+            let (len, out) = encode_to_array::<64, _>(payload, &Standard::new()).unwrap();
+
+            assert_eq!(&out[..len], expected.as_bytes(), "payload size {size}");
+
+            let expected = Base64String::<UrlSafe>::encode(payload).to_string();
+            let (len, out) = encode_to_array::<64, _>(payload, &UrlSafe::new()).unwrap();
+
+            assert_eq!(&out[..len], expected.as_bytes(), "payload size {size}");
+        }
+    }
+
+    #[test]
+    fn decode_matches_base64string() {
+        let data = (0..=48u8).map(|b| b.wrapping_mul(97)).collect::<Vec<_>>();
+
+        for size in 0..=48 {
+            let payload = &data[..size];
+            let encoded = Base64String::<Standard>::encode(payload).to_string();
+            let (len, out) = decode_to_array::<48, _>(&encoded, &Standard::new()).unwrap();
+
+            assert_eq!(&out[..len], payload, "payload size {size}");
+        }
+    }
+
+    #[test]
+    fn encode_exact_capacity() {
+        let mut out = [0; 8];
+
+        assert_eq!(
+            encode_to_slice(b"foobar", &mut out, &Standard::new()),
+            Ok(8)
+        );
+        assert_eq!(&out, b"Zm9vYmFy");
+    }
+
+    #[test]
+    fn encode_too_small() {
+        let mut out = [0; 7];
+
+        assert_eq!(
+            encode_to_slice(b"foobar", &mut out, &Standard::new()),
+            Err(EncodeSliceError {
+                required: 8,
+                capacity: 7
+            })
+        );
+    }
+
+    #[test]
+    fn decode_too_small() {
+        assert_eq!(
+            decode_to_array::<5, _>("Zm9vYmFy", &Standard::new()),
+            Err(DecodeSliceError::BufferTooSmall {
+                required: 6,
+                capacity: 5
+            })
+        );
+    }
+
+    #[test]
+    fn decode_invalid() {
+        assert_eq!(
+            decode_to_array::<6, _>("Zm9v#mFy", &Standard::new()),
+            Err(DecodeSliceError::Base64Error(B64Error::InvalidCharAt {
+                char: '#',
+                index: 4
+            }))
+        );
+    }
+}
@@ -0,0 +1,154 @@
+//! Hash data while encoding or decoding it, using any hash
+//! implementing [`Digest`], like those from `sha2` or `blake3`
+
+use std::io::{self, Write};
+
+use digest::{Digest, Output};
+
+use crate::{alphabet::Alphabet, Base64String, DecodeError};
+
+/// The number of bytes hashed & encoded at a time, a multiple of 3
+/// so only the final chunk can need padding
+const CHUNK_LEN: usize = 3 * 4096;
+
+impl<A> Base64String<A>
+where
+    A: Alphabet,
+{
+    /// Encode a sequence of bytes into a [`Base64String`] using a
+    /// given `alphabet` instance, hashing them with `D` in the same
+    /// pass
+    ///
+    /// The digest is of the bytes, not the encoded text
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, alphabet::Standard};
+    /// use sha2::{Digest, Sha256};
+    ///
+    /// let (b64, digest) = Base64String::encode_with_digest::<Sha256, _>(b"foo", Standard::new());
+    ///
+    /// assert_eq!(b64, "Zm9v");
+    /// assert_eq!(digest, Sha256::digest(b"foo"));
+    /// ```
+    pub fn encode_with_digest<D, B>(bytes: B, alphabet: A) -> (Self, Output<D>)
+    where
+        D: Digest,
+        B: AsRef<[u8]>,
+    {
+        let bytes = bytes.as_ref();
+        let mut hasher = D::new();
+        let mut content = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+        // Hash each chunk while it's still in cache from encoding it
+        for chunk in bytes.chunks(CHUNK_LEN) {
+            content.push_str(Base64String::encode_with(chunk, &alphabet).as_ref());
+            hasher.update(chunk);
+        }
+
+        (Self::from_parts(content, alphabet), hasher.finalize())
+    }
+
+    /// Hash the decoded contents of `self` with `D`, without keeping
+    /// them
+    ///
+    /// The data is decoded & hashed a chunk at a time, so only a
+    /// small buffer is used however long `self` is
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, alphabet::Standard};
+    /// use sha2::{Digest, Sha256};
+    ///
+    /// let b64 = Base64String::<Standard>::encode("Some data");
+    ///
+    /// assert_eq!(b64.decode_digest::<Sha256>()?, Sha256::digest("Some data"));
+    /// # Ok::<(), baze64::DecodeError>(())
+    /// ```
+    pub fn decode_digest<D>(&self) -> Result<Output<D>, DecodeError>
+    where
+        D: Digest,
+    {
+        let mut hasher = Hasher(D::new());
+        self.decode_into(&mut hasher)?;
+
+        Ok(hasher.0.finalize())
+    }
+}
+
+/// Hashes everything written to it
+struct Hasher<D>(D);
+
+impl<D> Write for Hasher<D>
+where
+    D: Digest,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sha2::{Sha256, Sha512};
+
+    use super::*;
+    use crate::alphabet::{Standard, UrlSafe};
+    use pretty_assertions::assert_eq;
+
+    /// SHA-256 digests of some fixtures, from `sha256sum`
+    const FIXTURES: [(&[u8], &str); 3] = [
+        (
+            b"",
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+        ),
+        (
+            b"foobar",
+            "c3ab8ff13720e8ad9047dd39466b3c8974e592c2fa383d4a3960714caef0c4f2",
+        ),
+        (
+            b"The quick brown fox jumps over the lazy dog",
+            "d7a8fbb307d7809469ca9abcb0082e4f8d5651e46d3cdb762d02d0bf37c9e592",
+        ),
+    ];
+
+    fn hex(digest: &[u8]) -> String {
+        digest.iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    #[test]
+    fn fixtures() {
+        for (data, expected) in FIXTURES {
+            let (b64, digest) =
+                Base64String::encode_with_digest::<Sha256, _>(data, Standard::new());
+
+            assert_eq!(b64, Base64String::encode_with(data, Standard::new()));
+            assert_eq!(hex(&digest), expected);
+            assert_eq!(hex(&b64.decode_digest::<Sha256>().unwrap()), expected);
+        }
+    }
+
+    #[test]
+    fn spans_chunks() {
+        let data = (0..CHUNK_LEN * 3 + 7)
+            .map(|i| (i as u8).wrapping_mul(31))
+            .collect::<Vec<_>>();
+        let (b64, digest) = Base64String::encode_with_digest::<Sha512, _>(&data, UrlSafe::new());
+
+        assert_eq!(b64, Base64String::encode_with(&data, UrlSafe::new()));
+        assert_eq!(digest, Sha512::digest(&data));
+        assert_eq!(b64.decode_digest::<Sha512>().unwrap(), digest);
+    }
+
+    #[test]
+    fn decode_errors() {
+        let b64 = Base64String::<Standard>::from_encoded("Zm9vY").unwrap();
+
+        assert!(b64.decode_digest::<Sha256>().is_err());
+    }
+}
@@ -0,0 +1,489 @@
+//! Encode hex text as base64 & decode base64 to hex text or a
+//! hex dump
+
+use std::io::{self, Write};
+
+use crate::{alphabet::Alphabet, Base64String, DecodeError, HexError};
+
+/// The case of the letters in hex output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HexCase {
+    /// `a` to `f`
+    #[default]
+    Lower,
+    /// `A` to `F`
+    Upper,
+}
+
+impl HexCase {
+    fn digits(self) -> &'static [u8; 16] {
+        match self {
+            HexCase::Lower => b"0123456789abcdef",
+            HexCase::Upper => b"0123456789ABCDEF",
+        }
+    }
+
+    /// Format bytes that have already been decoded as hex, with two
+    /// digits per byte & no prefix or separators
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::HexCase;
+    /// assert_eq!(HexCase::Lower.format(b"\x0f\xff"), "0fff");
+    /// assert_eq!(HexCase::Upper.format(b"\x0f\xff"), "0FFF");
+    /// ```
+    pub fn format(self, bytes: &[u8]) -> String {
+        let digits = self.digits();
+
+        bytes
+            .iter()
+            .flat_map(|b| [digits[(b >> 4) as usize], digits[(b & 0xf) as usize]])
+            .map(char::from)
+            .collect()
+    }
+}
+
+/// How [`Base64String::to_hex_dump`] lays out its output
+///
+/// The default is the classic layout of 16 bytes per line, each
+/// starting with its offset & ending with the bytes as ASCII, with
+/// lowercase hex:
+///
+/// ```text
+/// 00000000  48 65 6c 6c 6f 2c 20 77  6f 72 6c 64 21 0a 00 ff  |Hello, world!...|
+/// 00000010  ca fe                                             |..|
+/// ```
+///
+/// # Examples
+/// ```
+/// # use baze64::{HexCase, HexDumpConfig};
+/// let config = HexDumpConfig::new()
+///     .width(4)
+///     .case(HexCase::Upper)
+///     .offsets(false)
+///     .gutter(false);
+///
+/// assert_eq!(config.dump(b"\xca\xfe\xba\xbe\x00"), "CA FE BA BE\n00\n");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HexDumpConfig {
+    width: usize,
+    case: HexCase,
+    offsets: bool,
+    gutter: bool,
+}
+
+impl HexDumpConfig {
+    /// Create a config with the default layout
+    pub const fn new() -> Self {
+        Self {
+            width: 16,
+            case: HexCase::Lower,
+            offsets: true,
+            gutter: true,
+        }
+    }
+
+    /// Put `bytes` bytes on each line, or 1 if `bytes` is 0. 16 by
+    /// default
+    ///
+    /// An extra space separates each group of 8 bytes
+    pub const fn width(mut self, bytes: usize) -> Self {
+        self.width = if bytes == 0 { 1 } else { bytes };
+        self
+    }
+
+    /// The case of the letters in the hex & offsets. Lowercase by
+    /// default
+    pub const fn case(mut self, case: HexCase) -> Self {
+        self.case = case;
+        self
+    }
+
+    /// Whether each line starts with the offset of its first byte,
+    /// as at least 8 hex digits. On by default
+    pub const fn offsets(mut self, offsets: bool) -> Self {
+        self.offsets = offsets;
+        self
+    }
+
+    /// Whether each line ends with its bytes as ASCII between `|`s,
+    /// with `.` for anything that isn't printable. On by default
+    pub const fn gutter(mut self, gutter: bool) -> Self {
+        self.gutter = gutter;
+        self
+    }
+
+    /// Dump bytes that have already been decoded
+    ///
+    /// Every line, including the last, ends with a newline, & no
+    /// bytes give an empty string
+    pub fn dump(&self, bytes: &[u8]) -> String {
+        let mut dumper = HexDumper::new(*self);
+        dumper.push(bytes);
+
+        dumper.finish()
+    }
+}
+
+impl Default for HexDumpConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A> Base64String<A>
+where
+    A: Alphabet,
+{
+    /// Encode the bytes written as hex in `hex` into a
+    /// [`Base64String`] using a given `alphabet` instance
+    ///
+    /// `hex` may start with `0x` & contain whitespace between digits,
+    /// & either case of letters is accepted. An odd number of digits
+    /// is treated as if it had a leading `0`, so `fff` encodes the
+    /// bytes `0x0f 0xff`
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, alphabet::Standard};
+    /// let b64 = Base64String::encode_hex("0x66 6F 6f", Standard::new())?;
+    ///
+    /// assert_eq!(b64.to_string(), "Zm9v");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn encode_hex(hex: &str, alphabet: A) -> Result<Self, HexError> {
+        Ok(Self::encode_with(parse_hex(hex)?, alphabet))
+    }
+
+    /// Decode the contents of `self` into a string of hex digits,
+    /// with letters in the given `case`
+    ///
+    /// The output has two digits per byte, with no prefix or
+    /// separators
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, HexCase, alphabet::Standard};
+    /// let b64 = Base64String::<Standard>::encode([0x0f, 0xff]);
+    ///
+    /// assert_eq!(b64.decode_to_hex(HexCase::Lower)?, "0fff");
+    /// assert_eq!(b64.decode_to_hex(HexCase::Upper)?, "0FFF");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn decode_to_hex(&self, case: HexCase) -> Result<String, DecodeError> {
+        Ok(case.format(&self.decode()?))
+    }
+
+    /// Decode the contents of `self` into a hex dump laid out by
+    /// `config`, for reading binary data
+    ///
+    /// The data is decoded a chunk at a time, so it's never all held
+    /// in memory alongside the dump
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, HexDumpConfig, alphabet::Standard};
+    /// let b64 = Base64String::<Standard>::encode(b"Hi\0\xff");
+    ///
+    /// assert_eq!(
+    ///     b64.to_hex_dump(HexDumpConfig::new().width(4))?,
+    ///     "00000000  48 69 00 ff  |Hi..|\n"
+    /// );
+    /// # Ok::<(), baze64::DecodeError>(())
+    /// ```
+    pub fn to_hex_dump(&self, config: HexDumpConfig) -> Result<String, DecodeError> {
+        let mut dumper = HexDumper::new(config);
+        self.decode_into(&mut dumper)?;
+
+        Ok(dumper.finish())
+    }
+}
+
+/// Builds a hex dump from bytes written to it, a line at a time
+struct HexDumper {
+    config: HexDumpConfig,
+    out: String,
+    /// The bytes of the line that isn't full yet
+    line: Vec<u8>,
+    /// The offset of the first byte in `line`
+    offset: usize,
+}
+
+impl HexDumper {
+    fn new(config: HexDumpConfig) -> Self {
+        Self {
+            config,
+            out: String::new(),
+            line: Vec::with_capacity(config.width),
+            offset: 0,
+        }
+    }
+
+    fn push(&mut self, mut bytes: &[u8]) {
+        while !bytes.is_empty() {
+            let take = (self.config.width - self.line.len()).min(bytes.len());
+            self.line.extend_from_slice(&bytes[..take]);
+            bytes = &bytes[take..];
+
+            if self.line.len() == self.config.width {
+                self.write_line();
+            }
+        }
+    }
+
+    /// Write out the last line, even if it isn't full
+    fn finish(mut self) -> String {
+        if !self.line.is_empty() {
+            self.write_line();
+        }
+
+        self.out
+    }
+
+    fn write_line(&mut self) {
+        let HexDumpConfig {
+            width,
+            case,
+            offsets,
+            gutter,
+        } = self.config;
+        let digits = case.digits();
+
+        if offsets {
+            // At least 8 digits, with more past 4 GiB
+            let len = (usize::BITS - self.offset.leading_zeros())
+                .div_ceil(4)
+                .max(8);
+            for shift in (0..len as usize).rev() {
+                let nibble = (self.offset >> (shift * 4)) & 0xf;
+                self.out.push(char::from(digits[nibble]));
+            }
+            self.out.push_str("  ");
+        }
+        for i in 0..width {
+            if i > 0 {
+                self.out.push(' ');
+            }
+            if i > 0 && i % 8 == 0 {
+                self.out.push(' ');
+            }
+            match self.line.get(i) {
+                Some(b) => {
+                    self.out.push(char::from(digits[usize::from(b >> 4)]));
+                    self.out.push(char::from(digits[usize::from(b & 0xf)]));
+                }
+                None => self.out.push_str("  "),
+            }
+        }
+        if gutter {
+            self.out.push_str("  |");
+            self.out.extend(self.line.iter().map(|&b| {
+                if b == b' ' || b.is_ascii_graphic() {
+                    char::from(b)
+                } else {
+                    '.'
+                }
+            }));
+            self.out.push('|');
+        } else {
+            // Don't leave the padding of a short last line behind
+            self.out.truncate(self.out.trim_end().len());
+        }
+        self.out.push('\n');
+
+        self.offset += self.line.len();
+        self.line.clear();
+    }
+}
+
+impl Write for HexDumper {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.push(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Parse hex digits into bytes, skipping an optional `0x` prefix
+/// & any whitespace, & left-padding odd length input with a `0`
+fn parse_hex(hex: &str) -> Result<Vec<u8>, HexError> {
+    let (offset, hex) = match hex.strip_prefix("0x").or_else(|| hex.strip_prefix("0X")) {
+        Some(rest) => (2, rest),
+        None => (0, hex),
+    };
+
+    let mut nibbles = Vec::with_capacity(hex.len());
+    for (index, c) in hex.chars().enumerate() {
+        if c.is_whitespace() {
+            continue;
+        }
+        match c.to_digit(16) {
+            Some(n) => nibbles.push(n as u8),
+            None => {
+                return Err(HexError::InvalidChar {
+                    char: c,
+                    index: index + offset,
+                })
+            }
+        }
+    }
+    if nibbles.len() % 2 != 0 {
+        nibbles.insert(0, 0);
+    }
+
+    Ok(nibbles.chunks(2).map(|n| (n[0] << 4) | n[1]).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alphabet::Standard;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_even() {
+        assert_eq!(parse_hex("666f6F"), Ok(b"foo".to_vec()));
+    }
+
+    #[test]
+    fn parse_odd_length() {
+        assert_eq!(parse_hex("fff"), Ok(vec![0x0f, 0xff]));
+        assert_eq!(parse_hex("1"), Ok(vec![0x01]));
+    }
+
+    #[test]
+    fn parse_empty() {
+        assert_eq!(parse_hex(""), Ok(vec![]));
+        assert_eq!(parse_hex("0x"), Ok(vec![]));
+        assert_eq!(parse_hex("  "), Ok(vec![]));
+    }
+
+    #[test]
+    fn parse_prefix_and_whitespace() {
+        assert_eq!(parse_hex("0x66 6f\t6f\n"), Ok(b"foo".to_vec()));
+        assert_eq!(parse_hex("0XFF"), Ok(vec![0xff]));
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert_eq!(
+            parse_hex("0x6g"),
+            Err(HexError::InvalidChar {
+                char: 'g',
+                index: 3
+            })
+        );
+        assert_eq!(
+            parse_hex("x1"),
+            Err(HexError::InvalidChar {
+                char: 'x',
+                index: 0
+            })
+        );
+    }
+
+    #[test]
+    fn hex_roundtrip() {
+        let b64 = Base64String::encode_hex("00ff7f", Standard::new()).unwrap();
+
+        assert_eq!(b64.decode().unwrap(), [0x00, 0xff, 0x7f]);
+        assert_eq!(b64.decode_to_hex(HexCase::Lower).unwrap(), "00ff7f");
+        assert_eq!(b64.decode_to_hex(HexCase::Upper).unwrap(), "00FF7F");
+    }
+
+    #[test]
+    fn decode_to_hex_empty() {
+        let b64 = Base64String::<Standard>::encode(b"");
+
+        assert_eq!(b64.decode_to_hex(HexCase::Lower).unwrap(), "");
+    }
+
+    /// A binary fixture of 2 full lines & a partial one, with
+    /// printable ASCII, control characters & bytes past ASCII
+    fn fixture() -> Vec<u8> {
+        b"Hello, world!\n\0\xff"
+            .iter()
+            .copied()
+            .chain(0x7b..0x92)
+            .collect()
+    }
+
+    #[test]
+    fn hex_dump_default() {
+        let b64 = Base64String::<Standard>::encode(fixture());
+
+        assert_eq!(
+            b64.to_hex_dump(HexDumpConfig::new()).unwrap(),
+            "\
+00000000  48 65 6c 6c 6f 2c 20 77  6f 72 6c 64 21 0a 00 ff  |Hello, world!...|
+00000010  7b 7c 7d 7e 7f 80 81 82  83 84 85 86 87 88 89 8a  |{|}~............|
+00000020  8b 8c 8d 8e 8f 90 91                              |.......|
+"
+        );
+        assert_eq!(
+            HexDumpConfig::new().dump(&fixture()),
+            b64.to_hex_dump(HexDumpConfig::default()).unwrap()
+        );
+    }
+
+    #[test]
+    fn hex_dump_layouts() {
+        let b64 = Base64String::<Standard>::encode(fixture());
+
+        assert_eq!(
+            b64.to_hex_dump(
+                HexDumpConfig::new()
+                    .width(5)
+                    .case(HexCase::Upper)
+                    .gutter(false)
+            )
+            .unwrap(),
+            "\
+00000000  48 65 6C 6C 6F
+00000005  2C 20 77 6F 72
+0000000A  6C 64 21 0A 00
+0000000F  FF 7B 7C 7D 7E
+00000014  7F 80 81 82 83
+00000019  84 85 86 87 88
+0000001E  89 8A 8B 8C 8D
+00000023  8E 8F 90 91
+"
+        );
+        assert_eq!(
+            b64.to_hex_dump(HexDumpConfig::new().width(10).offsets(false))
+                .unwrap(),
+            "\
+48 65 6c 6c 6f 2c 20 77  6f 72  |Hello, wor|
+6c 64 21 0a 00 ff 7b 7c  7d 7e  |ld!...{|}~|
+7f 80 81 82 83 84 85 86  87 88  |..........|
+89 8a 8b 8c 8d 8e 8f 90  91     |.........|
+"
+        );
+    }
+
+    #[test]
+    fn hex_dump_edge_cases() {
+        let empty = Base64String::<Standard>::encode(b"");
+        assert_eq!(empty.to_hex_dump(HexDumpConfig::new()).unwrap(), "");
+        assert_eq!(
+            HexDumpConfig::new().width(0).offsets(false).dump(b"ab"),
+            "61  |a|\n62  |b|\n"
+        );
+
+        let mut dumper = HexDumper::new(HexDumpConfig::new().width(1).gutter(false));
+        dumper.offset = 0x1_2345_6789;
+        dumper.push(b"z");
+        assert_eq!(dumper.finish(), "123456789  7a\n");
+    }
+
+    #[test]
+    fn hex_dump_invalid() {
+        let b64 = Base64String::<Standard>::from_encoded("Zm9vY").unwrap();
+
+        assert!(b64.to_hex_dump(HexDumpConfig::new()).is_err());
+    }
+}
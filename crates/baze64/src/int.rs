@@ -0,0 +1,219 @@
+//! Encode integers as short base64 tokens & decode them back
+//!
+//! An integer is encoded as its big-endian bytes with any leading
+//! zero bytes removed, so small numbers give short tokens. Zero is
+//! the single byte `0x00` rather than no bytes at all. Every integer
+//! has exactly one encoding, which is what decoding accepts
+
+use crate::{alphabet::Alphabet, Base64String, DecodeError};
+
+impl<A> Base64String<A>
+where
+    A: Alphabet,
+{
+    /// Encode `n` into a [`Base64String`] using a given `alphabet`
+    /// instance
+    ///
+    /// The encoded bytes are the big-endian bytes of `n` without
+    /// leading zeros, so they're between 1 & 8 bytes long
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, alphabet::UrlSafe};
+    /// assert_eq!(Base64String::encode_u64_with(0, UrlSafe::new()).without_padding(), "AA");
+    /// assert_eq!(Base64String::encode_u64_with(256, UrlSafe::new()).without_padding(), "AQA");
+    /// ```
+    pub fn encode_u64_with(n: u64, alphabet: A) -> Self {
+        Self::encode_with(minimal(&n.to_be_bytes()), alphabet)
+    }
+
+    /// Encode `n` into a [`Base64String`] using a given `alphabet`
+    /// instance
+    ///
+    /// The encoded bytes are the big-endian bytes of `n` without
+    /// leading zeros, so they're between 1 & 16 bytes long. Values
+    /// that fit in a [`u64`] encode the same as with
+    /// [`Base64String::encode_u64_with`]
+    pub fn encode_u128_with(n: u128, alphabet: A) -> Self {
+        Self::encode_with(minimal(&n.to_be_bytes()), alphabet)
+    }
+
+    /// Decode the contents of `self` as a [`u64`] encoded by
+    /// [`Base64String::encode_u64_with`]
+    ///
+    /// Fails with [`DecodeError::InvalidInteger`] if the data is
+    /// empty, longer than 8 bytes, or has leading zero bytes
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, DecodeError, alphabet::UrlSafe};
+    /// let token = Base64String::<UrlSafe>::from_encoded("AQA")?;
+    /// assert_eq!(token.decode_u64()?, 256);
+    ///
+    /// let padded = Base64String::<UrlSafe>::from_encoded("AAEA")?;
+    /// assert!(matches!(
+    ///     padded.decode_u64(),
+    ///     Err(DecodeError::InvalidInteger { bits: 64, len: 3 })
+    /// ));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn decode_u64(&self) -> Result<u64, DecodeError> {
+        // Never more than 8 bytes, so this can't fail
+        Ok(self.decode_int(u64::BITS)? as u64)
+    }
+
+    /// Decode the contents of `self` as a [`u128`] encoded by
+    /// [`Base64String::encode_u128_with`]
+    ///
+    /// Fails with [`DecodeError::InvalidInteger`] if the data is
+    /// empty, longer than 16 bytes, or has leading zero bytes
+    pub fn decode_u128(&self) -> Result<u128, DecodeError> {
+        self.decode_int(u128::BITS)
+    }
+
+    /// Decode the contents of `self` as a minimal big-endian integer
+    /// of up to `bits` bits
+    fn decode_int(&self, bits: u32) -> Result<u128, DecodeError> {
+        let bytes = self.decode()?;
+        let max_len = bits as usize / 8;
+
+        match bytes.as_slice() {
+            [] | [0, _, ..] => {}
+            bytes if bytes.len() <= max_len => {
+                return Ok(bytes.iter().fold(0, |n, &b| n << 8 | u128::from(b)));
+            }
+            _ => {}
+        }
+
+        Err(DecodeError::InvalidInteger {
+            bits,
+            len: bytes.len(),
+        })
+    }
+}
+
+impl<A> Base64String<A>
+where
+    A: Alphabet + Default,
+{
+    /// Encode `n` into a [`Base64String`]
+    ///
+    /// Uses `A`'s [`Default`] impl as the alphabet to encode with.
+    /// Otherwise the same as [`Base64String::encode_u64_with`]
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, alphabet::UrlSafe};
+    /// let token = Base64String::<UrlSafe>::encode_u64(1_000_000);
+    ///
+    /// assert_eq!(token.without_padding(), "D0JA");
+    /// assert_eq!(token.decode_u64()?, 1_000_000);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn encode_u64(n: u64) -> Self {
+        Self::encode_u64_with(n, A::default())
+    }
+
+    /// Encode `n` into a [`Base64String`]
+    ///
+    /// Uses `A`'s [`Default`] impl as the alphabet to encode with.
+    /// Otherwise the same as [`Base64String::encode_u128_with`]
+    pub fn encode_u128(n: u128) -> Self {
+        Self::encode_u128_with(n, A::default())
+    }
+}
+
+/// Strip the leading zeros from big-endian `bytes`, keeping the
+/// last byte even if it's zero
+fn minimal(bytes: &[u8]) -> &[u8] {
+    let first = bytes
+        .iter()
+        .position(|&b| b != 0)
+        .unwrap_or(bytes.len() - 1);
+    &bytes[first..]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alphabet::{Standard, UrlSafe};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn fixed_tokens() {
+        // These end up stored elsewhere, so must never change
+        for (n, token) in [
+            (0, "AA"),
+            (1, "AQ"),
+            (255, "_w"),
+            (256, "AQA"),
+            (65_535, "__8"),
+            (65_536, "AQAA"),
+            (u64::MAX, "__________8"),
+        ] {
+            let encoded = Base64String::<UrlSafe>::encode_u64(n);
+
+            assert_eq!(encoded.without_padding(), token);
+            assert_eq!(encoded.decode_u64().unwrap(), n);
+        }
+    }
+
+    #[test]
+    fn minimal_bytes() {
+        assert_eq!(minimal(&0u64.to_be_bytes()), [0]);
+        assert_eq!(minimal(&255u64.to_be_bytes()), [0xff]);
+        assert_eq!(minimal(&256u64.to_be_bytes()), [1, 0]);
+        assert_eq!(minimal(&u64::MAX.to_be_bytes()), [0xff; 8]);
+    }
+
+    #[test]
+    fn u128_matches_u64() {
+        for n in [0, 1, 255, 256, u64::MAX] {
+            assert_eq!(
+                Base64String::<Standard>::encode_u128(n.into()),
+                Base64String::<Standard>::encode_u64(n)
+            );
+        }
+        assert_eq!(
+            Base64String::<Standard>::encode_u128(u128::MAX)
+                .decode_u128()
+                .unwrap(),
+            u128::MAX
+        );
+    }
+
+    #[test]
+    fn empty_is_invalid() {
+        let empty = Base64String::<Standard>::encode(b"");
+
+        assert!(matches!(
+            empty.decode_u64(),
+            Err(DecodeError::InvalidInteger { bits: 64, len: 0 })
+        ));
+    }
+
+    #[test]
+    fn overflow_is_invalid() {
+        let big = Base64String::<Standard>::encode_u128(u128::from(u64::MAX) + 1);
+
+        assert!(matches!(
+            big.decode_u64(),
+            Err(DecodeError::InvalidInteger { bits: 64, len: 9 })
+        ));
+        assert_eq!(big.decode_u128().unwrap(), u128::from(u64::MAX) + 1);
+        assert!(matches!(
+            Base64String::<Standard>::encode([1; 17]).decode_u128(),
+            Err(DecodeError::InvalidInteger { bits: 128, len: 17 })
+        ));
+    }
+
+    #[test]
+    fn leading_zeros_are_invalid() {
+        for bytes in [&[0, 0][..], &[0, 1], &[0; 8]] {
+            assert!(matches!(
+                Base64String::<Standard>::encode(bytes).decode_u64(),
+                Err(DecodeError::InvalidInteger { .. })
+            ));
+        }
+    }
+}
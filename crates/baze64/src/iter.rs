@@ -0,0 +1,186 @@
+//! Decode base64 a byte at a time, without allocating the output
+
+use core::{fmt, iter::FusedIterator};
+
+use crate::{alphabet::Alphabet, base64string::DecodedQuads, Base64Str, Base64String, DecodeError};
+
+/// Iterator over the decoded bytes of a [`Base64String`] or
+/// [`Base64Str`], returned by [`Base64String::decode_iter`] &
+/// [`Base64Str::decode_iter`]
+///
+/// Each quad is decoded when its first byte is needed, so only the 3
+/// bytes of the current quad are held at a time. If a quad fails to
+/// decode, its error is yielded in place of its bytes & the iterator
+/// ends
+///
+/// The contents are checked when they're parsed, so decoding can only
+/// fail if the alphabet's been changed through
+/// [`Base64String::alphabet_mut`] since. In that case the iterator can
+/// end before yielding as many items as its
+/// [`len`](ExactSizeIterator::len)
+pub struct DecodeIter<'a, A> {
+    quads: DecodedQuads<'a, A>,
+    quad: [u8; 3],
+    quad_pos: usize,
+    quad_len: usize,
+    remaining: usize,
+    done: bool,
+}
+
+impl<'a, A> DecodeIter<'a, A>
+where
+    A: Alphabet,
+{
+    fn new(content: &'a str, alphabet: &'a A, decoded_len: usize) -> Self {
+        Self {
+            quads: DecodedQuads::new(content, alphabet),
+            quad: [0; 3],
+            quad_pos: 0,
+            quad_len: 0,
+            remaining: decoded_len,
+            done: false,
+        }
+    }
+}
+
+impl<A> Iterator for DecodeIter<'_, A>
+where
+    A: Alphabet,
+{
+    type Item = Result<u8, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.quad_pos == self.quad_len {
+            if self.done {
+                return None;
+            }
+            match self.quads.next() {
+                Some(Ok((quad, len))) => {
+                    self.quad = quad;
+                    self.quad_pos = 0;
+                    self.quad_len = len;
+                }
+                Some(Err(e)) => {
+                    self.done = true;
+                    self.remaining = 0;
+                    return Some(Err(e.into()));
+                }
+                None => {
+                    self.done = true;
+                    self.remaining = 0;
+                    return None;
+                }
+            }
+        }
+
+        let byte = self.quad[self.quad_pos];
+        self.quad_pos += 1;
+        self.remaining = self.remaining.saturating_sub(1);
+        Some(Ok(byte))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<A> ExactSizeIterator for DecodeIter<'_, A> where A: Alphabet {}
+
+impl<A> FusedIterator for DecodeIter<'_, A> where A: Alphabet {}
+
+/// Shows how many bytes are left rather than the data, like
+/// [`Base64String`]'s [`Debug`](fmt::Debug) impl
+impl<A> fmt::Debug for DecodeIter<'_, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DecodeIter")
+            .field("remaining", &self.remaining)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<A> Base64String<A>
+where
+    A: Alphabet,
+{
+    /// Decode the contents of `self` a byte at a time, without
+    /// allocating the output, for a single pass over the data
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, alphabet::Standard};
+    /// let b64 = Base64String::<Standard>::encode(b"\x89PNG\r\n\x1a\n...");
+    /// let mut bytes = b64.decode_iter();
+    ///
+    /// assert_eq!(bytes.len(), 11);
+    /// assert!(bytes.by_ref().take(4).map(Result::unwrap).eq(*b"\x89PNG"));
+    /// assert_eq!(bytes.len(), 7);
+    /// ```
+    pub fn decode_iter(&self) -> DecodeIter<'_, A> {
+        DecodeIter::new(self.as_ref(), self.alphabet(), self.decoded_len())
+    }
+}
+
+impl<A> Base64Str<'_, A>
+where
+    A: Alphabet,
+{
+    /// Decode the contents of `self` a byte at a time, without
+    /// allocating the output, for a single pass over the data
+    pub fn decode_iter(&self) -> DecodeIter<'_, A> {
+        DecodeIter::new(self.as_str(), self.alphabet(), self.decoded_len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{alphabet, B64Error};
+
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn error_ends_iteration() {
+        let mut b64 =
+            Base64String::from_encoded_with("Zm9v+/8=Zm9v", alphabet::by_name("standard").unwrap())
+                .unwrap();
+        *b64.alphabet_mut() = alphabet::by_name("urlsafe").unwrap();
+        let mut bytes = b64.decode_iter();
+
+        assert_eq!(bytes.len(), 8);
+        for expected in *b"foo" {
+            assert_eq!(bytes.next().unwrap().unwrap(), expected);
+        }
+        assert!(matches!(
+            bytes.next(),
+            Some(Err(DecodeError::Base64Error(B64Error::InvalidCharAt {
+                char: '+',
+                index: 4
+            })))
+        ));
+        assert_eq!(bytes.len(), 0);
+        assert!(bytes.next().is_none());
+        assert!(bytes.next().is_none());
+    }
+
+    #[test]
+    fn exact_size() {
+        let b64 = Base64Str::<alphabet::Standard>::from_encoded("Zm9vYg").unwrap();
+        let mut bytes = b64.decode_iter();
+
+        for len in (0..=4).rev() {
+            assert_eq!(bytes.size_hint(), (len, Some(len)));
+            bytes.next();
+        }
+        assert!(bytes.next().is_none());
+    }
+
+    #[test]
+    fn debug_hides_data() {
+        let b64 = Base64String::<alphabet::Standard>::encode(b"secret");
+
+        assert_eq!(
+            format!("{:?}", b64.decode_iter()),
+            "DecodeIter { remaining: 6, .. }"
+        );
+    }
+}
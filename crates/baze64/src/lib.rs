@@ -19,30 +19,93 @@
 //!
 //! Encode & decode a file:
 //! ```no_run
-//! # use std::{fs::File, io::Read};
+//! # use std::fs::File;
 //! # use baze64::{Base64String, alphabet::Standard};
-//! let mut file = File::open("path/to/file.ext")?;
-//! let mut buffer = Vec::new();
-//! file.read_to_end(&mut buffer)?;
-//! let encoded = Base64String::<Standard>::encode(&buffer);
+//! let file = File::open("path/to/file.ext")?;
+//! let encoded = Base64String::<Standard>::encode_reader(file)?;
 //! let bytes = encoded.decode()?;
-//!
-//! assert_eq!(buffer, bytes);
 //! # Ok::<(), Box<dyn std::error::Error>>(())
 //! ```
 //!
+//! ## Panics
+//!
+//! Parsing & decoding never panic, whatever `&str` they're given.
+//! Invalid input is always reported as an error
+//!
+//! ## Features
+//!
+//! - `base64-compat`: Adds the `compat` module for using baze64
+//!   alongside the [`base64`](https://docs.rs/base64) crate
+//! - `digest`: Adds `Base64String::decode_digest` &
+//!   `Base64String::encode_with_digest`, for hashing data while
+//!   encoding or decoding it with any hash implementing the
+//!   [`digest`](https://docs.rs/digest) crate's `Digest` trait
+//! - `futures-io`: Adds `stream::AsyncBase64Decoder` &
+//!   `stream::AsyncBase64Encoder`, for streaming through
+//!   `AsyncRead` & `AsyncWrite` types
+//! - `rayon`: Adds `Base64String::encode_parallel` &
+//!   `Base64String::decode_parallel` for processing large
+//!   buffers across multiple threads
+//! - `simd`: Encodes & decodes with the [`Standard`](alphabet::Standard)
+//!   & [`UrlSafe`](alphabet::UrlSafe) alphabets using SIMD instructions,
+//!   picked when run from AVX2 & SSSE3 on x86 & x86-64, & NEON on
+//!   AArch64. Other alphabets & CPUs use the scalar code. This is the
+//!   only feature that uses `unsafe`
+//! - `strict-alphabet-checks`: Reports an alphabet decoding a character
+//!   to a value that doesn't fit in 6 bits as
+//!   [`B64Error::DecodedValueOOB`] in release builds too, rather than
+//!   masking the value. Debug builds always check. Useful when using
+//!   third-party alphabets
+//!
+
+#![cfg_attr(not(feature = "simd"), forbid(unsafe_code))]
+#![cfg_attr(feature = "simd", deny(unsafe_code))]
 
 pub mod alphabet;
+mod base64str;
 mod base64string;
+pub mod checked;
+mod codec;
+#[cfg(feature = "base64-compat")]
+pub mod compat;
+pub mod data_uri;
+mod diff;
+mod error;
+mod extract;
+pub mod fixed;
+#[cfg(feature = "digest")]
+mod hash;
+mod hex;
+mod int;
+mod iter;
+mod os_str;
+#[cfg(feature = "rayon")]
+mod parallel;
+pub mod pem;
+pub mod primitives;
+mod quirks;
+pub mod record;
+mod safe;
+pub mod sanitize;
+#[cfg(feature = "simd")]
+#[allow(unsafe_code)]
+mod simd;
+pub mod stream;
+mod text;
+mod validate;
 
-pub use alphabet::{Standard, UrlSafe};
-pub use base64string::{Base64String, DecodeError};
-use thiserror::Error;
-
-#[derive(Debug, Error)]
-pub enum B64Error {
-    #[error("Value `{0}` is outsite the 6-bit integer range")]
-    BitsOOB(u8),
-    #[error("Invalid Base64 character `{0}`")]
-    InvalidChar(char),
-}
+pub use alphabet::{Permissive, Standard, UrlSafe};
+pub use base64str::Base64Str;
+pub use base64string::Base64String;
+pub use codec::Codec;
+pub use diff::{diff_decoded, Divergence, DIFF_CONTEXT};
+pub use error::{
+    B64Error, DecodeError, DecodeIntoError, EncodeReaderError, ErrorKind, HexError, TransformError,
+    YamlError,
+};
+pub use extract::extract;
+pub use hex::{HexCase, HexDumpConfig};
+pub use iter::DecodeIter;
+pub use quirks::DecodeCompat;
+pub use text::TextEncoding;
+pub use validate::validate;
@@ -0,0 +1,246 @@
+//! Encode OS strings & paths exactly, including ones that aren't
+//! valid Unicode, like filenames on Unix that aren't UTF-8
+
+#[cfg(any(unix, windows))]
+use std::{
+    ffi::{OsStr, OsString},
+    path::{Path, PathBuf},
+};
+
+use crate::DecodeError;
+#[cfg(any(unix, windows))]
+use crate::{alphabet::Alphabet, Base64String};
+
+#[cfg(any(unix, windows))]
+impl<A> Base64String<A>
+where
+    A: Alphabet,
+{
+    /// Encode the OS string `s` into a [`Base64String`] using a given
+    /// `alphabet` instance, keeping it exactly even if it isn't valid
+    /// Unicode
+    ///
+    /// ## Format
+    ///
+    /// The bytes encoded are version 1 of this format, which any change
+    /// would be a breaking one for. Tokens are the same whichever run
+    /// of a program makes them, & valid Unicode is encoded as UTF-8 on
+    /// every platform
+    ///
+    /// - On Unix, the bytes of the string as they are, from
+    ///   [`OsStrExt::as_bytes`](std::os::unix::ffi::OsStrExt::as_bytes)
+    /// - On Windows, the string's UTF-16 code units from
+    ///   `OsStrExt::encode_wide` as
+    ///   [WTF-8](https://simonsapin.github.io/wtf-8/), which is UTF-8
+    ///   that can also encode unpaired surrogates, each as 3 bytes like
+    ///   any other code point from `U+0800` to `U+FFFF`
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::ffi::OsStr;
+    /// # use baze64::{Base64String, alphabet::UrlSafe};
+    /// let token = Base64String::encode_os_str_with(OsStr::new("notes.txt"), UrlSafe::new());
+    ///
+    /// assert_eq!(token.to_string(), "bm90ZXMudHh0");
+    /// assert_eq!(token.decode_to_os_string()?, "notes.txt");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn encode_os_str_with(s: &OsStr, alphabet: A) -> Self {
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStrExt;
+            Self::encode_with(s.as_bytes(), alphabet)
+        }
+        #[cfg(windows)]
+        {
+            use std::os::windows::ffi::OsStrExt;
+            Self::encode_with(encode_wtf8(s.encode_wide()), alphabet)
+        }
+    }
+
+    /// Encode `path` into a [`Base64String`] using a given `alphabet`
+    /// instance, the same as [`Base64String::encode_os_str_with`]
+    pub fn encode_path_with(path: &Path, alphabet: A) -> Self {
+        Self::encode_os_str_with(path.as_os_str(), alphabet)
+    }
+
+    /// Decode the contents of `self` into an [`OsString`], as
+    /// encoded by [`Base64String::encode_os_str_with`]
+    ///
+    /// On Windows, fails with [`DecodeError::InvalidOsString`] if the
+    /// data isn't WTF-8. On Unix any bytes are a valid OS string, so
+    /// this only fails if the base64 doesn't decode
+    pub fn decode_to_os_string(&self) -> Result<OsString, DecodeError> {
+        let bytes = self.decode()?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStringExt;
+            Ok(OsString::from_vec(bytes))
+        }
+        #[cfg(windows)]
+        {
+            use std::os::windows::ffi::OsStringExt;
+            Ok(OsString::from_wide(&decode_wtf8(&bytes)?))
+        }
+    }
+
+    /// Decode the contents of `self` into a [`PathBuf`], the same as
+    /// [`Base64String::decode_to_os_string`]
+    pub fn decode_to_path_buf(&self) -> Result<PathBuf, DecodeError> {
+        self.decode_to_os_string().map(PathBuf::from)
+    }
+}
+
+#[cfg(any(unix, windows))]
+impl<A> Base64String<A>
+where
+    A: Alphabet + Default,
+{
+    /// Encode the OS string `s` into a [`Base64String`]
+    ///
+    /// Uses `A`'s [`Default`] impl as the alphabet
+    /// to encode with
+    pub fn encode_os_str(s: &OsStr) -> Self {
+        Self::encode_os_str_with(s, A::default())
+    }
+
+    /// Encode `path` into a [`Base64String`]
+    ///
+    /// Uses `A`'s [`Default`] impl as the alphabet
+    /// to encode with
+    pub fn encode_path(path: &Path) -> Self {
+        Self::encode_path_with(path, A::default())
+    }
+}
+
+/// Encode UTF-16 code units as WTF-8
+#[cfg_attr(not(windows), allow(dead_code))]
+fn encode_wtf8<I>(units: I) -> Vec<u8>
+where
+    I: IntoIterator<Item = u16>,
+{
+    let mut bytes = Vec::new();
+    for c in char::decode_utf16(units) {
+        match c {
+            Ok(c) => bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes()),
+            Err(e) => {
+                let unit = e.unpaired_surrogate();
+                bytes.extend_from_slice(&[
+                    0xe0 | (unit >> 12) as u8,
+                    0x80 | (unit >> 6 & 0x3f) as u8,
+                    0x80 | (unit & 0x3f) as u8,
+                ]);
+            }
+        }
+    }
+
+    bytes
+}
+
+/// Decode WTF-8 into UTF-16 code units, rejecting anything that isn't
+/// WTF-8, including a surrogate pair encoded as two surrogates
+#[cfg_attr(not(windows), allow(dead_code))]
+fn decode_wtf8(bytes: &[u8]) -> Result<Vec<u16>, DecodeError> {
+    let mut units = Vec::with_capacity(bytes.len());
+    let mut offset = 0;
+    let mut after_lead = false;
+
+    while offset < bytes.len() {
+        let invalid = DecodeError::InvalidOsString { offset };
+        let first = bytes[offset];
+        // How many bytes the code point takes, & the range its
+        // second byte has to be in to not be overlong or too large
+        let (len, second) = match first {
+            0x00..=0x7f => (1, 0..=0),
+            0xc2..=0xdf => (2, 0x80..=0xbf),
+            0xe0 => (3, 0xa0..=0xbf),
+            0xe1..=0xef => (3, 0x80..=0xbf),
+            0xf0 => (4, 0x90..=0xbf),
+            0xf1..=0xf3 => (4, 0x80..=0xbf),
+            0xf4 => (4, 0x80..=0x8f),
+            _ => return Err(invalid),
+        };
+        let Some(rest) = bytes.get(offset + 1..offset + len) else {
+            return Err(invalid);
+        };
+        if len > 1 && !second.contains(&rest[0]) || rest.iter().any(|b| b & 0xc0 != 0x80) {
+            return Err(invalid);
+        }
+
+        let lead_bits = if len == 1 { first } else { first & 0x7f >> len };
+        let code_point = rest
+            .iter()
+            .fold(u32::from(lead_bits), |cp, b| cp << 6 | u32::from(b & 0x3f));
+        let is_trail = (0xdc00..=0xdfff).contains(&code_point);
+        // A lead & trail surrogate together have to be encoded as the
+        // code point they make up
+        if after_lead && is_trail {
+            return Err(invalid);
+        }
+        after_lead = (0xd800..=0xdbff).contains(&code_point);
+
+        match char::from_u32(code_point) {
+            Some(c) => units.extend_from_slice(c.encode_utf16(&mut [0; 2])),
+            // Only surrogates aren't chars, & they're a single unit
+            None => units.push(code_point as u16),
+        }
+        offset += len;
+    }
+
+    Ok(units)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ErrorKind;
+
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn wtf8_matches_utf8() {
+        let text = "a/é/€/🦀";
+
+        assert_eq!(encode_wtf8(text.encode_utf16()), text.as_bytes());
+        assert_eq!(
+            decode_wtf8(text.as_bytes()).unwrap(),
+            text.encode_utf16().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn wtf8_unpaired_surrogates() {
+        for units in [
+            &[0xd800][..],
+            &[0x61, 0xdbff, 0x62],
+            &[0xdc00, 0xd800],
+            &[0xd83e, 0xdd80, 0xdfff],
+        ] {
+            let bytes = encode_wtf8(units.iter().copied());
+            assert_eq!(decode_wtf8(&bytes).unwrap(), units);
+        }
+        assert_eq!(encode_wtf8([0xd800]), [0xed, 0xa0, 0x80]);
+    }
+
+    #[test]
+    fn wtf8_errors() {
+        for (bytes, at) in [
+            // A surrogate pair encoded as two surrogates
+            (&[0xed, 0xa0, 0xbd, 0xed, 0xb6, 0x80][..], 3),
+            (&[b'a', 0xff], 1),
+            (&[0xc0, 0x80], 0),
+            (&[0xe0, 0x80, 0x80], 0),
+            (&[0xf4, 0x90, 0x80, 0x80], 0),
+            (&[b'a', 0xe2, 0x82], 1),
+            (&[0xc3, b'a'], 0),
+        ] {
+            let err = decode_wtf8(bytes).unwrap_err();
+            assert!(
+                matches!(err, DecodeError::InvalidOsString { offset } if offset == at),
+                "{bytes:x?}"
+            );
+            assert_eq!(err.kind(), ErrorKind::InvalidOsString);
+        }
+    }
+}
@@ -0,0 +1,212 @@
+//! Encode & decode large buffers across multiple threads using
+//! [`rayon`]
+
+use rayon::prelude::*;
+
+use crate::{alphabet::Alphabet, base64string::DecodedQuads, B64Error, Base64String, DecodeError};
+
+/// The number of bytes encoded per task, a multiple of 3 so
+/// that only the final chunk can need padding
+const ENCODE_CHUNK_LEN: usize = 3 * 4096;
+/// The number of characters decoded per task, encoding the same
+/// amount of data as [`ENCODE_CHUNK_LEN`] bytes
+const DECODE_CHUNK_LEN: usize = ENCODE_CHUNK_LEN / 3 * 4;
+/// Inputs smaller than this many bytes are processed on the
+/// current thread, as splitting them up isn't worth the overhead
+const PARALLEL_THRESHOLD: usize = 16 * ENCODE_CHUNK_LEN;
+
+impl<A> Base64String<A>
+where
+    A: Alphabet + Sync,
+{
+    /// Encode a sequence of bytes into a [`Base64String`] using a
+    /// given `alphabet` instance, across multiple threads
+    ///
+    /// The output is identical to [`Base64String::encode_with`],
+    /// which is used directly for small inputs
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, alphabet::Standard};
+    /// let data = vec![0x2a; 1 << 20];
+    /// let encoded = Base64String::encode_parallel(&data, Standard::new());
+    ///
+    /// assert_eq!(encoded, Base64String::encode_with(&data, Standard::new()));
+    /// ```
+    pub fn encode_parallel<B>(bytes: B, alphabet: A) -> Self
+    where
+        B: AsRef<[u8]>,
+    {
+        let bytes = bytes.as_ref();
+        if bytes.len() < PARALLEL_THRESHOLD {
+            Self::encode_with(bytes, alphabet)
+        } else {
+            Self::encode_chunked(bytes, alphabet, ENCODE_CHUNK_LEN)
+        }
+    }
+
+    /// Decode the contents of `self` into a byte sequence, across
+    /// multiple threads
+    ///
+    /// The output is identical to [`Base64String::decode`], which is
+    /// used directly for small inputs
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, alphabet::Standard};
+    /// let data = vec![0x2a; 1 << 20];
+    /// let encoded = Base64String::<Standard>::encode(&data);
+    ///
+    /// assert_eq!(encoded.decode_parallel()?, data);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn decode_parallel(&self) -> Result<Vec<u8>, DecodeError> {
+        if self.as_ref().len() < PARALLEL_THRESHOLD / 3 * 4 {
+            self.decode()
+        } else {
+            self.decode_chunked(DECODE_CHUNK_LEN)
+        }
+    }
+
+    /// Encode `bytes` in parallel chunks of `chunk_len` bytes, which
+    /// must be a multiple of 3
+    fn encode_chunked(bytes: &[u8], alphabet: A, chunk_len: usize) -> Self {
+        debug_assert_eq!(chunk_len % 3, 0);
+
+        let content = bytes
+            .par_chunks(chunk_len)
+            .map(|chunk| {
+                let mut encoded = String::with_capacity(chunk.len().div_ceil(3) * 4);
+                Self::push_encoded(&mut encoded, chunk, &alphabet);
+                encoded
+            })
+            .collect::<Vec<_>>()
+            .concat();
+
+        Self::from_parts(content, alphabet)
+    }
+
+    /// Decode `self` in parallel chunks of `chunk_len` characters,
+    /// which must be a multiple of 4
+    fn decode_chunked(&self, chunk_len: usize) -> Result<Vec<u8>, DecodeError> {
+        debug_assert_eq!(chunk_len % 4, 0);
+
+        let content = self.as_ref();
+        let mut bounds = content
+            .char_indices()
+            .step_by(chunk_len)
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+        bounds.push(content.len());
+
+        let decoded = bounds
+            .par_windows(2)
+            .enumerate()
+            .map(|(n, window)| {
+                let chunk = &content[window[0]..window[1]];
+                let mut decoded = Vec::with_capacity(chunk_len / 4 * 3);
+                for quad in DecodedQuads::new(chunk, self.alphabet()) {
+                    let (tri, len) = quad.map_err(|e| e.offset_by(n * chunk_len))?;
+                    decoded.extend_from_slice(&tri[..len]);
+                }
+
+                // Only the last chunk can end with padding
+                let tail = decoded.len() % 3;
+                if tail != 0 && window[1] != content.len() {
+                    let index = chunk_len - 3 + tail;
+                    return Err(B64Error::InvalidCharAt {
+                        char: chunk.chars().nth(index).unwrap_or_default(),
+                        index: n * chunk_len + index,
+                    });
+                }
+                Ok(decoded)
+            })
+            .collect::<Result<Vec<_>, B64Error>>()?;
+
+        Ok(decoded.concat())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alphabet::{Standard, UrlSafe};
+
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn encode_matches_sequential(
+            data in prop::collection::vec(any::<u8>(), 0..4096),
+            chunks in 1..64usize,
+        ) {
+            let parallel = Base64String::encode_chunked(&data, Standard::new(), chunks * 3);
+            let sequential = Base64String::encode_with(&data, Standard::new());
+
+            prop_assert_eq!(parallel.to_string(), sequential.to_string());
+        }
+
+        #[test]
+        fn decode_matches_sequential(
+            data in prop::collection::vec(any::<u8>(), 0..4096),
+            chunks in 1..64usize,
+        ) {
+            let encoded = Base64String::encode_with(&data, UrlSafe::new());
+
+            prop_assert_eq!(encoded.decode_chunked(chunks * 4).unwrap(), encoded.decode().unwrap());
+        }
+    }
+
+    #[test]
+    fn above_threshold() {
+        let data = (0..PARALLEL_THRESHOLD * 2 + 1)
+            .map(|i| i as u8)
+            .collect::<Vec<_>>();
+        let encoded = Base64String::encode_parallel(&data, Standard::new());
+
+        assert_eq!(encoded, Base64String::encode_with(&data, Standard::new()));
+        assert_eq!(encoded.decode_parallel().unwrap(), data);
+    }
+
+    #[test]
+    fn decode_error_index() {
+        let content = "A".repeat(16) + "#AAA";
+        let b64 = Base64String::from_parts(content, Standard::new());
+
+        assert!(matches!(
+            b64.decode_chunked(8),
+            Err(DecodeError::Base64Error(B64Error::InvalidCharAt {
+                char: '#',
+                index: 16
+            }))
+        ));
+    }
+
+    #[test]
+    fn decode_truncated_length() {
+        let b64 = Base64String::from_parts("A".repeat(17), Standard::new());
+
+        assert!(matches!(
+            b64.decode_chunked(8),
+            Err(DecodeError::Base64Error(B64Error::Truncated {
+                expected: 18,
+                actual: 17
+            }))
+        ));
+    }
+
+    #[test]
+    fn decode_interior_padding() {
+        // Padding at the end of a chunk, then within one
+        for content in ["AAAAAA==AAAA", "AAAAAAAAAA==AAAA"] {
+            let b64 = Base64String::from_parts(content.to_string(), Standard::new());
+
+            assert_eq!(
+                format!("{:?}", b64.decode_chunked(8)),
+                format!("{:?}", b64.decode())
+            );
+            assert!(b64.decode_chunked(8).is_err());
+        }
+    }
+}
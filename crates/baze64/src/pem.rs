@@ -0,0 +1,284 @@
+//! Produce & parse PEM armored data, as used for certificates &
+//! keys, as defined in RFC 7468
+//!
+//! ## Examples
+//! ```
+//! # use baze64::pem::PemBlock;
+//! let block = PemBlock::new("MESSAGE", b"hello");
+//! let armored = block.to_string();
+//!
+//! assert_eq!(armored, "-----BEGIN MESSAGE-----\naGVsbG8=\n-----END MESSAGE-----\n");
+//! assert_eq!(PemBlock::parse(&armored)?, block);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use core::fmt;
+
+use crate::{alphabet::Standard, Base64String, DecodeError};
+
+const BEGIN: &str = "-----BEGIN ";
+const END: &str = "-----END ";
+const DASHES: &str = "-----";
+
+/// A block of data armored with `-----BEGIN <label>-----` &
+/// `-----END <label>-----` lines
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PemBlock {
+    /// The label naming what the data is, like `CERTIFICATE`
+    pub label: String,
+    /// The decoded data
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug)]
+pub enum PemError {
+    MissingBegin,
+    MissingEnd { label: String },
+    LabelMismatch { begin: String, end: String },
+    Base64Error(DecodeError),
+}
+
+impl fmt::Display for PemError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PemError::MissingBegin => write!(f, "No `-----BEGIN <label>-----` line found"),
+            PemError::MissingEnd { label } => write!(f, "No `-----END {label}-----` line found"),
+            PemError::LabelMismatch { begin, end } => {
+                write!(f, "Block begins with label `{begin}` but ends with `{end}`")
+            }
+            PemError::Base64Error(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for PemError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PemError::Base64Error(e) => e.source(),
+            _ => None,
+        }
+    }
+}
+
+impl From<DecodeError> for PemError {
+    fn from(value: DecodeError) -> Self {
+        PemError::Base64Error(value)
+    }
+}
+
+impl PemBlock {
+    /// Create a [`PemBlock`] holding `data`, labelled with `label`
+    pub fn new<S, B>(label: S, data: B) -> Self
+    where
+        S: ToString,
+        B: Into<Vec<u8>>,
+    {
+        Self {
+            label: label.to_string(),
+            data: data.into(),
+        }
+    }
+
+    /// Parse the first PEM block in `text`
+    ///
+    /// Text before & after the block is ignored, lines may end with
+    /// either `\n` or `\r\n`, & any whitespace in the base64 body is
+    /// skipped
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::pem::PemBlock;
+    /// let text = "Some notes\r\n-----BEGIN DATA-----\r\nZm9v\r\nYmFy\r\n-----END DATA-----\r\n";
+    /// let block = PemBlock::parse(text)?;
+    ///
+    /// assert_eq!(block.label, "DATA");
+    /// assert_eq!(block.data, b"foobar");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn parse(text: &str) -> Result<Self, PemError> {
+        let mut lines = text.lines();
+        Self::parse_next(&mut lines)?.ok_or(PemError::MissingBegin)
+    }
+
+    /// Parse every PEM block in `text`, in order
+    ///
+    /// Text between blocks is ignored, so a certificate chain or a
+    /// key alongside its certificate can be read in one go
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::pem::PemBlock;
+    /// let chain = [PemBlock::new("CERTIFICATE", b"leaf"), PemBlock::new("CERTIFICATE", b"root")];
+    /// let text = chain.iter().map(|block| block.to_string()).collect::<String>();
+    ///
+    /// assert_eq!(PemBlock::parse_all(&text)?, chain);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn parse_all(text: &str) -> Result<Vec<Self>, PemError> {
+        let mut lines = text.lines();
+        let mut blocks = vec![];
+        while let Some(block) = Self::parse_next(&mut lines)? {
+            blocks.push(block);
+        }
+
+        Ok(blocks)
+    }
+
+    /// Parse the next block from `lines`, or [`None`] if there are
+    /// no more
+    fn parse_next<'a, I>(lines: &mut I) -> Result<Option<Self>, PemError>
+    where
+        I: Iterator<Item = &'a str>,
+    {
+        let Some(label) = lines.find_map(|line| armor_label(line, BEGIN)) else {
+            return Ok(None);
+        };
+
+        let mut body = String::new();
+        for line in lines {
+            if let Some(end) = armor_label(line, END) {
+                if end != label {
+                    return Err(PemError::LabelMismatch {
+                        begin: label.to_string(),
+                        end: end.to_string(),
+                    });
+                }
+
+                let data = Base64String::<Standard>::from_encoded(body)
+                    .map_err(DecodeError::from)?
+                    .decode()?;
+                return Ok(Some(Self::new(label, data)));
+            }
+            body.extend(line.chars().filter(|c| !c.is_whitespace()));
+        }
+
+        Err(PemError::MissingEnd {
+            label: label.to_string(),
+        })
+    }
+}
+
+/// Get the label from a `-----BEGIN <label>-----` or
+/// `-----END <label>-----` line, depending on `prefix`
+fn armor_label<'a>(line: &'a str, prefix: &str) -> Option<&'a str> {
+    line.trim()
+        .strip_prefix(prefix)
+        .and_then(|rest| rest.strip_suffix(DASHES))
+}
+
+/// Formats the block with its base64 body wrapped at 64 columns,
+/// ending with a newline
+impl fmt::Display for PemBlock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{BEGIN}{}{DASHES}", self.label)?;
+        if !self.data.is_empty() {
+            writeln!(f, "{:#}", Base64String::<Standard>::encode(&self.data))?;
+        }
+        writeln!(f, "{END}{}{DASHES}", self.label)
+    }
+}
+
+impl core::str::FromStr for PemBlock {
+    type Err = PemError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::B64Error;
+    use pretty_assertions::assert_eq;
+
+    /// A self-signed P-256 certificate, as written by OpenSSL
+    const CERT: &str = include_str!("../tests/fixtures/cert.pem");
+
+    #[test]
+    fn certificate_roundtrip() {
+        let block = PemBlock::parse(CERT).unwrap();
+
+        assert_eq!(block.label, "CERTIFICATE");
+        assert_eq!(block.data.len(), 395);
+        // A DER `SEQUENCE` with a 2 byte length
+        assert_eq!(&block.data[..4], [0x30, 0x82, 0x01, 0x87]);
+        assert_eq!(block.to_string(), CERT);
+    }
+
+    #[test]
+    fn crlf() {
+        let block = PemBlock::parse(&CERT.replace('\n', "\r\n")).unwrap();
+
+        assert_eq!(block, PemBlock::parse(CERT).unwrap());
+    }
+
+    #[test]
+    fn surrounding_text() {
+        let text = format!("subject=CN = baze64.example\n\n{CERT}\ntrailing notes");
+
+        assert_eq!(
+            PemBlock::parse(&text).unwrap(),
+            PemBlock::parse(CERT).unwrap()
+        );
+    }
+
+    #[test]
+    fn whitespace_in_body() {
+        let text = "-----BEGIN DATA-----\n  Zm9v Ym\tFy  \n\n-----END DATA-----";
+
+        assert_eq!(PemBlock::parse(text).unwrap().data, b"foobar");
+    }
+
+    #[test]
+    fn empty_data() {
+        let block = PemBlock::new("EMPTY", vec![]);
+        let armored = block.to_string();
+
+        assert_eq!(armored, "-----BEGIN EMPTY-----\n-----END EMPTY-----\n");
+        assert_eq!(PemBlock::parse(&armored).unwrap(), block);
+    }
+
+    #[test]
+    fn parse_all() {
+        let key = PemBlock::new("PRIVATE KEY", [0x2a; 100]);
+        let text = format!("{key}\nsome text\n{CERT}");
+        let blocks = PemBlock::parse_all(&text).unwrap();
+
+        assert_eq!(blocks, [key, PemBlock::parse(CERT).unwrap()]);
+        assert!(PemBlock::parse_all("no blocks").unwrap().is_empty());
+    }
+
+    #[test]
+    fn label_mismatch() {
+        assert!(matches!(
+            PemBlock::parse("-----BEGIN FOO-----\nZm9v\n-----END BAR-----"),
+            Err(PemError::LabelMismatch { begin, end }) if begin == "FOO" && end == "BAR"
+        ));
+    }
+
+    #[test]
+    fn missing_armor() {
+        assert!(matches!(
+            PemBlock::parse("Zm9v"),
+            Err(PemError::MissingBegin)
+        ));
+        assert!(matches!(
+            PemBlock::parse("-----BEGIN FOO-----\nZm9v\n"),
+            Err(PemError::MissingEnd { label }) if label == "FOO"
+        ));
+    }
+
+    #[test]
+    fn invalid_base64() {
+        assert!(matches!(
+            PemBlock::parse("-----BEGIN FOO-----\nZm9v#mFy\n-----END FOO-----"),
+            Err(PemError::Base64Error(DecodeError::Base64Error(
+                B64Error::InvalidCharAt {
+                    char: '#',
+                    index: 4
+                }
+            )))
+        ));
+    }
+}
@@ -0,0 +1,394 @@
+//! Encode & decode single blocks of base64, for building formats
+//! that frame base64 differently to [`Base64String`](crate::Base64String)
+//!
+//! A block is 3 bytes of data & the 4 characters encoding them.
+//! [`Base64String`](crate::Base64String) is built on these, so they
+//! always agree with it
+//!
+//! Encoding masks every value to 6 bits before handing it to
+//! [`Alphabet::encode_bits`], so alphabets are only ever asked about
+//! values below 64. Decoding checks [`Alphabet::decode_char`] only
+//! gives values below 64 back, as the alphabet's contract requires,
+//! returning [`B64Error::DecodedValueOOB`] for any that don't in debug
+//! builds or with the `strict-alphabet-checks` feature. Otherwise
+//! they're masked to 6 bits, so they can't spill into other bytes
+//!
+//! ## Examples
+//!
+//! Encode data in lines of `LINE` bytes, each followed by the sum of
+//! its bytes:
+//! ```
+//! # use baze64::{alphabet::{Alphabet, Standard}, primitives};
+//! const LINE: usize = 6;
+//!
+//! fn encode_summed<A: Alphabet>(data: &[u8], alphabet: &A) -> String {
+//!     let mut out = String::new();
+//!     for line in data.chunks(LINE) {
+//!         let blocks = line.chunks_exact(3);
+//!         let rest = blocks.remainder();
+//!         for block in blocks {
+//!             out.extend(primitives::encode_block(block.try_into().unwrap(), alphabet));
+//!         }
+//!         if !rest.is_empty() {
+//!             let (chars, len) = primitives::encode_partial_block(rest, alphabet);
+//!             out.extend(&chars[..len]);
+//!         }
+//!
+//!         let sum = line.iter().fold(0u8, |sum, &b| sum.wrapping_add(b));
+//!         out.push_str(&format!(" {sum:02x}\n"));
+//!     }
+//!     out
+//! }
+//!
+//! assert_eq!(
+//!     encode_summed(b"foobarbaz", &Standard::new()),
+//!     "Zm9vYmFy 79\nYmF6 3d\n"
+//! );
+//! ```
+
+use crate::{alphabet::Alphabet, B64Error};
+
+/// Encode 3 bytes as 4 characters of `alphabet`
+///
+/// # Examples
+/// ```
+/// # use baze64::{alphabet::Standard, primitives};
+/// assert_eq!(primitives::encode_block(b"foo", &Standard::new()), ['Z', 'm', '9', 'v']);
+/// ```
+pub fn encode_block<A>(input: &[u8; 3], alphabet: &A) -> [char; 4]
+where
+    A: Alphabet,
+{
+    let [a, b, c] = *input;
+    let concated = ((a as u32) << 16) | ((b as u32) << 8) | c as u32;
+
+    [
+        alphabet.encode_bits(((concated >> 18) & 0b0011_1111) as u8),
+        alphabet.encode_bits(((concated >> 12) & 0b0011_1111) as u8),
+        alphabet.encode_bits(((concated >> 6) & 0b0011_1111) as u8),
+        alphabet.encode_bits((concated & 0b0011_1111) as u8),
+    ]
+}
+
+/// Encode the last 1 to 3 bytes of some data, padding the block if
+/// `alphabet` has padding
+///
+/// Returns the characters along with how many of them to use, which
+/// is 4 if the block is padded or full. The unused bits of the last
+/// character are always zero
+///
+/// # Panics
+///
+/// If `input` isn't between 1 & 3 bytes long
+///
+/// # Examples
+/// ```
+/// # use baze64::{alphabet::UrlSafe, primitives};
+/// assert_eq!(primitives::encode_partial_block(&[0xfb], &UrlSafe::new()), (['-', 'w', '=', '='], 4));
+/// ```
+pub fn encode_partial_block<A>(input: &[u8], alphabet: &A) -> ([char; 4], usize)
+where
+    A: Alphabet,
+{
+    let used = match input.len() {
+        1..=3 => input.len() + 1,
+        len => panic!("a partial block is 1 to 3 bytes, not {len}"),
+    };
+    let mut block = [0; 3];
+    block[..input.len()].copy_from_slice(input);
+    let mut chars = encode_block(&block, alphabet);
+
+    match alphabet.padding() {
+        Some(p) => {
+            chars[used..].fill(p);
+            (chars, 4)
+        }
+        None => {
+            chars[used..].fill('\0');
+            (chars, used)
+        }
+    }
+}
+
+/// Decode 4 characters of `alphabet` into the 3 bytes they encode
+///
+/// Every character must be data, so the padding character is an
+/// error. Errors give the index of the invalid character in `block`
+///
+/// # Examples
+/// ```
+/// # use baze64::{alphabet::Standard, primitives, B64Error};
+/// let standard = Standard::new();
+/// assert_eq!(primitives::decode_block(['Z', 'm', '9', 'v'], &standard)?, *b"foo");
+///
+/// assert_eq!(
+///     primitives::decode_block(['Z', 'm', '#', 'v'], &standard),
+///     Err(B64Error::InvalidCharAt { char: '#', index: 2 })
+/// );
+/// # Ok::<(), B64Error>(())
+/// ```
+pub fn decode_block<A>(block: [char; 4], alphabet: &A) -> Result<[u8; 3], B64Error>
+where
+    A: Alphabet,
+{
+    decode_chars(&block, alphabet)
+}
+
+/// Decode the last block of some base64, which may be padded or
+/// only 2 or 3 characters long
+///
+/// Returns the bytes along with how many of them are data. Bits
+/// left over in the last character aren't checked, so `Zh==` gives
+/// the same byte as `Zg==`. Errors give the index of the invalid
+/// character in `block`, or [`B64Error::Truncated`] if it's too
+/// short to hold any data or only has some of its padding
+///
+/// # Examples
+/// ```
+/// # use baze64::{alphabet::Standard, primitives, B64Error};
+/// let standard = Standard::new();
+/// assert_eq!(primitives::decode_partial_block(&['Z', 'm', '8', '='], &standard)?, (*b"fo\0", 2));
+/// assert_eq!(primitives::decode_partial_block(&['Z', 'm', '8'], &standard)?, (*b"fo\0", 2));
+/// assert_eq!(primitives::decode_partial_block(&['Z', 'm', '9', 'v'], &standard)?, (*b"foo", 3));
+///
+/// assert_eq!(
+///     primitives::decode_partial_block(&['Z'], &standard),
+///     Err(B64Error::Truncated { expected: 2, actual: 1 })
+/// );
+/// assert_eq!(
+///     primitives::decode_partial_block(&['Z', '=', '=', '='], &standard),
+///     Err(B64Error::Truncated { expected: 2, actual: 1 })
+/// );
+/// assert_eq!(
+///     primitives::decode_partial_block(&['Z', 'm', '='], &standard),
+///     Err(B64Error::Truncated { expected: 4, actual: 3 })
+/// );
+/// # Ok::<(), B64Error>(())
+/// ```
+pub fn decode_partial_block<A>(block: &[char], alphabet: &A) -> Result<([u8; 3], usize), B64Error>
+where
+    A: Alphabet,
+{
+    let padding = alphabet.padding();
+    let len = match *block {
+        // Padding after the end of the data
+        [c, ..] if Some(c) == padding => return Err(B64Error::InvalidCharAt { char: c, index: 0 }),
+        // A lone character can't hold a whole byte, whatever padding
+        // follows it
+        [_, ref rest @ ..] if rest.iter().all(|&c| Some(c) == padding) => {
+            return Err(B64Error::Truncated {
+                expected: 2,
+                actual: 1,
+            })
+        }
+        [_, _, c, d] if Some(c) == padding && Some(d) == padding => 1,
+        [_, _, _, d] if Some(d) == padding => 2,
+        [_, _, _, _] => 3,
+        // Only some of the padding, which means the input was cut
+        // short rather than left unpadded
+        [_, _, c] if Some(c) == padding => {
+            return Err(B64Error::Truncated {
+                expected: 4,
+                actual: 3,
+            })
+        }
+        [_, _, _] => 2,
+        [_, _] => 1,
+        _ => {
+            return Err(B64Error::Truncated {
+                expected: 1,
+                actual: 0,
+            })
+        }
+    };
+
+    decode_chars(&block[..len + 1], alphabet).map(|tri| (tri, len))
+}
+
+/// Whether values from [`Alphabet::decode_char`] that don't fit in 6
+/// bits are reported rather than masked
+pub(crate) const STRICT_CHECKS: bool =
+    cfg!(any(debug_assertions, feature = "strict-alphabet-checks"));
+
+/// Decode a single character with `alphabet`, checking the value
+/// fits in 6 bits when [`STRICT_CHECKS`] is on & masking it otherwise
+pub(crate) fn decode_bits<A>(alphabet: &A, c: char) -> Result<u8, B64Error>
+where
+    A: Alphabet + ?Sized,
+{
+    let value = alphabet.decode_char(c)?;
+    if value < 64 {
+        Ok(value)
+    } else if STRICT_CHECKS {
+        Err(B64Error::DecodedValueOOB { char: c, value })
+    } else {
+        Ok(value & 0b0011_1111)
+    }
+}
+
+/// Decode up to 4 characters, with any missing characters treated as
+/// zero bits
+///
+/// Bit fuckery courtesey of
+/// [Matheus Gomes](https://matgomes.com/base64-encode-decode-cpp)
+fn decode_chars<A>(chars: &[char], alphabet: &A) -> Result<[u8; 3], B64Error>
+where
+    A: Alphabet,
+{
+    let mut concat_bytes = 0;
+    for (i, &c) in chars.iter().take(4).enumerate() {
+        let bits = decode_bits(alphabet, c).map_err(|e| match e {
+            B64Error::InvalidChar(c) => B64Error::InvalidCharAt { char: c, index: i },
+            e => e,
+        })?;
+        concat_bytes |= (bits as u32) << (18 - 6 * i);
+    }
+
+    Ok([
+        ((concat_bytes >> 16) & 0b1111_1111) as u8,
+        ((concat_bytes >> 8) & 0b1111_1111) as u8,
+        (concat_bytes & 0b1111_1111) as u8,
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alphabet::{Standard, UrlSafe};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn encode_block_masks() {
+        assert_eq!(
+            encode_block(&[0xff; 3], &Standard::new()),
+            ['/', '/', '/', '/']
+        );
+        assert_eq!(encode_block(&[0; 3], &UrlSafe::new()), ['A'; 4]);
+    }
+
+    #[test]
+    fn partial_blocks() {
+        let standard = Standard::new();
+
+        assert_eq!(
+            encode_partial_block(b"f", &standard),
+            (['Z', 'g', '=', '='], 4)
+        );
+        assert_eq!(
+            encode_partial_block(b"fo", &standard),
+            (['Z', 'm', '8', '='], 4)
+        );
+        assert_eq!(
+            encode_partial_block(b"foo", &standard),
+            (['Z', 'm', '9', 'v'], 4)
+        );
+    }
+
+    /// The standard alphabet without padding
+    struct Unpadded;
+
+    impl Alphabet for Unpadded {
+        fn padding(&self) -> Option<char> {
+            None
+        }
+
+        fn encode_bits(&self, bits: u8) -> char {
+            Standard::new().encode_bits(bits)
+        }
+
+        fn decode_char(&self, c: char) -> Result<u8, B64Error> {
+            Standard::new().decode_char(c)
+        }
+    }
+
+    #[test]
+    fn unpadded_partial_blocks() {
+        let unpadded = Unpadded;
+
+        assert_eq!(
+            encode_partial_block(b"f", &unpadded),
+            (['Z', 'g', '\0', '\0'], 2)
+        );
+        assert_eq!(encode_partial_block(b"fo", &unpadded).1, 3);
+        assert_eq!(encode_partial_block(b"foo", &unpadded).1, 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "not 0")]
+    fn empty_partial_block() {
+        encode_partial_block(b"", &Standard::new());
+    }
+
+    #[test]
+    fn decode_block_rejects_padding() {
+        assert_eq!(
+            decode_block(['Z', 'm', '8', '='], &Standard::new()),
+            Err(B64Error::InvalidCharAt {
+                char: '=',
+                index: 3
+            })
+        );
+    }
+
+    #[test]
+    fn decode_partial_block_lengths() {
+        let standard = Standard::new();
+
+        assert_eq!(
+            decode_partial_block(&['Z', 'g', '=', '='], &standard),
+            Ok((*b"f\0\0", 1))
+        );
+        assert_eq!(
+            decode_partial_block(&['Z', 'g'], &standard),
+            Ok((*b"f\0\0", 1))
+        );
+        assert_eq!(
+            decode_partial_block(&[], &standard),
+            Err(B64Error::Truncated {
+                expected: 1,
+                actual: 0
+            })
+        );
+    }
+
+    #[test]
+    fn decode_partial_block_partial_padding() {
+        let standard = Standard::new();
+
+        assert_eq!(
+            decode_partial_block(&['Z', 'm', '='], &standard),
+            Err(B64Error::Truncated {
+                expected: 4,
+                actual: 3
+            })
+        );
+        assert_eq!(
+            decode_partial_block(&['Z', '='], &standard),
+            Err(B64Error::Truncated {
+                expected: 2,
+                actual: 1
+            })
+        );
+        for block in [&['='][..], &['=', '='], &['=', 'm', '=']] {
+            assert_eq!(
+                decode_partial_block(block, &standard),
+                Err(B64Error::InvalidCharAt {
+                    char: '=',
+                    index: 0
+                }),
+                "{block:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn decode_partial_block_misplaced_padding() {
+        assert_eq!(
+            decode_partial_block(&['Z', '=', 'g', '='], &Standard::new()),
+            Err(B64Error::InvalidCharAt {
+                char: '=',
+                index: 1
+            })
+        );
+    }
+}
@@ -0,0 +1,291 @@
+use crate::{alphabet::Alphabet, primitives, B64Error};
+
+/// How forgiving decoding is of input that isn't strictly valid
+/// base64, set with [`Codec::compat`](crate::Codec::compat)
+///
+/// The modes other than [`DecodeCompat::Strict`] copy exactly what
+/// another language's decoder accepts & what it decodes it to, for
+/// working out what the other end of a connection saw. They aren't
+/// meant for everyday decoding
+///
+/// Each mode replaces the codec's other decoding options apart from
+/// [`Codec::limit`](crate::Codec::limit), which is checked after
+/// decoding. Errors count only the characters that weren't skipped in
+/// their lengths, but index into the whole input
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum DecodeCompat {
+    /// Decode RFC 4648 base64, as set up by the rest of the codec's
+    /// options
+    #[default]
+    Strict,
+    /// Decode like Python's `base64.b64decode(s, validate=False)`
+    ///
+    /// - Skips every character that isn't in the alphabet or padding
+    /// - Skips padding at the start of a quad or after one character
+    /// - Stops at the padding that completes a quad, ignoring all of
+    ///   the input after it
+    /// - Rejects a quad of 2 or 3 characters without enough padding
+    ///   after it, & a lone character, as [`B64Error::Truncated`]
+    /// - Rejects non-ASCII characters, as Python does for `str`
+    /// - Ignores bits set after the end of the data
+    PythonForgiving,
+    /// Decode like Java's `java.util.Base64.getMimeDecoder().decode(s)`
+    ///
+    /// - Skips every character that isn't in the alphabet or padding,
+    ///   including characters above `\u{ff}`, which Java reads as `?`
+    /// - Accepts a quad of 2 or 3 characters without padding
+    /// - Stops at the first padding character, which must complete a
+    ///   quad of 3, or be followed straight away by another to
+    ///   complete a quad of 2
+    /// - Skips anything after the padding that isn't in the alphabet,
+    ///   including more padding, but rejects anything that is
+    /// - Rejects padding at the start of a quad as
+    ///   [`B64Error::InvalidCharAt`], & a lone character as
+    ///   [`B64Error::Truncated`]
+    /// - Ignores bits set after the end of the data
+    JavaMime,
+}
+
+impl DecodeCompat {
+    /// Decode `b64` with `alphabet` the way this mode does, or
+    /// [`None`] for [`DecodeCompat::Strict`], which the codec
+    /// handles itself
+    pub(crate) fn decode<A>(self, b64: &str, alphabet: &A) -> Option<Result<Vec<u8>, B64Error>>
+    where
+        A: Alphabet,
+    {
+        match self {
+            DecodeCompat::Strict => None,
+            DecodeCompat::PythonForgiving => Some(decode_python(b64, alphabet)),
+            DecodeCompat::JavaMime => Some(decode_java_mime(b64, alphabet)),
+        }
+    }
+}
+
+/// Decode a character to its 6 bits, or [`None`] if it isn't in the
+/// alphabet & should be skipped
+fn sextet<A>(alphabet: &A, c: char) -> Result<Option<u8>, B64Error>
+where
+    A: Alphabet,
+{
+    match primitives::decode_bits(alphabet, c) {
+        Ok(bits) => Ok(Some(bits)),
+        Err(e @ B64Error::DecodedValueOOB { .. }) => Err(e),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Decode like CPython's `binascii.a2b_base64` outside strict mode
+fn decode_python<A>(b64: &str, alphabet: &A) -> Result<Vec<u8>, B64Error>
+where
+    A: Alphabet,
+{
+    let padding = alphabet.padding();
+    let mut out = Vec::with_capacity(b64.len() / 4 * 3 + 2);
+    let mut quad_pos = 0;
+    let mut pads = 0;
+    let mut left = 0u8;
+    let mut data_len = 0;
+
+    for (index, c) in b64.chars().enumerate() {
+        if !c.is_ascii() {
+            return Err(B64Error::InvalidCharAt { char: c, index });
+        }
+        if Some(c) == padding {
+            // Padding only counts once a quad has 2 characters, &
+            // everything after the padding that completes it is ignored
+            if quad_pos >= 2 {
+                pads += 1;
+                if quad_pos + pads >= 4 {
+                    return Ok(out);
+                }
+            }
+            continue;
+        }
+        let Some(bits) = sextet(alphabet, c)? else {
+            continue;
+        };
+
+        pads = 0;
+        data_len += 1;
+        match quad_pos {
+            0 => left = bits,
+            1 => {
+                out.push(left << 2 | bits >> 4);
+                left = bits & 0b1111;
+            }
+            2 => {
+                out.push(left << 4 | bits >> 2);
+                left = bits & 0b11;
+            }
+            _ => out.push(left << 6 | bits),
+        }
+        quad_pos = (quad_pos + 1) % 4;
+    }
+
+    match quad_pos {
+        0 => Ok(out),
+        1 => Err(B64Error::Truncated {
+            expected: data_len + 1,
+            actual: data_len,
+        }),
+        _ => Err(B64Error::Truncated {
+            expected: data_len.div_ceil(4) * 4,
+            actual: data_len,
+        }),
+    }
+}
+
+/// Decode like the JDK's `Base64.Decoder` for MIME
+fn decode_java_mime<A>(b64: &str, alphabet: &A) -> Result<Vec<u8>, B64Error>
+where
+    A: Alphabet,
+{
+    let padding = alphabet.padding();
+    // Java turns strings into bytes as ISO-8859-1, replacing
+    // anything it can't represent with `?`
+    let mut chars = b64
+        .chars()
+        .map(|c| if c > '\u{ff}' { '?' } else { c })
+        .enumerate();
+    let mut out = Vec::with_capacity(b64.len() / 4 * 3 + 2);
+    let mut bits = 0u32;
+    let mut quad_pos = 0;
+    let mut data_len = 0;
+
+    while let Some((index, c)) = chars.next() {
+        if Some(c) == padding {
+            match quad_pos {
+                0 => return Err(B64Error::InvalidCharAt { char: c, index }),
+                // A quad of 2 needs both its padding characters
+                2 => match chars.next() {
+                    Some((_, p)) if Some(p) == padding => {}
+                    Some((index, char)) => return Err(B64Error::InvalidCharAt { char, index }),
+                    None => {
+                        return Err(B64Error::Truncated {
+                            expected: data_len + 2,
+                            actual: data_len + 1,
+                        })
+                    }
+                },
+                _ => {}
+            }
+            break;
+        }
+        let Some(sextet) = sextet(alphabet, c)? else {
+            continue;
+        };
+
+        bits |= u32::from(sextet) << (18 - 6 * quad_pos);
+        data_len += 1;
+        quad_pos += 1;
+        if quad_pos == 4 {
+            out.extend_from_slice(&bits.to_be_bytes()[1..]);
+            bits = 0;
+            quad_pos = 0;
+        }
+    }
+
+    match quad_pos {
+        1 => {
+            return Err(B64Error::Truncated {
+                expected: data_len + 1,
+                actual: data_len,
+            })
+        }
+        2 => out.push(bits.to_be_bytes()[1]),
+        3 => out.extend_from_slice(&bits.to_be_bytes()[1..3]),
+        _ => {}
+    }
+
+    for (index, c) in chars {
+        if Some(c) != padding && sextet(alphabet, c)?.is_some() {
+            return Err(B64Error::InvalidCharAt { char: c, index });
+        }
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alphabet::Standard;
+
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn strict_is_left_to_the_codec() {
+        assert_eq!(DecodeCompat::Strict.decode("Zg==", &Standard::new()), None);
+        assert_eq!(DecodeCompat::default(), DecodeCompat::Strict);
+    }
+
+    #[test]
+    fn python_errors() {
+        let python = |b64| decode_python(b64, &Standard::new()).unwrap_err();
+
+        assert_eq!(
+            python("Zm9v!Y"),
+            B64Error::Truncated {
+                expected: 6,
+                actual: 5
+            }
+        );
+        assert_eq!(
+            python("Zm9vYm="),
+            B64Error::Truncated {
+                expected: 8,
+                actual: 6
+            }
+        );
+        assert_eq!(
+            python("Zm9vé"),
+            B64Error::InvalidCharAt {
+                char: 'é',
+                index: 4
+            }
+        );
+    }
+
+    #[test]
+    fn java_errors() {
+        let java = |b64| decode_java_mime(b64, &Standard::new()).unwrap_err();
+
+        assert_eq!(
+            java("Zm9v="),
+            B64Error::InvalidCharAt {
+                char: '=',
+                index: 4
+            }
+        );
+        assert_eq!(
+            java("Zg=x"),
+            B64Error::InvalidCharAt {
+                char: 'x',
+                index: 3
+            }
+        );
+        assert_eq!(
+            java("Zg="),
+            B64Error::Truncated {
+                expected: 4,
+                actual: 3
+            }
+        );
+        assert_eq!(
+            java("Zm9v\nZ"),
+            B64Error::Truncated {
+                expected: 6,
+                actual: 5
+            }
+        );
+        assert_eq!(
+            java("Zg==\nZm9v"),
+            B64Error::InvalidCharAt {
+                char: 'Z',
+                index: 5
+            }
+        );
+    }
+}
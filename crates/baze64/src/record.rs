@@ -0,0 +1,426 @@
+//! Pack several binary fields, like a nonce, ciphertext & tag, into
+//! one base64 token, & split them back apart without any offset maths
+//!
+//! ## Format
+//!
+//! A record is encoded as normal base64 of these bytes:
+//!
+//! 1. The format version, [`VERSION`]
+//! 2. The number of fields, as a varint
+//! 3. Each field's length as a varint, followed by the field itself
+//!
+//! Varints are unsigned LEB128, as used by Protocol Buffers: 7 bits of
+//! the number per byte, least significant first, with the top bit set
+//! on every byte but the last. Lengths under 128 take 1 byte, so short
+//! fields cost a byte each, & fields of any length are allowed
+//!
+//! ## Examples
+//! ```
+//! # use baze64::{Base64String, alphabet::UrlSafe, record::{RecordBuilder, RecordReader}};
+//! let mut builder = RecordBuilder::<UrlSafe>::new();
+//! builder.push_field(b"nonce");
+//! builder.push_field(b"ciphertext");
+//! builder.push_field(b"");
+//! let token = builder.finish();
+//!
+//! let fields = RecordReader::parse(&token)?;
+//! assert_eq!(fields, [&b"nonce"[..], b"ciphertext", b""]);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use core::fmt;
+
+use crate::{alphabet::Alphabet, Base64String, DecodeError, DecodeIter};
+
+/// The version of the format written by [`RecordBuilder`], & the only
+/// one [`RecordReader`] reads
+pub const VERSION: u8 = 1;
+
+/// The most bytes a varint of a `u64` can take
+const MAX_VARINT_LEN: usize = 10;
+
+/// The error returned when reading a record fails
+///
+/// New variants may be added in future
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum RecordError {
+    /// The record starts with a format version other than [`VERSION`]
+    UnsupportedVersion {
+        version: u8,
+    },
+    /// The record ends part way through field `field`, or part way
+    /// through its header if [`None`]
+    Truncated {
+        field: Option<usize>,
+    },
+    /// There are `len` more bytes after the last field
+    TrailingBytes {
+        len: usize,
+    },
+    Base64Error(DecodeError),
+}
+
+impl fmt::Display for RecordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecordError::UnsupportedVersion { version } => write!(
+                f,
+                "Record has format version {version}, but only {VERSION} is supported"
+            ),
+            RecordError::Truncated { field: Some(field) } => {
+                write!(
+                    f,
+                    "Record is truncated, it ends part way through field {field}"
+                )
+            }
+            RecordError::Truncated { field: None } => {
+                write!(
+                    f,
+                    "Record is truncated, it ends part way through its header"
+                )
+            }
+            RecordError::TrailingBytes { len } => {
+                write!(f, "Record has {len} bytes after its last field")
+            }
+            RecordError::Base64Error(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for RecordError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RecordError::Base64Error(e) => e.source(),
+            _ => None,
+        }
+    }
+}
+
+impl From<DecodeError> for RecordError {
+    fn from(value: DecodeError) -> Self {
+        RecordError::Base64Error(value)
+    }
+}
+
+/// Builds a record a field at a time, in the format described in the
+/// [module docs](self)
+#[derive(Debug, Clone)]
+pub struct RecordBuilder<A> {
+    fields: usize,
+    body: Vec<u8>,
+    alphabet: A,
+}
+
+impl<A> RecordBuilder<A>
+where
+    A: Alphabet,
+{
+    /// Create a builder with no fields that encodes the record using a
+    /// given `alphabet` instance
+    pub fn new_with(alphabet: A) -> Self {
+        Self {
+            fields: 0,
+            body: Vec::new(),
+            alphabet,
+        }
+    }
+
+    /// Add `field` to the end of the record
+    pub fn push_field<B>(&mut self, field: B)
+    where
+        B: AsRef<[u8]>,
+    {
+        let field = field.as_ref();
+        push_varint(&mut self.body, field.len() as u64);
+        self.body.extend_from_slice(field);
+        self.fields += 1;
+    }
+
+    /// Encode the record with every field pushed so far
+    pub fn finish(self) -> Base64String<A> {
+        let mut record = Vec::with_capacity(1 + MAX_VARINT_LEN + self.body.len());
+        record.push(VERSION);
+        push_varint(&mut record, self.fields as u64);
+        record.extend_from_slice(&self.body);
+
+        Base64String::encode_with(record, self.alphabet)
+    }
+}
+
+impl<A> RecordBuilder<A>
+where
+    A: Alphabet + Default,
+{
+    /// Create a builder with no fields
+    ///
+    /// Uses `A`'s [`Default`] impl as the alphabet
+    /// to encode with
+    pub fn new() -> Self {
+        Self::new_with(A::default())
+    }
+}
+
+impl<A> Default for RecordBuilder<A>
+where
+    A: Alphabet + Default,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Reads the fields of a record a field at a time, decoding each
+/// straight into its own buffer rather than decoding the whole record
+/// first
+///
+/// # Examples
+/// ```
+/// # use baze64::{Base64String, alphabet::Standard, record::{RecordBuilder, RecordReader}};
+/// let mut builder = RecordBuilder::<Standard>::new();
+/// builder.push_field([1, 2, 3]);
+/// let record = builder.finish();
+///
+/// let mut reader = RecordReader::new(&record)?;
+/// assert_eq!(reader.fields_left(), 1);
+/// assert_eq!(reader.next_field()?, Some(vec![1, 2, 3]));
+/// assert_eq!(reader.next_field()?, None);
+/// # Ok::<(), baze64::record::RecordError>(())
+/// ```
+#[derive(Debug)]
+pub struct RecordReader<'a, A> {
+    bytes: DecodeIter<'a, A>,
+    fields: usize,
+    field: usize,
+}
+
+impl<'a, A> RecordReader<'a, A>
+where
+    A: Alphabet,
+{
+    /// Start reading `record`, checking its header
+    pub fn new(record: &'a Base64String<A>) -> Result<Self, RecordError> {
+        let mut bytes = record.decode_iter();
+        let version = bytes
+            .next()
+            .transpose()?
+            .ok_or(RecordError::Truncated { field: None })?;
+        if version != VERSION {
+            return Err(RecordError::UnsupportedVersion { version });
+        }
+        let fields = read_varint(&mut bytes)?
+            .and_then(|fields| usize::try_from(fields).ok())
+            .ok_or(RecordError::Truncated { field: None })?;
+
+        Ok(Self {
+            bytes,
+            fields,
+            field: 0,
+        })
+    }
+
+    /// Read every field of `record`
+    pub fn parse(record: &'a Base64String<A>) -> Result<Vec<Vec<u8>>, RecordError> {
+        let mut reader = Self::new(record)?;
+        // Each field takes at least a byte, so a bogus count can't
+        // allocate more than the record's size
+        let mut fields = Vec::with_capacity(reader.fields.min(reader.bytes.len()));
+        while let Some(field) = reader.next_field()? {
+            fields.push(field);
+        }
+
+        Ok(fields)
+    }
+
+    /// Read the next field, or [`None`] once they've all been read
+    ///
+    /// Once the last field's read, this checks nothing comes after it,
+    /// failing with [`RecordError::TrailingBytes`] if anything does
+    pub fn next_field(&mut self) -> Result<Option<Vec<u8>>, RecordError> {
+        if self.field == self.fields {
+            return match self.bytes.len() {
+                0 => Ok(None),
+                len => Err(RecordError::TrailingBytes { len }),
+            };
+        }
+
+        let truncated = RecordError::Truncated {
+            field: Some(self.field),
+        };
+        // Lengths past the end of the record can't be read, so are
+        // rejected before allocating anything for them
+        let len = read_varint(&mut self.bytes)?
+            .and_then(|len| usize::try_from(len).ok())
+            .filter(|&len| len <= self.bytes.len())
+            .ok_or(truncated)?;
+
+        let field = self
+            .bytes
+            .by_ref()
+            .take(len)
+            .collect::<Result<Vec<_>, _>>()?;
+        self.field += 1;
+
+        Ok(Some(field))
+    }
+
+    /// How many fields are left to read
+    pub fn fields_left(&self) -> usize {
+        self.fields - self.field
+    }
+}
+
+/// Append `n` to `buf` as a varint
+fn push_varint(buf: &mut Vec<u8>, mut n: u64) {
+    while n >= 0x80 {
+        buf.push(n as u8 | 0x80);
+        n >>= 7;
+    }
+    buf.push(n as u8);
+}
+
+/// Read a varint from `bytes`, or [`None`] if they end part way
+/// through it or it's too long to fit in a `u64`
+fn read_varint<A>(bytes: &mut DecodeIter<'_, A>) -> Result<Option<u64>, DecodeError>
+where
+    A: Alphabet,
+{
+    let mut n = 0u64;
+    for shift in (0..MAX_VARINT_LEN).map(|i| i * 7) {
+        let Some(byte) = bytes.next().transpose()? else {
+            return Ok(None);
+        };
+        let bits = u64::from(byte & 0x7f);
+        if bits.checked_shl(shift as u32).map(|b| b >> shift) != Some(bits) {
+            return Ok(None);
+        }
+        n |= bits << shift;
+        if byte & 0x80 == 0 {
+            return Ok(Some(n));
+        }
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alphabet::{Standard, UrlSafe};
+
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn build(fields: &[&[u8]]) -> Base64String<Standard> {
+        let mut builder = RecordBuilder::new();
+        for field in fields {
+            builder.push_field(field);
+        }
+        builder.finish()
+    }
+
+    fn record(bytes: &[u8]) -> Base64String<Standard> {
+        Base64String::encode(bytes)
+    }
+
+    #[test]
+    fn round_trips() {
+        let long = vec![0xa5; 300];
+        let many = (0..=255u8).map(|n| vec![n; n.into()]).collect::<Vec<_>>();
+        let many = many.iter().map(Vec::as_slice).collect::<Vec<_>>();
+
+        for fields in [
+            &[][..],
+            &[&b"one"[..]],
+            &[b""],
+            &[b"", b"", b""],
+            &[b"nonce", &long, b"", b"tag"],
+            &many,
+        ] {
+            assert_eq!(RecordReader::parse(&build(fields)).unwrap(), fields);
+        }
+    }
+
+    #[test]
+    fn layout() {
+        assert_eq!(build(&[]).decode().unwrap(), [VERSION, 0]);
+        assert_eq!(
+            build(&[b"ab", &[0; 200]]).decode().unwrap()[..7],
+            [VERSION, 2, 2, b'a', b'b', 0xc8, 0x01]
+        );
+
+        let mut builder = RecordBuilder::new_with(UrlSafe::new());
+        builder.push_field([0xfb, 0xff]);
+        assert_eq!(builder.finish(), "AQEC-_8=");
+    }
+
+    #[test]
+    fn next_field() {
+        let record = build(&[b"a", b"bc"]);
+        let mut reader = RecordReader::new(&record).unwrap();
+
+        assert_eq!(reader.fields_left(), 2);
+        assert_eq!(reader.next_field().unwrap().unwrap(), b"a");
+        assert_eq!(reader.fields_left(), 1);
+        assert_eq!(reader.next_field().unwrap().unwrap(), b"bc");
+        assert_eq!(reader.next_field().unwrap(), None);
+        assert_eq!(reader.next_field().unwrap(), None);
+    }
+
+    #[test]
+    fn truncated() {
+        for (bytes, field) in [
+            (&[][..], None),
+            (&[VERSION], None),
+            (&[VERSION, 0x80], None),
+            (&[VERSION, 1], Some(0)),
+            (&[VERSION, 1, 3, b'a', b'b'], Some(0)),
+            (&[VERSION, 2, 1, b'a', 0x81], Some(1)),
+            // Lengths too big for a u64
+            (
+                &[
+                    VERSION, 1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02,
+                ],
+                Some(0),
+            ),
+        ] {
+            let err = RecordReader::parse(&record(bytes)).unwrap_err();
+            assert!(
+                matches!(err, RecordError::Truncated { field: f } if f == field),
+                "{bytes:?} gave {err:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn trailing_bytes() {
+        let err = RecordReader::parse(&record(&[VERSION, 1, 1, b'a', b'b', b'c'])).unwrap_err();
+
+        assert!(matches!(err, RecordError::TrailingBytes { len: 2 }));
+        assert_eq!(err.to_string(), "Record has 2 bytes after its last field");
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let err = RecordReader::parse(&record(&[2, 0])).unwrap_err();
+
+        assert!(matches!(
+            err,
+            RecordError::UnsupportedVersion { version: 2 }
+        ));
+        assert_eq!(
+            err.to_string(),
+            "Record has format version 2, but only 1 is supported"
+        );
+    }
+
+    #[test]
+    fn varints() {
+        for n in [0, 1, 127, 128, 300, u32::MAX.into(), u64::MAX] {
+            let mut buf = Vec::new();
+            push_varint(&mut buf, n);
+            let b64 = record(&buf);
+
+            assert_eq!(read_varint(&mut b64.decode_iter()).unwrap(), Some(n));
+        }
+    }
+}
@@ -0,0 +1,264 @@
+//! Represent base64 so it can be pasted into shell commands & YAML
+//! without quoting headaches, & read it back
+
+use crate::{alphabet::Alphabet, alphabet::UrlSafe, Base64String, DecodeError, YamlError};
+
+impl<A> Base64String<A>
+where
+    A: Alphabet,
+{
+    /// Represent the data in `self` as text that's safe to use
+    /// unquoted in a shell command
+    ///
+    /// The text is the data encoded with the [`UrlSafe`] alphabet &
+    /// no padding, so only has letters, digits, `-` & `_`, none of
+    /// which shells or tools treating `=` specially will change. Read
+    /// it back with [`Base64String::from_shell_safe`]
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, alphabet::Standard};
+    /// let b64 = Base64String::<Standard>::encode([0xfb, 0xff]);
+    /// assert_eq!(b64.to_string(), "+/8=");
+    ///
+    /// let safe = b64.to_shell_safe()?;
+    /// assert_eq!(safe, "-_8");
+    /// assert_eq!(Base64String::from_shell_safe(&safe, Standard::new())?, b64);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn to_shell_safe(&self) -> Result<String, DecodeError> {
+        Ok(self.change_alphabet_with(UrlSafe::new())?.without_padding())
+    }
+
+    /// Read text from [`Base64String::to_shell_safe`] back into a
+    /// [`Base64String`] using a given `alphabet` instance, in its
+    /// canonical form
+    ///
+    /// Padded text is accepted too, as is whitespace around the text
+    pub fn from_shell_safe(text: &str, alphabet: A) -> Result<Self, DecodeError> {
+        Base64String::from_encoded_with(text, UrlSafe::new())?.change_alphabet_with(alphabet)
+    }
+
+    /// Represent `self` as a YAML scalar that's read back as the same
+    /// string, whatever the alphabet
+    ///
+    /// The text is `self` in its canonical form as a double-quoted
+    /// scalar, so YAML never reads it as a number, boolean or null,
+    /// like `1234` or `true` would be unquoted, or strips anything from
+    /// it. `"` & `\` are escaped with a `\`, & characters that aren't
+    /// printable are escaped as `\xXX`, `\uXXXX` or `\UXXXXXXXX`. Read
+    /// it back with [`Base64String::from_yaml_safe`]
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, alphabet::Standard};
+    /// let b64 = Base64String::<Standard>::encode([0xb6, 0xbb, 0x9e]);
+    /// assert_eq!(b64.to_string(), "true");
+    ///
+    /// let safe = b64.to_yaml_safe();
+    /// assert_eq!(safe, r#""true""#);
+    /// assert_eq!(Base64String::from_yaml_safe(&safe, Standard::new())?, b64);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn to_yaml_safe(&self) -> String {
+        let text = self.to_string();
+        let mut quoted = String::with_capacity(text.len() + 2);
+        quoted.push('"');
+        for c in text.chars() {
+            match c {
+                '"' | '\\' => {
+                    quoted.push('\\');
+                    quoted.push(c);
+                }
+                ' '..='~' => quoted.push(c),
+                c if u32::from(c) <= 0xff => quoted.push_str(&format!("\\x{:02X}", u32::from(c))),
+                c if u32::from(c) <= 0xffff => quoted.push_str(&format!("\\u{:04X}", u32::from(c))),
+                c => quoted.push_str(&format!("\\U{:08X}", u32::from(c))),
+            }
+        }
+        quoted.push('"');
+
+        quoted
+    }
+
+    /// Read a YAML double-quoted scalar, like one from
+    /// [`Base64String::to_yaml_safe`], back into a [`Base64String`]
+    /// using a given `alphabet` instance
+    ///
+    /// The escapes `to_yaml_safe` produces are understood, along with
+    /// `\/`, `\0`, `\t`, `\n` & `\r`. Whitespace around the quotes is
+    /// ignored, & errors in the base64 give indices into the unquoted
+    /// text
+    pub fn from_yaml_safe(text: &str, alphabet: A) -> Result<Self, YamlError> {
+        // Where the text inside the quotes starts, in characters
+        let start = text.chars().take_while(|c| c.is_whitespace()).count() + 1;
+        let quoted = text
+            .trim()
+            .strip_prefix('"')
+            .and_then(|t| t.strip_suffix('"'))
+            .ok_or(YamlError::NotQuoted)?;
+
+        let mut unquoted = String::with_capacity(quoted.len());
+        let mut chars = quoted.chars().enumerate();
+        while let Some((i, c)) = chars.next() {
+            if c == '"' {
+                return Err(YamlError::NotQuoted);
+            }
+            if c != '\\' {
+                unquoted.push(c);
+                continue;
+            }
+
+            let invalid = || YamlError::InvalidEscape { index: start + i };
+            let digits = match chars.next().ok_or_else(invalid)?.1 {
+                c @ ('"' | '\\' | '/') => {
+                    unquoted.push(c);
+                    continue;
+                }
+                '0' => {
+                    unquoted.push('\0');
+                    continue;
+                }
+                't' => {
+                    unquoted.push('\t');
+                    continue;
+                }
+                'n' => {
+                    unquoted.push('\n');
+                    continue;
+                }
+                'r' => {
+                    unquoted.push('\r');
+                    continue;
+                }
+                'x' => 2,
+                'u' => 4,
+                'U' => 8,
+                _ => return Err(invalid()),
+            };
+            let hex = (0..digits)
+                .map(|_| chars.next().map(|(_, c)| c))
+                .collect::<Option<String>>()
+                .ok_or_else(invalid)?;
+            let c = u32::from_str_radix(&hex, 16)
+                .ok()
+                .and_then(char::from_u32)
+                .ok_or_else(invalid)?;
+            unquoted.push(c);
+        }
+
+        Ok(Base64String::from_encoded_with(unquoted, alphabet)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        alphabet::{AlphabetKind, Standard},
+        B64Error,
+    };
+
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn shell_safe_characters() {
+        let b64 = Base64String::<Standard>::encode([0xfb, 0xef, 0xbe, 0xff, 0xff]);
+
+        assert_eq!(b64.to_string(), "++++//8=");
+        assert_eq!(b64.to_shell_safe().unwrap(), "----__8");
+    }
+
+    #[test]
+    fn shell_safe_rejects_unsafe_text() {
+        assert!(matches!(
+            Base64String::from_shell_safe("+/8", Standard::new()),
+            Err(DecodeError::WrongAlphabet {
+                char: '+',
+                position: 0,
+                suggestion: AlphabetKind::Standard
+            })
+        ));
+    }
+
+    #[test]
+    fn yaml_escapes() {
+        let b64 = Base64String::from_encoded_with("\"\\\u{7f}\u{2029}Zg\u{2028}\u{2028}", Quirky)
+            .unwrap();
+        let safe = b64.to_yaml_safe();
+
+        assert_eq!(safe, r#""\"\\\x7F\u2029Zg\u2028\u2028""#);
+        assert_eq!(Base64String::from_yaml_safe(&safe, Quirky).unwrap(), b64);
+    }
+
+    #[test]
+    fn yaml_other_escapes() {
+        assert_eq!(
+            Base64String::from_yaml_safe(r#" "\/\x2B8=" "#, Standard::new()).unwrap(),
+            "/+8="
+        );
+    }
+
+    #[test]
+    fn yaml_errors() {
+        for text in ["Zm9v", "\"Zm9v", "Zm9v\"", "\"", "'Zm9v'", "\"Zm\"9v\""] {
+            assert!(
+                matches!(
+                    Base64String::from_yaml_safe(text, Standard::new()),
+                    Err(YamlError::NotQuoted)
+                ),
+                "{text}"
+            );
+        }
+        for (text, index) in [(r#""Zm9v\""#, 5), (r#""Zm\q9v""#, 3), (r#" "\uD800" "#, 2)] {
+            assert!(
+                matches!(
+                    Base64String::from_yaml_safe(text, Standard::new()),
+                    Err(YamlError::InvalidEscape { index: i }) if i == index
+                ),
+                "{text}"
+            );
+        }
+        assert!(matches!(
+            Base64String::from_yaml_safe("\"Zm9v#\"", Standard::new()),
+            Err(YamlError::Base64Error(B64Error::InvalidCharAt {
+                char: '#',
+                index: 4
+            }))
+        ));
+    }
+
+    /// An alphabet using characters YAML needs escaped, in place of
+    /// `A` to `F` in the standard alphabet
+    #[derive(Debug, Clone, Copy)]
+    struct Quirky;
+
+    const QUIRKY: [char; 6] = ['"', '\\', '\u{7f}', '\u{85}', '\u{2029}', 'é'];
+
+    impl Alphabet for Quirky {
+        fn padding(&self) -> Option<char> {
+            Some('\u{2028}')
+        }
+
+        fn encode_bits(&self, bits: u8) -> char {
+            match bits {
+                0..=5 => QUIRKY[bits as usize],
+                _ => Standard::new().encode_bits(bits),
+            }
+        }
+
+        fn decode_char(&self, c: char) -> Result<u8, B64Error> {
+            match QUIRKY.iter().position(|&q| q == c) {
+                Some(bits) => Ok(bits as u8),
+                None => match Standard::new().decode_char(c)? {
+                    bits @ 6.. => Ok(bits),
+                    _ => Err(B64Error::InvalidChar(c)),
+                },
+            }
+        }
+
+        fn name(&self) -> &'static str {
+            "quirky"
+        }
+    }
+}
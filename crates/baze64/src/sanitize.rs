@@ -0,0 +1,291 @@
+//! Clean up base64 pasted from PDFs, word processors & chat apps
+//!
+//! Text copied out of those often picks up characters nobody can
+//! see, like zero-width spaces & soft hyphens, or has characters
+//! swapped for look-alikes, like fullwidth letters. [`sanitize`]
+//! undoes that, reporting what it changed so users can be told
+//!
+//! Removed:
+//! - Zero-width spaces, joiners & non-joiners (U+200B to U+200D),
+//!   the word joiner (U+2060) & byte order marks (U+FEFF)
+//! - Soft hyphens (U+00AD) & the Mongolian vowel separator (U+180E)
+//! - No-break spaces (U+00A0, U+2007 & U+202F)
+//! - Direction marks & controls (U+200E, U+200F, U+202A to U+202E
+//!   & U+2066 to U+2069)
+//! - Smart quotes (U+2018, U+2019, U+201C & U+201D) around the whole
+//!   text, as chat apps add when quoting it
+//!
+//! Replaced:
+//! - Fullwidth forms (U+FF01 to U+FF5E) by their ASCII equivalents
+//! - Hyphens & minus signs (U+2010, U+2011 & U+2212) by `-`
+//! - The small plus sign (U+FE62) by `+`
+//! - Division & fraction slashes (U+2215 & U+2044) by `/`
+//! - The small equals sign (U+FE66) by `=`
+//!
+//! Anything else is left alone, so visible mistakes are still
+//! reported when parsing
+
+use crate::{alphabet::Alphabet, B64Error, Base64String};
+
+/// What [`sanitize`] changed in some text
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Sanitized {
+    /// How many characters were removed
+    pub removed: usize,
+    /// How many characters were replaced by an ASCII equivalent
+    pub replaced: usize,
+}
+
+impl Sanitized {
+    /// How many characters were removed or replaced altogether
+    pub fn changed(&self) -> usize {
+        self.removed + self.replaced
+    }
+}
+
+/// Remove invisible characters from `text` & replace look-alikes
+/// with the ASCII characters they stand in for, as listed in the
+/// [module docs](self)
+///
+/// # Examples
+/// ```
+/// # use baze64::sanitize::{sanitize, Sanitized};
+/// let (clean, sanitized) = sanitize("Zm9v\u{200b}YmFy\u{ff1d}");
+///
+/// assert_eq!(clean, "Zm9vYmFy=");
+/// assert_eq!(sanitized, Sanitized { removed: 1, replaced: 1 });
+/// ```
+pub fn sanitize(text: &str) -> (String, Sanitized) {
+    let (clean, _, sanitized) = sanitize_mapped(text);
+    (clean, sanitized)
+}
+
+/// Sanitize `text`, also giving the index in `text` of every
+/// character in the output
+fn sanitize_mapped(text: &str) -> (String, Vec<usize>, Sanitized) {
+    let mut sanitized = Sanitized::default();
+    let mut clean = String::with_capacity(text.len());
+    let mut origins = Vec::with_capacity(text.len());
+
+    let len = text.chars().count();
+    let quoted = text.starts_with(is_smart_quote) && text.ends_with(is_smart_quote) && len >= 2;
+
+    for (index, c) in text.chars().enumerate() {
+        let outer = index == 0 || index == len - 1;
+        if is_invisible(c) || (quoted && outer) {
+            sanitized.removed += 1;
+            continue;
+        }
+
+        match replacement(c) {
+            Some(ascii) => {
+                sanitized.replaced += 1;
+                clean.push(ascii);
+            }
+            None => clean.push(c),
+        }
+        origins.push(index);
+    }
+
+    (clean, origins, sanitized)
+}
+
+/// Whether `c` is one of the invisible characters [`sanitize`]
+/// removes
+fn is_invisible(c: char) -> bool {
+    matches!(
+        c,
+        '\u{00a0}'
+            | '\u{00ad}'
+            | '\u{180e}'
+            | '\u{2007}'
+            | '\u{200b}'..='\u{200f}'
+            | '\u{202a}'..='\u{202f}'
+            | '\u{2060}'
+            | '\u{2066}'..='\u{2069}'
+            | '\u{feff}'
+    )
+}
+
+fn is_smart_quote(c: char) -> bool {
+    matches!(c, '\u{2018}' | '\u{2019}' | '\u{201c}' | '\u{201d}')
+}
+
+/// The ASCII character the look-alike `c` stands in for, if it is one
+fn replacement(c: char) -> Option<char> {
+    match c {
+        '\u{ff01}'..='\u{ff5e}' => char::from_u32(c as u32 - 0xfee0),
+        '\u{2010}' | '\u{2011}' | '\u{2212}' => Some('-'),
+        '\u{fe62}' => Some('+'),
+        '\u{2215}' | '\u{2044}' => Some('/'),
+        '\u{fe66}' => Some('='),
+        _ => None,
+    }
+}
+
+impl<A> Base64String<A>
+where
+    A: Alphabet,
+{
+    /// Contruct a [`Base64String`] from encoded text that may have
+    /// been mangled by copying & pasting, using a given `alphabet`
+    /// instance
+    ///
+    /// The text is [`sanitize`]d first, & what was changed is
+    /// returned alongside the [`Base64String`]. Errors report the
+    /// index of the character in the original text
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, B64Error, alphabet::Standard};
+    /// let pasted = "\u{201c}Zm9v\u{00ad}YmFy\u{201d}";
+    /// let (b64, sanitized) = Base64String::from_encoded_sanitized_with(pasted, Standard::new())?;
+    ///
+    /// assert_eq!(b64.decode()?, b"foobar");
+    /// assert_eq!(sanitized.removed, 3);
+    ///
+    /// let err = Base64String::from_encoded_sanitized_with("\u{200b}Zm9v#", Standard::new());
+    /// assert_eq!(err.unwrap_err(), B64Error::InvalidCharAt { char: '#', index: 5 });
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn from_encoded_sanitized_with(
+        b64: &str,
+        alphabet: A,
+    ) -> Result<(Self, Sanitized), B64Error> {
+        let (clean, origins, sanitized) = sanitize_mapped(b64);
+
+        match Self::from_encoded_with(clean, alphabet) {
+            Ok(b64) => Ok((b64, sanitized)),
+            Err(B64Error::InvalidCharAt { char, index }) => Err(B64Error::InvalidCharAt {
+                char,
+                index: origins.get(index).copied().unwrap_or(index),
+            }),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl<A> Base64String<A>
+where
+    A: Alphabet + Default,
+{
+    /// Contruct a [`Base64String`] from encoded text that may have
+    /// been mangled by copying & pasting
+    ///
+    /// Uses `A`'s [`Default`] impl as the alphabet. Otherwise the
+    /// same as [`Base64String::from_encoded_sanitized_with`]
+    pub fn from_encoded_sanitized(b64: &str) -> Result<(Self, Sanitized), B64Error> {
+        Self::from_encoded_sanitized_with(b64, A::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alphabet::{Standard, UrlSafe};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn removes_invisible() {
+        for c in [
+            '\u{00a0}', '\u{00ad}', '\u{180e}', '\u{2007}', '\u{200b}', '\u{200c}', '\u{200d}',
+            '\u{200e}', '\u{200f}', '\u{202a}', '\u{202e}', '\u{202f}', '\u{2060}', '\u{2066}',
+            '\u{2069}', '\u{feff}',
+        ] {
+            assert_eq!(
+                sanitize(&format!("Zm{c}9v")),
+                (
+                    "Zm9v".to_string(),
+                    Sanitized {
+                        removed: 1,
+                        replaced: 0
+                    }
+                ),
+                "{:?}",
+                c
+            );
+        }
+    }
+
+    #[test]
+    fn replaces_fullwidth() {
+        let (clean, sanitized) = sanitize("ＡＢＣｘｙｚ０１９＋／＝－＿");
+
+        assert_eq!(clean, "ABCxyz019+/=-_");
+        assert_eq!(
+            sanitized,
+            Sanitized {
+                removed: 0,
+                replaced: 14
+            }
+        );
+    }
+
+    #[test]
+    fn replaces_look_alikes() {
+        for (c, ascii) in [
+            ('\u{2010}', '-'),
+            ('\u{2011}', '-'),
+            ('\u{2212}', '-'),
+            ('\u{fe62}', '+'),
+            ('\u{2215}', '/'),
+            ('\u{2044}', '/'),
+            ('\u{fe66}', '='),
+        ] {
+            assert_eq!(replacement(c), Some(ascii), "{:?}", c);
+        }
+        assert_eq!(replacement('a'), None);
+        assert_eq!(replacement('#'), None);
+    }
+
+    #[test]
+    fn strips_wrapping_smart_quotes() {
+        assert_eq!(sanitize("\u{201c}Zm9v\u{201d}").0, "Zm9v");
+        assert_eq!(sanitize("\u{2018}Zm9v\u{2019}").0, "Zm9v");
+        // Only quotes around the whole text are removed
+        assert_eq!(sanitize("Zm\u{201c}9v").0, "Zm\u{201c}9v");
+        assert_eq!(sanitize("\u{201c}Zm9v").0, "\u{201c}Zm9v");
+    }
+
+    #[test]
+    fn leaves_clean_text() {
+        assert_eq!(
+            sanitize("Zm9v YmFy\n"),
+            ("Zm9v YmFy\n".to_string(), Sanitized::default())
+        );
+        assert_eq!(sanitize(""), (String::new(), Sanitized::default()));
+    }
+
+    #[test]
+    fn parses_sanitized() {
+        let (b64, sanitized) =
+            Base64String::<UrlSafe>::from_encoded_sanitized("\u{feff}－＿８\u{200b}").unwrap();
+
+        assert_eq!(b64, "-_8");
+        assert_eq!(
+            sanitized,
+            Sanitized {
+                removed: 2,
+                replaced: 3
+            }
+        );
+        assert_eq!(sanitized.changed(), 5);
+    }
+
+    #[test]
+    fn visible_mistakes_still_error() {
+        // The `#` is the 8th character of the original text, after
+        // three that are removed
+        let err =
+            Base64String::<Standard>::from_encoded_sanitized("\u{200b}\u{00a0}Zm\u{00ad}9v#mFy")
+                .unwrap_err();
+
+        assert_eq!(
+            err,
+            B64Error::InvalidCharAt {
+                char: '#',
+                index: 7
+            }
+        );
+    }
+}
@@ -0,0 +1,237 @@
+//! Vectorised encoding & decoding for the [`Standard`] & [`UrlSafe`]
+//! alphabets, whose fixed character ranges let whole registers of
+//! characters be translated with shuffles & adds rather than a lookup
+//! per character
+//!
+//! Alphabets are recognised by their lookup tables, so anything with
+//! the same characters, like [`AnyAlphabet`] or a reference to either
+//! alphabet, is vectorised too. Every other alphabet uses the scalar
+//! path. On x86 & x86-64 the instructions used are picked when called,
+//! from AVX2 & SSSE3, & on AArch64 NEON is always used. Anywhere else
+//! nothing is vectorised
+//!
+//! [`Standard`]: crate::alphabet::Standard
+//! [`UrlSafe`]: crate::alphabet::UrlSafe
+//! [`AnyAlphabet`]: crate::alphabet::AnyAlphabet
+
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+mod neon;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod x86;
+
+use crate::base64string::INVALID_BITS;
+
+const STANDARD_CHARS: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const URL_SAFE_CHARS: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+const STANDARD_DECODE: [u8; 128] = decode_table(STANDARD_CHARS);
+const URL_SAFE_DECODE: [u8; 128] = decode_table(URL_SAFE_CHARS);
+
+const fn decode_table(chars: &[u8; 64]) -> [u8; 128] {
+    let mut table = [INVALID_BITS; 128];
+    let mut bits = 0;
+    while bits < 64 {
+        table[chars[bits] as usize] = bits as u8;
+        bits += 1;
+    }
+
+    table
+}
+
+/// Which of the alphabets with vectorised paths is being used
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Variant {
+    Standard,
+    UrlSafe,
+}
+
+impl Variant {
+    /// Recognise an alphabet from the characters it encodes each
+    /// value to
+    fn from_encode_table(table: &[u8; 64]) -> Option<Self> {
+        if table == STANDARD_CHARS {
+            Some(Variant::Standard)
+        } else if table == URL_SAFE_CHARS {
+            Some(Variant::UrlSafe)
+        } else {
+            None
+        }
+    }
+
+    /// Recognise an alphabet from what it decodes each ASCII
+    /// character to
+    fn from_decode_table(table: &[u8; 128]) -> Option<Self> {
+        if *table == STANDARD_DECODE {
+            Some(Variant::Standard)
+        } else if *table == URL_SAFE_DECODE {
+            Some(Variant::UrlSafe)
+        } else {
+            None
+        }
+    }
+
+    #[cfg_attr(not(target_arch = "aarch64"), allow(dead_code))]
+    fn chars(self) -> &'static [u8; 64] {
+        match self {
+            Variant::Standard => STANDARD_CHARS,
+            Variant::UrlSafe => URL_SAFE_CHARS,
+        }
+    }
+
+    #[cfg_attr(not(target_arch = "aarch64"), allow(dead_code))]
+    fn decode_table(self) -> &'static [u8; 128] {
+        match self {
+            Variant::Standard => &STANDARD_DECODE,
+            Variant::UrlSafe => &URL_SAFE_DECODE,
+        }
+    }
+}
+
+/// Encode as much of the start of `bytes` as fits in whole blocks,
+/// appending the characters to `out`, if `table` is from an alphabet
+/// with a vectorised path
+///
+/// Returns how many bytes were encoded, which is always a multiple of
+/// 3 & is 0 if nothing could be vectorised
+pub(crate) fn encode_prefix(bytes: &[u8], table: &[u8; 64], out: &mut Vec<u8>) -> usize {
+    Variant::from_encode_table(table).map_or(0, |variant| encode(bytes, variant, out))
+}
+
+/// Decode as much of the start of `chars` as fits in whole blocks,
+/// appending the bytes to `out`, if `table` is from an alphabet with
+/// a vectorised path
+///
+/// `chars` mustn't have any padding. Returns how many characters were
+/// decoded, which is always a multiple of 4, or [`None`] as soon as an
+/// invalid character is found, leaving the scalar path to report it
+pub(crate) fn decode_prefix(chars: &[u8], table: &[u8; 128], out: &mut Vec<u8>) -> Option<usize> {
+    Variant::from_decode_table(table).map_or(Some(0), |variant| decode(chars, variant, out))
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use x86::{decode, encode};
+
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+use neon::{decode, encode};
+
+#[cfg(not(any(
+    target_arch = "x86",
+    target_arch = "x86_64",
+    all(target_arch = "aarch64", target_feature = "neon")
+)))]
+fn encode(_: &[u8], _: Variant, _: &mut Vec<u8>) -> usize {
+    0
+}
+
+#[cfg(not(any(
+    target_arch = "x86",
+    target_arch = "x86_64",
+    all(target_arch = "aarch64", target_feature = "neon")
+)))]
+fn decode(_: &[u8], _: Variant, _: &mut Vec<u8>) -> Option<usize> {
+    Some(0)
+}
+
+/// Scalar versions of the vectorised paths, for checking them against
+#[cfg(test)]
+pub(crate) mod scalar {
+    use super::Variant;
+
+    pub(crate) fn encode(bytes: &[u8], variant: Variant) -> Vec<u8> {
+        let chars = variant.chars();
+        bytes
+            .chunks_exact(3)
+            .flat_map(|tri| {
+                let concated = u32::from(tri[0]) << 16 | u32::from(tri[1]) << 8 | u32::from(tri[2]);
+                [18, 12, 6, 0].map(|shift| chars[(concated >> shift & 0x3f) as usize])
+            })
+            .collect()
+    }
+
+    pub(crate) fn decode(chars: &[u8], variant: Variant) -> Option<Vec<u8>> {
+        let table = variant.decode_table();
+        let mut decoded = Vec::new();
+        for quad in chars.chunks_exact(4) {
+            let mut concated = 0;
+            for &c in quad {
+                let bits = *table.get(usize::from(c))?;
+                if bits == super::INVALID_BITS {
+                    return None;
+                }
+                concated = concated << 6 | u32::from(bits);
+            }
+            decoded.extend_from_slice(&concated.to_be_bytes()[1..]);
+        }
+
+        Some(decoded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        alphabet::{self, Standard, UrlSafe},
+        base64string::ascii_decode_table,
+        Base64String,
+    };
+
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn recognises_alphabets() {
+        let encode_table = |alphabet: &dyn crate::alphabet::Alphabet| {
+            let mut table = [0; 64];
+            for (bits, entry) in (0..64).zip(&mut table) {
+                *entry = alphabet.encode_bits(bits) as u8;
+            }
+            table
+        };
+
+        for (name, variant) in [
+            ("standard", Variant::Standard),
+            ("urlsafe", Variant::UrlSafe),
+        ] {
+            let alphabet = alphabet::by_name(name).unwrap();
+            assert_eq!(
+                Variant::from_encode_table(&encode_table(&alphabet)),
+                Some(variant)
+            );
+            assert_eq!(
+                Variant::from_decode_table(&ascii_decode_table(&alphabet)),
+                Some(variant)
+            );
+        }
+        assert_eq!(
+            Variant::from_decode_table(&ascii_decode_table(&Standard::new())),
+            Some(Variant::Standard)
+        );
+        assert_eq!(
+            Variant::from_decode_table(&ascii_decode_table(&UrlSafe::new())),
+            Some(Variant::UrlSafe)
+        );
+
+        // Alphabets with other characters use the scalar path
+        let mut swapped = *STANDARD_CHARS;
+        swapped.swap(0, 1);
+        assert_eq!(Variant::from_encode_table(&swapped), None);
+        assert_eq!(
+            Variant::from_decode_table(&ascii_decode_table(&alphabet::Permissive::new())),
+            None
+        );
+    }
+
+    #[test]
+    fn whole_blocks_only() {
+        let data = (0..=255).collect::<Vec<u8>>();
+        let b64 = Base64String::encode_with(&data, Standard::new());
+
+        assert_eq!(b64.decode().unwrap(), data);
+        assert_eq!(
+            b64.to_string().as_bytes()[..340],
+            scalar::encode(&data[..255], Variant::Standard)[..]
+        );
+    }
+}
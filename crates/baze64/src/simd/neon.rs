@@ -0,0 +1,149 @@
+//! NEON paths, which translate with 64-byte table lookups rather than
+//! shuffles, as NEON can look up in 4 registers at once
+//!
+//! NEON is part of every AArch64 CPU Rust targets, so there's no need
+//! to check for it when run
+
+use std::arch::aarch64::*;
+
+use super::Variant;
+
+pub(super) fn encode(bytes: &[u8], variant: Variant, out: &mut Vec<u8>) -> usize {
+    // SAFETY: NEON is enabled at build time
+    unsafe { encode_neon(bytes, variant, out) }
+}
+
+pub(super) fn decode(chars: &[u8], variant: Variant, out: &mut Vec<u8>) -> Option<usize> {
+    // SAFETY: NEON is enabled at build time
+    unsafe { decode_neon(chars, variant, out) }
+}
+
+#[target_feature(enable = "neon")]
+fn encode_neon(bytes: &[u8], variant: Variant, out: &mut Vec<u8>) -> usize {
+    // SAFETY: The table is 64 bytes long
+    let table = unsafe { vld1q_u8_x4(variant.chars().as_ptr()) };
+    let mask = vdupq_n_u8(0x3f);
+    let mut encoded = 0;
+
+    while let Some(block) = bytes[encoded..].first_chunk::<48>() {
+        // SAFETY: `block` is 48 bytes long
+        let uint8x16x3_t(a, b, c) = unsafe { vld3q_u8(block.as_ptr()) };
+
+        let values = uint8x16x4_t(
+            vshrq_n_u8::<2>(a),
+            vandq_u8(vorrq_u8(vshlq_n_u8::<4>(a), vshrq_n_u8::<4>(b)), mask),
+            vandq_u8(vorrq_u8(vshlq_n_u8::<2>(b), vshrq_n_u8::<6>(c)), mask),
+            vandq_u8(c, mask),
+        );
+        let chars = uint8x16x4_t(
+            vqtbl4q_u8(table, values.0),
+            vqtbl4q_u8(table, values.1),
+            vqtbl4q_u8(table, values.2),
+            vqtbl4q_u8(table, values.3),
+        );
+
+        let mut block = [0; 64];
+        // SAFETY: `block` is 64 bytes long
+        unsafe { vst4q_u8(block.as_mut_ptr(), chars) };
+        out.extend_from_slice(&block);
+        encoded += 48;
+    }
+
+    encoded
+}
+
+#[target_feature(enable = "neon")]
+fn decode_neon(chars: &[u8], variant: Variant, out: &mut Vec<u8>) -> Option<usize> {
+    let table = variant.decode_table();
+    // SAFETY: Both halves of the table are 64 bytes long
+    let (low, high) = unsafe {
+        (
+            vld1q_u8_x4(table.as_ptr()),
+            vld1q_u8_x4(table[64..].as_ptr()),
+        )
+    };
+    let mut decoded = 0;
+
+    // Characters from 64 to 127 are out of range for the low half of
+    // the table, so give 0 there & are then looked up in the high
+    // half, & non-ASCII characters are out of range for both
+    let lookup = |c| vqtbx4q_u8(vqtbl4q_u8(low, c), high, vsubq_u8(c, vdupq_n_u8(64)));
+
+    while let Some(block) = chars[decoded..].first_chunk::<64>() {
+        // SAFETY: `block` is 64 bytes long
+        let input = unsafe { vld4q_u8(block.as_ptr()) };
+        let (a, b, c, d) = (
+            lookup(input.0),
+            lookup(input.1),
+            lookup(input.2),
+            lookup(input.3),
+        );
+
+        // Invalid characters have a value of 0xff, & non-ASCII ones
+        // have their top bit set
+        let values = vorrq_u8(vorrq_u8(a, b), vorrq_u8(c, d));
+        let inputs = vorrq_u8(vorrq_u8(input.0, input.1), vorrq_u8(input.2, input.3));
+        if vmaxvq_u8(vorrq_u8(values, vandq_u8(inputs, vdupq_n_u8(0x80)))) > 63 {
+            return None;
+        }
+
+        let bytes = uint8x16x3_t(
+            vorrq_u8(vshlq_n_u8::<2>(a), vshrq_n_u8::<4>(b)),
+            vorrq_u8(vshlq_n_u8::<4>(b), vshrq_n_u8::<2>(c)),
+            vorrq_u8(vshlq_n_u8::<6>(c), d),
+        );
+
+        let mut block = [0; 48];
+        // SAFETY: `block` is 48 bytes long
+        unsafe { vst3q_u8(block.as_mut_ptr(), bytes) };
+        out.extend_from_slice(&block);
+        decoded += 64;
+    }
+
+    Some(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simd::scalar;
+    use proptest::prelude::*;
+
+    fn variant() -> impl Strategy<Value = Variant> {
+        prop_oneof![Just(Variant::Standard), Just(Variant::UrlSafe)]
+    }
+
+    proptest! {
+        #[test]
+        fn encode_matches_scalar(
+            data in prop::collection::vec(any::<u8>(), 0..256),
+            variant in variant(),
+        ) {
+            let mut out = Vec::new();
+            let encoded = encode(&data, variant, &mut out);
+
+            prop_assert_eq!(encoded, data.len() / 48 * 48);
+            prop_assert_eq!(out, scalar::encode(&data[..encoded], variant));
+        }
+
+        #[test]
+        fn decode_matches_scalar(
+            data in prop::collection::vec(any::<u8>(), 0..256),
+            variant in variant(),
+            at in any::<prop::sample::Index>(),
+            invalid in any::<u8>(),
+        ) {
+            let mut chars = scalar::encode(&data, variant);
+            let covered = chars.len() / 64 * 64;
+            let mut out = Vec::new();
+            prop_assert_eq!(decode(&chars, variant, &mut out), Some(covered));
+            prop_assert_eq!(Some(out), scalar::decode(&chars[..covered], variant));
+
+            if covered > 0 {
+                chars[at.index(covered)] = invalid;
+                let expected = scalar::decode(&chars[..covered], variant).map(|_| covered);
+                prop_assert_eq!(decode(&chars, variant, &mut Vec::new()), expected);
+            }
+        }
+    }
+}
@@ -0,0 +1,469 @@
+//! SSSE3 & AVX2 paths, using the shuffle-based translation from
+//! Wojciech Muła's "Base64 encoding & decoding at almost the speed of
+//! a memory copy"
+//!
+//! Each 16 byte lane of a register holds 12 bytes of data or 16
+//! characters. The AVX2 paths do the same as the SSSE3 ones on 2
+//! lanes at once, & leave anything too short for a whole register to
+//! the SSSE3 ones
+
+#[cfg(target_arch = "x86")]
+use std::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+
+use super::Variant;
+
+/// What to add to each 6-bit value to get its character, indexed by
+/// the range the value is in, as worked out by [`translate`]
+fn encode_offsets(variant: Variant) -> [i8; 16] {
+    let (c62, c63) = match variant {
+        Variant::Standard => (b'+', b'/'),
+        Variant::UrlSafe => (b'-', b'_'),
+    };
+    let digits = b'0'.wrapping_sub(52) as i8;
+
+    [
+        (b'a' - 26) as i8,
+        digits,
+        digits,
+        digits,
+        digits,
+        digits,
+        digits,
+        digits,
+        digits,
+        digits,
+        digits,
+        c62.wrapping_sub(62) as i8,
+        c63.wrapping_sub(63) as i8,
+        b'A' as i8,
+        0,
+        0,
+    ]
+}
+
+/// Lookup tables for validating & translating characters
+struct DecodeTables {
+    /// Bits for the low nibble of each character, which are set for
+    /// any high nibble class it's invalid with
+    lo: [i8; 16],
+    /// The class bit for the high nibble of each character
+    hi: [i8; 16],
+    /// What to add to each character to get its 6-bit value, indexed
+    /// by its high nibble
+    roll: [i8; 16],
+    /// The character that needs a different offset to the rest with
+    /// its high nibble
+    odd_one_out: i8,
+    /// What's added to the odd one out's high nibble to give it its
+    /// own entry in `roll`
+    odd_index: i8,
+}
+
+impl DecodeTables {
+    fn new(variant: Variant) -> Self {
+        match variant {
+            // `+` is the only valid character with a high nibble of 2,
+            // & `/` uses the unused entry for a high nibble of 1
+            Variant::Standard => Self {
+                lo: [
+                    0x15, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x13, 0x1a, 0x1b,
+                    0x1b, 0x1b, 0x1a,
+                ],
+                hi: [
+                    0x10, 0x10, 0x01, 0x02, 0x04, 0x08, 0x04, 0x08, 0x10, 0x10, 0x10, 0x10, 0x10,
+                    0x10, 0x10, 0x10,
+                ],
+                roll: [0, 16, 19, 4, -65, -65, -71, -71, 0, 0, 0, 0, 0, 0, 0, 0],
+                odd_one_out: b'/' as i8,
+                odd_index: -1,
+            },
+            // `-` is the only valid character with a high nibble of 2,
+            // & `_` shares 5 with `P` to `Z`, so it's moved to 15. It's
+            // also the only valid character from 0x5B to 0x5F, where
+            // 0x7B to 0x7F are all invalid, so 7 gets its own class
+            Variant::UrlSafe => Self {
+                lo: [
+                    0x15, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x13, 0x3b, 0x3b,
+                    0x3a, 0x3b, 0x33,
+                ],
+                hi: [
+                    0x10, 0x10, 0x01, 0x02, 0x04, 0x08, 0x04, 0x20, 0x10, 0x10, 0x10, 0x10, 0x10,
+                    0x10, 0x10, 0x10,
+                ],
+                roll: [0, 0, 17, 4, -65, -65, -71, -71, 0, 0, 0, 0, 0, 0, 0, -32],
+                odd_one_out: b'_' as i8,
+                odd_index: 10,
+            },
+        }
+    }
+}
+
+pub(super) fn encode(bytes: &[u8], variant: Variant, out: &mut Vec<u8>) -> usize {
+    if is_x86_feature_detected!("avx2") {
+        // SAFETY: The CPU supports AVX2
+        unsafe { encode_avx2(bytes, variant, out) }
+    } else if is_x86_feature_detected!("ssse3") {
+        // SAFETY: The CPU supports SSSE3
+        unsafe { encode_ssse3(bytes, variant, out) }
+    } else {
+        0
+    }
+}
+
+pub(super) fn decode(chars: &[u8], variant: Variant, out: &mut Vec<u8>) -> Option<usize> {
+    if is_x86_feature_detected!("avx2") {
+        // SAFETY: The CPU supports AVX2
+        unsafe { decode_avx2(chars, variant, out) }
+    } else if is_x86_feature_detected!("ssse3") {
+        // SAFETY: The CPU supports SSSE3
+        unsafe { decode_ssse3(chars, variant, out) }
+    } else {
+        Some(0)
+    }
+}
+
+#[target_feature(enable = "sse2")]
+fn load(bytes: &[u8; 16]) -> __m128i {
+    // SAFETY: `bytes` is 16 bytes long, & the load doesn't need to be
+    // aligned
+    unsafe { _mm_loadu_si128(bytes.as_ptr().cast()) }
+}
+
+#[target_feature(enable = "sse2")]
+fn store(register: __m128i) -> [u8; 16] {
+    let mut bytes = [0; 16];
+    // SAFETY: `bytes` is 16 bytes long, & the store doesn't need to be
+    // aligned
+    unsafe { _mm_storeu_si128(bytes.as_mut_ptr().cast(), register) };
+    bytes
+}
+
+#[target_feature(enable = "avx")]
+fn store256(register: __m256i) -> [u8; 32] {
+    let mut bytes = [0; 32];
+    // SAFETY: `bytes` is 32 bytes long, & the store doesn't need to be
+    // aligned
+    unsafe { _mm256_storeu_si256(bytes.as_mut_ptr().cast(), register) };
+    bytes
+}
+
+#[target_feature(enable = "sse2")]
+fn load_i8(values: &[i8; 16]) -> __m128i {
+    // SAFETY: `values` is 16 bytes long, & the load doesn't need to be
+    // aligned
+    unsafe { _mm_loadu_si128(values.as_ptr().cast()) }
+}
+
+/// Put each 3 bytes of the first 12 in `input` into the 4 bytes of a
+/// 32-bit lane, with each 6-bit value in the bottom of its own byte
+#[target_feature(enable = "ssse3")]
+fn split_values(input: __m128i) -> __m128i {
+    let input = _mm_shuffle_epi8(
+        input,
+        _mm_setr_epi8(1, 0, 2, 1, 4, 3, 5, 4, 7, 6, 8, 7, 10, 9, 11, 10),
+    );
+    let ac = _mm_mulhi_epu16(
+        _mm_and_si128(input, _mm_set1_epi32(0x0fc0_fc00)),
+        _mm_set1_epi32(0x0400_0040),
+    );
+    let bd = _mm_mullo_epi16(
+        _mm_and_si128(input, _mm_set1_epi32(0x003f_03f0)),
+        _mm_set1_epi32(0x0100_0010),
+    );
+    _mm_or_si128(ac, bd)
+}
+
+/// Turn 6-bit values into characters, by adding an offset picked by
+/// which range of the alphabet the value is in: 0 to 25 are given 13,
+/// 26 to 51 are given 0, 52 to 61 are given 1 to 10, & 62 & 63 are
+/// given 11 & 12
+#[target_feature(enable = "ssse3")]
+fn translate(values: __m128i, offsets: __m128i) -> __m128i {
+    let ranges = _mm_or_si128(
+        _mm_subs_epu8(values, _mm_set1_epi8(51)),
+        _mm_and_si128(_mm_cmpgt_epi8(_mm_set1_epi8(26), values), _mm_set1_epi8(13)),
+    );
+    _mm_add_epi8(values, _mm_shuffle_epi8(offsets, ranges))
+}
+
+#[target_feature(enable = "ssse3")]
+fn encode_ssse3(bytes: &[u8], variant: Variant, out: &mut Vec<u8>) -> usize {
+    let offsets = load_i8(&encode_offsets(variant));
+    let mut encoded = 0;
+
+    // Each block loads 16 bytes but only encodes the first 12, so the
+    // last few are always left to the scalar path
+    while let Some(block) = bytes[encoded..].first_chunk::<16>() {
+        let chars = translate(split_values(load(block)), offsets);
+        out.extend_from_slice(&store(chars));
+        encoded += 12;
+    }
+
+    encoded
+}
+
+#[target_feature(enable = "avx2")]
+fn encode_avx2(bytes: &[u8], variant: Variant, out: &mut Vec<u8>) -> usize {
+    let offsets = _mm256_broadcastsi128_si256(load_i8(&encode_offsets(variant)));
+    let mut encoded = 0;
+
+    // The 2 lanes are loaded from 12 bytes apart, so each has 12 bytes
+    // of data at its start, like the SSSE3 path
+    while let Some(block) = bytes[encoded..].first_chunk::<28>() {
+        let (low, high) = (block[..16].try_into(), block[12..].try_into());
+        let (Ok(low), Ok(high)) = (low, high) else {
+            unreachable!("block is 28 bytes long");
+        };
+        let input = _mm256_set_m128i(load(high), load(low));
+
+        let input = _mm256_shuffle_epi8(
+            input,
+            _mm256_broadcastsi128_si256(_mm_setr_epi8(
+                1, 0, 2, 1, 4, 3, 5, 4, 7, 6, 8, 7, 10, 9, 11, 10,
+            )),
+        );
+        let ac = _mm256_mulhi_epu16(
+            _mm256_and_si256(input, _mm256_set1_epi32(0x0fc0_fc00)),
+            _mm256_set1_epi32(0x0400_0040),
+        );
+        let bd = _mm256_mullo_epi16(
+            _mm256_and_si256(input, _mm256_set1_epi32(0x003f_03f0)),
+            _mm256_set1_epi32(0x0100_0010),
+        );
+        let values = _mm256_or_si256(ac, bd);
+
+        let ranges = _mm256_or_si256(
+            _mm256_subs_epu8(values, _mm256_set1_epi8(51)),
+            _mm256_and_si256(
+                _mm256_cmpgt_epi8(_mm256_set1_epi8(26), values),
+                _mm256_set1_epi8(13),
+            ),
+        );
+        let chars = _mm256_add_epi8(values, _mm256_shuffle_epi8(offsets, ranges));
+
+        out.extend_from_slice(&store256(chars));
+        encoded += 24;
+    }
+
+    encoded + encode_ssse3(&bytes[encoded..], variant, out)
+}
+
+#[target_feature(enable = "ssse3")]
+fn decode_ssse3(chars: &[u8], variant: Variant, out: &mut Vec<u8>) -> Option<usize> {
+    let tables = DecodeTables::new(variant);
+    let (lo, hi, roll) = (
+        load_i8(&tables.lo),
+        load_i8(&tables.hi),
+        load_i8(&tables.roll),
+    );
+    let odd_one_out = _mm_set1_epi8(tables.odd_one_out);
+    let odd_index = _mm_set1_epi8(tables.odd_index);
+    let nibble_mask = _mm_set1_epi8(0x0f);
+    let mut decoded = 0;
+
+    while let Some(block) = chars[decoded..].first_chunk::<16>() {
+        let input = load(block);
+        let hi_nibbles = _mm_and_si128(_mm_srli_epi32(input, 4), nibble_mask);
+        let lo_nibbles = _mm_and_si128(input, nibble_mask);
+
+        // A character is invalid if its low nibble's bits include its
+        // high nibble's class
+        let invalid = _mm_and_si128(
+            _mm_shuffle_epi8(lo, lo_nibbles),
+            _mm_shuffle_epi8(hi, hi_nibbles),
+        );
+        if _mm_movemask_epi8(_mm_cmpgt_epi8(invalid, _mm_setzero_si128())) != 0 {
+            return None;
+        }
+
+        let odd = _mm_and_si128(_mm_cmpeq_epi8(input, odd_one_out), odd_index);
+        let roll_index = _mm_add_epi8(hi_nibbles, odd);
+        let values = _mm_add_epi8(input, _mm_shuffle_epi8(roll, roll_index));
+
+        // Join the 6-bit values of each 32-bit lane into 3 bytes, then
+        // put the bytes of every lane next to each other
+        let pairs = _mm_maddubs_epi16(values, _mm_set1_epi32(0x0140_0140));
+        let triplets = _mm_madd_epi16(pairs, _mm_set1_epi32(0x0001_1000));
+        let bytes = _mm_shuffle_epi8(
+            triplets,
+            _mm_setr_epi8(2, 1, 0, 6, 5, 4, 10, 9, 8, 14, 13, 12, -1, -1, -1, -1),
+        );
+
+        out.extend_from_slice(&store(bytes)[..12]);
+        decoded += 16;
+    }
+
+    Some(decoded)
+}
+
+#[target_feature(enable = "avx2")]
+fn decode_avx2(chars: &[u8], variant: Variant, out: &mut Vec<u8>) -> Option<usize> {
+    let tables = DecodeTables::new(variant);
+    let (lo, hi, roll) = (
+        _mm256_broadcastsi128_si256(load_i8(&tables.lo)),
+        _mm256_broadcastsi128_si256(load_i8(&tables.hi)),
+        _mm256_broadcastsi128_si256(load_i8(&tables.roll)),
+    );
+    let odd_one_out = _mm256_set1_epi8(tables.odd_one_out);
+    let odd_index = _mm256_set1_epi8(tables.odd_index);
+    let nibble_mask = _mm256_set1_epi8(0x0f);
+    let mut decoded = 0;
+
+    while let Some(block) = chars[decoded..].first_chunk::<32>() {
+        let (low, high) = block.split_at(16);
+        let (Ok(low), Ok(high)) = (low.try_into(), high.try_into()) else {
+            unreachable!("block is 32 bytes long");
+        };
+        let input = _mm256_set_m128i(load(high), load(low));
+        let hi_nibbles = _mm256_and_si256(_mm256_srli_epi32(input, 4), nibble_mask);
+        let lo_nibbles = _mm256_and_si256(input, nibble_mask);
+
+        let invalid = _mm256_and_si256(
+            _mm256_shuffle_epi8(lo, lo_nibbles),
+            _mm256_shuffle_epi8(hi, hi_nibbles),
+        );
+        if _mm256_movemask_epi8(_mm256_cmpgt_epi8(invalid, _mm256_setzero_si256())) != 0 {
+            return None;
+        }
+
+        let odd = _mm256_and_si256(_mm256_cmpeq_epi8(input, odd_one_out), odd_index);
+        let roll_index = _mm256_add_epi8(hi_nibbles, odd);
+        let values = _mm256_add_epi8(input, _mm256_shuffle_epi8(roll, roll_index));
+
+        let pairs = _mm256_maddubs_epi16(values, _mm256_set1_epi32(0x0140_0140));
+        let triplets = _mm256_madd_epi16(pairs, _mm256_set1_epi32(0x0001_1000));
+        let lanes = _mm256_shuffle_epi8(
+            triplets,
+            _mm256_broadcastsi128_si256(_mm_setr_epi8(
+                2, 1, 0, 6, 5, 4, 10, 9, 8, 14, 13, 12, -1, -1, -1, -1,
+            )),
+        );
+        // Move the 12 bytes at the start of the high lane to just after
+        // those in the low lane
+        let bytes = _mm256_permutevar8x32_epi32(lanes, _mm256_setr_epi32(0, 1, 2, 4, 5, 6, 7, 7));
+
+        out.extend_from_slice(&store256(bytes)[..24]);
+        decoded += 32;
+    }
+
+    decode_ssse3(&chars[decoded..], variant, out).map(|rest| decoded + rest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simd::scalar;
+    use proptest::prelude::*;
+
+    type Encoder = unsafe fn(&[u8], Variant, &mut Vec<u8>) -> usize;
+    type Decoder = unsafe fn(&[u8], Variant, &mut Vec<u8>) -> Option<usize>;
+
+    /// Every path this CPU can run, by name
+    fn paths() -> Vec<(&'static str, Encoder, Decoder)> {
+        let mut paths = Vec::<(_, Encoder, Decoder)>::new();
+        if is_x86_feature_detected!("ssse3") {
+            paths.push(("ssse3", encode_ssse3, decode_ssse3));
+        }
+        if is_x86_feature_detected!("avx2") {
+            paths.push(("avx2", encode_avx2, decode_avx2));
+        }
+        paths
+    }
+
+    fn variant() -> impl Strategy<Value = Variant> {
+        prop_oneof![Just(Variant::Standard), Just(Variant::UrlSafe)]
+    }
+
+    proptest! {
+        #[test]
+        fn encode_matches_scalar(
+            data in prop::collection::vec(any::<u8>(), 0..256),
+            variant in variant(),
+        ) {
+            for (name, encode, _) in paths() {
+                let mut out = Vec::new();
+                // SAFETY: `paths` only gives paths the CPU supports
+                let encoded = unsafe { encode(&data, variant, &mut out) };
+
+                prop_assert_eq!(encoded % 3, 0, "{}", name);
+                prop_assert!(data.len() - encoded < 28, "{} left {} bytes", name, data.len() - encoded);
+                prop_assert_eq!(&out, &scalar::encode(&data[..encoded], variant), "{}", name);
+            }
+        }
+
+        #[test]
+        fn decode_matches_scalar(
+            data in prop::collection::vec(any::<u8>(), 0..256),
+            variant in variant(),
+        ) {
+            let chars = scalar::encode(&data, variant);
+            for (name, _, decode) in paths() {
+                let mut out = Vec::new();
+                // SAFETY: `paths` only gives paths the CPU supports
+                let decoded = unsafe { decode(&chars, variant, &mut out) }.unwrap();
+
+                prop_assert_eq!(decoded % 16, 0, "{}", name);
+                prop_assert!(chars.len() - decoded < 16, "{}", name);
+                prop_assert_eq!(&out, &data[..decoded / 4 * 3], "{}", name);
+            }
+        }
+
+        #[test]
+        fn decode_rejects_invalid(
+            data in prop::collection::vec(any::<u8>(), 12..96),
+            variant in variant(),
+            at in any::<prop::sample::Index>(),
+            invalid in any::<u8>(),
+        ) {
+            let mut chars = scalar::encode(&data, variant);
+            let at = at.index(chars.len());
+            chars[at] = invalid;
+            let valid = is_valid(variant, invalid);
+
+            for (name, _, decode) in paths() {
+                let mut out = Vec::new();
+                // SAFETY: `paths` only gives paths the CPU supports
+                let decoded = unsafe { decode(&chars, variant, &mut out) };
+                let covered = chars.len() / 16 * 16;
+
+                if valid || at >= covered {
+                    prop_assert_eq!(decoded, Some(covered), "{}", name);
+                    prop_assert_eq!(Some(out), scalar::decode(&chars[..covered], variant), "{}", name);
+                } else {
+                    prop_assert_eq!(decoded, None, "{} with {:#x} at {}", name, invalid, at);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_at_every_lane() {
+        for variant in [Variant::Standard, Variant::UrlSafe] {
+            let chars = scalar::encode(&[0xa5; 48], variant);
+            let invalid = (0..=255)
+                .filter(|&c| !is_valid(variant, c))
+                .collect::<Vec<_>>();
+
+            for (name, _, decode) in paths() {
+                for at in 0..chars.len() {
+                    for &c in &invalid {
+                        let mut chars = chars.clone();
+                        chars[at] = c;
+                        // SAFETY: `paths` only gives paths the CPU supports
+                        let decoded = unsafe { decode(&chars, variant, &mut Vec::new()) };
+                        assert_eq!(decoded, None, "{name} with {c:#x} at {at}");
+                    }
+                }
+            }
+        }
+    }
+
+    fn is_valid(variant: Variant, c: u8) -> bool {
+        variant
+            .decode_table()
+            .get(usize::from(c))
+            .is_some_and(|&bits| bits < 64)
+    }
+}
@@ -0,0 +1,692 @@
+//! Encode & decode base64 as it streams through a reader or writer,
+//! without holding all of it in memory
+//!
+//! [`Base64Decoder`] wraps a reader of base64 text, & reads as the
+//! bytes it decodes to. [`Base64Encoder`] wraps a writer, & writes
+//! the bytes written to it as base64 text.
+//!
+//! With the `futures-io` feature, [`AsyncBase64Decoder`] &
+//! [`AsyncBase64Encoder`] do the same for
+//! [`AsyncRead`](futures_io::AsyncRead) &
+//! [`AsyncWrite`](futures_io::AsyncWrite) types
+//!
+//! Invalid input is reported as an [`io::Error`] of kind
+//! [`InvalidData`](io::ErrorKind::InvalidData), wrapping the
+//! [`DecodeError`] describing it
+//!
+//! # Examples
+//! ```
+//! # use std::io::{Read, Write};
+//! # use baze64::{alphabet::Standard, stream::{Base64Decoder, Base64Encoder}};
+//! let mut encoder = Base64Encoder::<_, Standard>::new(Vec::new());
+//! encoder.write_all(b"streamed")?;
+//! let encoded = encoder.finish()?;
+//! assert_eq!(encoded, b"c3RyZWFtZWQ=");
+//!
+//! let mut decoded = Vec::new();
+//! Base64Decoder::<_, Standard>::new(&encoded[..]).read_to_end(&mut decoded)?;
+//! assert_eq!(decoded, b"streamed");
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+use std::io::{self, Read, Write};
+
+use crate::{
+    alphabet::Alphabet,
+    base64string::{decode_chars, Base64String},
+    B64Error, DecodeError,
+};
+
+#[cfg(feature = "futures-io")]
+mod async_io;
+
+#[cfg(feature = "futures-io")]
+pub use async_io::{AsyncBase64Decoder, AsyncBase64Encoder};
+
+/// The number of bytes of base64 text the decoders read at a time
+const STREAM_CHUNK_LEN: usize = 8 * 1024;
+
+/// The state shared by every streaming encoder & decoder, carrying
+/// partial characters, quads & triplets from one chunk to the next
+pub(crate) struct Base64StreamState<A> {
+    alphabet: A,
+    /// The start of a UTF-8 encoded character split between chunks
+    partial_char: [u8; 4],
+    partial_char_len: usize,
+    /// The characters of the quad being decoded
+    quad: [char; 4],
+    quad_len: usize,
+    /// The index in the input of the start of `quad`
+    index: usize,
+    /// The first padding character of a padded quad & its index,
+    /// which is invalid if any more characters follow it
+    padding: Option<(char, usize)>,
+    /// The total number of bytes decoded so far
+    decoded_len: usize,
+    /// The most bytes that may be decoded
+    limit: Option<usize>,
+    /// Whether the end of the input has been decoded
+    done: bool,
+    /// The error decoding failed with, returned again by every later
+    /// call rather than carrying on part way through a quad
+    failed: Option<DecodeError>,
+    /// Bytes written to an encoder that don't fill a triplet yet
+    carry: [u8; 3],
+    carry_len: usize,
+}
+
+impl<A> Base64StreamState<A>
+where
+    A: Alphabet,
+{
+    pub(crate) fn new(alphabet: A) -> Self {
+        Self {
+            alphabet,
+            partial_char: [0; 4],
+            partial_char_len: 0,
+            quad: ['\0'; 4],
+            quad_len: 0,
+            index: 0,
+            padding: None,
+            decoded_len: 0,
+            limit: None,
+            done: false,
+            failed: None,
+            carry: [0; 3],
+            carry_len: 0,
+        }
+    }
+
+    pub(crate) fn set_limit(&mut self, max_bytes: usize) {
+        self.limit = Some(max_bytes);
+    }
+
+    /// Whether the end of the input has been decoded
+    pub(crate) fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// The error decoding failed with, if it has
+    pub(crate) fn check_failed(&self) -> io::Result<()> {
+        match &self.failed {
+            Some(e) => Err(io::Error::new(io::ErrorKind::InvalidData, e.duplicate())),
+            None => Ok(()),
+        }
+    }
+
+    /// Decode a chunk of base64 text read from the input, appending
+    /// the bytes it decodes to onto `out`
+    ///
+    /// An empty `chunk` marks the end of the input, so any partial
+    /// quad left is decoded or reported as truncated. Once decoding
+    /// fails, nothing is appended & every call fails the same way
+    pub(crate) fn decode_chunk(&mut self, chunk: &[u8], out: &mut Vec<u8>) -> io::Result<()> {
+        self.check_failed()?;
+
+        let start = out.len();
+        let res = if chunk.is_empty() {
+            self.done = true;
+            self.finish_decode(out)
+        } else {
+            self.decode_bytes(chunk, out)
+        };
+        let res = res
+            .map_err(DecodeError::from)
+            .and_then(|()| self.count_decoded(out.len() - start));
+        if let Err(e) = res {
+            out.truncate(start);
+            self.failed = Some(e);
+        }
+        self.check_failed()
+    }
+
+    /// Check the total decoded is still within the limit
+    fn count_decoded(&mut self, len: usize) -> Result<(), DecodeError> {
+        self.decoded_len += len;
+        match self.limit {
+            Some(limit) if self.decoded_len > limit => Err(DecodeError::TooLarge {
+                needed: self.decoded_len,
+                limit,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    fn decode_bytes(&mut self, mut chunk: &[u8], out: &mut Vec<u8>) -> Result<(), B64Error> {
+        // Finish off a character split from the last chunk first
+        if self.partial_char_len > 0 {
+            let width = utf8_width(self.partial_char[0]);
+            let take = (width - self.partial_char_len).min(chunk.len());
+            self.partial_char[self.partial_char_len..self.partial_char_len + take]
+                .copy_from_slice(&chunk[..take]);
+            self.partial_char_len += take;
+            chunk = &chunk[take..];
+
+            if self.partial_char_len < width {
+                return Ok(());
+            }
+            let c = std::str::from_utf8(&self.partial_char[..width])
+                .ok()
+                .and_then(|s| s.chars().next())
+                .ok_or_else(|| self.invalid_utf8())?;
+            self.partial_char_len = 0;
+            self.push_char(c, out)?;
+        }
+
+        let (text, rest) = match std::str::from_utf8(chunk) {
+            Ok(text) => (text, &[][..]),
+            Err(e) => {
+                let (valid, rest) = chunk.split_at(e.valid_up_to());
+                // Only the end of a character cut off by the end of
+                // the chunk is allowed
+                let text = std::str::from_utf8(valid).unwrap_or_default();
+                match e.error_len() {
+                    None => (text, rest),
+                    Some(_) => {
+                        for c in text.chars() {
+                            self.push_char(c, out)?;
+                        }
+                        return Err(self.invalid_utf8());
+                    }
+                }
+            }
+        };
+
+        for c in text.chars() {
+            self.push_char(c, out)?;
+        }
+        self.partial_char[..rest.len()].copy_from_slice(rest);
+        self.partial_char_len = rest.len();
+
+        Ok(())
+    }
+
+    fn push_char(&mut self, c: char, out: &mut Vec<u8>) -> Result<(), B64Error> {
+        if let Some((char, index)) = self.padding {
+            return Err(B64Error::InvalidCharAt { char, index });
+        }
+        self.quad[self.quad_len] = c;
+        self.quad_len += 1;
+
+        if self.quad_len == 4 {
+            let (tri, len) = decode_chars(&self.quad, self.index, &self.alphabet)?;
+            if len < 3 {
+                self.padding = Some((self.quad[len + 1], self.index + len + 1));
+            }
+            out.extend_from_slice(&tri[..len]);
+            self.index += 4;
+            self.quad_len = 0;
+        }
+
+        Ok(())
+    }
+
+    fn finish_decode(&mut self, out: &mut Vec<u8>) -> Result<(), B64Error> {
+        if self.partial_char_len > 0 {
+            return Err(self.invalid_utf8());
+        }
+        if self.quad_len > 0 {
+            let (tri, len) = decode_chars(&self.quad[..self.quad_len], self.index, &self.alphabet)?;
+            out.extend_from_slice(&tri[..len]);
+            self.index += self.quad_len;
+            self.quad_len = 0;
+        }
+
+        Ok(())
+    }
+
+    /// The error for input that isn't valid UTF-8, reported as a
+    /// replacement character where the next character would be
+    fn invalid_utf8(&self) -> B64Error {
+        B64Error::InvalidCharAt {
+            char: char::REPLACEMENT_CHARACTER,
+            index: self.index + self.quad_len,
+        }
+    }
+
+    /// Encode `bytes`, appending the text to `out` & keeping any
+    /// bytes that don't fill a triplet for the next call
+    pub(crate) fn encode_chunk(&mut self, mut bytes: &[u8], out: &mut String) {
+        if self.carry_len > 0 {
+            let take = (3 - self.carry_len).min(bytes.len());
+            self.carry[self.carry_len..self.carry_len + take].copy_from_slice(&bytes[..take]);
+            self.carry_len += take;
+            bytes = &bytes[take..];
+
+            if self.carry_len < 3 {
+                return;
+            }
+            Base64String::push_encoded(out, &self.carry, &self.alphabet);
+            self.carry_len = 0;
+        }
+
+        let whole = bytes.len() / 3 * 3;
+        Base64String::push_encoded(out, &bytes[..whole], &self.alphabet);
+        self.carry[..bytes.len() - whole].copy_from_slice(&bytes[whole..]);
+        self.carry_len = bytes.len() - whole;
+    }
+
+    /// Encode the bytes carried over from the last call to
+    /// [`Base64StreamState::encode_chunk`], padding them if needed
+    pub(crate) fn finish_encode(&mut self, out: &mut String) {
+        Base64String::push_encoded(out, &self.carry[..self.carry_len], &self.alphabet);
+        self.carry_len = 0;
+    }
+}
+
+/// The number of bytes in a UTF-8 character starting with `first`
+fn utf8_width(first: u8) -> usize {
+    match first {
+        0xc0..=0xdf => 2,
+        0xe0..=0xef => 3,
+        _ => 4,
+    }
+}
+
+/// Reads base64 text from a reader, & reads as the bytes it
+/// decodes to
+///
+/// Partial quads are carried between reads of the inner reader, so
+/// it can return any amount of text at a time. Input that ends part
+/// way through a quad that can't encode any bytes is an error. Once a
+/// read fails on invalid input, every later read fails the same way
+///
+/// # Examples
+/// ```
+/// # use std::io::Read;
+/// # use baze64::{alphabet::UrlSafe, stream::Base64Decoder};
+/// let mut decoder = Base64Decoder::new_with("aGk_".as_bytes(), UrlSafe::new());
+/// let mut decoded = Vec::new();
+/// decoder.read_to_end(&mut decoded)?;
+///
+/// assert_eq!(decoded, b"hi?");
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub struct Base64Decoder<R, A> {
+    reader: R,
+    state: Base64StreamState<A>,
+    input: Box<[u8]>,
+    decoded: Vec<u8>,
+    pos: usize,
+}
+
+impl<R, A> Base64Decoder<R, A>
+where
+    R: Read,
+    A: Alphabet,
+{
+    /// Decode the text read from `reader` using a given `alphabet`
+    /// instance
+    pub fn new_with(reader: R, alphabet: A) -> Self {
+        Self {
+            reader,
+            state: Base64StreamState::new(alphabet),
+            input: vec![0; STREAM_CHUNK_LEN].into_boxed_slice(),
+            decoded: Vec::new(),
+            pos: 0,
+        }
+    }
+
+    /// Fail with [`DecodeError::TooLarge`] once more than
+    /// `max_bytes` have been decoded
+    ///
+    /// The total length isn't known up front, so the bytes within
+    /// the limit are still read
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::io::Read;
+    /// # use baze64::{alphabet::Standard, stream::Base64Decoder};
+    /// let mut decoder = Base64Decoder::<_, Standard>::new("Zm9vYmFy".as_bytes()).with_limit(4);
+    ///
+    /// assert!(decoder.read_to_end(&mut Vec::new()).is_err());
+    /// ```
+    pub fn with_limit(mut self, max_bytes: usize) -> Self {
+        self.state.set_limit(max_bytes);
+        self
+    }
+
+    /// Get back the inner reader
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R, A> Base64Decoder<R, A>
+where
+    R: Read,
+    A: Alphabet + Default,
+{
+    /// Decode the text read from `reader`
+    ///
+    /// Uses `A`'s [`Default`] impl as the alphabet to decode
+    /// with
+    pub fn new(reader: R) -> Self {
+        Self::new_with(reader, A::default())
+    }
+}
+
+impl<R, A> Read for Base64Decoder<R, A>
+where
+    R: Read,
+    A: Alphabet,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            if self.pos < self.decoded.len() {
+                let n = buf.len().min(self.decoded.len() - self.pos);
+                buf[..n].copy_from_slice(&self.decoded[self.pos..self.pos + n]);
+                self.pos += n;
+                return Ok(n);
+            }
+            self.state.check_failed()?;
+            if self.state.is_done() || buf.is_empty() {
+                return Ok(0);
+            }
+
+            self.decoded.clear();
+            self.pos = 0;
+            let n = self.reader.read(&mut self.input)?;
+            self.state
+                .decode_chunk(&self.input[..n], &mut self.decoded)?;
+        }
+    }
+}
+
+/// Writes the bytes written to it as base64 text to a writer
+///
+/// Encoded text is held until the next write or flush, like a
+/// [`BufWriter`](std::io::BufWriter). Call
+/// [`Base64Encoder::finish`] once everything's been written, to
+/// write the last quad & its padding. Dropping the encoder without
+/// finishing it loses them
+///
+/// # Examples
+/// ```
+/// # use std::io::Write;
+/// # use baze64::{alphabet::Standard, stream::Base64Encoder};
+/// let mut encoder = Base64Encoder::new_with(Vec::new(), Standard::new());
+/// encoder.write_all(b"f")?;
+/// encoder.write_all(b"oo")?;
+///
+/// assert_eq!(encoder.finish()?, b"Zm9v");
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub struct Base64Encoder<W, A> {
+    writer: W,
+    state: Base64StreamState<A>,
+    pending: String,
+}
+
+impl<W, A> Base64Encoder<W, A>
+where
+    W: Write,
+    A: Alphabet,
+{
+    /// Write the encoded text to `writer` using a given `alphabet`
+    /// instance
+    pub fn new_with(writer: W, alphabet: A) -> Self {
+        Self {
+            writer,
+            state: Base64StreamState::new(alphabet),
+            pending: String::new(),
+        }
+    }
+
+    /// Write the end of the encoded text, with any padding it
+    /// needs, & flush the inner writer, returning it
+    pub fn finish(mut self) -> io::Result<W> {
+        self.state.finish_encode(&mut self.pending);
+        self.flush()?;
+        Ok(self.writer)
+    }
+
+    fn write_pending(&mut self) -> io::Result<()> {
+        self.writer.write_all(self.pending.as_bytes())?;
+        self.pending.clear();
+        Ok(())
+    }
+}
+
+impl<W, A> Base64Encoder<W, A>
+where
+    W: Write,
+    A: Alphabet + Default,
+{
+    /// Write the encoded text to `writer`
+    ///
+    /// Uses `A`'s [`Default`] impl as the alphabet to encode
+    /// with
+    pub fn new(writer: W) -> Self {
+        Self::new_with(writer, A::default())
+    }
+}
+
+impl<W, A> Write for Base64Encoder<W, A>
+where
+    W: Write,
+    A: Alphabet,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // Write out the last call's text first, so an error doesn't
+        // lose any of `buf`
+        self.write_pending()?;
+        self.state.encode_chunk(buf, &mut self.pending);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.write_pending()?;
+        self.writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alphabet::{Standard, UrlSafe};
+
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    /// A reader that returns at most `step` bytes at a time
+    struct SlowReader<'a> {
+        data: &'a [u8],
+        step: usize,
+    }
+
+    impl Read for SlowReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = self.step.min(buf.len()).min(self.data.len());
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data = &self.data[n..];
+            Ok(n)
+        }
+    }
+
+    fn decode_slowly<A>(text: &str, step: usize, alphabet: A) -> io::Result<Vec<u8>>
+    where
+        A: Alphabet,
+    {
+        let reader = SlowReader {
+            data: text.as_bytes(),
+            step,
+        };
+        let mut decoded = Vec::new();
+        Base64Decoder::new_with(reader, alphabet).read_to_end(&mut decoded)?;
+        Ok(decoded)
+    }
+
+    /// The [`DecodeError`] wrapped in an error from a decoder
+    fn decode_error(e: &io::Error) -> &DecodeError {
+        e.get_ref().unwrap().downcast_ref().unwrap()
+    }
+
+    #[test]
+    fn decoder_matches_decode() {
+        let data = (0..=255).collect::<Vec<u8>>();
+        for len in 0..=data.len() {
+            let b64 = Base64String::<UrlSafe>::encode(&data[..len]);
+            for step in [1, 3, 4, 7, 4096] {
+                assert_eq!(
+                    decode_slowly(b64.as_ref(), step, UrlSafe::new()).unwrap(),
+                    data[..len]
+                );
+                assert_eq!(
+                    decode_slowly(&b64.without_padding(), step, UrlSafe::new()).unwrap(),
+                    data[..len]
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn decoder_multi_byte_padding() {
+        let alphabet = Standard::new().with_padding('…').unwrap();
+        let b64 = Base64String::encode_with(b"fooba", alphabet);
+
+        for step in 1..8 {
+            assert_eq!(
+                decode_slowly(b64.as_ref(), step, alphabet).unwrap(),
+                b"fooba"
+            );
+        }
+    }
+
+    #[test]
+    fn decoder_truncated() {
+        let err = decode_slowly("Zm9vY", 2, Standard::new()).unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(matches!(
+            decode_error(&err),
+            DecodeError::Base64Error(B64Error::Truncated {
+                expected: 6,
+                actual: 5
+            })
+        ));
+    }
+
+    #[test]
+    fn decoder_fused_after_error() {
+        // The bytes after the error are never read, even though the
+        // quads they're in are valid
+        let mut decoder = Base64Decoder::<_, Standard>::new("Zm9v#mFyYmF6".as_bytes());
+        let mut buf = [0; 16];
+
+        for _ in 0..3 {
+            let err = decoder.read(&mut buf).unwrap_err();
+            assert!(matches!(
+                decode_error(&err),
+                DecodeError::Base64Error(B64Error::InvalidCharAt {
+                    char: '#',
+                    index: 4
+                })
+            ));
+        }
+
+        let mut decoder = Base64Decoder::<_, Standard>::new("Zm9vYmFy".as_bytes()).with_limit(4);
+        for _ in 0..2 {
+            let err = decoder.read(&mut buf).unwrap_err();
+            assert!(matches!(
+                decode_error(&err),
+                DecodeError::TooLarge {
+                    needed: 6,
+                    limit: 4
+                }
+            ));
+        }
+    }
+
+    #[test]
+    fn decoder_error_index() {
+        for step in [1, 5, 100] {
+            let err = decode_slowly("Zm9vYmFy=mF6", step, Standard::new()).unwrap_err();
+
+            assert!(matches!(
+                decode_error(&err),
+                DecodeError::Base64Error(B64Error::InvalidCharAt {
+                    char: '=',
+                    index: 8
+                })
+            ));
+        }
+    }
+
+    #[test]
+    fn decoder_interior_padding() {
+        for step in [1, 3, 100] {
+            let err = decode_slowly("Zm8=YmFy", step, Standard::new()).unwrap_err();
+
+            assert!(matches!(
+                decode_error(&err),
+                DecodeError::Base64Error(B64Error::InvalidCharAt {
+                    char: '=',
+                    index: 3
+                })
+            ));
+        }
+    }
+
+    #[test]
+    fn decoder_invalid_utf8() {
+        let reader = SlowReader {
+            data: b"Zm9v\xffmFy",
+            step: 3,
+        };
+        let err = Base64Decoder::<_, Standard>::new(reader)
+            .read_to_end(&mut Vec::new())
+            .unwrap_err();
+
+        assert!(matches!(
+            decode_error(&err),
+            DecodeError::Base64Error(B64Error::InvalidCharAt {
+                char: char::REPLACEMENT_CHARACTER,
+                index: 4
+            })
+        ));
+    }
+
+    #[test]
+    fn decoder_limit() {
+        let b64 = Base64String::<Standard>::encode([0; 64]).to_string();
+        let decoder = |limit| Base64Decoder::<_, Standard>::new(b64.as_bytes()).with_limit(limit);
+
+        let mut decoded = Vec::new();
+        decoder(64).read_to_end(&mut decoded).unwrap();
+        assert_eq!(decoded.len(), 64);
+
+        let err = decoder(63).read_to_end(&mut Vec::new()).unwrap_err();
+        assert!(matches!(
+            decode_error(&err),
+            DecodeError::TooLarge { limit: 63, .. }
+        ));
+    }
+
+    #[test]
+    fn encoder_matches_encode() {
+        let data = (0..=255).collect::<Vec<u8>>();
+        for len in 0..=data.len() {
+            for step in [1, 2, 3, 5, 4096] {
+                let mut encoder = Base64Encoder::<_, Standard>::new(Vec::new());
+                for chunk in data[..len].chunks(step) {
+                    encoder.write_all(chunk).unwrap();
+                }
+
+                assert_eq!(
+                    String::from_utf8(encoder.finish().unwrap()).unwrap(),
+                    Base64String::<Standard>::encode(&data[..len]).to_string()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn encoder_flush_writes_whole_quads() {
+        let mut encoder = Base64Encoder::<_, Standard>::new(Vec::new());
+        encoder.write_all(b"foob").unwrap();
+        encoder.flush().unwrap();
+
+        assert_eq!(encoder.writer, b"Zm9v");
+        assert_eq!(encoder.finish().unwrap(), b"Zm9vYg==");
+    }
+}
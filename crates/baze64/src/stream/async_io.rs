@@ -0,0 +1,245 @@
+use std::{
+    io,
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+
+use futures_io::{AsyncRead, AsyncWrite};
+
+use crate::alphabet::Alphabet;
+
+use super::{Base64StreamState, STREAM_CHUNK_LEN};
+
+/// Reads base64 text from an [`AsyncRead`], & reads as the bytes
+/// it decodes to
+///
+/// The async counterpart to [`Base64Decoder`](super::Base64Decoder),
+/// decoding the same way. The inner reader needs to be [`Unpin`],
+/// so wrap it in [`Box::pin`] if it isn't. Tokio's readers can be
+/// used through `tokio_util::compat`
+///
+/// # Examples
+/// ```
+/// # use baze64::{alphabet::Standard, stream::AsyncBase64Decoder};
+/// # use futures_lite::{future, AsyncReadExt};
+/// # future::block_on(async {
+/// let mut decoder = AsyncBase64Decoder::<_, Standard>::new("aGk=".as_bytes());
+/// let mut decoded = Vec::new();
+/// decoder.read_to_end(&mut decoded).await?;
+///
+/// assert_eq!(decoded, b"hi");
+/// # Ok::<(), std::io::Error>(())
+/// # }).unwrap();
+/// ```
+pub struct AsyncBase64Decoder<R, A> {
+    reader: R,
+    state: Base64StreamState<A>,
+    input: Box<[u8]>,
+    decoded: Vec<u8>,
+    pos: usize,
+}
+
+impl<R, A> AsyncBase64Decoder<R, A>
+where
+    R: AsyncRead + Unpin,
+    A: Alphabet + Unpin,
+{
+    /// Decode the text read from `reader` using a given `alphabet`
+    /// instance
+    pub fn new_with(reader: R, alphabet: A) -> Self {
+        Self {
+            reader,
+            state: Base64StreamState::new(alphabet),
+            input: vec![0; STREAM_CHUNK_LEN].into_boxed_slice(),
+            decoded: Vec::new(),
+            pos: 0,
+        }
+    }
+
+    /// Fail with [`DecodeError::TooLarge`](crate::DecodeError::TooLarge)
+    /// once more than `max_bytes` have been decoded
+    pub fn with_limit(mut self, max_bytes: usize) -> Self {
+        self.state.set_limit(max_bytes);
+        self
+    }
+
+    /// Get back the inner reader
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R, A> AsyncBase64Decoder<R, A>
+where
+    R: AsyncRead + Unpin,
+    A: Alphabet + Default + Unpin,
+{
+    /// Decode the text read from `reader`
+    ///
+    /// Uses `A`'s [`Default`] impl as the alphabet to decode
+    /// with
+    pub fn new(reader: R) -> Self {
+        Self::new_with(reader, A::default())
+    }
+}
+
+impl<R, A> AsyncRead for AsyncBase64Decoder<R, A>
+where
+    R: AsyncRead + Unpin,
+    A: Alphabet + Unpin,
+{
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+
+        loop {
+            if this.pos < this.decoded.len() {
+                let n = buf.len().min(this.decoded.len() - this.pos);
+                buf[..n].copy_from_slice(&this.decoded[this.pos..this.pos + n]);
+                this.pos += n;
+                return Poll::Ready(Ok(n));
+            }
+            this.state.check_failed()?;
+            if this.state.is_done() || buf.is_empty() {
+                return Poll::Ready(Ok(0));
+            }
+
+            // Any partial quad stays in the state while the reader
+            // is pending
+            let n = ready!(Pin::new(&mut this.reader).poll_read(cx, &mut this.input))?;
+            this.decoded.clear();
+            this.pos = 0;
+            this.state
+                .decode_chunk(&this.input[..n], &mut this.decoded)?;
+        }
+    }
+}
+
+/// Writes the bytes written to it as base64 text to an
+/// [`AsyncWrite`]
+///
+/// The async counterpart to [`Base64Encoder`](super::Base64Encoder).
+/// Closing the encoder writes the last quad & its padding before
+/// closing the inner writer, so it must be closed once everything's
+/// been written
+///
+/// # Examples
+/// ```
+/// # use baze64::{alphabet::Standard, stream::AsyncBase64Encoder};
+/// # use futures_lite::{future, AsyncWriteExt};
+/// # future::block_on(async {
+/// let mut encoder = AsyncBase64Encoder::<_, Standard>::new(Vec::new());
+/// encoder.write_all(b"hi").await?;
+/// encoder.close().await?;
+///
+/// assert_eq!(encoder.into_inner(), b"aGk=");
+/// # Ok::<(), std::io::Error>(())
+/// # }).unwrap();
+/// ```
+pub struct AsyncBase64Encoder<W, A> {
+    writer: W,
+    state: Base64StreamState<A>,
+    pending: String,
+    written: usize,
+    finished: bool,
+}
+
+impl<W, A> AsyncBase64Encoder<W, A>
+where
+    W: AsyncWrite + Unpin,
+    A: Alphabet + Unpin,
+{
+    /// Write the encoded text to `writer` using a given `alphabet`
+    /// instance
+    pub fn new_with(writer: W, alphabet: A) -> Self {
+        Self {
+            writer,
+            state: Base64StreamState::new(alphabet),
+            pending: String::new(),
+            written: 0,
+            finished: false,
+        }
+    }
+
+    /// Get back the inner writer
+    ///
+    /// Anything not yet written by flushing or closing the encoder
+    /// is lost
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn poll_write_pending(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        while self.written < self.pending.len() {
+            let n =
+                ready!(Pin::new(&mut self.writer)
+                    .poll_write(cx, &self.pending.as_bytes()[self.written..]))?;
+            if n == 0 {
+                return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+            }
+            self.written += n;
+        }
+        self.pending.clear();
+        self.written = 0;
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<W, A> AsyncBase64Encoder<W, A>
+where
+    W: AsyncWrite + Unpin,
+    A: Alphabet + Default + Unpin,
+{
+    /// Write the encoded text to `writer`
+    ///
+    /// Uses `A`'s [`Default`] impl as the alphabet to encode
+    /// with
+    pub fn new(writer: W) -> Self {
+        Self::new_with(writer, A::default())
+    }
+}
+
+impl<W, A> AsyncWrite for AsyncBase64Encoder<W, A>
+where
+    W: AsyncWrite + Unpin,
+    A: Alphabet + Unpin,
+{
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        if this.finished {
+            return Poll::Ready(Err(io::Error::other("write to a closed encoder")));
+        }
+
+        // Write out the last call's text first, so being pending
+        // doesn't lose any of `buf`
+        ready!(this.poll_write_pending(cx))?;
+        this.state.encode_chunk(buf, &mut this.pending);
+
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_write_pending(cx))?;
+        Pin::new(&mut this.writer).poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        if !this.finished {
+            this.state.finish_encode(&mut this.pending);
+            this.finished = true;
+        }
+
+        ready!(this.poll_write_pending(cx))?;
+        Pin::new(&mut this.writer).poll_close(cx)
+    }
+}
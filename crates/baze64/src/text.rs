@@ -0,0 +1,220 @@
+//! Encode text in encodings other than UTF-8, like the UTF-16LE that
+//! Windows tools encode to base64
+
+use crate::{alphabet::Alphabet, Base64String, DecodeError};
+
+/// How text is turned into the bytes that are base64 encoded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TextEncoding {
+    /// UTF-8, as [`Base64String::encode_str`] &
+    /// [`Base64String::decode_to_string`] use
+    #[default]
+    Utf8,
+    /// UTF-16 with the low byte of each code unit first, as used by
+    /// PowerShell's `-EncodedCommand`
+    Utf16Le,
+    /// UTF-16 with the high byte of each code unit first
+    Utf16Be,
+}
+
+impl TextEncoding {
+    /// Get the bytes of `text` in this encoding, without a byte order
+    /// mark
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::TextEncoding;
+    /// assert_eq!(TextEncoding::Utf16Le.encode_text("hi"), b"h\0i\0");
+    /// assert_eq!(TextEncoding::Utf16Be.encode_text("hi"), b"\0h\0i");
+    /// ```
+    pub fn encode_text(self, text: &str) -> Vec<u8> {
+        match self {
+            TextEncoding::Utf8 => text.as_bytes().to_vec(),
+            TextEncoding::Utf16Le => text.encode_utf16().flat_map(u16::to_le_bytes).collect(),
+            TextEncoding::Utf16Be => text.encode_utf16().flat_map(u16::to_be_bytes).collect(),
+        }
+    }
+
+    /// Read `bytes` as text in this encoding
+    ///
+    /// A byte order mark at the start is kept as `\u{feff}`. Fails
+    /// with [`DecodeError::InvalidUtf8`] or [`DecodeError::InvalidUtf16`]
+    /// if `bytes` aren't valid text
+    pub fn decode_text(self, bytes: Vec<u8>) -> Result<String, DecodeError> {
+        let from_bytes = match self {
+            TextEncoding::Utf8 => return Ok(String::from_utf8(bytes)?),
+            TextEncoding::Utf16Le => u16::from_le_bytes,
+            TextEncoding::Utf16Be => u16::from_be_bytes,
+        };
+        if !bytes.len().is_multiple_of(2) {
+            return Err(DecodeError::InvalidUtf16 {
+                offset: bytes.len() - 1,
+            });
+        }
+
+        let units = bytes
+            .chunks_exact(2)
+            .map(|pair| from_bytes([pair[0], pair[1]]));
+        let mut text = String::with_capacity(bytes.len() / 2);
+        for c in char::decode_utf16(units) {
+            match c {
+                Ok(c) => text.push(c),
+                Err(_) => {
+                    // Everything before the surrogate was valid, so the
+                    // text so far is exactly what came before it
+                    let offset = text.encode_utf16().count() * 2;
+                    return Err(DecodeError::InvalidUtf16 { offset });
+                }
+            }
+        }
+
+        Ok(text)
+    }
+}
+
+impl<A> Base64String<A>
+where
+    A: Alphabet,
+{
+    /// Encode the UTF-16LE bytes of `text` into a [`Base64String`]
+    /// using a given `alphabet` instance, like PowerShell's
+    /// `-EncodedCommand` expects
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, alphabet::Standard};
+    /// let command = Base64String::encode_utf16le_with("dir", Standard::new());
+    ///
+    /// assert_eq!(command.to_string(), "ZABpAHIA");
+    /// ```
+    pub fn encode_utf16le_with(text: &str, alphabet: A) -> Self {
+        Self::encode_with(TextEncoding::Utf16Le.encode_text(text), alphabet)
+    }
+
+    /// Encode the UTF-16BE bytes of `text` into a [`Base64String`]
+    /// using a given `alphabet` instance
+    pub fn encode_utf16be_with(text: &str, alphabet: A) -> Self {
+        Self::encode_with(TextEncoding::Utf16Be.encode_text(text), alphabet)
+    }
+
+    /// Decode the contents of `self` as UTF-16LE text into a [`String`]
+    ///
+    /// Fails with [`DecodeError::InvalidUtf16`] if the data has an odd
+    /// number of bytes or an unpaired surrogate
+    ///
+    /// # Examples
+    /// ```
+    /// # use baze64::{Base64String, alphabet::Standard};
+    /// let command = Base64String::<Standard>::from_encoded("ZABpAHIA")?;
+    ///
+    /// assert_eq!(command.decode_to_string_utf16le()?, "dir");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn decode_to_string_utf16le(&self) -> Result<String, DecodeError> {
+        TextEncoding::Utf16Le.decode_text(self.decode()?)
+    }
+
+    /// Decode the contents of `self` as UTF-16BE text into a [`String`]
+    ///
+    /// Fails with [`DecodeError::InvalidUtf16`] if the data has an odd
+    /// number of bytes or an unpaired surrogate
+    pub fn decode_to_string_utf16be(&self) -> Result<String, DecodeError> {
+        TextEncoding::Utf16Be.decode_text(self.decode()?)
+    }
+}
+
+impl<A> Base64String<A>
+where
+    A: Alphabet + Default,
+{
+    /// Encode the UTF-16LE bytes of `text` into a [`Base64String`]
+    ///
+    /// Uses `A`'s [`Default`] impl as the alphabet
+    /// to encode with
+    pub fn encode_utf16le(text: &str) -> Self {
+        Self::encode_utf16le_with(text, A::default())
+    }
+
+    /// Encode the UTF-16BE bytes of `text` into a [`Base64String`]
+    ///
+    /// Uses `A`'s [`Default`] impl as the alphabet
+    /// to encode with
+    pub fn encode_utf16be(text: &str) -> Self {
+        Self::encode_utf16be_with(text, A::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{alphabet::Standard, ErrorKind};
+
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn surrogate_pairs() {
+        let crab = "🦀 rust";
+
+        for encoding in [TextEncoding::Utf16Le, TextEncoding::Utf16Be] {
+            let bytes = encoding.encode_text(crab);
+            assert_eq!(bytes.len(), 14);
+            assert_eq!(encoding.decode_text(bytes).unwrap(), crab);
+        }
+        assert_eq!(
+            Base64String::<Standard>::encode_utf16be(crab)
+                .decode_to_string_utf16be()
+                .unwrap(),
+            crab
+        );
+    }
+
+    #[test]
+    fn byte_order() {
+        let le = Base64String::<Standard>::encode_utf16le("é");
+        let be = Base64String::<Standard>::encode_utf16be("é");
+
+        assert_eq!(le.decode().unwrap(), [0xe9, 0x00]);
+        assert_eq!(be.decode().unwrap(), [0x00, 0xe9]);
+        assert_eq!(le.decode_to_string_utf16be().unwrap(), "\u{e900}");
+    }
+
+    #[test]
+    fn odd_length() {
+        let err = TextEncoding::Utf16Le
+            .decode_text(vec![b'h', 0, b'i'])
+            .unwrap_err();
+
+        assert!(matches!(err, DecodeError::InvalidUtf16 { offset: 2 }));
+        assert_eq!(err.kind(), ErrorKind::InvalidUtf16);
+    }
+
+    #[test]
+    fn lone_surrogates() {
+        // A high surrogate followed by a letter rather than a low one
+        let high = [b'h', 0, 0x3d, 0xd8, b'i', 0];
+        // A low surrogate with nothing before it
+        let low = [0x80, 0xdd, b'i', 0];
+
+        assert!(matches!(
+            TextEncoding::Utf16Le.decode_text(high.to_vec()),
+            Err(DecodeError::InvalidUtf16 { offset: 2 })
+        ));
+        assert!(matches!(
+            TextEncoding::Utf16Le.decode_text(low.to_vec()),
+            Err(DecodeError::InvalidUtf16 { offset: 0 })
+        ));
+        assert!(matches!(
+            TextEncoding::Utf16Be.decode_text(vec![0xd8, 0x3d]),
+            Err(DecodeError::InvalidUtf16 { offset: 0 })
+        ));
+    }
+
+    #[test]
+    fn utf8() {
+        assert_eq!(TextEncoding::Utf8.encode_text("hé"), "hé".as_bytes());
+        assert!(matches!(
+            TextEncoding::Utf8.decode_text(vec![0xff]),
+            Err(DecodeError::InvalidUtf8(_))
+        ));
+    }
+}
@@ -0,0 +1,156 @@
+use crate::{alphabet::Alphabet, primitives, B64Error};
+
+/// Check that `b64` is well-formed base64 in the given `alphabet`,
+/// without decoding it
+///
+/// Every character must be in the alphabet, padding may only
+/// appear at the end & only as much as is needed to complete the
+/// last quad, & the length must be able to encode whole bytes.
+/// Unpadded input is accepted, as is empty input
+///
+/// # Examples
+/// ```
+/// # use baze64::{validate, B64Error, alphabet::Standard};
+/// assert_eq!(validate("Zm9vYg==", &Standard::new()), Ok(()));
+/// assert_eq!(validate("Zm9vYg", &Standard::new()), Ok(()));
+/// assert_eq!(
+///     validate("Zm9v=mFy", &Standard::new()),
+///     Err(B64Error::InvalidCharAt { char: '=', index: 4 })
+/// );
+/// ```
+pub fn validate<A>(b64: &str, alphabet: &A) -> Result<(), B64Error>
+where
+    A: Alphabet,
+{
+    let padding = alphabet.padding();
+    let mut data_len = 0;
+    let mut pad_len = 0;
+
+    for (index, c) in b64.chars().enumerate() {
+        if Some(c) == padding {
+            pad_len += 1;
+            continue;
+        }
+        if pad_len > 0 {
+            // Padding before the end of the input, which can only
+            // happen if the alphabet has a padding character
+            return Err(B64Error::InvalidCharAt {
+                char: padding.unwrap_or_default(),
+                index: data_len,
+            });
+        }
+
+        primitives::decode_bits(alphabet, c).map_err(|e| match e {
+            e @ B64Error::DecodedValueOOB { .. } => e,
+            _ => B64Error::InvalidCharAt { char: c, index },
+        })?;
+        data_len += 1;
+    }
+
+    if data_len % 4 == 1 {
+        return Err(B64Error::Truncated {
+            expected: data_len + 1,
+            actual: data_len,
+        });
+    }
+
+    let required_pad = (4 - data_len % 4) % 4;
+    if pad_len > required_pad {
+        Err(B64Error::InvalidCharAt {
+            char: padding.unwrap_or_default(),
+            index: data_len + required_pad,
+        })
+    } else if pad_len > 0 && pad_len < required_pad {
+        Err(B64Error::Truncated {
+            expected: data_len + required_pad,
+            actual: data_len + pad_len,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alphabet::{Standard, UrlSafe};
+
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn valid_padded() {
+        for b64 in ["Zg==", "Zm8=", "Zm9v", "Zm9vYmE=", "Zm9vYmFy"] {
+            assert_eq!(validate(b64, &Standard::new()), Ok(()), "{b64}");
+        }
+    }
+
+    #[test]
+    fn valid_unpadded() {
+        for b64 in ["Zg", "Zm8", "Zm9vYmE", "-_8"] {
+            assert_eq!(validate(b64, &UrlSafe::new()), Ok(()), "{b64}");
+        }
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(validate("", &Standard::new()), Ok(()));
+    }
+
+    #[test]
+    fn interior_padding() {
+        assert_eq!(
+            validate("Zm8=YmFy", &Standard::new()),
+            Err(B64Error::InvalidCharAt {
+                char: '=',
+                index: 3
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_char() {
+        assert_eq!(
+            validate("Zm9v#mFy", &Standard::new()),
+            Err(B64Error::InvalidCharAt {
+                char: '#',
+                index: 4
+            })
+        );
+        assert_eq!(
+            validate("-_8=", &Standard::new()),
+            Err(B64Error::InvalidCharAt {
+                char: '-',
+                index: 0
+            })
+        );
+    }
+
+    #[test]
+    fn excess_padding() {
+        assert_eq!(
+            validate("Zm8==", &Standard::new()),
+            Err(B64Error::InvalidCharAt {
+                char: '=',
+                index: 4
+            })
+        );
+    }
+
+    #[test]
+    fn bad_length() {
+        assert_eq!(
+            validate("Zm9vY", &Standard::new()),
+            Err(B64Error::Truncated {
+                expected: 6,
+                actual: 5
+            })
+        );
+        assert_eq!(
+            validate("Zm=", &Standard::new()),
+            Err(B64Error::Truncated {
+                expected: 4,
+                actual: 3
+            })
+        );
+    }
+}
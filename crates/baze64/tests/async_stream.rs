@@ -0,0 +1,240 @@
+#![cfg(feature = "futures-io")]
+
+use std::{
+    io::{self, Read, Write},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use baze64::{
+    alphabet::{Standard, UrlSafe},
+    stream::{AsyncBase64Decoder, AsyncBase64Encoder, Base64Decoder, Base64Encoder},
+    B64Error, Base64String, DecodeError,
+};
+use futures_lite::{future, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use proptest::prelude::*;
+
+/// An async reader or writer that's pending before every other
+/// operation, & handles at most the next of `steps` bytes at a time
+struct Choppy<T> {
+    inner: T,
+    steps: Vec<usize>,
+    step: usize,
+    ready: bool,
+}
+
+impl<T> Choppy<T> {
+    fn new(inner: T, steps: Vec<usize>) -> Self {
+        Self {
+            inner,
+            steps,
+            step: 0,
+            ready: false,
+        }
+    }
+
+    /// Be pending every other call, otherwise get how many bytes
+    /// to handle next
+    fn poll_step(&mut self, cx: &mut Context<'_>) -> Poll<usize> {
+        self.ready = !self.ready;
+        if !self.ready {
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+
+        let step = self.steps[self.step % self.steps.len()];
+        self.step += 1;
+        Poll::Ready(step)
+    }
+}
+
+impl AsyncRead for Choppy<&[u8]> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let step = std::task::ready!(this.poll_step(cx));
+        let n = step.min(buf.len()).min(this.inner.len());
+        buf[..n].copy_from_slice(&this.inner[..n]);
+        this.inner = &this.inner[n..];
+
+        Poll::Ready(Ok(n))
+    }
+}
+
+impl AsyncWrite for Choppy<Vec<u8>> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let step = std::task::ready!(this.poll_step(cx));
+        let n = step.min(buf.len());
+        this.inner.extend_from_slice(&buf[..n]);
+
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+fn decode_async(text: &str, steps: Vec<usize>) -> io::Result<Vec<u8>> {
+    future::block_on(async {
+        let reader = Choppy::new(text.as_bytes(), steps);
+        let mut decoded = Vec::new();
+        AsyncBase64Decoder::<_, UrlSafe>::new(reader)
+            .read_to_end(&mut decoded)
+            .await?;
+        Ok(decoded)
+    })
+}
+
+fn decode_sync(text: &str) -> io::Result<Vec<u8>> {
+    let mut decoded = Vec::new();
+    Base64Decoder::<_, UrlSafe>::new(text.as_bytes()).read_to_end(&mut decoded)?;
+    Ok(decoded)
+}
+
+fn encode_async(data: &[u8], steps: Vec<usize>) -> Vec<u8> {
+    future::block_on(async {
+        let writer = Choppy::new(Vec::new(), steps.clone());
+        let mut encoder = AsyncBase64Encoder::<_, UrlSafe>::new(writer);
+        for chunk in data.chunks(steps[0]) {
+            encoder.write_all(chunk).await.unwrap();
+        }
+        encoder.close().await.unwrap();
+        encoder.into_inner().inner
+    })
+}
+
+/// The [`DecodeError`] wrapped in an error from a decoder
+fn decode_error(e: &io::Error) -> &DecodeError {
+    e.get_ref().unwrap().downcast_ref().unwrap()
+}
+
+proptest! {
+    #[test]
+    fn decoder_matches_sync(
+        data in prop::collection::vec(any::<u8>(), 0..2048),
+        steps in prop::collection::vec(1..64usize, 1..8),
+        padded in any::<bool>(),
+    ) {
+        let b64 = Base64String::<UrlSafe>::encode(&data);
+        let text = if padded { b64.to_string() } else { b64.without_padding() };
+
+        prop_assert_eq!(decode_async(&text, steps).unwrap(), decode_sync(&text).unwrap());
+    }
+
+    #[test]
+    fn decoder_errors_match_sync(
+        text in "[A-Za-z0-9_=#-]{0,64}",
+        steps in prop::collection::vec(1..8usize, 1..8),
+    ) {
+        match (decode_async(&text, steps), decode_sync(&text)) {
+            (Ok(a), Ok(s)) => prop_assert_eq!(a, s),
+            (Err(a), Err(s)) => {
+                prop_assert_eq!(decode_error(&a).to_string(), decode_error(&s).to_string())
+            }
+            (a, s) => prop_assert!(false, "async gave {:?}, sync gave {:?}", a, s),
+        }
+    }
+
+    #[test]
+    fn encoder_matches_sync(
+        data in prop::collection::vec(any::<u8>(), 0..2048),
+        steps in prop::collection::vec(1..64usize, 1..8),
+    ) {
+        let mut encoder = Base64Encoder::<_, UrlSafe>::new(Vec::new());
+        encoder.write_all(&data).unwrap();
+
+        prop_assert_eq!(encode_async(&data, steps), encoder.finish().unwrap());
+    }
+}
+
+#[test]
+fn decoder_eof_mid_quad() {
+    let err = decode_async("Zm9vY", vec![1]).unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert!(matches!(
+        decode_error(&err),
+        DecodeError::Base64Error(B64Error::Truncated {
+            expected: 6,
+            actual: 5
+        })
+    ));
+}
+
+#[test]
+fn decoder_fused_after_error() {
+    future::block_on(async {
+        let reader = Choppy::new("Zm9vYm#yYmF6".as_bytes(), vec![2]);
+        let mut decoder = AsyncBase64Decoder::<_, Standard>::new(reader);
+        let mut buf = [0; 16];
+
+        // The first quad is read before the error is reached
+        let mut decoded = Vec::new();
+        let err = loop {
+            match decoder.read(&mut buf).await {
+                Ok(n) => decoded.extend_from_slice(&buf[..n]),
+                Err(e) => break e,
+            }
+        };
+        assert_eq!(decoded, b"foo");
+
+        for err in [err, decoder.read(&mut buf).await.unwrap_err()] {
+            assert!(matches!(
+                decode_error(&err),
+                DecodeError::Base64Error(B64Error::InvalidCharAt {
+                    char: '#',
+                    index: 6
+                })
+            ));
+        }
+    });
+}
+
+#[test]
+fn decoder_partial_quads_across_reads() {
+    // Every read ends part way through a quad
+    assert_eq!(decode_async("Zm9vYmFyYmF6", vec![3]).unwrap(), b"foobarbaz");
+    assert_eq!(decode_async("Zm9vYmE", vec![5, 1]).unwrap(), b"fooba");
+}
+
+#[test]
+fn decoder_limit() {
+    let err = future::block_on(async {
+        let mut decoder =
+            AsyncBase64Decoder::<_, Standard>::new("Zm9vYmFy".as_bytes()).with_limit(5);
+        decoder.read_to_end(&mut Vec::new()).await.unwrap_err()
+    });
+
+    assert!(matches!(
+        decode_error(&err),
+        DecodeError::TooLarge { limit: 5, .. }
+    ));
+}
+
+#[test]
+fn encoder_close_pads() {
+    assert_eq!(encode_async(b"fooba", vec![1]), b"Zm9vYmE=");
+}
+
+#[test]
+fn encoder_write_after_close() {
+    future::block_on(async {
+        let mut encoder = AsyncBase64Encoder::<_, Standard>::new(Vec::new());
+        encoder.close().await.unwrap();
+
+        assert!(encoder.write_all(b"more").await.is_err());
+    });
+}
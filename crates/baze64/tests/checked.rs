@@ -0,0 +1,55 @@
+//! Corruption that [`Base64String::decode_checked`] needs to catch
+use baze64::{alphabet::Standard, Base64String, DecodeError};
+
+const KEY: &[u8] = b"ABCD-1234-EFGH-5678";
+
+fn decodes_checked(b64: &str) -> bool {
+    Base64String::<Standard>::from_encoded(b64).is_ok_and(|b64| b64.decode_checked().is_ok())
+}
+
+#[test]
+fn every_single_character_change_is_caught() {
+    let encoded = Base64String::encode_checked(KEY, Standard::new()).to_string();
+    let original = Base64String::<Standard>::from_encoded(&encoded)
+        .unwrap()
+        .decode()
+        .unwrap();
+    let chars = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/=";
+
+    for i in 0..encoded.len() {
+        for c in chars.chars() {
+            let mut corrupted = encoded.clone();
+            corrupted.replace_range(i..i + 1, c.encode_utf8(&mut [0; 4]));
+            let same_data = Base64String::<Standard>::from_encoded(&corrupted)
+                .is_ok_and(|b64| b64.decode().is_ok_and(|data| data == original));
+
+            // Changing bits after the end of the data leaves the data
+            // the same, which the checksum can't see
+            assert_eq!(decodes_checked(&corrupted), same_data, "{corrupted}");
+        }
+    }
+}
+
+#[test]
+fn every_truncation_is_caught() {
+    let encoded = Base64String::encode_checked(KEY, Standard::new()).to_string();
+    // Padding can be left off without losing any data
+    let unpadded = encoded.trim_end_matches('=');
+
+    for len in 0..unpadded.len() {
+        assert!(!decodes_checked(&encoded[..len]), "{}", &encoded[..len]);
+    }
+    assert!(decodes_checked(unpadded));
+}
+
+#[test]
+fn dropped_bytes_are_caught() {
+    let mut framed = baze64::checked::frame(KEY);
+    framed.remove(4);
+    let b64 = Base64String::<Standard>::encode(&framed);
+
+    assert!(matches!(
+        b64.decode_checked(),
+        Err(DecodeError::ChecksumMismatch { .. })
+    ));
+}
@@ -0,0 +1,69 @@
+//! Checks decoding with a limit fails before allocating, using an
+//! allocator that records the largest allocation made. This is its
+//! own test binary so the allocator doesn't affect other tests
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+};
+
+use baze64::{alphabet::Standard, Base64String, DecodeError};
+
+/// Passes allocations to [`System`], recording the largest
+struct MaxAlloc;
+
+static LARGEST: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for MaxAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        LARGEST.fetch_max(layout.size(), Ordering::SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: MaxAlloc = MaxAlloc;
+
+/// Tests share [`LARGEST`], so only one can measure at a time
+static MEASURING: Mutex<()> = Mutex::new(());
+
+/// Run `f`, returning its result & the largest allocation it made
+fn largest_alloc<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let _guard = MEASURING.lock().unwrap();
+    LARGEST.store(0, Ordering::SeqCst);
+    let res = f();
+    (res, LARGEST.load(Ordering::SeqCst))
+}
+
+const LEN: usize = 4 * 1024 * 1024;
+
+#[test]
+fn over_limit_doesnt_allocate() {
+    let b64 = Base64String::<Standard>::encode(vec![0; LEN]);
+    let (res, largest) = largest_alloc(|| b64.decode_with_limit(LEN - 1));
+
+    assert!(matches!(
+        res,
+        Err(DecodeError::TooLarge {
+            needed: LEN,
+            limit
+        }) if limit == LEN - 1
+    ));
+    assert!(largest < 1024, "allocated {largest} bytes");
+}
+
+#[test]
+fn at_limit_decodes() {
+    let b64 = Base64String::<Standard>::encode(vec![0; LEN]);
+    let (res, largest) = largest_alloc(|| b64.decode_with_limit(LEN));
+
+    assert_eq!(res.unwrap().len(), LEN);
+    assert!(largest >= LEN);
+}
@@ -0,0 +1,127 @@
+//! Inputs with no data in them, or too little to hold a byte, give the
+//! same result whichever way they're decoded & with either built-in
+//! alphabet
+use baze64::{
+    alphabet::{Alphabet, Standard, UrlSafe},
+    validate, B64Error, Base64Str, Base64String, Codec, DecodeError,
+};
+use pretty_assertions::assert_eq;
+
+/// What decoding gives back
+type Expected = Result<&'static [u8], B64Error>;
+
+const PADDING_ONLY: Expected = Err(B64Error::InvalidCharAt {
+    char: '=',
+    index: 0,
+});
+const LONE_CHAR: Expected = Err(B64Error::Truncated {
+    expected: 2,
+    actual: 1,
+});
+
+/// `(input, expected)`, for inputs valid in both alphabets
+const VECTORS: &[(&str, Expected)] = &[
+    ("", Ok(b"")),
+    (" ", Ok(b"")),
+    ("\r\n", Ok(b"")),
+    ("  \n\t", Ok(b"")),
+    ("=", PADDING_ONLY),
+    ("==", PADDING_ONLY),
+    ("===", PADDING_ONLY),
+    ("====", PADDING_ONLY),
+    ("=====", PADDING_ONLY),
+    ("========", PADDING_ONLY),
+    ("Z", LONE_CHAR),
+    ("Z=", LONE_CHAR),
+    ("Z==", LONE_CHAR),
+    ("Z===", LONE_CHAR),
+    ("A", LONE_CHAR),
+    ("9===", LONE_CHAR),
+];
+
+/// Decode `input` every way there is, checking they all agree
+fn check<A>(input: &str, expected: Expected, alphabet: A)
+where
+    A: Alphabet + Clone,
+{
+    let decoded = expected
+        .clone()
+        .map(<[u8]>::to_vec)
+        .map_err(DecodeError::from);
+    let results = [
+        (
+            "from_encoded",
+            Base64String::from_encoded_with(input, alphabet.clone())
+                .map_err(DecodeError::from)
+                .and_then(|b64| b64.decode()),
+        ),
+        (
+            "from_encoded_normalized",
+            Base64String::from_encoded_normalized_with(input, alphabet.clone())
+                .map_err(DecodeError::from)
+                .and_then(|b64| b64.decode()),
+        ),
+        (
+            "Base64Str",
+            Base64Str::from_encoded_with(input, alphabet.clone())
+                .map_err(DecodeError::from)
+                .and_then(|b64| b64.decode()),
+        ),
+        ("Codec", Codec::new(alphabet.clone()).decode(input)),
+        (
+            "Codec::normalize_padding",
+            Codec::new(alphabet.clone())
+                .normalize_padding(true)
+                .decode(input),
+        ),
+    ];
+
+    for (how, result) in results {
+        assert_eq!(
+            format!("{result:?}"),
+            format!("{decoded:?}"),
+            "{input:?} with {how}"
+        );
+    }
+
+    // `validate` doesn't trim whitespace, as it checks text is exactly
+    // base64
+    assert_eq!(
+        validate(input.trim_ascii(), &alphabet),
+        expected.map(|_| ()),
+        "{input:?} with validate"
+    );
+}
+
+#[test]
+fn standard() {
+    for (input, expected) in VECTORS {
+        check(input, expected.clone(), Standard::new());
+    }
+    check("+", LONE_CHAR, Standard::new());
+}
+
+#[test]
+fn url_safe() {
+    for (input, expected) in VECTORS {
+        check(input, expected.clone(), UrlSafe::new());
+    }
+    check("-", LONE_CHAR, UrlSafe::new());
+}
+
+#[test]
+fn display_keeps_errors() {
+    // Formatting & parsing again mustn't turn an error into data
+    for (input, expected) in VECTORS {
+        let b64 = Base64String::<Standard>::from_encoded(*input).unwrap();
+        let reparsed = Base64String::<Standard>::from_encoded(b64.to_string()).unwrap();
+        assert_eq!(
+            reparsed.decode().map_err(|e| e.to_string()),
+            expected
+                .clone()
+                .map(<[u8]>::to_vec)
+                .map_err(|e| e.to_string()),
+            "{input:?}"
+        );
+    }
+}
@@ -0,0 +1,49 @@
+//! Keeps the library free of dependencies unless a feature asks for
+//! them, so depending on it never builds more than it has to
+use std::process::Command;
+
+use pretty_assertions::assert_eq;
+
+/// The normal dependencies of the library, as listed by `cargo tree`
+fn dependencies(args: &[&str]) -> Vec<String> {
+    let output = Command::new(env!("CARGO"))
+        .args(["tree", "--manifest-path"])
+        .arg(concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml"))
+        .args([
+            "-p", "baze64", "-e", "normal", "--depth", "1", "--prefix", "none",
+        ])
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        // The first line is the library itself
+        .skip(1)
+        .filter_map(|line| line.split_whitespace().next())
+        .map(str::to_string)
+        .collect()
+}
+
+#[test]
+fn none_by_default() {
+    assert_eq!(dependencies(&[]), Vec::<String>::new());
+    assert_eq!(
+        dependencies(&["--no-default-features"]),
+        Vec::<String>::new()
+    );
+}
+
+#[test]
+fn only_optional_with_features() {
+    let mut all = dependencies(&["--all-features"]);
+    all.sort();
+
+    assert_eq!(all, ["base64", "digest", "futures-io", "rayon"]);
+}
@@ -0,0 +1,75 @@
+//! Filenames that aren't valid Unicode, round-tripped through URL-safe
+//! tokens
+use std::{ffi::OsString, path::Path};
+
+use baze64::{alphabet::UrlSafe, Base64String};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn unicode_paths_are_utf8() {
+    let path = Path::new("reports/2024/résumé.pdf");
+    let token = Base64String::<UrlSafe>::encode_path(path);
+
+    assert_eq!(
+        token.decode().unwrap(),
+        "reports/2024/résumé.pdf".as_bytes()
+    );
+    assert_eq!(token.decode_to_path_buf().unwrap(), path);
+}
+
+#[cfg(unix)]
+#[test]
+fn non_utf8_unix_filename() {
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+    let name = OsStr::from_bytes(&[0xff, 0xfe, b'r', b'e', 0x80, b'.', b'l', b'o', b'g']);
+    assert!(name.to_str().is_none());
+    let token = Base64String::<UrlSafe>::encode_os_str(name);
+
+    assert_eq!(token.to_string(), "__5yZYAubG9n");
+    assert_eq!(token.decode_to_os_string().unwrap(), name);
+    assert_eq!(
+        token.decode_to_os_string().unwrap().as_bytes(),
+        name.as_bytes()
+    );
+    assert_eq!(token.decode_to_path_buf().unwrap(), Path::new(name));
+}
+
+#[cfg(windows)]
+#[test]
+fn unpaired_surrogate_windows_filename() {
+    use std::os::windows::ffi::{OsStrExt, OsStringExt};
+
+    use baze64::{DecodeError, ErrorKind};
+
+    let wide = [0x61, 0xd800, 0x2e, 0x6c, 0x6f, 0x67];
+    let name = OsString::from_wide(&wide);
+    assert!(name.to_str().is_none());
+    let token = Base64String::<UrlSafe>::encode_os_str(&name);
+
+    assert_eq!(token.decode().unwrap(), b"a\xed\xa0\x80.log");
+    assert_eq!(token.decode_to_os_string().unwrap(), name);
+    assert_eq!(
+        token
+            .decode_to_os_string()
+            .unwrap()
+            .encode_wide()
+            .collect::<Vec<_>>(),
+        wide
+    );
+
+    // Not WTF-8, so not an OS string on Windows
+    let err = Base64String::<UrlSafe>::encode([b'a', 0xff])
+        .decode_to_os_string()
+        .unwrap_err();
+    assert!(matches!(err, DecodeError::InvalidOsString { offset: 1 }));
+    assert_eq!(err.kind(), ErrorKind::InvalidOsString);
+}
+
+#[test]
+fn empty() {
+    let token = Base64String::<UrlSafe>::encode_os_str(&OsString::new());
+
+    assert_eq!(token.to_string(), "");
+    assert_eq!(token.decode_to_os_string().unwrap(), OsString::new());
+}
@@ -0,0 +1,114 @@
+//! Untrusted input must never make parsing or decoding panic. Inputs
+//! that have caused problems before are kept here as a corpus
+
+use baze64::{
+    alphabet::{Permissive, Standard, UrlSafe, WithPadding},
+    B64Error, Base64String,
+};
+use proptest::prelude::*;
+
+/// Inputs that have been mishandled, or look likely to be
+const CORPUS: &[&str] = &[
+    "",
+    "=",
+    "==",
+    "====",
+    "A",
+    "A=",
+    "A===",
+    "=AAA",
+    "AA=A",
+    "Zm9v====",
+    "Zg==Zg==",
+    "\0",
+    "\0\0\0\0",
+    "Zm9\0",
+    "é",
+    "Zmé=",
+    "Zm9vé",
+    "🌍🌍🌍🌍",
+    "Zm9v\u{200b}",
+    "Zm9v\r\n",
+    " Zm9v",
+    "-_+/",
+    "\u{ff21}\u{ff21}\u{ff21}\u{ff21}",
+];
+
+/// Run every parsing & decoding path over `input`
+fn exercise(input: &str) {
+    if let Ok(b64) = Base64String::<Standard>::from_encoded(input) {
+        let _ = b64.decode();
+        let _ = b64.decode_to_string();
+        let _ = b64.decode_concatenated();
+        let _ = b64.decode_into(&mut vec![]);
+        let _ = b64.matches_data(input.as_bytes());
+        let _ = baze64::record::RecordReader::parse(&b64);
+        let _ = format!("{b64:#} {b64:?}");
+    }
+    if let Ok(b64) = Base64String::<UrlSafe>::from_encoded(input) {
+        let _ = b64.decode();
+    }
+    if let Ok(b64) = Base64String::<Permissive>::from_encoded(input) {
+        let _ = b64.decode();
+    }
+    let _ = baze64::validate(input, &Standard::new());
+    for (range, b64) in baze64::extract(input, 0, &Permissive::new()) {
+        let _ = &input[range];
+        let _ = b64.decode();
+    }
+}
+
+#[test]
+fn corpus() {
+    for input in CORPUS {
+        exercise(input);
+    }
+}
+
+#[test]
+fn nul_is_invalid() {
+    // NUL used to be accepted & decoded to a value outside 6 bits
+    assert_eq!(
+        Base64String::<Standard>::from_encoded("Zm9\0").unwrap_err(),
+        B64Error::InvalidCharAt {
+            char: '\0',
+            index: 3
+        }
+    );
+}
+
+#[test]
+fn multi_byte_chars_are_invalid() {
+    assert_eq!(
+        Base64String::<Standard>::from_encoded("Zm9vé").unwrap_err(),
+        B64Error::InvalidCharAt {
+            char: 'é',
+            index: 4
+        }
+    );
+}
+
+#[test]
+fn multi_byte_padding() {
+    // Padding used to be added until the length in bytes was a
+    // multiple of 4, leaving too few padding characters
+    let alphabet = WithPadding::new(Standard::new(), 'é').unwrap();
+    for text in ["Zg", "Zgé"] {
+        let b64 = Base64String::from_encoded_normalized_with(text, alphabet).unwrap();
+
+        assert_eq!(b64, "Zgéé");
+        assert_eq!(b64.decode().unwrap(), b"f");
+    }
+}
+
+proptest! {
+    #[test]
+    fn arbitrary_strings(input in any::<String>()) {
+        exercise(&input);
+    }
+
+    #[test]
+    fn arbitrary_base64_like(input in "[A-Za-z0-9+/=_\\-\0é]{0,64}") {
+        exercise(&input);
+    }
+}
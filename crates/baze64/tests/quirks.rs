@@ -0,0 +1,97 @@
+//! Vectors captured from Python 3.11's `base64.b64decode(s, validate=False)`
+//! & Java 17's `Base64.getMimeDecoder().decode(s)`, with [`None`] where
+//! they raised an error
+use baze64::{alphabet::Standard, Codec, DecodeCompat};
+use pretty_assertions::assert_eq;
+
+/// What a decoder gave back, or [`None`] for an error
+type Output = Option<&'static [u8]>;
+
+/// `(input, python, java)`
+const VECTORS: &[(&str, Output, Output)] = &[
+    ("", Some(b""), Some(b"")),
+    ("Zg==", Some(b"f"), Some(b"f")),
+    ("Zm8=", Some(b"fo"), Some(b"fo")),
+    ("Zm9v", Some(b"foo"), Some(b"foo")),
+    ("Zm9vYmFy", Some(b"foobar"), Some(b"foobar")),
+    ("Zg", None, Some(b"f")),
+    ("Zm8", None, Some(b"fo")),
+    ("Z", None, None),
+    ("Zg=", None, None),
+    ("Zm8==", Some(b"fo"), Some(b"fo")),
+    ("Zg===", Some(b"f"), Some(b"f")),
+    ("Zm9v\nYmFy\n", Some(b"foobar"), Some(b"foobar")),
+    ("Zm9v\r\nYmFy", Some(b"foobar"), Some(b"foobar")),
+    (" Zm 9v ", Some(b"foo"), Some(b"foo")),
+    ("Zm9v\tYmFy", Some(b"foobar"), Some(b"foobar")),
+    ("Zm9v!YmFy", Some(b"foobar"), Some(b"foobar")),
+    ("Zm9v-_YmFy", Some(b"foobar"), Some(b"foobar")),
+    ("Zm9v*", Some(b"foo"), Some(b"foo")),
+    ("@@Zm9v@@", Some(b"foo"), Some(b"foo")),
+    ("Zg==Zm9v", Some(b"f"), None),
+    ("Zm8=Zm9v", Some(b"fo"), None),
+    ("Zg==\nZm9v", Some(b"f"), None),
+    ("Zg==\n", Some(b"f"), Some(b"f")),
+    ("Zg==!!", Some(b"f"), Some(b"f")),
+    ("=Zg==", Some(b"f"), None),
+    ("Z=g==", Some(b"f"), None),
+    ("Zg=\n=", Some(b"f"), None),
+    ("Zg=x", None, None),
+    ("Zm8=\n=", Some(b"fo"), Some(b"fo")),
+    ("Zm9vY", None, None),
+    ("Zm9vY=", None, None),
+    ("Zm9vY==", None, None),
+    ("Zm9vYm=", None, None),
+    ("Zh==", Some(b"f"), Some(b"f")),
+    ("Zm9=", Some(b"fo"), Some(b"fo")),
+    ("////", Some(&[0xff, 0xff, 0xff]), Some(&[0xff, 0xff, 0xff])),
+    ("++8=", Some(&[0xfb, 0xef]), Some(&[0xfb, 0xef])),
+    ("Zm9véYmFy", None, Some(b"foobar")),
+    ("Zm9v☃YmFy", None, Some(b"foobar")),
+    ("ÿÿZg==", None, Some(b"f")),
+    ("====", Some(b""), None),
+    ("\n", Some(b""), Some(b"")),
+    ("Z\n", None, None),
+    ("YWJj\nZGVm\nZ2g=\n", Some(b"abcdefgh"), Some(b"abcdefgh")),
+    ("Y W J j", Some(b"abc"), Some(b"abc")),
+    ("Zm9v=YmFy", Some(b"foobar"), None),
+    ("Zm9=vYmFy", Some(b"fo"), None),
+    ("Zg=====", Some(b"f"), Some(b"f")),
+];
+
+#[test]
+fn python_forgiving() {
+    let codec = Codec::new(Standard::new()).compat(DecodeCompat::PythonForgiving);
+
+    for &(input, python, _) in VECTORS {
+        assert_eq!(codec.decode(input).ok().as_deref(), python, "{input:?}");
+    }
+}
+
+#[test]
+fn java_mime() {
+    let codec = Codec::new(Standard::new()).compat(DecodeCompat::JavaMime);
+
+    for &(input, _, java) in VECTORS {
+        assert_eq!(codec.decode(input).ok().as_deref(), java, "{input:?}");
+    }
+}
+
+#[test]
+fn strict_by_default() {
+    let codec = Codec::new(Standard::new());
+
+    for input in ["Zm9v!YmFy", "Zm9v-_YmFy", "=Zg==", "Zg=\n=", "Zm9v\nYmFy"] {
+        assert!(codec.decode(input).is_err(), "{input:?}");
+    }
+}
+
+#[test]
+fn limit_applies_after_decoding() {
+    let codec = Codec::new(Standard::new())
+        .compat(DecodeCompat::JavaMime)
+        .limit(2);
+
+    assert_eq!(codec.decode("Zm8=\n").unwrap(), b"fo");
+    assert!(codec.decode("Zm9v\n").is_err());
+}
@@ -0,0 +1,97 @@
+use baze64::{
+    alphabet::{Standard, UrlSafe},
+    Base64String,
+};
+use pretty_assertions::assert_eq;
+
+/// The test vectors from RFC 4648 §10
+const RFC_VECTORS: &[(&[u8], &str)] = &[
+    (b"", ""),
+    (b"f", "Zg=="),
+    (b"fo", "Zm8="),
+    (b"foo", "Zm9v"),
+    (b"foob", "Zm9vYg=="),
+    (b"fooba", "Zm9vYmE="),
+    (b"foobar", "Zm9vYmFy"),
+];
+
+/// Vectors using the characters for 62 & 63, which are
+/// the only ones that differ between the alphabets
+const STANDARD_VECTORS: &[(&[u8], &str)] = &[
+    (&[0xfb], "+w=="),
+    (&[0xff], "/w=="),
+    (&[0xfb, 0xef], "++8="),
+    (&[0xff, 0xff], "//8="),
+    (&[0xfb, 0xef, 0xbe], "++++"),
+    (&[0xff, 0xff, 0xff], "////"),
+];
+
+const URL_SAFE_VECTORS: &[(&[u8], &str)] = &[
+    (&[0xfb], "-w=="),
+    (&[0xff], "_w=="),
+    (&[0xfb, 0xef], "--8="),
+    (&[0xff, 0xff], "__8="),
+    (&[0xfb, 0xef, 0xbe], "----"),
+    (&[0xff, 0xff, 0xff], "____"),
+];
+
+#[test]
+fn standard_encode() {
+    for &(data, expected) in RFC_VECTORS.iter().chain(STANDARD_VECTORS) {
+        assert_eq!(Base64String::<Standard>::encode(data).to_string(), expected);
+    }
+}
+
+#[test]
+fn standard_decode() {
+    for &(expected, b64) in RFC_VECTORS.iter().chain(STANDARD_VECTORS) {
+        let decoded = Base64String::<Standard>::from_encoded(b64)
+            .unwrap()
+            .decode()
+            .unwrap();
+        assert_eq!(decoded, expected, "decoding {b64:?}");
+    }
+}
+
+#[test]
+fn url_safe_encode() {
+    for &(data, expected) in RFC_VECTORS.iter().chain(URL_SAFE_VECTORS) {
+        assert_eq!(Base64String::<UrlSafe>::encode(data).to_string(), expected);
+    }
+}
+
+#[test]
+fn url_safe_decode() {
+    for &(expected, b64) in RFC_VECTORS.iter().chain(URL_SAFE_VECTORS) {
+        let decoded = Base64String::<UrlSafe>::from_encoded(b64)
+            .unwrap()
+            .decode()
+            .unwrap();
+        assert_eq!(decoded, expected, "decoding {b64:?}");
+    }
+}
+
+#[test]
+fn alphabets_reject_each_other() {
+    for &(_, b64) in STANDARD_VECTORS {
+        assert!(Base64String::<UrlSafe>::from_encoded(b64).is_err());
+    }
+    for &(_, b64) in URL_SAFE_VECTORS {
+        assert!(Base64String::<Standard>::from_encoded(b64).is_err());
+    }
+}
+
+#[test]
+fn empty() {
+    let encoded = Base64String::<Standard>::encode([]);
+
+    assert_eq!(encoded.to_string(), "");
+    assert_eq!(encoded.decode().unwrap(), b"");
+    assert_eq!(
+        Base64String::<Standard>::from_encoded("")
+            .unwrap()
+            .decode()
+            .unwrap(),
+        b""
+    );
+}
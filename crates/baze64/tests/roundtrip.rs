@@ -0,0 +1,525 @@
+use std::io::{self, Read, Write};
+
+use baze64::{
+    alphabet::{self, Alphabet, AnyAlphabet, Permissive, Standard, UrlSafe, WithPadding},
+    primitives, B64Error, Base64String, DecodeError, ErrorKind,
+};
+use proptest::prelude::*;
+
+/// The standard alphabet without any padding
+#[derive(Debug, Clone, Copy, Default)]
+struct Unpadded;
+
+impl Alphabet for Unpadded {
+    fn padding(&self) -> Option<char> {
+        None
+    }
+
+    fn encode_bits(&self, bits: u8) -> char {
+        Standard::new().encode_bits(bits)
+    }
+
+    fn decode_char(&self, c: char) -> Result<u8, B64Error> {
+        Standard::new().decode_char(c)
+    }
+}
+
+/// A writer that fails once it's been written to `limit` times
+struct FailingWriter {
+    limit: usize,
+}
+
+impl Write for FailingWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.limit == 0 {
+            return Err(io::Error::other("writer is full"));
+        }
+        self.limit -= 1;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A reader that hands out at most `step` bytes per read
+struct SlowReader<'a> {
+    data: &'a [u8],
+    step: usize,
+}
+
+impl Read for SlowReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = self.step.min(buf.len()).min(self.data.len());
+        buf[..len].copy_from_slice(&self.data[..len]);
+        self.data = &self.data[len..];
+        Ok(len)
+    }
+}
+
+/// A writer that counts how many times it's written to
+#[derive(Default)]
+struct CountingWriter {
+    writes: usize,
+    bytes: usize,
+}
+
+impl Write for CountingWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.writes += 1;
+        self.bytes += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Encode `data` a block at a time with the `primitives` module
+fn encode_blocks<A: Alphabet>(data: &[u8], alphabet: &A) -> String {
+    let blocks = data.chunks_exact(3);
+    let rest = blocks.remainder();
+    let mut out: String = blocks
+        .flat_map(|block| primitives::encode_block(block.try_into().unwrap(), alphabet))
+        .collect();
+    if !rest.is_empty() {
+        let (chars, len) = primitives::encode_partial_block(rest, alphabet);
+        out.extend(&chars[..len]);
+    }
+    out
+}
+
+/// Decode `b64` a block at a time with the `primitives` module
+fn decode_blocks<A: Alphabet>(b64: &str, alphabet: &A) -> Result<Vec<u8>, B64Error> {
+    let chars: Vec<char> = b64.chars().collect();
+    let blocks = chars.chunks(4);
+    let last = blocks.len().saturating_sub(1);
+    let mut out = Vec::new();
+    for (i, block) in blocks.enumerate() {
+        if i == last {
+            let (bytes, len) = primitives::decode_partial_block(block, alphabet)?;
+            out.extend_from_slice(&bytes[..len]);
+        } else {
+            out.extend(primitives::decode_block(
+                block.try_into().unwrap(),
+                alphabet,
+            )?);
+        }
+    }
+    Ok(out)
+}
+
+proptest! {
+    #[test]
+    fn encode_string_matches_encode(text in any::<String>()) {
+        let encoded = Base64String::<Standard>::encode_string(text.clone());
+
+        prop_assert_eq!(&encoded, &Base64String::<Standard>::encode(text.as_bytes()));
+        prop_assert_eq!(String::try_from(encoded).unwrap(), text);
+    }
+
+    #[test]
+    fn standard_roundtrip(data in prop::collection::vec(any::<u8>(), 0..4096)) {
+        let encoded = Base64String::<Standard>::encode(&data);
+        prop_assert_eq!(encoded.decode().unwrap(), data);
+    }
+
+    #[test]
+    fn url_safe_roundtrip(data in prop::collection::vec(any::<u8>(), 0..4096)) {
+        let encoded = Base64String::<UrlSafe>::encode(&data);
+        prop_assert_eq!(encoded.decode().unwrap(), data);
+    }
+
+    #[test]
+    fn unpadded_roundtrip(data in prop::collection::vec(any::<u8>(), 0..4096)) {
+        let encoded = Base64String::<Unpadded>::encode(&data);
+        prop_assert_eq!(encoded.decode().unwrap(), data);
+    }
+
+    #[test]
+    fn permissive_decodes_both(data in prop::collection::vec(any::<u8>(), 0..4096)) {
+        let standard = Base64String::<Standard>::encode(&data).to_string();
+        let url_safe = Base64String::<UrlSafe>::encode(&data).to_string();
+
+        for b64 in [standard, url_safe] {
+            let decoded = Base64String::<Permissive>::from_encoded(b64).unwrap().decode();
+            prop_assert_eq!(decoded.unwrap(), data.clone());
+        }
+    }
+
+    #[test]
+    fn custom_padding_roundtrip(data in prop::collection::vec(any::<u8>(), 0..4096)) {
+        let dotted = Standard::new().with_padding('.').unwrap();
+        let encoded = Base64String::encode_with(&data, dotted);
+        let standard = Base64String::<Standard>::encode(&data);
+
+        prop_assert_eq!(encoded.to_string().replace('.', "="), standard.to_string());
+        prop_assert_eq!(encoded.without_padding(), standard.without_padding());
+        prop_assert_eq!(encoded.decode().unwrap(), data);
+    }
+
+    #[test]
+    fn reader_matches_slice(
+        data in prop::collection::vec(any::<u8>(), 0..4096),
+        step in 1..16usize,
+    ) {
+        let reader = SlowReader { data: &data, step };
+        prop_assert_eq!(
+            Base64String::<Standard>::encode_reader(reader).unwrap(),
+            Base64String::<Standard>::encode(&data)
+        );
+    }
+
+    #[test]
+    fn reparsed_roundtrip(data in prop::collection::vec(any::<u8>(), 0..4096)) {
+        let encoded = Base64String::<Standard>::encode(&data).to_string();
+        let reparsed = Base64String::<Standard>::from_encoded(&encoded).unwrap();
+        prop_assert_eq!(reparsed.decode().unwrap(), data);
+    }
+
+    #[test]
+    fn display_reparses_equal(data in prop::collection::vec(any::<u8>(), 0..4096)) {
+        fn check<A>(data: &[u8], alphabet: A) -> Result<(), TestCaseError>
+        where
+            A: Alphabet + Clone,
+        {
+            let encoded = Base64String::encode_with(data, alphabet.clone());
+            let reparsed = Base64String::from_encoded_with(encoded.to_string(), alphabet).unwrap();
+            prop_assert_eq!(&reparsed, &encoded);
+            prop_assert_eq!(reparsed.canonical(), encoded);
+            Ok(())
+        }
+
+        check(&data, Standard::new())?;
+        check(&data, UrlSafe::new())?;
+        check(&data, Permissive::new())?;
+        check(&data, Unpadded)?;
+        check(&data, Standard::new().with_padding('.').unwrap())?;
+        for name in alphabet::names() {
+            check(&data, name.parse::<AnyAlphabet>().unwrap())?;
+        }
+    }
+
+    #[test]
+    fn unpadded_matches_without_padding(data in prop::collection::vec(any::<u8>(), 0..4096)) {
+        let padded = Base64String::<Standard>::encode(&data);
+        let unpadded = Base64String::<Unpadded>::encode(&data);
+        prop_assert_eq!(padded.without_padding(), unpadded.to_string());
+    }
+
+    #[test]
+    fn decode_range_matches_slice(
+        data in prop::collection::vec(any::<u8>(), 0..4096),
+        a in any::<prop::sample::Index>(),
+        b in any::<prop::sample::Index>(),
+    ) {
+        let (start, end) = {
+            let (a, b) = (a.index(data.len() + 1), b.index(data.len() + 1));
+            (a.min(b), a.max(b))
+        };
+        let padded = Base64String::<Standard>::encode(&data);
+        let unpadded = Base64String::<Unpadded>::encode(&data);
+
+        prop_assert_eq!(padded.decode_range(start..end).unwrap(), &data[start..end]);
+        prop_assert_eq!(unpadded.decode_range(start..end).unwrap(), &data[start..end]);
+        prop_assert!(padded.decode_range(start..data.len() + 1).is_err());
+    }
+
+    #[test]
+    fn u64_roundtrip(n in any::<u64>()) {
+        let token = Base64String::<UrlSafe>::encode_u64(n);
+        let reparsed = Base64String::<UrlSafe>::from_encoded(token.without_padding()).unwrap();
+
+        prop_assert_eq!(reparsed.decode_u64().unwrap(), n);
+        prop_assert_eq!(token.decode().unwrap().len(), (u64::BITS - n.leading_zeros()).div_ceil(8).max(1) as usize);
+    }
+
+    #[test]
+    fn u128_roundtrip(n in any::<u128>()) {
+        let token = Base64String::<Unpadded>::encode_u128(n);
+
+        prop_assert_eq!(token.decode_u128().unwrap(), n);
+        prop_assert_eq!(token.decode_u64().ok(), u64::try_from(n).ok());
+    }
+
+    #[test]
+    fn vectored_matches_concatenated(
+        bufs in prop::collection::vec(prop::collection::vec(any::<u8>(), 0..16), 0..32)
+    ) {
+        let concatenated = bufs.concat();
+        prop_assert_eq!(
+            Base64String::<Standard>::encode_vectored(&bufs),
+            Base64String::<Standard>::encode(&concatenated)
+        );
+        prop_assert_eq!(
+            Base64String::<Unpadded>::encode_vectored(&bufs),
+            Base64String::<Unpadded>::encode(&concatenated)
+        );
+    }
+
+    #[test]
+    fn shell_and_yaml_safe_roundtrip(data in prop::collection::vec(any::<u8>(), 0..4096)) {
+        fn check<A>(data: &[u8], alphabet: A) -> Result<(), TestCaseError>
+        where
+            A: Alphabet + Clone,
+        {
+            let encoded = Base64String::encode_with(data, alphabet.clone());
+
+            let shell = encoded.to_shell_safe().unwrap();
+            prop_assert!(shell.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+            let from_shell = Base64String::from_shell_safe(&shell, alphabet.clone()).unwrap();
+            prop_assert_eq!(&from_shell, &encoded);
+
+            let yaml = encoded.to_yaml_safe();
+            prop_assert_eq!(Base64String::from_yaml_safe(&yaml, alphabet).unwrap(), encoded);
+            Ok(())
+        }
+
+        check(&data, Standard::new())?;
+        check(&data, UrlSafe::new())?;
+        check(&data, Unpadded)?;
+        check(&data, Standard::new().with_padding('.').unwrap())?;
+    }
+
+    #[test]
+    fn checked_roundtrip(data in prop::collection::vec(any::<u8>(), 0..4096)) {
+        let encoded = Base64String::encode_checked(&data, UrlSafe::new());
+
+        prop_assert_eq!(encoded.decode_checked().unwrap(), data.clone());
+        prop_assert_eq!(encoded.decode().unwrap(), baze64::checked::frame(&data));
+        prop_assert_eq!(Base64String::encode_checked(&data, Unpadded).decode_checked().unwrap(), data);
+    }
+
+    #[test]
+    fn decode_iter_matches_decode(data in prop::collection::vec(any::<u8>(), 0..4096)) {
+        let encoded = Base64String::<Standard>::encode(&data);
+        let bytes = encoded.decode_iter();
+
+        prop_assert_eq!(bytes.len(), data.len());
+        prop_assert_eq!(bytes.collect::<Result<Vec<u8>, _>>().unwrap(), encoded.decode().unwrap());
+
+        let unpadded = Base64String::<Unpadded>::encode(&data);
+        let borrowed = unpadded.as_base64_str();
+        prop_assert_eq!(borrowed.decode_iter().collect::<Result<Vec<u8>, _>>().unwrap(), data);
+    }
+
+    #[test]
+    fn primitives_match_encode(data in prop::collection::vec(any::<u8>(), 0..4096)) {
+        let standard = encode_blocks(&data, &Standard::new());
+        prop_assert_eq!(Base64String::<Standard>::encode(&data), standard.as_str());
+        prop_assert_eq!(decode_blocks(&standard, &Standard::new()).unwrap(), data.clone());
+
+        let unpadded = encode_blocks(&data, &Unpadded);
+        prop_assert_eq!(Base64String::<Unpadded>::encode(&data), unpadded.as_str());
+        prop_assert_eq!(decode_blocks(&unpadded, &Unpadded).unwrap(), data.clone());
+    }
+}
+
+#[test]
+fn unpadded_tail_lengths() {
+    for (data, expected) in [(&b"f"[..], "Zg"), (b"fo", "Zm8"), (b"foo", "Zm9v")] {
+        let encoded = Base64String::<Unpadded>::encode(data);
+        assert_eq!(encoded.to_string(), expected);
+
+        let decoded = Base64String::<Unpadded>::from_encoded(expected)
+            .unwrap()
+            .decode()
+            .unwrap();
+        assert_eq!(decoded, data);
+    }
+}
+
+#[test]
+fn unpadded_conforms() {
+    alphabet::conformance::assert_alphabet_conformance(&Unpadded);
+}
+
+#[test]
+fn unpadded_drops_padding() {
+    let b64 = Base64String::<Unpadded>::from_encoded("Zm9vYg==").unwrap();
+
+    assert_eq!(b64, "Zm9vYg");
+    assert_eq!(b64.decode().unwrap(), b"foob");
+    assert_eq!(
+        Base64String::<Unpadded>::from_encoded(b64.to_string()).unwrap(),
+        b64
+    );
+}
+
+#[test]
+fn unpadded_rejects_interior_padding() {
+    assert_eq!(
+        Base64String::<Unpadded>::from_encoded("Zg==Zg==").unwrap_err(),
+        B64Error::InvalidCharAt {
+            char: '=',
+            index: 2
+        }
+    );
+}
+
+#[test]
+fn unpadded_lone_char_errors() {
+    let b64 = Base64String::<Unpadded>::from_encoded("Zm9vY").unwrap();
+
+    let err = b64.decode().unwrap_err();
+
+    assert!(matches!(
+        err,
+        DecodeError::Base64Error(B64Error::Truncated {
+            expected: 6,
+            actual: 5
+        })
+    ));
+    assert_eq!(err.kind(), ErrorKind::Truncated);
+    assert_eq!(err.position(), Some(5));
+}
+
+#[test]
+fn permissive_accepts_mixed() {
+    // `+` is only standard & `_` is only URL safe
+    let mixed = "+_8=";
+
+    assert!(Base64String::<Standard>::from_encoded(mixed).is_err());
+    assert!(Base64String::<UrlSafe>::from_encoded(mixed).is_err());
+    assert_eq!(
+        Base64String::<Permissive>::from_encoded(mixed)
+            .unwrap()
+            .decode()
+            .unwrap(),
+        [0xfb, 0xff]
+    );
+}
+
+#[test]
+fn custom_padding_conversion() {
+    let dotted = Standard::new().with_padding('.').unwrap();
+    let b64 = Base64String::from_encoded_with("Zm9vYg", dotted).unwrap();
+    assert_eq!(b64.canonical().to_string(), "Zm9vYg..");
+
+    let mut out = vec![];
+    b64.decode_into(&mut out).unwrap();
+    assert_eq!(out, b"foob");
+
+    let standard = b64.change_alphabet_with(Standard::new()).unwrap();
+    assert_eq!(standard.to_string(), "Zm9vYg==");
+
+    let dotted = standard
+        .change_alphabet_with(WithPadding::new(Standard::new(), '.').unwrap())
+        .unwrap();
+    assert_eq!(dotted.to_string(), "Zm9vYg..");
+}
+
+#[test]
+fn custom_padding_rejects_original() {
+    let dotted = Standard::new().with_padding('.').unwrap();
+    let err = Base64String::from_encoded_with("Zm9vYg==", dotted).unwrap_err();
+
+    assert_eq!(
+        err,
+        B64Error::InvalidCharAt {
+            char: '=',
+            index: 6
+        }
+    );
+}
+
+#[test]
+fn write_error_surfaced() {
+    let b64 = Base64String::<Standard>::encode([0x2a; 3 * 8192]);
+    let mut writer = FailingWriter { limit: 1 };
+    let err = b64.decode_into(&mut writer).unwrap_err();
+
+    assert_eq!(err.bytes_written, 8190);
+    assert!(matches!(err.error, DecodeError::WriteError(_)));
+}
+
+#[test]
+fn reader_one_byte_at_a_time() {
+    let data = (0..=255).cycle().take(10_000).collect::<Vec<u8>>();
+    let reader = SlowReader {
+        data: &data,
+        step: 1,
+    };
+
+    assert_eq!(
+        Base64String::encode_reader_with(reader, UrlSafe::new()).unwrap(),
+        Base64String::<UrlSafe>::encode(&data)
+    );
+}
+
+#[test]
+fn reader_10_mb() {
+    let data = (0..10 * 1024 * 1024)
+        .map(|i: u32| (i * 31 % 251) as u8)
+        .collect::<Vec<_>>();
+    let len = data.len() as u64;
+
+    let streamed = Base64String::encode_reader_with_len(&data[..], len, Standard::new()).unwrap();
+    let slowly = Base64String::<Standard>::encode_reader(SlowReader {
+        data: &data,
+        step: 1000,
+    })
+    .unwrap();
+
+    assert_eq!(streamed, Base64String::<Standard>::encode(&data));
+    assert_eq!(slowly, streamed);
+}
+
+#[test]
+fn decode_into_writes_in_chunks() {
+    let len = 1 << 20;
+    let b64 = Base64String::<Standard>::encode(vec![0x2a; len]);
+    let mut writer = CountingWriter::default();
+
+    assert_eq!(b64.decode_into(&mut writer).unwrap(), len);
+    assert_eq!(writer.bytes, len);
+    assert!(writer.writes <= len / 8190 + 1);
+}
+
+#[test]
+fn buffered_write_error_surfaced() {
+    let b64 = Base64String::<Standard>::encode(b"foobarbaz");
+    let mut writer = FailingWriter { limit: 0 };
+
+    assert!(matches!(
+        b64.decode_into_buffered(&mut writer),
+        Err(DecodeError::WriteError(_))
+    ));
+}
+
+#[test]
+fn shell_and_yaml_safe_every_padding_class() {
+    for len in 0..=6 {
+        let data = vec![0xfb; len];
+        let b64 = Base64String::<Standard>::encode(&data);
+
+        let shell = b64.to_shell_safe().unwrap();
+        assert!(!shell.contains(['=', '+', '/']), "{shell}");
+        assert_eq!(
+            Base64String::from_shell_safe(&shell, Standard::new()).unwrap(),
+            b64
+        );
+        assert_eq!(
+            Base64String::from_yaml_safe(&b64.to_yaml_safe(), Standard::new()).unwrap(),
+            b64
+        );
+    }
+}
+
+#[test]
+fn yaml_safe_quotes_reinterpreted_words() {
+    // Each of these is valid base64 that YAML would read as something
+    // other than a string if it were left unquoted
+    for word in ["true", "null", "1234", "0x1F", "TRUE", "Null", "1e10"] {
+        let b64 = Base64String::<Standard>::from_encoded(word).unwrap();
+        let yaml = b64.to_yaml_safe();
+
+        assert_eq!(yaml, format!("\"{word}\""));
+        assert_eq!(
+            Base64String::from_yaml_safe(&yaml, Standard::new()).unwrap(),
+            b64
+        );
+    }
+}
@@ -0,0 +1,57 @@
+//! A PowerShell `-EncodedCommand`, which is base64 of UTF-16LE text
+use baze64::{alphabet::Standard, Base64String, DecodeError, ErrorKind, TextEncoding};
+use pretty_assertions::assert_eq;
+
+const ENCODED_COMMAND: &str = include_str!("fixtures/encoded_command.txt");
+
+const COMMAND: &str = r#"Get-ChildItem -Path C:\Temp | Where-Object { $_.Length -gt 1KB } | ForEach-Object { Write-Output "$($_.Name) → $($_.Length)" }"#;
+
+#[test]
+fn decode_encoded_command() {
+    let b64 = Base64String::<Standard>::from_encoded(ENCODED_COMMAND).unwrap();
+
+    assert_eq!(b64.decode_to_string_utf16le().unwrap(), COMMAND);
+}
+
+#[test]
+fn encode_encoded_command() {
+    let b64 = Base64String::<Standard>::encode_utf16le(COMMAND);
+
+    assert_eq!(b64.to_string(), ENCODED_COMMAND.trim_end());
+    assert_eq!(
+        b64.decode().unwrap(),
+        TextEncoding::Utf16Le.encode_text(COMMAND)
+    );
+}
+
+#[test]
+fn big_endian_roundtrip() {
+    let b64 = Base64String::<Standard>::encode_utf16be(COMMAND);
+
+    assert_eq!(b64.decode_to_string_utf16be().unwrap(), COMMAND);
+    assert_ne!(b64.to_string(), ENCODED_COMMAND.trim_end());
+}
+
+#[test]
+fn lone_surrogate() {
+    // `h` then the high half of a surrogate pair, with nothing after it
+    let b64 = Base64String::<Standard>::encode([b'h', 0x00, 0x3d, 0xd8]);
+    let err = b64.decode_to_string_utf16le().unwrap_err();
+
+    assert!(matches!(err, DecodeError::InvalidUtf16 { offset: 2 }));
+    assert_eq!(err.kind(), ErrorKind::InvalidUtf16);
+    assert_eq!(
+        err.to_string(),
+        "Decoded data isn't valid UTF-16, at byte 2"
+    );
+}
+
+#[test]
+fn odd_byte_count() {
+    let b64 = Base64String::<Standard>::from_encoded("ZABpAHI=").unwrap();
+
+    assert!(matches!(
+        b64.decode_to_string_utf16le(),
+        Err(DecodeError::InvalidUtf16 { offset: 4 })
+    ));
+}